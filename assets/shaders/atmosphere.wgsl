@@ -0,0 +1,38 @@
+// Atmosphere shell: fresnel rim glow, blue on the day side, orange at the terminator,
+// fading out on the night side. Rendered additively over the Earth.
+
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct AtmosphereMaterial {
+    // Direction from Earth's center toward the sun (xyz), w: glow intensity
+    sun_direction: vec4<f32>,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: AtmosphereMaterial;
+
+const DAY_COLOR: vec3<f32> = vec3<f32>(0.3, 0.6, 1.0);
+const SUNSET_COLOR: vec3<f32> = vec3<f32>(1.0, 0.45, 0.15);
+const NIGHT_COLOR: vec3<f32> = vec3<f32>(0.05, 0.08, 0.2);
+const RIM_POWER: f32 = 3.0;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.world_normal);
+    let sun = normalize(material.sun_direction.xyz);
+    let view_dir = normalize(view.world_position - in.world_position.xyz);
+
+    // Thicker air along grazing lines of sight near the limb
+    let rim = pow(1.0 - clamp(dot(normal, view_dir), 0.0, 1.0), RIM_POWER);
+
+    let cos_sun = dot(normal, sun);
+    let day = smoothstep(-0.3, 0.2, cos_sun);
+    // Peaks where the sun sits on the horizon
+    let sunset = exp(-(cos_sun * cos_sun) / 0.02);
+
+    let tint = mix(mix(NIGHT_COLOR, DAY_COLOR, day), SUNSET_COLOR, sunset * 0.7);
+    let intensity = rim * (0.15 + 0.85 * max(day, sunset * 0.8)) * material.sun_direction.w;
+    return vec4<f32>(tint * intensity, intensity);
+}
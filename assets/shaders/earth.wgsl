@@ -0,0 +1,63 @@
+// Earth surface shader: day/night blending from the real sun direction,
+// ocean specular highlight and an optional scrolling cloud layer.
+
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::{view, globals},
+}
+
+struct EarthMaterial {
+    // Direction from Earth's center toward the sun (xyz)
+    sun_direction: vec4<f32>,
+    // x: clouds enabled (0/1), y: cloud drift (texture widths per second),
+    // z: ocean specular strength, w: ambient light on the night side
+    params: vec4<f32>,
+    // x: brightest the city lights may get
+    night_params: vec4<f32>,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: EarthMaterial;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var day_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var day_sampler: sampler;
+@group(#{MATERIAL_BIND_GROUP}) @binding(3) var night_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(4) var night_sampler: sampler;
+@group(#{MATERIAL_BIND_GROUP}) @binding(5) var clouds_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(6) var clouds_sampler: sampler;
+
+// Half-width of the twilight band, as the cosine of the sun's angle to the horizon (~8°)
+const TWILIGHT_WIDTH: f32 = 0.14;
+const SPECULAR_POWER: f32 = 60.0;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.world_normal);
+    let sun = normalize(material.sun_direction.xyz);
+    let view_dir = normalize(view.world_position - in.world_position.xyz);
+    let cos_sun = dot(normal, sun);
+    let ambient = material.params.w;
+
+    let day_color = textureSample(day_texture, day_sampler, in.uv).rgb;
+    let night_color = min(textureSample(night_texture, night_sampler, in.uv).rgb, vec3<f32>(material.night_params.x));
+
+    // Clouds drift eastward; the fallback texture is white, so gate on the enabled flag
+    let cloud_uv = vec2<f32>(fract(in.uv.x - globals.time * material.params.y), in.uv.y);
+    let cloud = textureSample(clouds_texture, clouds_sampler, cloud_uv).r * material.params.x;
+
+    // Day side: diffuse sunlight over the surface, clouds on top
+    let diffuse = max(cos_sun, 0.0);
+    let surface = mix(day_color, vec3<f32>(1.0), cloud);
+    let lit = surface * (ambient + (1.0 - ambient) * diffuse);
+
+    // Oceans are the dark, blue-dominant parts of the day texture
+    let ocean = smoothstep(0.0, 0.05, day_color.b - max(day_color.r, day_color.g)) * (1.0 - cloud);
+    let half_dir = normalize(sun + view_dir);
+    let specular = pow(max(dot(normal, half_dir), 0.0), SPECULAR_POWER)
+        * material.params.z * ocean * diffuse;
+
+    // Night side: city lights, dimmed under clouds, over a faint ambient-lit surface
+    let dark = night_color * (1.0 - 0.7 * cloud) + surface * ambient * 0.3;
+
+    let day_amount = smoothstep(-TWILIGHT_WIDTH, TWILIGHT_WIDTH, cos_sun);
+    let color = mix(dark, lit, day_amount) + vec3<f32>(specular);
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,80 @@
+//! Compare propagating 10k satellites with and without cached SGP4 constants
+//!
+//! Run with `cargo bench --bench propagation`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SATELLITE_COUNT: usize = 10_000;
+const FRAMES: usize = 20;
+
+/// A few real TLEs (LEO, MEO, GEO) repeated to reach the satellite count
+const SAMPLE_TLES: &[(&str, &str, &str)] = &[
+    (
+        "ISS (ZARYA)",
+        "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992",
+        "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008",
+    ),
+    (
+        "GPS BIIR-2 (PRN 13)",
+        "1 24876U 97035A   20195.18898246  .00000036  00000-0  00000-0 0  9993",
+        "2 24876  55.6140 123.5453 0036972  60.5981 299.7971  2.00561989167940",
+    ),
+    (
+        "INTELSAT 901",
+        "1 26824U 01024A   20195.31303281 -.00000139  00000-0  00000-0 0  9991",
+        "2 26824   0.0145 132.1718 0003373 136.7385 198.2009  1.00271183 70263",
+    ),
+];
+
+fn load_elements() -> Vec<sgp4::Elements> {
+    (0..SATELLITE_COUNT)
+        .map(|i| {
+            let (name, line1, line2) = SAMPLE_TLES[i % SAMPLE_TLES.len()];
+            sgp4::Elements::from_tle(Some(name.to_owned()), line1.as_bytes(), line2.as_bytes())
+                .expect("sample TLE should parse")
+        })
+        .collect()
+}
+
+/// Time `FRAMES` runs of `frame`, returning the mean duration per frame
+fn time_per_frame(mut frame: impl FnMut(f64)) -> Duration {
+    frame(0.0); // Warm up
+    let start = Instant::now();
+    for i in 0..FRAMES {
+        frame(i as f64);
+    }
+    start.elapsed() / FRAMES as u32
+}
+
+fn main() {
+    let elements = load_elements();
+
+    // Before: initialize the propagator for every satellite on every frame
+    let uncached = time_per_frame(|minutes| {
+        for element in &elements {
+            if let Ok(constants) = sgp4::Constants::from_elements(element) {
+                black_box(constants.propagate(minutes).ok());
+            }
+        }
+    });
+
+    // After: initialize once at spawn, only propagate per frame
+    let constants: Vec<sgp4::Constants> = elements
+        .iter()
+        .filter_map(|element| sgp4::Constants::from_elements(element).ok())
+        .collect();
+    let cached = time_per_frame(|minutes| {
+        for constants in &constants {
+            black_box(constants.propagate(minutes).ok());
+        }
+    });
+
+    println!("Propagating {} satellites ({} frames)", SATELLITE_COUNT, FRAMES);
+    println!("  from_elements every frame: {:>10.3?} per frame", uncached);
+    println!("  cached constants:          {:>10.3?} per frame", cached);
+    println!(
+        "  speedup:                   {:>10.1}x",
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
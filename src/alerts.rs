@@ -0,0 +1,544 @@
+//! Watch list: alerts when a watched satellite comes overhead
+//!
+//! Each rule predicts the passes of one satellite above an elevation at a station, over
+//! the next few hours of simulated time. A notification is raised some minutes before each
+//! pass and when it starts, on screen and optionally on the desktop. Rules come from the
+//! `[alerts]` settings; the panel adds the selected satellite for the current session and
+//! exports the predicted passes as an iCalendar file.
+
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{AlertRule, ExportSettings, Settings};
+use crate::export::{self, PassEvent};
+use crate::ground_station::{self, GroundStation};
+use crate::horizon::Horizon;
+use crate::keymap::{Action, Keymap};
+use crate::notifications::Notify;
+use crate::passes::{self, Pass};
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+use crate::time_bar::TIME_BAR_STRIP;
+use crate::time_simulation::TimeSimulation;
+
+/// Watch list alerts and their panel
+pub struct AlertsPlugin;
+
+impl Plugin for AlertsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = &app.world().resource::<Settings>().alerts;
+        let watch_list = WatchList {
+            rules: settings.rules.iter().filter_map(Watch::from_rule).collect(),
+            lead: Duration::seconds((settings.lead_minutes * 60.0) as i64),
+            desktop_notifications: settings.desktop_notifications,
+        };
+
+        app.insert_resource(watch_list)
+            .add_systems(Startup, setup_watch_panel)
+            .add_systems(Update, (
+                toggle_watch_panel,
+                handle_watch_button,
+                evaluate_alerts,
+                export_watch_calendar,
+                update_watch_panel,
+            ).chain());
+    }
+}
+
+/// Rules listed in the panel
+const MAX_LISTED_WATCHES: usize = 8;
+/// Elevation threshold of a watch added from the panel, when no station sets one (degrees)
+const DEFAULT_MIN_ELEVATION_DEG: f64 = 30.0;
+/// Passes predicted ahead by a watch added from the panel (hours)
+const DEFAULT_WITHIN_HOURS: f64 = 12.0;
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// What a notification announced, so each is raised once per pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AlertKind {
+    Upcoming,
+    Overhead,
+}
+
+/// One watched satellite and station, with its predicted passes
+pub struct Watch {
+    /// Satellite name or NORAD catalog number, as configured
+    pub satellite: String,
+    pub station: GroundStation,
+    pub min_elevation_deg: f64,
+    pub within: Duration,
+    /// The satellite matched, its name and catalog number, once loaded
+    entity: Option<Entity>,
+    name: Option<String>,
+    norad_id: Option<u64>,
+    /// Passes found from `predicted_from`
+    passes: Vec<Pass>,
+    predicted_from: Option<DateTime<Utc>>,
+    /// Notifications raised, by pass (set time) and kind
+    alerted: HashSet<(DateTime<Utc>, AlertKind)>,
+}
+
+impl Watch {
+    fn new(satellite: String, station: GroundStation, min_elevation_deg: f64, within_hours: f64) -> Self {
+        Self {
+            satellite,
+            station,
+            min_elevation_deg,
+            within: Duration::seconds((within_hours * 3600.0) as i64),
+            entity: None,
+            name: None,
+            norad_id: None,
+            passes: Vec::new(),
+            predicted_from: None,
+            alerted: HashSet::new(),
+        }
+    }
+
+    /// Watch from the settings; rules whose station can't be found are skipped with a warning
+    fn from_rule(rule: &AlertRule) -> Option<Self> {
+        match ground_station::resolve_station(&rule.station) {
+            Ok(station) => Some(Self::new(rule.satellite.clone(), station, rule.min_elevation_deg, rule.within_hours)),
+            Err(e) => {
+                eprintln!("Warning: Ignoring the alert for {}: {}", rule.satellite, e);
+                None
+            }
+        }
+    }
+
+    /// Whether a satellite is the one this watch refers to
+    fn matches(&self, satellite: &Satellite) -> bool {
+        let wanted = self.satellite.trim();
+        wanted.parse::<u64>().ok() == Some(satellite.elements.norad_id) || satellite.name.eq_ignore_ascii_case(wanted)
+    }
+
+    /// The pass in progress or the next one
+    fn next_pass(&self, now: DateTime<Utc>) -> Option<&Pass> {
+        self.passes.iter().find(|pass| pass.set_time >= now)
+    }
+
+    /// Forget the predictions, e.g. after new elements arrived
+    fn invalidate(&mut self) {
+        self.passes.clear();
+        self.predicted_from = None;
+    }
+}
+
+/// Watched satellites (panel toggled with Y)
+#[derive(Resource)]
+pub struct WatchList {
+    pub rules: Vec<Watch>,
+    /// Warning ahead of a pass (simulated time)
+    pub lead: Duration,
+    pub desktop_notifications: bool,
+}
+
+#[derive(Component)]
+pub struct WatchPanel;
+
+#[derive(Component)]
+pub struct WatchHeader;
+
+/// Row listing the watch at this index
+#[derive(Component)]
+pub struct WatchRow(pub usize);
+
+/// Adds the selected satellite to the watch list
+#[derive(Component)]
+pub struct WatchSelectedButton;
+
+/// Writes the predicted passes of the watch list to an .ics file
+#[derive(Component)]
+pub struct ExportCalendarButton;
+
+pub fn setup_watch_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                // Above the diagnostics overlay
+                bottom: Val::Px(TIME_BAR_STRIP + 150.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            Visibility::Hidden,
+            WatchPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                WatchHeader,
+            ));
+            for row in 0..MAX_LISTED_WATCHES {
+                parent.spawn((
+                    Node {
+                        display: Display::None,
+                        ..default()
+                    },
+                    Text::new(""),
+                    TextFont {
+                        font_size: 13.0,
+                        ..default()
+                    },
+                    WatchRow(row),
+                ));
+            }
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                            ..default()
+                        },
+                        Text::new("Watch selected"),
+                        TextFont {
+                            font_size: 13.0,
+                            ..default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                        Interaction::default(),
+                        WatchSelectedButton,
+                    ));
+                    parent.spawn((
+                        Node {
+                            padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                            ..default()
+                        },
+                        Text::new("Export calendar"),
+                        TextFont {
+                            font_size: 13.0,
+                            ..default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                        Interaction::default(),
+                        ExportCalendarButton,
+                    ));
+                });
+        });
+}
+
+/// Show or hide the watch list panel with Y
+pub fn toggle_watch_panel(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut query: Query<&mut Visibility, With<WatchPanel>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleWatchList, &keyboard_input) {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// Watch the selected satellite from the first ground station, for this session
+#[allow(clippy::type_complexity)]
+fn handle_watch_button(
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), (With<WatchSelectedButton>, Changed<Interaction>)>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    station_query: Query<&GroundStation>,
+    mut watch_list: ResMut<WatchList>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    for (interaction, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let (satellite, station) = match (selected_query.iter().next(), station_query.iter().next()) {
+            (Some(satellite), Some(station)) => (satellite, station),
+            (None, _) => {
+                notify_writer.write(Notify::info("Select a satellite to watch"));
+                continue;
+            }
+            (_, None) => {
+                notify_writer.write(Notify::info("Add a ground station to watch passes over"));
+                continue;
+            }
+        };
+        let norad_id = satellite.elements.norad_id.to_string();
+        if watch_list.rules.iter().any(|watch| watch.satellite == norad_id && watch.station.name == station.name) {
+            continue;
+        }
+        let min_elevation_deg = station.min_elevation_deg.max(DEFAULT_MIN_ELEVATION_DEG);
+        println!("Watching {} above {:.0}° at {}", satellite.name, min_elevation_deg, station.name);
+        watch_list
+            .rules
+            .push(Watch::new(norad_id, station.clone(), min_elevation_deg, DEFAULT_WITHIN_HOURS));
+    }
+}
+
+/// Write the watch list's passes that haven't ended yet to an iCalendar file, returning its path
+fn write_pass_calendar(watch_list: &WatchList, now: DateTime<Utc>, settings: &ExportSettings) -> Result<String, String> {
+    let mut events: Vec<PassEvent> = watch_list
+        .rules
+        .iter()
+        .flat_map(|watch| {
+            let name = watch.name.clone().unwrap_or_else(|| watch.satellite.clone());
+            let norad_id = watch.norad_id.unwrap_or(0);
+            watch.passes.iter().filter(move |pass| pass.set_time >= now).map(move |pass| PassEvent {
+                satellite: name.clone(),
+                norad_id,
+                station: watch.station.name.clone(),
+                pass: *pass,
+            })
+        })
+        .collect();
+    if events.is_empty() {
+        return Err("no predicted pass to export".to_string());
+    }
+    events.sort_by_key(|event| event.pass.rise_time);
+
+    fs::create_dir_all(&settings.directory)
+        .map_err(|e| format!("Failed to create {}: {}", settings.directory, e))?;
+    let path = Path::new(&settings.directory).join(format!("passes_{}.ics", now.format("%Y%m%dT%H%M%SZ")));
+    fs::write(&path, export::to_ical(&events, Utc::now()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path.display().to_string())
+}
+
+/// Export the watch list's passes when the calendar button is clicked
+#[allow(clippy::type_complexity)]
+fn export_watch_calendar(
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), (With<ExportCalendarButton>, Changed<Interaction>)>,
+    watch_list: Res<WatchList>,
+    sim_time: Res<TimeSimulation>,
+    settings: Res<Settings>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    for (interaction, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match write_pass_calendar(&watch_list, sim_time.current_time(), &settings.export) {
+            Ok(path) => {
+                println!("✓ Exported the pass schedule to {}", path);
+                notify_writer.write(Notify::info(format!("Saved {}", path)));
+            }
+            Err(e) => {
+                eprintln!("Error: Failed to export the pass schedule: {}", e);
+                notify_writer.write(Notify::error(format!("Pass schedule export failed: {}", e)));
+            }
+        }
+    }
+}
+
+/// Predict the passes of each watch and raise its alerts as the simulation clock reaches them
+pub fn evaluate_alerts(
+    mut watch_list: ResMut<WatchList>,
+    sim_time: Res<TimeSimulation>,
+    satellite_query: Query<(Entity, &Satellite)>,
+    added: Query<(), Added<Satellite>>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let now = sim_time.current_time();
+    let satellites_changed = !added.is_empty() || TleRefreshed::any_changed(&mut refreshed);
+    let lead = watch_list.lead;
+    let mut alerts = Vec::new();
+
+    for watch in watch_list.rules.iter_mut() {
+        // Find the satellite again after a reload; new elements give new passes
+        let found = watch.entity.and_then(|entity| satellite_query.get(entity).ok());
+        let satellite = match found {
+            Some((_, satellite)) if !satellites_changed => satellite,
+            _ => {
+                watch.invalidate();
+                match satellite_query.iter().find(|(_, satellite)| watch.matches(satellite)) {
+                    Some((entity, satellite)) => {
+                        watch.entity = Some(entity);
+                        watch.name = Some(satellite.name.clone());
+                        watch.norad_id = Some(satellite.elements.norad_id);
+                        satellite
+                    }
+                    None => {
+                        watch.entity = None;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        // Predict again once a quarter of the window has passed, or when the clock jumped back
+        let stale = watch
+            .predicted_from
+            .is_none_or(|from| now < from || now > from + watch.within / 4);
+        if stale {
+            watch.passes = passes::predict_passes(
+                satellite.propagator(),
+                watch.station.geodetic(),
+                &Horizon {
+                    min_elevation_deg: watch.min_elevation_deg,
+                    mask: watch.station.mask.clone(),
+                },
+                now,
+                now + watch.within,
+                Duration::seconds(passes::PASS_SEARCH_STEP_SECONDS),
+            );
+            watch.predicted_from = Some(now);
+            // Passes long gone can't be announced again
+            watch.alerted.retain(|(set_time, _)| *set_time + Duration::days(1) > now);
+        }
+
+        let Some(pass) = watch.next_pass(now).copied() else { continue };
+        let key = pass.set_time;
+        let kind = if pass.rise_time <= now {
+            AlertKind::Overhead
+        } else if pass.rise_time - lead <= now {
+            AlertKind::Upcoming
+        } else {
+            continue;
+        };
+        if !watch.alerted.insert((key, kind)) {
+            continue;
+        }
+        let text = match kind {
+            AlertKind::Upcoming => format!(
+                "{} rises above {:.0}° at {} in {} min (AOS {} UTC, max {:.0}°)",
+                satellite.name,
+                watch.min_elevation_deg,
+                watch.station.name,
+                (pass.rise_time - now).num_minutes().max(1),
+                pass.rise_time.format("%H:%M:%S"),
+                pass.max_elevation_deg,
+            ),
+            AlertKind::Overhead => format!(
+                "{} is above {:.0}° at {} now (max {:.0}° at {} UTC)",
+                satellite.name,
+                watch.min_elevation_deg,
+                watch.station.name,
+                pass.max_elevation_deg,
+                pass.max_elevation_time.format("%H:%M:%S"),
+            ),
+        };
+        alerts.push(text);
+    }
+
+    for text in alerts {
+        println!("Alert: {}", text);
+        if watch_list.desktop_notifications {
+            desktop_notification("AI Space Tracker", &text);
+        }
+        notify_writer.write(Notify::info(text));
+    }
+}
+
+/// Pop up a desktop notification without blocking the frame
+fn desktop_notification(title: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!("display notification {:?} with title {:?}", body, title));
+        command
+    } else if cfg!(target_os = "linux") {
+        let mut command = Command::new("notify-send");
+        command.arg(title).arg(body);
+        command
+    } else {
+        eprintln!("Warning: Desktop notifications aren't supported on this platform");
+        return;
+    };
+    std::thread::spawn(move || {
+        if let Err(e) = command.status() {
+            eprintln!("Warning: Desktop notification failed: {}", e);
+        }
+    });
+}
+
+/// "in 1 h 05 min", "in 12 min"
+fn format_countdown(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes >= 60 {
+        format!("in {} h {:02} min", minutes / 60, minutes % 60)
+    } else {
+        format!("in {} min", minutes)
+    }
+}
+
+/// List the watches with their next pass
+fn update_watch_panel(
+    watch_list: Res<WatchList>,
+    sim_time: Res<TimeSimulation>,
+    keymap: Res<Keymap>,
+    panel_query: Query<&Visibility, With<WatchPanel>>,
+    mut header_query: Query<&mut Text, (With<WatchHeader>, Without<WatchRow>)>,
+    mut row_query: Query<(&WatchRow, &mut Text, &mut Node)>,
+) {
+    if panel_query.iter().all(|visibility| *visibility == Visibility::Hidden) {
+        return;
+    }
+    let now = sim_time.current_time();
+
+    for mut text in header_query.iter_mut() {
+        let mut header = format!("Watch list: {}  [{}] hide", watch_list.rules.len(), keymap.label(Action::ToggleWatchList));
+        if watch_list.rules.len() > MAX_LISTED_WATCHES {
+            header.push_str(&format!("\n({} more not listed)", watch_list.rules.len() - MAX_LISTED_WATCHES));
+        }
+        if text.0 != header {
+            text.0 = header;
+        }
+    }
+
+    for (row, mut text, mut node) in row_query.iter_mut() {
+        let watch = watch_list.rules.get(row.0);
+        let display = if watch.is_some() { Display::Flex } else { Display::None };
+        if node.display != display {
+            node.display = display;
+        }
+        let Some(watch) = watch else { continue };
+
+        let status = if watch.entity.is_none() {
+            "not loaded".to_string()
+        } else {
+            match watch.next_pass(now) {
+                Some(pass) if pass.rise_time <= now => format!("overhead now, max {:.0}°", pass.max_elevation_deg),
+                Some(pass) => format!(
+                    "AOS {} UTC ({}), max {:.0}°",
+                    pass.rise_time.format("%H:%M"),
+                    format_countdown(pass.rise_time - now),
+                    pass.max_elevation_deg,
+                ),
+                None => format!("no pass within {} h", watch.within.num_hours()),
+            }
+        };
+        let line = format!(
+            "{} at {} > {:.0}°: {}",
+            watch.name.as_deref().unwrap_or(&watch.satellite),
+            watch.station.name,
+            watch.min_elevation_deg,
+            status,
+        );
+        if text.0 != line {
+            text.0 = line;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::shader::ShaderRef;
+
+use crate::config::Settings;
+use crate::coords::{self, GlobeShape};
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::sun;
+use crate::time_simulation::TimeSimulation;
+use crate::text_input::InputFocus;
+
+/// Glowing atmosphere shell around the Earth
+pub struct AtmospherePlugin;
+
+impl Plugin for AtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<AtmosphereMaterial>::default())
+            .add_systems(Startup, spawn_atmosphere)
+            .add_systems(Update, (update_atmosphere_material, toggle_atmosphere));
+    }
+}
+
+/// Path of the atmosphere shader, relative to the assets directory
+const ATMOSPHERE_SHADER_PATH: &str = "shaders/atmosphere.wgsl";
+/// Height of the glow shell above the Earth's surface (km)
+const ATMOSPHERE_HEIGHT_KM: f32 = 160.0;
+/// Brightness of the rim glow
+const GLOW_INTENSITY: f32 = 1.4;
+
+/// Fresnel rim glow tinted by the sun: blue on the day side, orange at the terminator
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct AtmosphereMaterial {
+    /// Direction from Earth's center toward the sun (xyz) and glow intensity (w)
+    #[uniform(0)]
+    pub sun_direction: Vec4,
+}
+
+impl Material for AtmosphereMaterial {
+    fn fragment_shader() -> ShaderRef {
+        ATMOSPHERE_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        // Additive, so the glow brightens whatever is behind it
+        AlphaMode::Add
+    }
+}
+
+/// Atmosphere shell around the Earth
+#[derive(Component)]
+pub struct Atmosphere;
+
+pub fn spawn_atmosphere(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<AtmosphereMaterial>>,
+    settings: Res<Settings>,
+    globe: Res<GlobeShape>,
+) {
+    let mesh = meshes.add(Sphere::new(coords::km_to_scene(coords::EARTH_RADIUS_KM as f32 + ATMOSPHERE_HEIGHT_KM)).mesh().uv(64, 32));
+    let material = materials.add(AtmosphereMaterial {
+        sun_direction: Vec3::X.extend(GLOW_INTENSITY),
+    });
+
+    let visibility = if settings.earth.atmosphere {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    commands.spawn((
+        Mesh3d(mesh),
+        MeshMaterial3d(material),
+        // Follows the globe's flattening
+        Transform::from_scale(globe.scale()),
+        ScenePosition::default(),
+        visibility,
+        Atmosphere,
+        Name::new("Atmosphere"),
+    ));
+}
+
+/// Keep the glow tint in sync with the sun direction
+pub fn update_atmosphere_material(
+    mut materials: ResMut<Assets<AtmosphereMaterial>>,
+    query: Query<&MeshMaterial3d<AtmosphereMaterial>, With<Atmosphere>>,
+    sim_time: Res<TimeSimulation>,
+) {
+    let sun_direction = sun::calculate_sun_direction(sim_time.current_time());
+
+    for material_3d in query.iter() {
+        if let Some(material) = materials.get_mut(&material_3d.0) {
+            material.sun_direction = sun_direction.extend(GLOW_INTENSITY);
+        }
+    }
+}
+
+/// Toggle the atmosphere glow with A
+pub fn toggle_atmosphere(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut query: Query<&mut Visibility, With<Atmosphere>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleAtmosphere, &keyboard_input) {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
@@ -0,0 +1,447 @@
+//! Named camera views, jumped to with the number keys or the "Views" menu
+//!
+//! The list starts from a few presets and is saved to `bookmarks.toml` whenever a view is
+//! added or removed, like the groups in `groups.toml`, so settings.toml is never rewritten.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::camera::{CameraController, CameraMode, CameraTween};
+use crate::coords::{self, GlobeShape};
+use crate::keymap::{Action, Keymap};
+use crate::text_input::{InputFocus, TextInput, TextSubmitted};
+use crate::export_panel::EXPORT_BUTTON_COLOR;
+
+/// Bookmarks file, read from and written to the working directory
+pub const BOOKMARKS_FILE: &str = "bookmarks.toml";
+
+/// Camera bookmarks with a number key and a menu
+pub struct BookmarksPlugin;
+
+impl Plugin for BookmarksPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CameraBookmarks::load(BOOKMARKS_FILE))
+            .init_resource::<InputFocus>()
+            .add_message::<TextSubmitted>()
+            .add_systems(Startup, setup_bookmarks_menu)
+            .add_systems(Update, (
+                bookmark_keys,
+                toggle_bookmarks_menu,
+                handle_bookmark_buttons,
+                bookmark_name_input,
+                update_bookmarks_menu,
+                save_bookmarks,
+            ).chain());
+    }
+}
+
+/// Rows of the bookmark menu
+const MAX_LISTED_BOOKMARKS: usize = 12;
+/// Distance of a saved view is kept within these bounds (km), like the camera's own
+const MIN_BOOKMARK_DISTANCE_KM: f32 = 1000.0;
+
+/// A camera looking straight down on a point of the globe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    /// Distance from Earth's center (km)
+    pub distance_km: f32,
+}
+
+impl CameraBookmark {
+    fn new(name: &str, latitude_deg: f64, longitude_deg: f64, distance_km: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            latitude_deg,
+            longitude_deg,
+            distance_km,
+        }
+    }
+}
+
+/// Saved camera views; the first nine are on the number keys
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct CameraBookmarks {
+    #[serde(default)]
+    pub bookmarks: Vec<CameraBookmark>,
+}
+
+impl Default for CameraBookmarks {
+    fn default() -> Self {
+        Self {
+            bookmarks: vec![
+                CameraBookmark::new("Europe", 50.0, 10.0, 15000.0),
+                CameraBookmark::new("Americas", 15.0, -85.0, 20000.0),
+                CameraBookmark::new("Pacific", 0.0, -160.0, 20000.0),
+                CameraBookmark::new("Asia", 30.0, 100.0, 20000.0),
+                CameraBookmark::new("Polar view from north", 90.0, 0.0, 20000.0),
+                CameraBookmark::new("Polar view from south", -90.0, 0.0, 20000.0),
+                CameraBookmark::new("Geostationary belt", 0.0, 0.0, 100000.0),
+            ],
+        }
+    }
+}
+
+impl CameraBookmarks {
+    /// Load bookmarks from a TOML file, starting from the presets if it is missing or invalid
+    pub fn load(path: &str) -> Self {
+        if !Path::new(path).exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str::<Self>(&contents).map_err(|e| e.to_string()))
+        {
+            Ok(bookmarks) => {
+                println!("✓ Loaded {} camera bookmarks from {}", bookmarks.bookmarks.len(), path);
+                bookmarks
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to load {}: {}. Using the preset views", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    /// Add the current view under a name, replacing a bookmark of the same name
+    pub fn save_view(
+        &mut self,
+        name: &str,
+        controller: &CameraController,
+        globe: GlobeShape,
+    ) -> Result<&CameraBookmark, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Type a name for the view".to_string());
+        }
+        let (latitude_deg, longitude_deg) = controller.target_ground_point(globe);
+        let bookmark = CameraBookmark::new(
+            name,
+            latitude_deg,
+            longitude_deg,
+            coords::scene_to_km(controller.target_distance).max(MIN_BOOKMARK_DISTANCE_KM),
+        );
+
+        let index = match self.bookmarks.iter().position(|existing| existing.name == name) {
+            Some(index) => {
+                self.bookmarks[index] = bookmark;
+                index
+            }
+            None => {
+                self.bookmarks.push(bookmark);
+                self.bookmarks.len() - 1
+            }
+        };
+        Ok(&self.bookmarks[index])
+    }
+}
+
+/// Number key of the bookmark at `index`, if it has one
+fn bookmark_action(index: usize) -> Option<Action> {
+    Action::BOOKMARKS.get(index).copied()
+}
+
+/// "Views" button opening the bookmark menu
+#[derive(Component)]
+pub struct BookmarksMenuButton;
+
+/// The drop-down list under the button
+#[derive(Component)]
+pub struct BookmarksMenu;
+
+/// Row of the menu showing the bookmark at this index
+#[derive(Component)]
+pub struct BookmarkRow(pub usize);
+
+#[derive(Component)]
+pub struct BookmarkRowText(pub usize);
+
+/// What a bookmark row button does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkAction {
+    Go,
+    Delete,
+}
+
+#[derive(Component)]
+pub struct BookmarkButton {
+    pub row: usize,
+    pub action: BookmarkAction,
+}
+
+/// "Save view as" field: type a name, Enter to bookmark the current view
+#[derive(Component)]
+pub struct BookmarkNameText;
+
+/// Drop-down menu (top, right of the filter box), closed at startup
+pub fn setup_bookmarks_menu(mut commands: Commands) {
+    let small_font = TextFont {
+        font_size: 13.0,
+        ..default()
+    };
+
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(430.0),
+            top: Val::Px(20.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(2.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    padding: UiRect::axes(Val::Px(8.0), Val::Px(6.0)),
+                    align_self: AlignSelf::FlexStart,
+                    ..default()
+                },
+                Text::new("Views ▾"),
+                small_font.clone(),
+                BackgroundColor(EXPORT_BUTTON_COLOR),
+                Interaction::default(),
+                BookmarksMenuButton,
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(300.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(2.0),
+                        padding: UiRect::all(Val::Px(6.0)),
+                        display: Display::None,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+                    BookmarksMenu,
+                ))
+                .with_children(|parent| {
+                    for row in 0..MAX_LISTED_BOOKMARKS {
+                        parent
+                            .spawn((
+                                Node {
+                                    column_gap: Val::Px(4.0),
+                                    display: Display::None,
+                                    ..default()
+                                },
+                                BookmarkRow(row),
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Node {
+                                        flex_grow: 1.0,
+                                        padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+                                        ..default()
+                                    },
+                                    Text::new(""),
+                                    small_font.clone(),
+                                    BackgroundColor(Color::NONE),
+                                    Interaction::default(),
+                                    BookmarkRowText(row),
+                                    BookmarkButton {
+                                        row,
+                                        action: BookmarkAction::Go,
+                                    },
+                                ));
+                                parent.spawn((
+                                    Node {
+                                        padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+                                        ..default()
+                                    },
+                                    Text::new("x"),
+                                    small_font.clone(),
+                                    BackgroundColor(EXPORT_BUTTON_COLOR),
+                                    Interaction::default(),
+                                    BookmarkButton {
+                                        row,
+                                        action: BookmarkAction::Delete,
+                                    },
+                                ));
+                            });
+                    }
+
+                    parent
+                        .spawn((
+                            Node {
+                                padding: UiRect::all(Val::Px(4.0)),
+                                column_gap: Val::Px(6.0),
+                                margin: UiRect::top(Val::Px(4.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((Text::new("Save view as:"), small_font.clone()));
+                            parent.spawn((
+                                Node {
+                                    flex_grow: 1.0,
+                                    ..default()
+                                },
+                                small_font.clone(),
+                                TextInput::default(),
+                                BookmarkNameText,
+                            ));
+                        });
+                });
+        });
+}
+
+/// Fly to a bookmark: an animation to its pose around Earth's center
+fn go_to_bookmark(
+    commands: &mut Commands,
+    bookmark: &CameraBookmark,
+    mode: &mut CameraMode,
+    camera_query: &Query<(Entity, &CameraController), With<Camera3d>>,
+    globe: GlobeShape,
+) {
+    let view = CameraController::looking_down_on(
+        bookmark.latitude_deg,
+        bookmark.longitude_deg,
+        bookmark.distance_km,
+        globe,
+    );
+    // Leave the chase camera, which would pull the orbit center back to the satellite
+    *mode = CameraMode::EarthCentered;
+    for (entity, controller) in camera_query.iter() {
+        commands
+            .entity(entity)
+            .insert(CameraTween::to_pose(controller, view.yaw, view.pitch, view.distance));
+    }
+    println!("Camera: {}", bookmark.name);
+}
+
+/// Jump to the first nine bookmarks with the number keys
+#[allow(clippy::too_many_arguments)]
+pub fn bookmark_keys(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    bookmarks: Res<CameraBookmarks>,
+    mut mode: ResMut<CameraMode>,
+    camera_query: Query<(Entity, &CameraController), With<Camera3d>>,
+    globe: Res<GlobeShape>,
+) {
+    if focus.is_focused() {
+        return;
+    }
+    let pressed = Action::BOOKMARKS
+        .iter()
+        .position(|action| keymap.just_pressed(*action, &keyboard_input));
+    if let Some(bookmark) = pressed.and_then(|index| bookmarks.bookmarks.get(index)) {
+        go_to_bookmark(&mut commands, bookmark, &mut mode, &camera_query, *globe);
+    }
+}
+
+/// Open and close the menu with its button
+pub fn toggle_bookmarks_menu(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<BookmarksMenuButton>)>,
+    mut menu_query: Query<&mut Node, With<BookmarksMenu>>,
+) {
+    if !button_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        return;
+    }
+    for mut node in menu_query.iter_mut() {
+        node.display = match node.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+}
+
+/// Go to or delete the bookmark of a clicked row
+pub fn handle_bookmark_buttons(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &BookmarkButton), Changed<Interaction>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut mode: ResMut<CameraMode>,
+    camera_query: Query<(Entity, &CameraController), With<Camera3d>>,
+    globe: Res<GlobeShape>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed || button.row >= bookmarks.bookmarks.len() {
+            continue;
+        }
+        match button.action {
+            BookmarkAction::Go => {
+                go_to_bookmark(&mut commands, &bookmarks.bookmarks[button.row], &mut mode, &camera_query, *globe);
+            }
+            BookmarkAction::Delete => {
+                let removed = bookmarks.bookmarks.remove(button.row);
+                println!("✓ Removed camera bookmark \"{}\"", removed.name);
+            }
+        }
+    }
+}
+
+/// Bookmark the current view under the name typed in the menu when Enter is pressed
+pub fn bookmark_name_input(
+    mut submitted: MessageReader<TextSubmitted>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut input_query: Query<&mut TextInput, With<BookmarkNameText>>,
+    camera_query: Query<&CameraController, With<Camera3d>>,
+    globe: Res<GlobeShape>,
+) {
+    for TextSubmitted(field) in submitted.read() {
+        let Ok(mut input) = input_query.get_mut(*field) else { continue };
+        let Some(controller) = camera_query.iter().next() else { continue };
+        match bookmarks.save_view(&input.value, controller, *globe) {
+            Ok(bookmark) => {
+                println!(
+                    "✓ Saved camera bookmark \"{}\" ({:.1}°, {:.1}°, {:.0} km)",
+                    bookmark.name, bookmark.latitude_deg, bookmark.longitude_deg, bookmark.distance_km
+                );
+                input.clear();
+            }
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+/// Refresh the menu rows: name and number key of each bookmark
+pub fn update_bookmarks_menu(
+    bookmarks: Res<CameraBookmarks>,
+    keymap: Res<Keymap>,
+    mut row_query: Query<(&BookmarkRow, &mut Node)>,
+    mut text_query: Query<(&BookmarkRowText, &Interaction, &mut Text, &mut BackgroundColor)>,
+) {
+    for (row, mut node) in row_query.iter_mut() {
+        node.display = if row.0 < bookmarks.bookmarks.len() { Display::Flex } else { Display::None };
+    }
+    for (row, interaction, mut text, mut background) in text_query.iter_mut() {
+        background.0 = if *interaction == Interaction::Hovered {
+            Color::srgba(1.0, 1.0, 1.0, 0.1)
+        } else {
+            Color::NONE
+        };
+        if !bookmarks.is_changed() {
+            continue;
+        }
+        let Some(bookmark) = bookmarks.bookmarks.get(row.0) else { continue };
+        *text = Text::new(match bookmark_action(row.0) {
+            Some(action) => format!("[{}] {}", keymap.label(action), bookmark.name),
+            None => bookmark.name.clone(),
+        });
+    }
+}
+
+/// Write the bookmarks to disk after every change
+pub fn save_bookmarks(bookmarks: Res<CameraBookmarks>) {
+    if !bookmarks.is_changed() || bookmarks.is_added() {
+        return;
+    }
+    if let Err(e) = bookmarks.save(BOOKMARKS_FILE) {
+        eprintln!("Error: Failed to save {}: {}", BOOKMARKS_FILE, e);
+    }
+}
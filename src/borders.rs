@@ -0,0 +1,183 @@
+//! Coastlines and country borders draped over the globe, from Natural Earth GeoJSON files
+//!
+//! Line features (LineString, MultiLineString) are drawn as they are; polygon features
+//! (Polygon, MultiPolygon) are drawn as their outlines, so country shapes work too.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Settings;
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::text_input::InputFocus;
+
+/// Coastline and border overlay
+pub struct BordersPlugin;
+
+impl Plugin for BordersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let enabled = app.world().resource::<Settings>().borders.enabled;
+        app.insert_resource(BorderDisplay { enabled })
+            .add_systems(Startup, spawn_borders)
+            .add_systems(Update, (toggle_borders, update_borders).chain());
+    }
+}
+
+/// Bevy's asset directory, which the GeoJSON paths are relative to
+const ASSETS_DIR: &str = "assets";
+/// Height above the surface, enough to stay clear of the globe mesh facets (km);
+/// below the lat/lon grid so the grid stays readable where they cross
+const BORDER_ALTITUDE_KM: f64 = 15.0;
+const COASTLINE_COLOR: Color = Color::srgba(0.55, 0.8, 1.0, 0.7);
+const BORDER_COLOR: Color = Color::srgba(1.0, 0.85, 0.5, 0.5);
+
+/// Whether coastlines and borders are drawn (toggle with D)
+#[derive(Resource)]
+pub struct BorderDisplay {
+    pub enabled: bool,
+}
+
+/// One line mesh of the overlay (coastlines or borders)
+#[derive(Component)]
+pub struct BorderLines;
+
+/// Polylines of a GeoJSON document as (longitude, latitude) points
+pub fn parse_geojson_lines(contents: &str) -> Result<Vec<Vec<(f64, f64)>>, String> {
+    let document: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    let mut lines = Vec::new();
+    collect_lines(&document, &mut lines);
+    Ok(lines)
+}
+
+/// Walk a GeoJSON object (FeatureCollection, Feature or geometry) and gather its lines
+fn collect_lines(value: &Value, lines: &mut Vec<Vec<(f64, f64)>>) {
+    let coordinates = &value["coordinates"];
+    match value["type"].as_str() {
+        Some("FeatureCollection") => {
+            for feature in value["features"].as_array().into_iter().flatten() {
+                collect_lines(feature, lines);
+            }
+        }
+        Some("Feature") => collect_lines(&value["geometry"], lines),
+        Some("GeometryCollection") => {
+            for geometry in value["geometries"].as_array().into_iter().flatten() {
+                collect_lines(geometry, lines);
+            }
+        }
+        Some("LineString") => lines.extend(parse_line(coordinates)),
+        Some("MultiLineString") | Some("Polygon") => {
+            lines.extend(coordinates.as_array().into_iter().flatten().filter_map(parse_line));
+        }
+        Some("MultiPolygon") => {
+            for polygon in coordinates.as_array().into_iter().flatten() {
+                lines.extend(polygon.as_array().into_iter().flatten().filter_map(parse_line));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// [[lon, lat], ...] -> points, or None if it is not a line of at least two points
+fn parse_line(value: &Value) -> Option<Vec<(f64, f64)>> {
+    let points: Vec<(f64, f64)> = value
+        .as_array()?
+        .iter()
+        .filter_map(|point| Some((point.get(0)?.as_f64()?, point.get(1)?.as_f64()?)))
+        .collect();
+    (points.len() >= 2).then_some(points)
+}
+
+/// Load a GeoJSON file from the assets directory as line list positions on the globe
+fn load_line_positions(path: &str, globe: GlobeShape) -> Result<Vec<[f32; 3]>, String> {
+    let file = Path::new(ASSETS_DIR).join(path);
+    let contents = fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let lines = parse_geojson_lines(&contents).map_err(|e| format!("{}: {}", file.display(), e))?;
+
+    let mut positions = Vec::new();
+    for line in lines {
+        for pair in line.windows(2) {
+            for &(longitude, latitude) in pair {
+                let point = Geodetic::new(latitude, longitude, BORDER_ALTITUDE_KM);
+                positions.push(coords::geodetic_to_scene(point, globe).to_array());
+            }
+        }
+    }
+    Ok(positions)
+}
+
+pub fn spawn_borders(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    settings: Res<Settings>,
+    display: Res<BorderDisplay>,
+    globe: Res<GlobeShape>,
+) {
+    let layers = [
+        ("Coastlines", &settings.borders.coastlines, COASTLINE_COLOR),
+        ("Borders", &settings.borders.borders, BORDER_COLOR),
+    ];
+    for (name, path, color) in layers {
+        if path.is_empty() {
+            continue;
+        }
+        let positions = match load_line_positions(path, *globe) {
+            Ok(positions) => positions,
+            Err(e) => {
+                eprintln!("Warning: {} overlay not loaded: {}", name, e);
+                continue;
+            }
+        };
+        println!("✓ Loaded {} overlay ({} segments)", name.to_lowercase(), positions.len() / 2);
+
+        // The lines turn with the Earth, so they are built once in the Earth-fixed scene frame
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: color,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })),
+            Transform::default(),
+            ScenePosition::default(),
+            if display.enabled { Visibility::Visible } else { Visibility::Hidden },
+            BorderLines,
+            Name::new(name),
+        ));
+    }
+}
+
+/// Show or hide the overlay
+pub fn update_borders(
+    display: Res<BorderDisplay>,
+    mut lines_query: Query<&mut Visibility, With<BorderLines>>,
+) {
+    if !display.is_changed() {
+        return;
+    }
+    let visibility = if display.enabled { Visibility::Visible } else { Visibility::Hidden };
+    for mut lines_visibility in lines_query.iter_mut() {
+        lines_visibility.set_if_neq(visibility);
+    }
+}
+
+/// Toggle coastlines and borders with D
+pub fn toggle_borders(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<BorderDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleBorders, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
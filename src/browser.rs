@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+
+use crate::filter::constellation_of;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::Satellite;
+use crate::selection::{SelectSatellite, Selected};
+use crate::text_input::InputFocus;
+use crate::time_bar::TIME_BAR_STRIP;
+use crate::ui::{anchored_panel, PANEL_MARGIN};
+
+/// Side panel listing the loaded satellites by constellation (toggle with B)
+pub struct BrowserPlugin;
+
+impl Plugin for BrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SatelliteBrowser>()
+            .init_resource::<BrowserList>()
+            .add_systems(Update, (toggle_browser_panel, rebuild_browser_list).chain())
+            .add_systems(EguiPrimaryContextPass, browser_panel);
+    }
+}
+
+/// Members listed under an expanded constellation; the filter narrows down the rest
+const MAX_LISTED_MEMBERS: usize = 100;
+/// Shortest time between two rebuilds of the list while satellites keep changing (seconds)
+const REBUILD_INTERVAL_SECONDS: f32 = 0.5;
+/// Top of the panel, under the filter box
+const PANEL_TOP: f32 = 56.0;
+const PANEL_WIDTH: f32 = 320.0;
+
+/// What the browser shows and hides
+#[derive(Resource, Default)]
+pub struct SatelliteBrowser {
+    /// Constellations whose members are listed
+    pub expanded: BTreeSet<String>,
+    /// Constellations unchecked in the browser
+    pub hidden_constellations: BTreeSet<String>,
+    /// Satellites unchecked in the browser (NORAD IDs)
+    pub hidden_satellites: BTreeSet<u64>,
+}
+
+impl SatelliteBrowser {
+    /// Whether the browser's checkboxes let this satellite be drawn
+    pub fn is_shown(&self, name: &str, norad_id: u64) -> bool {
+        !self.hidden_satellites.contains(&norad_id) && !self.hidden_constellations.contains(&constellation_of(name))
+    }
+}
+
+/// The loaded satellites grouped by constellation, regrouped at most every `REBUILD_INTERVAL_SECONDS`
+#[derive(Resource)]
+pub struct BrowserList {
+    /// Whether the panel is shown (toggle with B)
+    pub open: bool,
+    /// Largest constellations first
+    constellations: Vec<(String, Constellation)>,
+    shown: usize,
+    total: usize,
+}
+
+impl Default for BrowserList {
+    fn default() -> Self {
+        Self {
+            open: true,
+            constellations: Vec::new(),
+            shown: 0,
+            total: 0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Constellation {
+    /// (entity, name, NORAD ID), by name
+    members: Vec<(Entity, String, u64)>,
+    /// Members shown by the filter and checkboxes
+    shown: usize,
+}
+
+/// One line of the list
+enum Row<'a> {
+    Constellation(&'a str, &'a Constellation),
+    Member(&'a (Entity, String, u64)),
+    /// Members of an expanded constellation beyond `MAX_LISTED_MEMBERS`
+    More(usize),
+}
+
+/// Show or hide the browser with B
+pub fn toggle_browser_panel(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut list: ResMut<BrowserList>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleBrowser, &keyboard_input) {
+        return;
+    }
+    list.open = !list.open;
+}
+
+/// Regroup the satellites when they, their visibility or the browser change
+#[allow(clippy::too_many_arguments)]
+pub fn rebuild_browser_list(
+    mut list: ResMut<BrowserList>,
+    browser: Res<SatelliteBrowser>,
+    time: Res<Time>,
+    mut dirty: Local<bool>,
+    mut since_rebuild: Local<f32>,
+    added: Query<(), Added<Satellite>>,
+    changed: Query<(), (With<Satellite>, Changed<Visibility>)>,
+    satellite_query: Query<(Entity, &Satellite, &Visibility)>,
+) {
+    if browser.is_changed() || !added.is_empty() || !changed.is_empty() {
+        *dirty = true;
+    }
+    *since_rebuild += time.delta_secs();
+    if !*dirty || !list.open || (*since_rebuild < REBUILD_INTERVAL_SECONDS && !browser.is_changed()) {
+        return;
+    }
+    *dirty = false;
+    *since_rebuild = 0.0;
+
+    let mut grouped: BTreeMap<String, Constellation> = BTreeMap::new();
+    for (entity, satellite, visibility) in satellite_query.iter() {
+        let constellation = grouped.entry(constellation_of(&satellite.name)).or_default();
+        constellation.members.push((entity, satellite.name.clone(), satellite.elements.norad_id));
+        if *visibility != Visibility::Hidden {
+            constellation.shown += 1;
+        }
+    }
+    let mut constellations: Vec<(String, Constellation)> = grouped.into_iter().collect();
+    for (_, constellation) in &mut constellations {
+        constellation.members.sort_by(|a, b| a.1.cmp(&b.1));
+    }
+    // Largest constellations first
+    constellations.sort_by(|a, b| b.1.members.len().cmp(&a.1.members.len()).then_with(|| a.0.cmp(&b.0)));
+
+    list.total = constellations.iter().map(|(_, constellation)| constellation.members.len()).sum();
+    list.shown = constellations.iter().map(|(_, constellation)| constellation.shown).sum();
+    list.constellations = constellations;
+}
+
+fn toggle<T: Ord>(set: &mut BTreeSet<T>, item: T) {
+    if !set.remove(&item) {
+        set.insert(item);
+    }
+}
+
+/// Browser panel (left, between the filter box and the time bar): expand constellations,
+/// tick checkboxes and select satellites; only the rows scrolled into view are laid out
+pub fn browser_panel(
+    mut contexts: EguiContexts,
+    list: Res<BrowserList>,
+    mut browser: ResMut<SatelliteBrowser>,
+    keymap: Res<Keymap>,
+    selected_query: Query<(Entity, &Satellite), With<Selected>>,
+    mut select_writer: MessageWriter<SelectSatellite>,
+) -> Result {
+    if !list.open {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    let selected = selected_query.iter().next();
+    let selected_constellation = selected.map(|(_, satellite)| constellation_of(&satellite.name));
+    let bottom = ctx.content_rect().bottom() - TIME_BAR_STRIP - PANEL_MARGIN;
+
+    let mut rows = Vec::new();
+    for (name, constellation) in &list.constellations {
+        rows.push(Row::Constellation(name, constellation));
+        if browser.expanded.contains(name) {
+            rows.extend(constellation.members.iter().take(MAX_LISTED_MEMBERS).map(Row::Member));
+            if constellation.members.len() > MAX_LISTED_MEMBERS {
+                rows.push(Row::More(constellation.members.len() - MAX_LISTED_MEMBERS));
+            }
+        }
+    }
+
+    anchored_panel("browser", egui::Align2::LEFT_TOP, [PANEL_MARGIN, PANEL_TOP]).show(ctx, |ui| {
+        ui.set_width(PANEL_WIDTH);
+        ui.label(format!(
+            "Satellites: {} / {} shown  [{}] hide",
+            list.shown,
+            list.total,
+            keymap.label(Action::ToggleBrowser)
+        ));
+        ui.separator();
+
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical()
+            .max_height((bottom - ui.cursor().top()).max(row_height))
+            .auto_shrink([false, true])
+            .show_rows(ui, row_height, rows.len(), |ui, range| {
+                for row in &rows[range] {
+                    ui.horizontal(|ui| match row {
+                        Row::Constellation(name, constellation) => {
+                            let expanded = browser.expanded.contains(*name);
+                            if ui.small_button(if expanded { "-" } else { "+" }).clicked() {
+                                toggle(&mut browser.expanded, name.to_string());
+                            }
+                            let mut checked = !browser.hidden_constellations.contains(*name);
+                            if ui.checkbox(&mut checked, "").changed() {
+                                toggle(&mut browser.hidden_constellations, name.to_string());
+                            }
+                            // The selected satellite's constellation stands out while collapsed
+                            let highlighted = !expanded && selected_constellation.as_deref() == Some(*name);
+                            let label = format!("{}  {}/{}", name, constellation.shown, constellation.members.len());
+                            if ui.selectable_label(highlighted, label).clicked() {
+                                toggle(&mut browser.expanded, name.to_string());
+                            }
+                        }
+                        Row::Member((entity, name, norad_id)) => {
+                            ui.add_space(20.0);
+                            let mut checked = !browser.hidden_satellites.contains(norad_id);
+                            if ui.checkbox(&mut checked, "").changed() {
+                                toggle(&mut browser.hidden_satellites, *norad_id);
+                            }
+                            let is_selected = selected.is_some_and(|(selected, _)| selected == *entity);
+                            if ui.selectable_label(is_selected, format!("{} ({})", name, norad_id)).clicked() {
+                                select_writer.write(SelectSatellite(Some(*entity)));
+                            }
+                        }
+                        Row::More(count) => {
+                            ui.add_space(20.0);
+                            ui.label(format!("... {} more, narrow down with the filter", count));
+                        }
+                    });
+                }
+            });
+    });
+    Ok(())
+}
@@ -1,95 +1,652 @@
+use bevy::camera::CameraUpdateSystems;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::touch::Touch;
+use bevy::math::{DQuat, DVec3};
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Settings;
+use crate::coords::{self, GlobeShape};
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
+use crate::ui::ScrollArea;
+use bevy::ui::RelativeCursorPosition;
+
+/// Closest and farthest camera distance from the orbit center (km)
+const MIN_DISTANCE: f32 = coords::km_to_scene(1000.0);
+const MAX_DISTANCE: f32 = coords::km_to_scene(100000.0);
+/// Fraction of the current distance zoomed per scroll-wheel notch
+const SCROLL_ZOOM_STEP: f32 = 0.1;
+/// Pixels per notch for touchpads reporting pixel scroll deltas
+const PIXELS_PER_SCROLL_LINE: f32 = 100.0;
+/// Pan distance per pixel, as a fraction of the camera distance
+const PAN_SPEED: f32 = 0.001;
+/// Distance from the satellite when entering chase mode (km)
+const CHASE_DISTANCE: f32 = coords::km_to_scene(2000.0);
+/// Distance from Earth's center when returning from chase mode (km)
+const EARTH_VIEW_DISTANCE: f32 = coords::km_to_scene(15000.0);
+
+/// Orbit camera with focus animations and a chase mode following the selected satellite
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = &app.world().resource::<Settings>().camera;
+        let smoothing = CameraSmoothing {
+            rate: settings.smoothing.max(0.0),
+            damping: settings.damping.max(0.0),
+        };
+
+        let frame = settings.frame;
+
+        app.insert_resource(smoothing)
+            .insert_resource(frame)
+            .init_resource::<CameraMode>()
+            .add_message::<FocusCamera>()
+            .add_systems(Startup, spawn_camera)
+            .add_systems(Update, (
+                toggle_camera_frame,
+                follow_camera_frame,
+                handle_focus_requests,
+                animate_camera_tween,
+                toggle_camera_mode,
+                follow_chase_target,
+                camera_controller_system,
+            ).chain().after(update_satellite_positions))
+            .add_systems(PostUpdate, update_clip_planes.before(CameraUpdateSystems));
+    }
+}
+
+/// What the camera orbits around
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Orbit Earth's center (or the panned orbit center)
+    #[default]
+    EarthCentered,
+    /// Orbit the selected satellite, following it as it moves
+    Chase,
+}
+
+/// Reference frame the camera holds still in
+///
+/// The scene itself is Earth-fixed; in the inertial frame the camera is turned with
+/// sidereal time so the Earth spins underneath it and orbits keep their shape.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CameraFrame {
+    /// Turns with the Earth: ground features stay put and satellites drift west with their ground tracks
+    #[default]
+    EarthFixed,
+    /// Fixed against the stars: the Earth turns once a sidereal day
+    Inertial,
+}
+
+impl CameraFrame {
+    pub fn name(self) -> &'static str {
+        match self {
+            CameraFrame::EarthFixed => "Earth-fixed",
+            CameraFrame::Inertial => "inertial",
+        }
+    }
+}
+
+/// How the camera eases toward where the controls point it
+#[derive(Resource, Clone, Copy)]
+pub struct CameraSmoothing {
+    /// Rate at which orbit, zoom and pan catch up with the controls (1/s); 0 follows them directly
+    pub rate: f32,
+    /// Rate at which the spin left after a drag dies out (1/s); 0 stops it on release
+    pub damping: f32,
+}
 
 #[derive(Component)]
 pub struct CameraController {
-    pub orbit_center: Vec3,
+    /// Scene point the camera orbits, in double precision like the satellites it follows
+    pub orbit_center: DVec3,
     pub distance: f32,
     pub yaw: f32,
     pub pitch: f32,
+    /// Orientation the camera eases toward (drags and arrow keys)
+    pub target_yaw: f32,
+    pub target_pitch: f32,
+    /// Distance the camera eases toward (scroll wheel and W/S)
+    pub target_distance: f32,
+    /// Orbit center the camera eases toward (right/middle-drag pan)
+    pub target_orbit_center: DVec3,
+    /// Spin left over after releasing a drag (rad/s)
+    pub yaw_velocity: f32,
+    pub pitch_velocity: f32,
+}
+
+/// Request to smoothly move the camera so it frames a world position
+#[derive(Message, Clone, Copy)]
+pub struct FocusCamera {
+    pub target: Vec3,
+}
+
+/// Active camera animation between two orbit poses
+#[derive(Component)]
+pub struct CameraTween {
+    pub from_yaw: f32,
+    pub from_pitch: f32,
+    pub from_distance: f32,
+    pub to_yaw: f32,
+    pub to_pitch: f32,
+    pub to_distance: f32,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+/// Duration of the focus animation (seconds)
+const FOCUS_TWEEN_DURATION: f32 = 1.5;
+/// The far plane lies this far beyond Earth's center from the camera, past the Moon's orbit (km)
+const FAR_PLANE_BEYOND_KM: f32 = 500_000.0;
+/// Near plane as a fraction of the camera's height above the globe, within bounds (km)
+const NEAR_PLANE_FRACTION: f32 = 0.01;
+const MIN_NEAR_PLANE_KM: f32 = 0.001;
+const MAX_NEAR_PLANE_KM: f32 = 10.0;
+
+/// Extra distance beyond the target's radius when framing it (km)
+const FOCUS_FRAMING_DISTANCE: f32 = coords::km_to_scene(4000.0);
+
+impl CameraTween {
+    /// Build a tween from the controller's current pose to one looking at `target` from outside the Earth
+    pub fn focus_on(controller: &CameraController, target: Vec3) -> Self {
+        let (yaw, pitch) = orbit_angles(target);
+        let distance = target.length() + FOCUS_FRAMING_DISTANCE;
+        Self::to_pose(controller, yaw, pitch, distance)
+    }
+
+    /// Build a tween from the controller's current pose to the given one, around Earth's center
+    pub fn to_pose(controller: &CameraController, to_yaw: f32, to_pitch: f32, to_distance: f32) -> Self {
+        let mut to_yaw = to_yaw;
+
+        // Take the shortest way around
+        let two_pi = std::f32::consts::TAU;
+        while to_yaw - controller.yaw > std::f32::consts::PI {
+            to_yaw -= two_pi;
+        }
+        while to_yaw - controller.yaw < -std::f32::consts::PI {
+            to_yaw += two_pi;
+        }
+
+        Self {
+            from_yaw: controller.yaw,
+            from_pitch: controller.pitch,
+            from_distance: controller.distance,
+            to_yaw,
+            to_pitch: clamp_pitch(to_pitch),
+            to_distance: to_distance.clamp(MIN_DISTANCE, MAX_DISTANCE),
+            elapsed: 0.0,
+            duration: FOCUS_TWEEN_DURATION,
+        }
+    }
+}
+
+/// Yaw and pitch of the orbit camera placed in the direction of `position` from Earth's center
+///
+/// The camera sits at distance * (cos(pitch) sin(yaw), sin(pitch), cos(pitch) cos(yaw)).
+pub fn orbit_angles(position: Vec3) -> (f32, f32) {
+    let direction = position.normalize_or_zero();
+    (direction.x.atan2(direction.z), clamp_pitch(direction.y.clamp(-1.0, 1.0).asin()))
+}
+
+/// Spawn the 3D camera (order 0) looking down on the configured target
+fn spawn_camera(mut commands: Commands, settings: Res<Settings>, globe: Res<GlobeShape>) {
+    let controller = CameraController::looking_down_on(
+        settings.camera.target_latitude_deg as f64,
+        settings.camera.target_longitude_deg as f64,
+        settings.camera.distance_km,
+        *globe,
+    );
+
+    commands.spawn((
+        Camera3d::default(),
+        Camera::default(),
+        Transform::from_translation(controller.offset()).looking_at(Vec3::ZERO, Vec3::Y),
+        ScenePosition(controller.offset().as_dvec3()),
+        controller,
+    ));
+}
+
+/// Fit the near and far planes to where the camera is, wherever the views put it
+///
+/// The far plane keeps everything out to the Moon's orbit in view, and the near plane stays
+/// well short of the globe and of satellites seen from up close.
+pub fn update_clip_planes(
+    origin: Res<FloatingOrigin>,
+    mut camera_query: Query<(&Transform, &mut Projection), With<CameraController>>,
+) {
+    for (transform, mut projection) in camera_query.iter_mut() {
+        let Projection::Perspective(perspective) = projection.as_mut() else { continue };
+        let from_center_km = coords::scene_to_km(origin.to_scene(transform.translation).length() as f32);
+        let height_km = from_center_km - coords::EARTH_RADIUS_KM as f32;
+        let near = coords::km_to_scene((height_km * NEAR_PLANE_FRACTION).clamp(MIN_NEAR_PLANE_KM, MAX_NEAR_PLANE_KM));
+        let far = coords::km_to_scene(from_center_km + FAR_PLANE_BEYOND_KM);
+        if perspective.near != near || perspective.far != far {
+            perspective.near = near;
+            perspective.far = far;
+        }
+    }
+}
+
+/// Start a camera tween for each focus request
+pub fn handle_focus_requests(
+    mut commands: Commands,
+    mut requests: MessageReader<FocusCamera>,
+    query: Query<(Entity, &CameraController), With<Camera3d>>,
+) {
+    let request = match requests.read().last() {
+        Some(request) => *request,
+        None => return,
+    };
+    for (entity, controller) in query.iter() {
+        commands.entity(entity).insert(CameraTween::focus_on(controller, request.target));
+    }
+}
+
+/// Advance active camera tweens (smoothstep easing) and remove them once finished
+pub fn animate_camera_tween(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut CameraController, &mut CameraTween)>,
+    time: Res<Time>,
+) {
+    for (entity, mut controller, mut tween) in query.iter_mut() {
+        tween.elapsed += time.delta_secs();
+        let t = (tween.elapsed / tween.duration).clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+
+        controller.yaw = tween.from_yaw + (tween.to_yaw - tween.from_yaw) * eased;
+        controller.pitch = tween.from_pitch + (tween.to_pitch - tween.from_pitch) * eased;
+        controller.distance = tween.from_distance + (tween.to_distance - tween.from_distance) * eased;
+
+        // Keep the damped controls from fighting the animation, and frame from Earth's center
+        controller.target_yaw = controller.yaw;
+        controller.target_pitch = controller.pitch;
+        controller.target_distance = controller.distance;
+        controller.target_orbit_center = DVec3::ZERO;
+        controller.yaw_velocity = 0.0;
+        controller.pitch_velocity = 0.0;
+
+        if t >= 1.0 {
+            commands.entity(entity).remove::<CameraTween>();
+        }
+    }
 }
 
 impl Default for CameraController {
     fn default() -> Self {
         Self {
-            orbit_center: Vec3::ZERO,
-            distance: 15000.0,
+            orbit_center: DVec3::ZERO,
+            distance: EARTH_VIEW_DISTANCE,
             yaw: 0.0,
             pitch: 0.0,
+            target_yaw: 0.0,
+            target_pitch: 0.0,
+            target_distance: EARTH_VIEW_DISTANCE,
+            target_orbit_center: DVec3::ZERO,
+            yaw_velocity: 0.0,
+            pitch_velocity: 0.0,
         }
     }
 }
 
+impl CameraController {
+    /// Controller at rest with the given pose, orbiting Earth's center
+    pub fn new(distance: f32, yaw: f32, pitch: f32) -> Self {
+        Self {
+            distance,
+            yaw,
+            pitch,
+            target_yaw: yaw,
+            target_pitch: pitch,
+            target_distance: distance,
+            ..default()
+        }
+    }
+
+    /// Controller at rest looking straight down on a point of the globe
+    pub fn looking_down_on(latitude_deg: f64, longitude_deg: f64, distance_km: f32, globe: GlobeShape) -> Self {
+        let ground = coords::Geodetic::new(latitude_deg, longitude_deg, 0.0);
+        let (yaw, pitch) = orbit_angles(coords::geodetic_to_scene(ground, globe));
+        Self::new(coords::km_to_scene(distance_km), yaw, pitch)
+    }
+
+    /// Latitude and longitude (degrees) of the point the camera is heading to look down on
+    pub fn target_ground_point(&self, globe: GlobeShape) -> (f64, f64) {
+        let direction = Vec3::new(
+            self.target_pitch.cos() * self.target_yaw.sin(),
+            self.target_pitch.sin(),
+            self.target_pitch.cos() * self.target_yaw.cos(),
+        );
+        let geodetic = coords::scene_to_geodetic(direction * coords::km_to_scene(coords::EARTH_RADIUS_KM as f32), globe);
+        (geodetic.latitude_deg, geodetic.longitude_deg)
+    }
+
+    /// Camera position relative to the orbit center
+    fn offset(&self) -> Vec3 {
+        Vec3::new(
+            self.distance * self.pitch.cos() * self.yaw.sin(),
+            self.distance * self.pitch.sin(),
+            self.distance * self.pitch.cos() * self.yaw.cos(),
+        )
+    }
+}
+
+fn clamp_pitch(pitch: f32) -> f32 {
+    // Clamp pitch to avoid gimbal lock
+    pitch.clamp(
+        -std::f32::consts::PI / 2.0 + 0.1,
+        std::f32::consts::PI / 2.0 - 0.1,
+    )
+}
+
+/// Cycle between Earth-centered and satellite-chase camera modes (C by default)
+pub fn toggle_camera_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut mode: ResMut<CameraMode>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    mut camera_query: Query<&mut CameraController, With<Camera3d>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::CycleCameraMode, &keyboard_input) {
+        return;
+    }
+
+    match *mode {
+        CameraMode::EarthCentered => {
+            let satellite = match selected_query.iter().next() {
+                Some(satellite) => satellite,
+                None => {
+                    println!("Select a satellite to use the chase camera");
+                    return;
+                }
+            };
+            println!("Camera: chasing {}", satellite.name);
+            *mode = CameraMode::Chase;
+            for mut controller in camera_query.iter_mut() {
+                controller.target_distance = CHASE_DISTANCE;
+            }
+        }
+        CameraMode::Chase => {
+            println!("Camera: Earth-centered");
+            *mode = CameraMode::EarthCentered;
+            for mut controller in camera_query.iter_mut() {
+                controller.target_orbit_center = DVec3::ZERO;
+                controller.target_distance = EARTH_VIEW_DISTANCE;
+            }
+        }
+    }
+}
+
+/// Switch between the Earth-fixed and inertial camera frames (I by default)
+pub fn toggle_camera_frame(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut frame: ResMut<CameraFrame>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleCameraFrame, &keyboard_input) {
+        return;
+    }
+
+    *frame = match *frame {
+        CameraFrame::EarthFixed => CameraFrame::Inertial,
+        CameraFrame::Inertial => CameraFrame::EarthFixed,
+    };
+    println!("Camera frame: {}", frame.name());
+}
+
+/// In the inertial frame, turn the camera backward about the pole by the Earth's rotation since last frame
+///
+/// Follows the simulation clock, so time acceleration, reversing and jumps spin the Earth
+/// accordingly. The orbit center turns with the camera so a panned view stays put too.
+pub fn follow_camera_frame(
+    frame: Res<CameraFrame>,
+    sim_time: Res<TimeSimulation>,
+    mut last_angle: Local<Option<f64>>,
+    mut camera_query: Query<&mut CameraController, With<Camera3d>>,
+) {
+    let angle = coords::gmst(sim_time.current_time());
+    let previous = last_angle.replace(angle);
+    if *frame != CameraFrame::Inertial {
+        return;
+    }
+    let Some(previous) = previous else { return };
+
+    // Wrapped so the day boundary (and long jumps) take the short way round
+    let turn = -((angle - previous + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU) - std::f64::consts::PI) as f32;
+    if turn == 0.0 {
+        return;
+    }
+    let rotation = DQuat::from_rotation_y(turn as f64);
+    for mut controller in camera_query.iter_mut() {
+        controller.yaw += turn;
+        controller.target_yaw += turn;
+        controller.orbit_center = rotation * controller.orbit_center;
+        controller.target_orbit_center = rotation * controller.target_orbit_center;
+    }
+}
+
+/// In chase mode, lock the orbit center onto the selected satellite every frame
+///
+/// Falls back to Earth-centered mode when the selection is cleared.
+pub fn follow_chase_target(
+    mut mode: ResMut<CameraMode>,
+    selected_query: Query<&ScenePosition, (With<Selected>, With<Satellite>)>,
+    mut camera_query: Query<&mut CameraController, With<Camera3d>>,
+) {
+    if *mode != CameraMode::Chase {
+        return;
+    }
+
+    let target = match selected_query.iter().next() {
+        Some(position) => position.0,
+        None => {
+            println!("Camera: selection cleared, back to Earth-centered");
+            *mode = CameraMode::EarthCentered;
+            for mut controller in camera_query.iter_mut() {
+                controller.target_orbit_center = DVec3::ZERO;
+                controller.target_distance = EARTH_VIEW_DISTANCE;
+            }
+            return;
+        }
+    };
+
+    for mut controller in camera_query.iter_mut() {
+        // Snap rather than ease: the satellite moves several km per frame
+        controller.orbit_center = target;
+        controller.target_orbit_center = target;
+    }
+}
+
+/// Camera motion from the fingers on a touchscreen this frame
+#[derive(Default)]
+struct TouchGesture {
+    /// One finger: orbit by its movement (pixels)
+    orbit: Option<Vec2>,
+    /// Two fingers: pan by their mean movement (pixels)
+    pan: Option<Vec2>,
+    /// Two fingers: fraction of the distance to zoom in by as they spread (negative = out)
+    zoom: f32,
+}
+
+impl TouchGesture {
+    fn from_touches(touches: &Touches) -> Self {
+        let fingers: Vec<&Touch> = touches.iter().collect();
+        match fingers.as_slice() {
+            [finger] => Self {
+                orbit: Some(finger.delta()),
+                ..default()
+            },
+            [first, second] => {
+                let spread = first.position().distance(second.position());
+                let previous_spread = first.previous_position().distance(second.previous_position());
+                Self {
+                    pan: Some((first.delta() + second.delta()) / 2.0),
+                    zoom: if spread > 0.0 && previous_spread > 0.0 { 1.0 - previous_spread / spread } else { 0.0 },
+                    ..default()
+                }
+            }
+            // Three or more fingers are left to the system
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Orbit, zoom and pan the 3D camera
+///
+/// - Left drag / one-finger drag / arrow keys: orbit (a released drag keeps spinning briefly)
+/// - Right or middle drag / two-finger drag: pan the orbit center
+/// - Scroll wheel / pinch / W/S: zoom, proportional to the current distance
+/// - Home: re-center on Earth
+/// - C: toggle the chase camera on the selected satellite (see `toggle_camera_mode`)
+/// - I: toggle the Earth-fixed and inertial frames (see `follow_camera_frame`)
+///
+/// Keys are the defaults and can be rebound (see `keymap`).
+#[allow(clippy::too_many_arguments)]
 pub fn camera_controller_system(
-    mut query: Query<(&mut Transform, &mut CameraController), With<Camera3d>>,
+    mut query: Query<(&mut Transform, &mut ScenePosition, &mut CameraController), With<Camera3d>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    keymap: Res<Keymap>,
+    focus: Res<InputFocus>,
     mouse_button: Res<ButtonInput<MouseButton>>,
-    mut mouse_motion_events: EventReader<CursorMoved>,
+    touches: Res<Touches>,
+    mut mouse_motion_events: MessageReader<CursorMoved>,
+    mut mouse_wheel_events: MessageReader<MouseWheel>,
+    scroll_areas: Query<(&RelativeCursorPosition, &InheritedVisibility), With<ScrollArea>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
     mut last_cursor_pos: Local<Option<Vec2>>,
+    smoothing: Res<CameraSmoothing>,
+    origin: Res<FloatingOrigin>,
     time: Res<Time>,
 ) {
-    for (mut transform, mut controller) in query.iter_mut() {
-        // Handle mouse drag for rotation
-        if mouse_button.pressed(MouseButton::Left) {
-            for event in mouse_motion_events.read() {
-                if let Some(last_pos) = *last_cursor_pos {
-                    let delta = event.position - last_pos;
-                    controller.yaw -= delta.x * 0.001;
-                    controller.pitch -= delta.y * 0.001;
-                    // Clamp pitch to avoid gimbal lock
-                    controller.pitch = controller.pitch.clamp(
-                        -std::f32::consts::PI / 2.0 + 0.1,
-                        std::f32::consts::PI / 2.0 - 0.1,
-                    );
-                }
-                *last_cursor_pos = Some(event.position);
+    let delta_time = time.delta_secs();
+    // Drags and scrolling in the data window leave the globe alone
+    let primary_window = primary_window.iter().next();
+    // Letters typed into a text field must not move the camera
+    let key_held = |action| !focus.is_focused() && keymap.pressed(action, &keyboard_input);
+
+    // Accumulate this frame's cursor movement while a button is held
+    let mouse_orbiting = mouse_button.pressed(MouseButton::Left);
+    let mouse_panning = mouse_button.pressed(MouseButton::Right) || mouse_button.pressed(MouseButton::Middle);
+    let mut drag_delta = Vec2::ZERO;
+    if mouse_orbiting || mouse_panning {
+        for event in mouse_motion_events.read().filter(|event| Some(event.window) == primary_window) {
+            if let Some(last_pos) = *last_cursor_pos {
+                drag_delta += event.position - last_pos;
+            }
+            *last_cursor_pos = Some(event.position);
+        }
+    } else {
+        mouse_motion_events.clear();
+        *last_cursor_pos = None;
+    }
+
+    // Finger movement drives the same orbit and pan as mouse drags
+    let touch = TouchGesture::from_touches(&touches);
+    let orbiting = mouse_orbiting || touch.orbit.is_some();
+    let panning = mouse_panning || touch.pan.is_some();
+    drag_delta += touch.orbit.or(touch.pan).unwrap_or(Vec2::ZERO);
+
+    // Scroll notches this frame (positive = zoom in); the wheel scrolls lists under the cursor instead
+    let over_list = scroll_areas.iter().any(|(cursor, visibility)| visibility.get() && cursor.cursor_over());
+    let scroll: f32 = mouse_wheel_events
+        .read()
+        .filter(|event| Some(event.window) == primary_window)
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_SCROLL_LINE,
+        })
+        .sum();
+    let scroll = if over_list { 0.0 } else { scroll };
+
+    for (mut transform, mut position, mut controller) in query.iter_mut() {
+        // Drags move the target orientation; the camera follows it below
+        if orbiting {
+            let yaw_delta = -drag_delta.x * 0.001;
+            let pitch_delta = -drag_delta.y * 0.001;
+            controller.target_yaw += yaw_delta;
+            controller.target_pitch = clamp_pitch(controller.target_pitch + pitch_delta);
+            if delta_time > 0.0 {
+                controller.yaw_velocity = yaw_delta / delta_time;
+                controller.pitch_velocity = pitch_delta / delta_time;
             }
+        } else if smoothing.damping > 0.0 {
+            // Coast after a drag, slowing down exponentially
+            let yaw_velocity = controller.yaw_velocity;
+            let pitch_velocity = controller.pitch_velocity;
+            controller.target_yaw += yaw_velocity * delta_time;
+            controller.target_pitch = clamp_pitch(controller.target_pitch + pitch_velocity * delta_time);
+            let decay = (-smoothing.damping * delta_time).exp();
+            controller.yaw_velocity *= decay;
+            controller.pitch_velocity *= decay;
         } else {
-            *last_cursor_pos = None;
+            controller.yaw_velocity = 0.0;
+            controller.pitch_velocity = 0.0;
+        }
+
+        // Pan the orbit center in the camera's view plane
+        if panning && !orbiting {
+            let pan_scale = controller.distance * PAN_SPEED;
+            let offset = (transform.left() * drag_delta.x + transform.up() * drag_delta.y) * pan_scale;
+            controller.target_orbit_center += offset.as_dvec3();
+        }
+        if !focus.is_focused() && keymap.just_pressed(Action::RecenterCamera, &keyboard_input) {
+            controller.target_orbit_center = DVec3::ZERO;
         }
 
         // Handle arrow keys for camera rotation
         let rotation_speed = 1.0; // radians per second
-        let delta_time = time.delta_secs();
         
-        if keyboard_input.pressed(KeyCode::ArrowLeft) {
-            controller.yaw -= rotation_speed * delta_time;
-        }
-        if keyboard_input.pressed(KeyCode::ArrowRight) {
-            controller.yaw += rotation_speed * delta_time;
-        }
-        if keyboard_input.pressed(KeyCode::ArrowUp) {
-            controller.pitch += rotation_speed * delta_time;
-            // Clamp pitch to avoid gimbal lock
-            controller.pitch = controller.pitch.clamp(
-                -std::f32::consts::PI / 2.0 + 0.1,
-                std::f32::consts::PI / 2.0 - 0.1,
-            );
-        }
-        if keyboard_input.pressed(KeyCode::ArrowDown) {
-            controller.pitch -= rotation_speed * delta_time;
-            // Clamp pitch to avoid gimbal lock
-            controller.pitch = controller.pitch.clamp(
-                -std::f32::consts::PI / 2.0 + 0.1,
-                std::f32::consts::PI / 2.0 - 0.1,
-            );
-        }
-
-        // Handle zoom with W/S keys
-        let zoom_speed = 500.0; // units per second
-        if keyboard_input.pressed(KeyCode::KeyW) {
-            controller.distance = (controller.distance - zoom_speed * delta_time).max(1000.0);
-        }
-        if keyboard_input.pressed(KeyCode::KeyS) {
-            controller.distance = (controller.distance + zoom_speed * delta_time).min(100000.0);
-        }
-
-        // Update camera position based on yaw and pitch
-        let x = controller.distance * controller.pitch.cos() * controller.yaw.sin();
-        let y = controller.distance * controller.pitch.sin();
-        let z = controller.distance * controller.pitch.cos() * controller.yaw.cos();
-        
-        transform.translation = controller.orbit_center + Vec3::new(x, y, z);
-        transform.look_at(controller.orbit_center, Vec3::Y);
+        if key_held(Action::OrbitLeft) {
+            controller.target_yaw -= rotation_speed * delta_time;
+        }
+        if key_held(Action::OrbitRight) {
+            controller.target_yaw += rotation_speed * delta_time;
+        }
+        if key_held(Action::OrbitUp) {
+            controller.target_pitch = clamp_pitch(controller.target_pitch + rotation_speed * delta_time);
+        }
+        if key_held(Action::OrbitDown) {
+            controller.target_pitch = clamp_pitch(controller.target_pitch - rotation_speed * delta_time);
+        }
+
+        // Zoom with the scroll wheel, pinching and W/S keys, proportional to the current distance
+        let mut zoom = scroll * SCROLL_ZOOM_STEP + touch.zoom;
+        let zoom_speed = 1.0; // fraction of the distance per second
+        if key_held(Action::ZoomIn) {
+            zoom += zoom_speed * delta_time;
+        }
+        if key_held(Action::ZoomOut) {
+            zoom -= zoom_speed * delta_time;
+        }
+        if zoom != 0.0 {
+            controller.target_distance =
+                (controller.target_distance * (1.0 - zoom).max(0.1)).clamp(MIN_DISTANCE, MAX_DISTANCE);
+        }
+
+        // Ease orientation, distance and orbit center toward their targets
+        let smoothing = if smoothing.rate > 0.0 { 1.0 - (-smoothing.rate * delta_time).exp() } else { 1.0 };
+        let yaw_error = controller.target_yaw - controller.yaw;
+        controller.yaw += yaw_error * smoothing;
+        let pitch_error = controller.target_pitch - controller.pitch;
+        controller.pitch += pitch_error * smoothing;
+        let distance_error = controller.target_distance - controller.distance;
+        controller.distance += distance_error * smoothing;
+        let center_error = controller.target_orbit_center - controller.orbit_center;
+        controller.orbit_center += center_error * smoothing as f64;
+
+        // Update camera position based on yaw and pitch, offset in f64 before going relative to the origin
+        position.0 = controller.orbit_center + controller.offset().as_dvec3();
+        transform.translation = origin.to_render(position.0);
+        transform.look_at(origin.to_render(controller.orbit_center), Vec3::Y);
     }
 }
-
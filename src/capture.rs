@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Settings;
+use crate::keymap::{Action, Keymap};
+use crate::text_input::InputFocus;
+
+/// Screenshots (F12) and frame-sequence recording (F9) of the main window
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = &app.world().resource::<Settings>().capture;
+        let recording = Recording {
+            directory: PathBuf::from(&settings.directory),
+            every_n_frames: settings.record_every_n_frames.max(1),
+            ..default()
+        };
+        app.insert_resource(recording)
+            .add_systems(Update, (take_screenshot, toggle_recording, record_frame).chain());
+    }
+}
+
+/// State of the frame-sequence recording
+#[derive(Resource, Default)]
+pub struct Recording {
+    /// Sequence being written, None while not recording
+    pub sequence: Option<PathBuf>,
+    /// Frames rendered since the recording started
+    pub frame: u64,
+    /// Frames saved to the current sequence
+    pub saved: u32,
+    /// Parent directory of screenshots and sequences
+    pub directory: PathBuf,
+    /// Keep one rendered frame out of this many
+    pub every_n_frames: u32,
+}
+
+/// Timestamp used in capture file names, unique to the millisecond
+fn timestamp() -> String {
+    Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string().replace('.', "_")
+}
+
+fn create_directory(path: &Path) -> bool {
+    match fs::create_dir_all(path) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Error: Failed to create {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// Save the window to a timestamped PNG with F12
+pub fn take_screenshot(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    recording: Res<Recording>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::Screenshot, &keyboard_input) {
+        return;
+    }
+    if !create_directory(&recording.directory) {
+        return;
+    }
+    let path = recording.directory.join(format!("screenshot_{}.png", timestamp()));
+    println!("✓ Saving screenshot to {}", path.display());
+    commands.spawn(Screenshot::primary_window()).observe(save_to_disk(path));
+}
+
+/// Start or stop recording a numbered PNG sequence with F9
+pub fn toggle_recording(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut recording: ResMut<Recording>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleRecording, &keyboard_input) {
+        return;
+    }
+
+    if let Some(sequence) = recording.sequence.take() {
+        println!("✓ Recorded {} frames to {}", recording.saved, sequence.display());
+        return;
+    }
+
+    let sequence = recording.directory.join(format!("recording_{}", timestamp()));
+    if !create_directory(&sequence) {
+        return;
+    }
+    println!("✓ Recording frames to {} (press {} to stop)", sequence.display(), keymap.label(Action::ToggleRecording));
+    recording.sequence = Some(sequence);
+    recording.frame = 0;
+    recording.saved = 0;
+}
+
+/// Capture the current frame while recording
+pub fn record_frame(mut commands: Commands, mut recording: ResMut<Recording>) {
+    let sequence = match &recording.sequence {
+        Some(sequence) => sequence.clone(),
+        None => return,
+    };
+    let frame = recording.frame;
+    recording.frame += 1;
+    if !frame.is_multiple_of(recording.every_n_frames as u64) {
+        return;
+    }
+
+    // Frames are numbered consecutively so encoders can read them as a sequence, e.g.
+    // ffmpeg -framerate 30 -i frame_%06d.png orbit.mp4
+    let path = sequence.join(format!("frame_{:06}.png", recording.saved));
+    recording.saved += 1;
+    commands.spawn(Screenshot::primary_window()).observe(save_to_disk(path));
+}
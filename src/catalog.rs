@@ -0,0 +1,252 @@
+//! Changes to the catalog: launches dated from the international designators, and objects
+//! that dropped out of the data between two downloads (decayed, or no longer tracked)
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::tle_loader::TleSet;
+
+/// How long dropped objects stay in the log (days)
+pub const DROP_LOG_DAYS: i64 = 90;
+
+/// Launch year, launch number within the year and piece of an object ("1998-067A")
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternationalDesignator {
+    pub year: i32,
+    pub launch: u32,
+    pub piece: String,
+}
+
+impl InternationalDesignator {
+    /// Parse the "YYYY-NNNP" form of OMM records and `sgp4::Elements`
+    pub fn parse(text: &str) -> Option<Self> {
+        let (year, rest) = text.trim().split_once('-')?;
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let (launch, piece) = rest.split_at(digits);
+        Some(Self {
+            year: year.parse().ok().filter(|year| *year >= 1957)?,
+            launch: launch.parse().ok()?,
+            piece: piece.to_string(),
+        })
+    }
+}
+
+/// An object of the loaded catalog
+pub struct CatalogObject {
+    pub norad_id: u64,
+    pub name: String,
+    pub designator: Option<String>,
+    /// Epoch of its elements
+    pub epoch: DateTime<Utc>,
+}
+
+/// A launch with the objects it put in the catalog
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchEvent {
+    pub year: i32,
+    pub launch: u32,
+    /// Estimated launch date (see `recent_launches`)
+    pub date: DateTime<Utc>,
+    /// Catalog number and name of its objects, by catalog number
+    pub objects: Vec<(u64, String)>,
+}
+
+impl LaunchEvent {
+    /// "1998-067", the designator shared by the pieces of the launch
+    pub fn label(&self) -> String {
+        format!("{}-{:03}", self.year, self.launch)
+    }
+}
+
+/// Oldest epoch and objects of each launch (year, number)
+type LaunchObjects = BTreeMap<(i32, u32), (DateTime<Utc>, Vec<(u64, String)>)>;
+
+/// Launches estimated to have happened in the `days` before `now`, newest first
+///
+/// Designators only number the launches of a year, so launch n of a year whose last launch
+/// in the catalog is N is placed at (n - 1/2) / N of that year (of the part before `now` for
+/// the current year). The estimate is capped by the oldest epoch of the launch's objects,
+/// since elements can't predate the launch.
+pub fn recent_launches(objects: impl IntoIterator<Item = CatalogObject>, now: DateTime<Utc>, days: i64) -> Vec<LaunchEvent> {
+    let since = now - Duration::days(days);
+    let mut launches = LaunchObjects::new();
+    let mut last_launch: BTreeMap<i32, u32> = BTreeMap::new();
+    for object in objects {
+        let Some(designator) = object.designator.as_deref().and_then(InternationalDesignator::parse) else {
+            continue;
+        };
+        let last = last_launch.entry(designator.year).or_default();
+        *last = (*last).max(designator.launch);
+        let (earliest, members) = launches
+            .entry((designator.year, designator.launch))
+            .or_insert_with(|| (object.epoch, Vec::new()));
+        *earliest = (*earliest).min(object.epoch);
+        members.push((object.norad_id, object.name));
+    }
+
+    let mut recent: Vec<LaunchEvent> = launches
+        .into_iter()
+        .filter(|((year, _), _)| *year >= since.year() && *year <= now.year())
+        .filter_map(|((year, launch), (earliest, mut objects))| {
+            let start = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single()?;
+            let end = Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).single()?.min(now);
+            let fraction = (launch as f64 - 0.5) / last_launch[&year] as f64;
+            let estimate = start + Duration::seconds(((end - start).num_seconds() as f64 * fraction) as i64);
+            let date = estimate.min(earliest);
+            objects.sort();
+            (date >= since && date <= now).then_some(LaunchEvent { year, launch, date, objects })
+        })
+        .collect();
+    recent.sort_by_key(|launch| std::cmp::Reverse(launch.date));
+    recent
+}
+
+/// An object listed in a download and missing from the next one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DroppedObject {
+    pub norad_id: u64,
+    pub name: String,
+    pub designator: Option<String>,
+    /// When the download that no longer listed it happened (Unix seconds)
+    pub noticed_at: i64,
+}
+
+impl DroppedObject {
+    pub fn noticed_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.noticed_at, 0)
+    }
+}
+
+/// Objects of `previous` that `current` no longer lists, by catalog number
+pub fn dropped_objects(previous: &TleSet, current: &TleSet, noticed_at: i64) -> Vec<DroppedObject> {
+    let mut dropped: Vec<DroppedObject> = previous
+        .iter()
+        .filter(|(norad_id, _)| !current.contains_key(norad_id))
+        .map(|(&norad_id, data)| DroppedObject {
+            norad_id,
+            name: data.name.clone(),
+            designator: data.to_elements().ok().and_then(|elements| elements.international_designator),
+            noticed_at,
+        })
+        .collect();
+    dropped.sort_by_key(|object| object.norad_id);
+    dropped
+}
+
+/// Read the log of dropped objects; a missing or unreadable log is empty
+pub fn read_drop_log(path: &Path) -> Vec<DroppedObject> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Add the objects dropped between two downloads to the log at `path`
+///
+/// Objects listed again are taken out, and entries older than `DROP_LOG_DAYS` are forgotten.
+/// Returns how many objects were newly dropped.
+pub fn update_drop_log(path: &Path, previous: &TleSet, current: &TleSet, now: i64) -> Result<usize, Box<dyn std::error::Error>> {
+    let dropped = dropped_objects(previous, current, now);
+    let mut log = read_drop_log(path);
+    let logged: HashSet<u64> = log.iter().map(|object| object.norad_id).collect();
+    let added = dropped.iter().filter(|object| !logged.contains(&object.norad_id)).count();
+
+    log.retain(|object| !current.contains_key(&object.norad_id) && now - object.noticed_at <= DROP_LOG_DAYS * 86_400);
+    log.extend(dropped.into_iter().filter(|object| !logged.contains(&object.norad_id)));
+    if added == 0 && log.len() == logged.len() {
+        return Ok(0);
+    }
+    fs::write(path, serde_json::to_string_pretty(&log)?)?;
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tle_loader::TleData;
+    use std::collections::HashMap;
+
+    fn object(norad_id: u64, designator: &str, epoch: &str) -> CatalogObject {
+        CatalogObject {
+            norad_id,
+            name: format!("OBJECT {}", norad_id),
+            designator: Some(designator.to_string()),
+            epoch: epoch.parse().unwrap(),
+        }
+    }
+
+    fn tle_set(ids: &[u64]) -> TleSet {
+        ids.iter()
+            .map(|&id| {
+                (id, TleData {
+                    line1: String::new(),
+                    line2: String::new(),
+                    name: format!("OBJECT {}", id),
+                    omm: None,
+                })
+            })
+            .collect::<HashMap<_, _>>()
+    }
+
+    #[test]
+    fn designators_parse() {
+        let iss = InternationalDesignator::parse("1998-067A").unwrap();
+        assert_eq!((iss.year, iss.launch, iss.piece.as_str()), (1998, 67, "A"));
+        assert_eq!(InternationalDesignator::parse("2024-123BZ").unwrap().piece, "BZ");
+        assert_eq!(InternationalDesignator::parse("98067A"), None);
+        assert_eq!(InternationalDesignator::parse(""), None);
+    }
+
+    #[test]
+    fn launches_are_dated_through_the_year() {
+        let now: DateTime<Utc> = "2024-12-31T00:00:00Z".parse().unwrap();
+        let objects = vec![
+            object(1, "2024-001A", "2024-12-30T00:00:00Z"),
+            object(2, "2024-100A", "2024-12-30T00:00:00Z"),
+            object(3, "2024-100B", "2024-12-30T00:00:00Z"),
+            object(4, "2024-050A", "2024-12-30T00:00:00Z"),
+        ];
+        // Only the last launch of the year falls in the last 30 days
+        let recent = recent_launches(objects, now, 30);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].label(), "2024-100");
+        assert_eq!(recent[0].objects.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(recent[0].date > now - Duration::days(30));
+    }
+
+    #[test]
+    fn launch_dates_never_follow_the_first_epoch() {
+        let now: DateTime<Utc> = "2024-12-31T00:00:00Z".parse().unwrap();
+        // The last launch of the year would be dated a couple of days ago, but its elements
+        // say it flew by early December
+        let recent = recent_launches(vec![object(1, "2024-100A", "2024-12-05T00:00:00Z")], now, 30);
+        assert_eq!(recent[0].date, "2024-12-05T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert!(recent_launches(vec![object(1, "2023-001A", "2023-12-05T00:00:00Z")], now, 30).is_empty());
+    }
+
+    #[test]
+    fn drop_log_tracks_missing_objects() {
+        let path = std::env::temp_dir().join(format!("drop_log_test_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(update_drop_log(&path, &tle_set(&[1, 2, 3]), &tle_set(&[1, 3]), 1_000).unwrap(), 1);
+        let log = read_drop_log(&path);
+        assert_eq!(log.len(), 1);
+        assert_eq!((log[0].norad_id, log[0].noticed_at), (2, 1_000));
+
+        // Still missing: logged once, with its first date
+        assert_eq!(update_drop_log(&path, &tle_set(&[1, 3]), &tle_set(&[1]), 2_000).unwrap(), 1);
+        assert_eq!(read_drop_log(&path).iter().map(|o| (o.norad_id, o.noticed_at)).collect::<Vec<_>>(), vec![(2, 1_000), (3, 2_000)]);
+
+        // Listed again, or too old
+        let later = 2_000 + DROP_LOG_DAYS * 86_400;
+        assert_eq!(update_drop_log(&path, &tle_set(&[1]), &tle_set(&[1, 3]), later).unwrap(), 0);
+        assert!(read_drop_log(&path).is_empty());
+
+        let _ = fs::remove_file(&path);
+    }
+}
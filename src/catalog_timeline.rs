@@ -0,0 +1,219 @@
+//! Timeline of the recent changes to the catalog: launches of the last days, dated from the
+//! international designators, and objects that dropped out of the downloads (decayed, or no
+//! longer tracked by the source)
+
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::catalog::{self, CatalogObject, DroppedObject, LaunchEvent};
+use crate::config::Settings;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{tle_loader, Satellite, TleRefreshed};
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
+
+/// Launch and decay timeline panel
+pub struct CatalogTimelinePlugin;
+
+impl Plugin for CatalogTimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CatalogTimeline>()
+            .add_systems(Startup, setup_timeline_panel)
+            .add_systems(Update, (
+                toggle_catalog_timeline,
+                build_catalog_timeline,
+                update_timeline_panel,
+            ).chain());
+    }
+}
+
+/// How far back the timeline goes (days)
+const TIMELINE_DAYS: i64 = 30;
+/// Events listed in the panel
+const MAX_LISTED_EVENTS: usize = 25;
+/// Names listed for a launch before "+N"
+const LISTED_NAMES: usize = 2;
+
+/// A change to the catalog
+pub enum CatalogEvent {
+    Launch(LaunchEvent),
+    Dropped(DroppedObject),
+}
+
+impl CatalogEvent {
+    fn date(&self) -> DateTime<Utc> {
+        match self {
+            CatalogEvent::Launch(launch) => launch.date,
+            CatalogEvent::Dropped(object) => object.noticed_at().unwrap_or_default(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            CatalogEvent::Launch(launch) => {
+                let names: Vec<&str> = launch.objects.iter().take(LISTED_NAMES).map(|(_, name)| name.as_str()).collect();
+                let more = launch.objects.len().saturating_sub(LISTED_NAMES);
+                let more = if more > 0 { format!(" +{}", more) } else { String::new() };
+                format!(
+                    "~{}  launch  {}  {}{}",
+                    launch.date.format("%m-%d"),
+                    launch.label(),
+                    names.join(", "),
+                    more
+                )
+            }
+            CatalogEvent::Dropped(object) => format!(
+                " {}  gone    {}  {}",
+                self.date().format("%m-%d"),
+                object.designator.as_deref().unwrap_or("-"),
+                object.name
+            ),
+        }
+    }
+}
+
+/// Timeline mode (toggle with F7) and its events, newest first
+#[derive(Resource, Default)]
+pub struct CatalogTimeline {
+    pub enabled: bool,
+    pub events: Vec<CatalogEvent>,
+    pub launches: usize,
+    pub dropped: usize,
+}
+
+#[derive(Component)]
+pub struct CatalogTimelinePanel;
+
+#[derive(Component)]
+pub struct CatalogTimelineText;
+
+pub fn setup_timeline_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Under the time controls
+                left: Val::Percent(40.0),
+                top: Val::Px(90.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            CatalogTimelinePanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                CatalogTimelineText,
+            ));
+        });
+}
+
+/// Toggle the timeline with F7
+pub fn toggle_catalog_timeline(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut timeline: ResMut<CatalogTimeline>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleCatalogTimeline, &keyboard_input) {
+        return;
+    }
+    timeline.enabled = !timeline.enabled;
+}
+
+/// Collect the events when the timeline opens and when the satellites change
+pub fn build_catalog_timeline(
+    mut timeline: ResMut<CatalogTimeline>,
+    settings: Res<Settings>,
+    added: Query<(), Added<Satellite>>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    satellite_query: Query<&Satellite>,
+    sim_time: Res<TimeSimulation>,
+    mut built: Local<bool>,
+) {
+    let satellites_changed = !added.is_empty() || refreshed.read().count() > 0;
+    if satellites_changed || !timeline.enabled {
+        *built = false;
+    }
+    if !timeline.enabled || *built {
+        return;
+    }
+    *built = true;
+
+    // Catalog changes happen in real time, whatever the simulation clock shows
+    let now = sim_time.real_time();
+    let objects = satellite_query.iter().map(|satellite| CatalogObject {
+        norad_id: satellite.elements.norad_id,
+        name: satellite.name.clone(),
+        designator: satellite.elements.international_designator.clone(),
+        epoch: satellite.elements.datetime.and_utc(),
+    });
+    let launches = catalog::recent_launches(objects, now, TIMELINE_DAYS);
+    let since = now - Duration::days(TIMELINE_DAYS);
+    let dropped: Vec<DroppedObject> = tle_loader(&settings)
+        .dropped_objects()
+        .into_iter()
+        .filter(|object| object.noticed_at().is_some_and(|noticed_at| noticed_at >= since))
+        .collect();
+
+    timeline.launches = launches.len();
+    timeline.dropped = dropped.len();
+    let mut events: Vec<CatalogEvent> = launches
+        .into_iter()
+        .map(CatalogEvent::Launch)
+        .chain(dropped.into_iter().map(CatalogEvent::Dropped))
+        .collect();
+    events.sort_by_key(|event| std::cmp::Reverse(event.date()));
+    timeline.events = events;
+}
+
+fn update_timeline_panel(
+    timeline: Res<CatalogTimeline>,
+    keymap: Res<Keymap>,
+    mut panel_query: Query<&mut Node, With<CatalogTimelinePanel>>,
+    mut text_query: Query<&mut Text, With<CatalogTimelineText>>,
+) {
+    if !timeline.is_changed() {
+        return;
+    }
+    let display = if timeline.enabled { Display::Flex } else { Display::None };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !timeline.enabled {
+        return;
+    }
+
+    let mut lines = vec![
+        format!(
+            "Catalog, last {} days: {} launches, {} objects gone  [{}] hide",
+            TIMELINE_DAYS,
+            timeline.launches,
+            timeline.dropped,
+            keymap.label(Action::ToggleCatalogTimeline)
+        ),
+        "Launch dates (~) are estimated from the designators; gone: dropped from a download".to_string(),
+    ];
+    lines.extend(timeline.events.iter().take(MAX_LISTED_EVENTS).map(CatalogEvent::describe));
+    if timeline.events.len() > MAX_LISTED_EVENTS {
+        lines.push(format!("({} more not listed)", timeline.events.len() - MAX_LISTED_EVENTS));
+    }
+    if timeline.events.is_empty() {
+        lines.push("No launches or dropped objects in the loaded data".to_string());
+    }
+    let content = lines.join("\n");
+    for mut text in text_query.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::camera::Viewport;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::camera::camera_controller_system;
+use crate::coords;
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{Satellite, SATELLITE_RADIUS};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+
+/// Picture-in-picture view of the selected satellite (cycle with P)
+pub struct ChaseViewPlugin;
+
+impl Plugin for ChaseViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChaseView>()
+            .add_systems(Startup, spawn_chase_camera)
+            .add_systems(Update, (
+                cycle_chase_view,
+                update_chase_camera.after(camera_controller_system),
+            ).chain());
+    }
+}
+
+/// Size of the inset as a fraction of the window width (16:9)
+const INSET_WIDTH_FRACTION: f32 = 0.25;
+/// Gap between the inset and the bottom edge of the window (logical pixels)
+const INSET_MARGIN: f32 = 10.0;
+/// Distance behind the satellite in the close-up view (km)
+const CLOSE_UP_DISTANCE: f32 = coords::km_to_scene(600.0);
+/// Height above the satellite's orbit in the close-up view (km)
+const CLOSE_UP_HEIGHT: f32 = coords::km_to_scene(200.0);
+
+/// What the inset shows
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaseView {
+    #[default]
+    Off,
+    /// Behind and above the selected satellite, looking at it
+    CloseUp,
+    /// From the selected satellite, looking down at Earth
+    EarthView,
+}
+
+impl ChaseView {
+    fn next(self) -> Self {
+        match self {
+            ChaseView::Off => ChaseView::CloseUp,
+            ChaseView::CloseUp => ChaseView::EarthView,
+            ChaseView::EarthView => ChaseView::Off,
+        }
+    }
+}
+
+/// Camera rendering the inset
+#[derive(Component)]
+pub struct ChaseCamera;
+
+/// Inset camera, drawn over the main 3D view and the map and under the UI
+pub fn spawn_chase_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            order: 2,
+            is_active: false,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        Transform::default(),
+        ScenePosition::default(),
+        ChaseCamera,
+        Name::new("ChaseCamera"),
+    ));
+}
+
+/// Cycle the inset between off, close-up and Earth view with P
+pub fn cycle_chase_view(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut chase_view: ResMut<ChaseView>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::CycleChaseView, &keyboard_input) {
+        return;
+    }
+    *chase_view = chase_view.next();
+    println!("Chase view: {:?}", *chase_view);
+}
+
+/// Place the inset in the window and point its camera at (or from) the selected satellite
+#[allow(clippy::type_complexity)]
+pub fn update_chase_camera(
+    chase_view: Res<ChaseView>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    origin: Res<FloatingOrigin>,
+    selected_query: Query<(&Satellite, &ScenePosition), (With<Selected>, Without<ChaseCamera>)>,
+    mut camera_query: Query<(&mut Camera, &mut Transform, &mut ScenePosition), With<ChaseCamera>>,
+) {
+    let (mut camera, mut transform, mut eye) = match camera_query.single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    let selected = selected_query.iter().next().and_then(|(satellite, satellite_position)| {
+        let position = satellite.position?;
+        let velocity = satellite.velocity?;
+        // Direction of motion in the scene frame, as for the velocity arrow
+        let forward = (coords::teme_to_scene_f64(position + velocity, satellite.last_update)
+            - coords::teme_to_scene_f64(position, satellite.last_update))
+        .normalize_or_zero();
+        Some((satellite_position.0, forward))
+    });
+    let window = windows.iter().next();
+    let (satellite, forward, window) = match (selected, window) {
+        (Some((satellite, forward)), Some(window)) if *chase_view != ChaseView::Off => (satellite, forward, window),
+        _ => {
+            camera.is_active = false;
+            return;
+        }
+    };
+
+    // Bottom-center of the window, between the time and diagnostics panels
+    let scale = window.scale_factor();
+    let size = Vec2::new(window.width() * INSET_WIDTH_FRACTION, window.width() * INSET_WIDTH_FRACTION * 9.0 / 16.0);
+    let position = Vec2::new((window.width() - size.x) / 2.0, window.height() - size.y - INSET_MARGIN);
+    camera.viewport = Some(Viewport {
+        physical_position: (position * scale).as_uvec2(),
+        physical_size: (size * scale).as_uvec2().max(UVec2::ONE),
+        ..default()
+    });
+    camera.is_active = true;
+
+    // Placed in f64 like the satellite, so the close-up doesn't shake far from the origin
+    let up = satellite.normalize_or_zero();
+    let (from, target, view_up) = match *chase_view {
+        ChaseView::CloseUp => (
+            satellite - forward * CLOSE_UP_DISTANCE as f64 + up * CLOSE_UP_HEIGHT as f64,
+            satellite,
+            up,
+        ),
+        // Just outside the satellite's sphere, facing Earth's center with the direction of motion up
+        _ => (satellite - up * (SATELLITE_RADIUS * 3.0) as f64, DVec3::ZERO, forward),
+    };
+    eye.0 = from;
+    *transform = Transform::from_translation(origin.to_render(from)).looking_at(origin.to_render(target), view_up.as_vec3());
+}
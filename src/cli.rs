@@ -0,0 +1,477 @@
+//! Headless command-line mode: compute positions and passes without opening a window
+//!
+//! ```text
+//! ai-space-tracker --headless positions --sat iss --duration 90 --step 60
+//! ai-space-tracker --headless passes --sat "name:iss" --station 43.6,1.44 --duration 1440
+//! ai-space-tracker --headless passes --sat iss --station Toulouse --format ics --output iss.ics
+//! ai-space-tracker --headless track --sat iss --duration 270 --format kml --output iss.kml
+//! ai-space-tracker --headless ephemeris --sat iss --duration 1440 --step 60 --frame ecef --output iss.oem
+//! ```
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+
+use crate::config::Settings;
+use crate::coords;
+use crate::export::{self, Ephemeris, EphemerisFormat, EphemerisFrame, GroundTrack, PassEvent, TrackFormat};
+use crate::filter::FilterExpr;
+use crate::ground_station::{self, GroundStation};
+use crate::passes;
+use crate::satcat::{SatcatLoader, SatcatSet};
+use crate::satellite::{self, Satellite};
+
+const USAGE: &str = "\
+Usage: ai-space-tracker --headless <command> [options]
+
+Commands:
+  positions   Satellite positions (ground track) over a time range
+  passes      Passes of satellites over a ground station
+  track       Ground tracks as GeoJSON or KML, for GIS tools
+  ephemeris   Position/velocity states as a CCSDS OEM or CSV, for flight dynamics tools
+
+Options:
+  --sat <filter>             Satellites to compute, as a filter expression (e.g. \"iss\", \"group:starlink alt<400\")
+  --start <time>             Start time, RFC 3339 or \"now\" (default: now)
+  --end <time>               End time, RFC 3339
+  --duration <minutes>       Time range length if --end is not given (default: 90 for positions and track,
+                             1440 for passes and ephemeris)
+  --step <seconds>           Sampling step (default: 60 for positions and ephemeris, 10 for passes, 30 for track)
+  --station <name|lat,lon[,alt_km]>
+                             Ground station for passes: a name from ground_stations.json or coordinates
+  --min-elevation <deg>      Minimum pass elevation (default: the station's, or 10)
+  --frame <teme|ecef>        Ephemeris reference frame (default: teme)
+  --format <csv|json|geojson|kml|oem|ics>
+                             Output format (default: csv, geojson for track, oem for ephemeris;
+                             ics writes passes as calendar events)
+  --output <file>            Write results to a file instead of stdout
+";
+
+/// Longest time range accepted, to keep accidental runs bounded (SGP4 is only good for days anyway)
+const MAX_DURATION_MINUTES: i64 = 14 * 24 * 60;
+
+#[derive(Clone, Copy)]
+enum Format {
+    Csv,
+    Json,
+    GeoJson,
+    Kml,
+    Oem,
+    Ics,
+}
+
+struct Options {
+    command: String,
+    satellites: String,
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+    duration_minutes: Option<i64>,
+    step_seconds: Option<i64>,
+    station: Option<String>,
+    min_elevation_deg: Option<f64>,
+    frame: EphemerisFrame,
+    format: Option<Format>,
+    output: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PositionRow {
+    time: String,
+    name: String,
+    norad_id: u64,
+    latitude_deg: f64,
+    longitude_deg: f64,
+    altitude_km: f64,
+    /// TEME position (km)
+    x_km: f64,
+    y_km: f64,
+    z_km: f64,
+}
+
+#[derive(Serialize)]
+struct PassRow {
+    name: String,
+    norad_id: u64,
+    station: String,
+    rise_time: String,
+    rise_azimuth_deg: f64,
+    max_elevation_time: String,
+    max_elevation_deg: f64,
+    set_time: String,
+    set_azimuth_deg: f64,
+}
+
+/// Run a headless command; `args` are the process arguments after the program name
+pub fn run(args: &[String], settings: &Settings) -> Result<(), String> {
+    let options = parse_args(args)?;
+
+    let satellites = load_satellites(settings, &options.satellites)?;
+    if satellites.is_empty() {
+        return Err(format!("No satellite matches \"{}\"", options.satellites));
+    }
+
+    let output = match options.command.as_str() {
+        "positions" => {
+            let rows = compute_positions(&satellites, &options)?;
+            render(&rows, options.format.unwrap_or(Format::Csv))?
+        }
+        "passes" => {
+            let events = compute_passes(&satellites, &options)?;
+            match options.format.unwrap_or(Format::Csv) {
+                Format::Ics => export::to_ical(&events, Utc::now()),
+                format => render(&events.iter().map(PassRow::from).collect::<Vec<_>>(), format)?,
+            }
+        }
+        "track" => {
+            let format = match options.format.unwrap_or(Format::GeoJson) {
+                Format::GeoJson => TrackFormat::GeoJson,
+                Format::Kml => TrackFormat::Kml,
+                _ => return Err("track output is geojson or kml".to_string()),
+            };
+            let tracks = compute_tracks(&satellites, &options)?;
+            export::render_tracks(&tracks, format)
+        }
+        "ephemeris" => {
+            let format = match options.format.unwrap_or(Format::Oem) {
+                Format::Oem => EphemerisFormat::Oem,
+                Format::Csv => EphemerisFormat::Csv,
+                _ => return Err("ephemeris output is oem or csv".to_string()),
+            };
+            let ephemerides = compute_ephemerides(&satellites, &options)?;
+            export::render_ephemerides(&ephemerides, format)
+        }
+        other => return Err(format!("Unknown command \"{}\"\n\n{}", other, USAGE)),
+    };
+
+    match &options.output {
+        Some(path) => {
+            fs::write(path, output).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            println!("✓ Wrote {}", path);
+        }
+        None => {
+            std::io::stdout()
+                .write_all(output.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: String::new(),
+        satellites: String::new(),
+        start: Utc::now(),
+        end: None,
+        duration_minutes: None,
+        step_seconds: None,
+        station: None,
+        min_elevation_deg: None,
+        frame: EphemerisFrame::Teme,
+        format: None,
+        output: None,
+    };
+
+    let mut iter = args.iter().filter(|arg| arg.as_str() != "--headless");
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}", name))
+        };
+        match arg.as_str() {
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            "--sat" => options.satellites = value(arg)?,
+            "--start" => options.start = parse_time(&value(arg)?)?,
+            "--end" => options.end = Some(parse_time(&value(arg)?)?),
+            "--duration" => options.duration_minutes = Some(parse_number(arg, &value(arg)?)?),
+            "--step" => options.step_seconds = Some(parse_number(arg, &value(arg)?)?),
+            "--station" => options.station = Some(value(arg)?),
+            "--min-elevation" => options.min_elevation_deg = Some(parse_number(arg, &value(arg)?)?),
+            "--format" => {
+                options.format = Some(match value(arg)?.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    "geojson" => Format::GeoJson,
+                    "kml" => Format::Kml,
+                    "oem" => Format::Oem,
+                    "ics" => Format::Ics,
+                    other => return Err(format!("Unknown format \"{}\" (expected csv, json, geojson, kml, oem or ics)", other)),
+                })
+            }
+            "--frame" => options.frame = EphemerisFrame::parse(&value(arg)?)?,
+            "--output" => options.output = Some(value(arg)?),
+            other if other.starts_with("--") => return Err(format!("Unknown option {}\n\n{}", other, USAGE)),
+            command if options.command.is_empty() => options.command = command.to_string(),
+            other => return Err(format!("Unexpected argument \"{}\"\n\n{}", other, USAGE)),
+        }
+    }
+
+    if options.command.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    if options.satellites.is_empty() {
+        return Err("--sat is required".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    if value == "now" {
+        return Ok(Utc::now());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| format!("Invalid time \"{}\": {}", value, e))
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number for {}: \"{}\"", name, value))
+}
+
+fn load_satellites(settings: &Settings, filter: &str) -> Result<Vec<Satellite>, String> {
+    let expr = FilterExpr::parse(filter)?;
+    let loader = satellite::tle_loader(settings);
+    let data = loader.load_active_satellites().map_err(|e| e.to_string())?;
+
+    // SATCAT terms in the filter need the catalog; without it they just don't match
+    let satcat = if settings.satcat.enabled {
+        let loader = SatcatLoader::new()
+            .with_source(settings.satcat.source.clone())
+            .with_cache_max_age_hours(settings.satcat.cache_max_age_hours);
+        loader.load().unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to load SATCAT data: {}", e);
+            SatcatSet::new()
+        })
+    } else {
+        SatcatSet::new()
+    };
+
+    let mut satellites: Vec<Satellite> = data
+        .into_values()
+        .filter_map(|tle| {
+            let elements = tle.to_elements().ok()?;
+            let mut satellite = Satellite::new(tle.name, elements);
+            satellite.satcat = satcat.get(&satellite.elements.norad_id).cloned();
+            Some(satellite)
+        })
+        .filter(|satellite| expr.matches(satellite))
+        .collect();
+    // Names can repeat, so the catalog number breaks ties
+    satellites.sort_by(|a, b| a.name.cmp(&b.name).then(a.elements.norad_id.cmp(&b.elements.norad_id)));
+    Ok(satellites)
+}
+
+/// Start and end of the requested range, and the sampling step
+fn time_range(options: &Options, default_minutes: i64, default_step: i64) -> Result<(DateTime<Utc>, DateTime<Utc>, Duration), String> {
+    let end = options
+        .end
+        .unwrap_or_else(|| options.start + Duration::minutes(options.duration_minutes.unwrap_or(default_minutes)));
+    if end <= options.start {
+        return Err("End time must be after the start time".to_string());
+    }
+    if (end - options.start).num_minutes() > MAX_DURATION_MINUTES {
+        return Err(format!("Time range is limited to {} days", MAX_DURATION_MINUTES / 1440));
+    }
+    let step = options.step_seconds.unwrap_or(default_step);
+    if step <= 0 {
+        return Err("--step must be positive".to_string());
+    }
+    Ok((options.start, end, Duration::seconds(step)))
+}
+
+fn compute_positions(satellites: &[Satellite], options: &Options) -> Result<Vec<PositionRow>, String> {
+    let (start, end, step) = time_range(options, 90, 60)?;
+
+    let mut rows = Vec::new();
+    for satellite in satellites {
+        let mut time = start;
+        while time <= end {
+            if let Some(position) = satellite.propagate(time) {
+                let geodetic = coords::teme_to_geodetic(position, time);
+                rows.push(PositionRow {
+                    time: time.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    name: satellite.name.clone(),
+                    norad_id: satellite.elements.norad_id,
+                    latitude_deg: geodetic.latitude_deg,
+                    longitude_deg: geodetic.longitude_deg,
+                    altitude_km: geodetic.altitude_km,
+                    x_km: position.x,
+                    y_km: position.y,
+                    z_km: position.z,
+                });
+            }
+            time += step;
+        }
+    }
+    Ok(rows)
+}
+
+fn compute_tracks(satellites: &[Satellite], options: &Options) -> Result<Vec<GroundTrack>, String> {
+    let (start, end, step) = time_range(options, 90, 30)?;
+
+    Ok(satellites
+        .iter()
+        .map(|satellite| {
+            GroundTrack::compute(
+                &satellite.name,
+                satellite.elements.norad_id,
+                satellite.propagator(),
+                start,
+                end,
+                step,
+            )
+        })
+        .collect())
+}
+
+fn compute_ephemerides(satellites: &[Satellite], options: &Options) -> Result<Vec<Ephemeris>, String> {
+    let (start, end, step) = time_range(options, 1440, 60)?;
+
+    Ok(satellites
+        .iter()
+        .map(|satellite| {
+            Ephemeris::compute(
+                &satellite.name,
+                &satellite.elements,
+                satellite.propagator(),
+                options.frame,
+                start,
+                end,
+                step,
+            )
+        })
+        .collect())
+}
+
+/// Resolve `--station` as coordinates or as a name from the ground station file
+fn resolve_station(options: &Options) -> Result<GroundStation, String> {
+    let value = options.station.as_deref().ok_or("--station is required for passes")?;
+
+    let mut station = ground_station::resolve_station(value)?;
+
+    if let Some(min_elevation) = options.min_elevation_deg {
+        station.min_elevation_deg = min_elevation;
+    }
+    Ok(station)
+}
+
+fn compute_passes(satellites: &[Satellite], options: &Options) -> Result<Vec<PassEvent>, String> {
+    let (start, end, step) = time_range(options, 1440, passes::PASS_SEARCH_STEP_SECONDS)?;
+    let station = resolve_station(options)?;
+
+    let mut events = Vec::new();
+    for satellite in satellites {
+        let passes = passes::predict_passes(
+            satellite.propagator(),
+            station.geodetic(),
+            &station.horizon(),
+            start,
+            end,
+            step,
+        );
+        events.extend(passes.into_iter().map(|pass| PassEvent {
+            satellite: satellite.name.clone(),
+            norad_id: satellite.elements.norad_id,
+            station: station.name.clone(),
+            pass,
+        }));
+    }
+
+    events.sort_by_key(|event| event.pass.rise_time);
+    Ok(events)
+}
+
+impl From<&PassEvent> for PassRow {
+    fn from(event: &PassEvent) -> Self {
+        let pass = &event.pass;
+        PassRow {
+            name: event.satellite.clone(),
+            norad_id: event.norad_id,
+            station: event.station.clone(),
+            rise_time: pass.rise_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            rise_azimuth_deg: pass.rise_azimuth_deg,
+            max_elevation_time: pass.max_elevation_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            max_elevation_deg: pass.max_elevation_deg,
+            set_time: pass.set_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            set_azimuth_deg: pass.set_azimuth_deg,
+        }
+    }
+}
+
+/// Rows that can be written as CSV
+trait CsvRow {
+    const HEADER: &'static str;
+    fn to_csv(&self) -> String;
+}
+
+impl CsvRow for PositionRow {
+    const HEADER: &'static str = "time,name,norad_id,latitude_deg,longitude_deg,altitude_km,x_km,y_km,z_km";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{:.4},{:.4},{:.3},{:.3},{:.3},{:.3}",
+            self.time,
+            csv_field(&self.name),
+            self.norad_id,
+            self.latitude_deg,
+            self.longitude_deg,
+            self.altitude_km,
+            self.x_km,
+            self.y_km,
+            self.z_km,
+        )
+    }
+}
+
+impl CsvRow for PassRow {
+    const HEADER: &'static str = "name,norad_id,station,rise_time,rise_azimuth_deg,max_elevation_time,max_elevation_deg,set_time,set_azimuth_deg";
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{:.1},{},{:.1},{},{:.1}",
+            csv_field(&self.name),
+            self.norad_id,
+            csv_field(&self.station),
+            self.rise_time,
+            self.rise_azimuth_deg,
+            self.max_elevation_time,
+            self.max_elevation_deg,
+            self.set_time,
+            self.set_azimuth_deg,
+        )
+    }
+}
+
+/// Quote a CSV field if it contains a separator or quote
+fn csv_field(text: &str) -> String {
+    if text.contains(',') || text.contains('"') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Render rows as CSV with a header line, or as a JSON array
+fn render<T: Serialize + CsvRow>(rows: &[T], format: Format) -> Result<String, String> {
+    match format {
+        Format::Json => serde_json::to_string_pretty(rows)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string()),
+        Format::GeoJson | Format::Kml => Err("geojson and kml output is only available for track".to_string()),
+        Format::Oem => Err("oem output is only available for ephemeris".to_string()),
+        Format::Ics => Err("ics output is only available for passes".to_string()),
+        Format::Csv => {
+            let mut csv = String::from(T::HEADER);
+            csv.push('\n');
+            for row in rows {
+                csv.push_str(&row.to_csv());
+                csv.push('\n');
+            }
+            Ok(csv)
+        }
+    }
+}
@@ -0,0 +1,871 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::camera::CameraFrame;
+use crate::export::EphemerisFrame;
+
+/// Default settings file, read from the working directory
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// Application settings loaded from `settings.toml`
+///
+/// Every field has a default, so the file (and any section or key in it) is optional.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window: WindowSettings,
+    pub satellites: SatelliteSettings,
+    pub tle: TleSettings,
+    pub n2yo: N2yoSettings,
+    pub space_track: SpaceTrackSettings,
+    pub camera: CameraSettings,
+    pub labels: LabelSettings,
+    pub time: TimeSettings,
+    pub textures: TextureSettings,
+    pub earth: EarthSettings,
+    pub conjunctions: ConjunctionSettings,
+    pub alerts: AlertSettings,
+    pub export: ExportSettings,
+    pub capture: CaptureSettings,
+    pub footprint: FootprintSettings,
+    pub sensor: SensorSettings,
+    pub coverage: CoverageSettings,
+    pub revisit: RevisitSettings,
+    pub trail: TrailSettings,
+    pub models: ModelSettings,
+    pub iss: IssSettings,
+    pub transmitters: TransmitterSettings,
+    pub satcat: SatcatSettings,
+    pub session: SessionSettings,
+    pub borders: BorderSettings,
+    pub region: RegionSettings,
+    pub launch_sites: LaunchSiteSettings,
+    pub stream: StreamSettings,
+    pub rest: RestSettings,
+    pub rotator: RotatorSettings,
+    /// Shortcut overrides: action name -> keys (see `keymap`)
+    pub keys: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SatelliteSettings {
+    /// Maximum number of satellites spawned
+    pub max_count: usize,
+    /// How often positions are propagated per real second (0 = every frame);
+    /// frames in between are interpolated
+    pub update_rate_hz: f64,
+    /// Slow movers (MEO, GEO) are propagated only every few updates, up to this many (1 = never skip)
+    pub max_update_stride: u32,
+    /// Skip updating satellites well outside the view of the orbit camera
+    pub culling: bool,
+    /// When zoomed in closer than this to Earth's center (km), satellites farther than this
+    /// from the camera are hidden and not updated (0 = off)
+    pub cull_distance_km: f64,
+    /// How satellites are drawn
+    pub marker: MarkerStyle,
+    /// How big satellites are drawn
+    pub size: MarkerSize,
+    /// Diameter of a payload marker on screen with `size = "fixed_pixels"` (pixels)
+    pub pixel_size: f32,
+}
+
+/// Shape of the satellite markers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerStyle {
+    /// Shaded spheres
+    #[default]
+    Sphere,
+    /// Flat icons turned toward the camera, shaped by object type: a disc for payloads, a hexagon
+    /// for space stations, a triangle for rocket bodies and a small diamond for debris
+    Sprite,
+}
+
+/// Size of the satellite markers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerSize {
+    /// 50 km markers, visible from afar but overlapping when zoomed in
+    #[default]
+    Exaggerated,
+    /// About the size of an actual satellite: invisible unless the camera is within a few km
+    TrueScale,
+    /// The same number of pixels on screen at any distance
+    FixedPixels,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TleSettings {
+    /// Cache lifetime before TLE data is downloaded again
+    pub cache_max_age_hours: u64,
+    /// Celestrak groups to download (see celestrak.org/NORAD/elements)
+    pub groups: Vec<String>,
+    /// Celestrak groups downloaded by the "debris" source: debris clouds, and recent
+    /// launches for their rocket bodies
+    pub debris_groups: Vec<String>,
+    /// Download fresh data in the background when the cache expires while running
+    pub auto_refresh: bool,
+    /// Where current data comes from; with several sources, the newest elements of each satellite win
+    pub sources: Vec<TleProvider>,
+    /// Local TLE or OMM JSON files read by the "file" source
+    pub files: Vec<String>,
+}
+
+/// Provider of the TLE data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TleProvider {
+    /// Celestrak groups, see `groups`
+    Celestrak,
+    /// Celestrak debris and rocket body groups, see `debris_groups`
+    Debris,
+    /// A watch list from the N2YO API, see `[n2yo]` (builds with the "n2yo" feature)
+    N2yo,
+    /// The full catalog from Space-Track, see `[space_track]`
+    SpaceTrack,
+    /// The files in `files`
+    File,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpaceTrackSettings {
+    /// Account on space-track.org
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct N2yoSettings {
+    /// Personal API key from n2yo.com
+    pub api_key: String,
+    /// NORAD catalog numbers to download
+    pub satellites: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
+    /// Initial point the camera looks down on
+    pub target_latitude_deg: f32,
+    pub target_longitude_deg: f32,
+    /// Initial distance from Earth's center (km)
+    pub distance_km: f32,
+    /// How quickly the view catches up with drags, zoom and pan (1/s): higher is snappier,
+    /// 0 follows the mouse directly
+    pub smoothing: f32,
+    /// How quickly the spin left after a drag dies out (1/s): lower coasts longer,
+    /// 0 stops the camera when the button is released
+    pub damping: f32,
+    /// "earth_fixed" (the ground stays put) or "inertial" (the Earth turns under the camera)
+    pub frame: CameraFrame,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LabelSettings {
+    /// Where a satellite's label starts, in pixels right of and below the satellite
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// Draw a line from each label to its satellite
+    pub leader_lines: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSettings {
+    /// Initial time acceleration (1 = real time)
+    pub acceleration: f64,
+    /// Instant the simulation starts at, in any form the time field accepts
+    /// ("2024-05-01T12:00:00Z", "2024-05-01", "-6h"); None starts now
+    pub start: Option<String>,
+    /// Start with the clock paused
+    pub paused: bool,
+    /// Show the time bar along the bottom of the window
+    pub time_bar: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextureSettings {
+    /// Day texture, relative to the assets directory
+    pub day: String,
+    /// Night (city lights) texture, relative to the assets directory
+    pub night: String,
+    /// Optional cloud layer (grayscale, equirectangular), drawn drifting over the day side
+    pub clouds: Option<String>,
+    /// Day and night texture resolution; anything but "bundled" replaces `day` and `night`
+    /// with NASA Blue Marble / Black Marble textures in assets/textures/<quality>/
+    pub quality: TextureQuality,
+    /// Download the textures for `quality` in the background when they are missing
+    pub download: bool,
+    /// Brightest the city lights may get (0-1), to keep saturated night textures from glaring
+    pub city_lights_max: f32,
+}
+
+/// Resolution of the Earth day and night textures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextureQuality {
+    /// The `day` and `night` textures as configured
+    #[default]
+    #[serde(rename = "bundled")]
+    Bundled,
+    #[serde(rename = "2k")]
+    K2,
+    #[serde(rename = "4k")]
+    K4,
+    #[serde(rename = "8k")]
+    K8,
+    #[serde(rename = "16k")]
+    K16,
+}
+
+impl TextureQuality {
+    /// Name as written in the settings file
+    pub fn name(self) -> &'static str {
+        match self {
+            TextureQuality::Bundled => "bundled",
+            TextureQuality::K2 => "2k",
+            TextureQuality::K4 => "4k",
+            TextureQuality::K8 => "8k",
+            TextureQuality::K16 => "16k",
+        }
+    }
+
+    /// Texture width in pixels (the height is half of it), None for the bundled textures
+    pub fn width(self) -> Option<u32> {
+        match self {
+            TextureQuality::Bundled => None,
+            TextureQuality::K2 => Some(2048),
+            TextureQuality::K4 => Some(4096),
+            TextureQuality::K8 => Some(8192),
+            TextureQuality::K16 => Some(16384),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EarthSettings {
+    /// Draw the atmosphere glow around the globe (toggle with A)
+    pub atmosphere: bool,
+    /// Render the globe as the flattened WGS84 ellipsoid instead of a sphere, placing
+    /// ground points and testing occlusion against it
+    pub ellipsoid: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConjunctionSettings {
+    /// Pairs of satellites closer than this are flagged (km)
+    pub threshold_km: f64,
+    /// How often the screening runs (real seconds)
+    pub interval_seconds: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertSettings {
+    /// Also raise a desktop notification (notify-send on Linux, osascript on macOS)
+    pub desktop_notifications: bool,
+    /// Warn this long before a watched pass starts (simulated minutes)
+    pub lead_minutes: f64,
+    /// Watch list
+    pub rules: Vec<AlertRule>,
+}
+
+/// "Notify when `satellite` is above `min_elevation_deg` at `station` within `within_hours`"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertRule {
+    /// Satellite name (case-insensitive) or NORAD catalog number
+    pub satellite: String,
+    /// Name of a station in ground_stations.json, or "lat,lon[,alt_km]"
+    pub station: String,
+    pub min_elevation_deg: f64,
+    /// How far ahead passes are predicted (hours)
+    pub within_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportSettings {
+    /// Directory files exported from the UI are written to
+    pub directory: String,
+    /// Length of an exported ground track, starting at the simulation time (minutes)
+    pub track_duration_minutes: i64,
+    /// Sampling step of an exported ground track (seconds)
+    pub track_step_seconds: i64,
+    /// Length of an exported ephemeris, starting at the simulation time (minutes)
+    pub ephemeris_duration_minutes: i64,
+    /// Step between exported ephemeris states (seconds)
+    pub ephemeris_step_seconds: i64,
+    /// Frame of exported ephemeris states ("teme" or "ecef")
+    pub ephemeris_frame: EphemerisFrame,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureSettings {
+    /// Directory screenshots and recorded frame sequences are written to
+    pub directory: String,
+    /// While recording, save one frame out of this many (1 = every frame)
+    pub record_every_n_frames: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FootprintSettings {
+    /// Elevation above the horizon a ground point needs to count as covered (degrees)
+    pub min_elevation_deg: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorSettings {
+    /// Half-angle of the nadir-pointing sensor cone: boresight to edge of the field of view (degrees)
+    pub half_angle_deg: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoverageSettings {
+    /// Length of the coverage analysis window, from the simulation time (hours)
+    pub hours: f64,
+    /// Simulated time between two coverage samples (seconds)
+    pub step_seconds: f64,
+    /// Size of the latitude/longitude cells of the coverage map (degrees)
+    pub cell_deg: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RevisitSettings {
+    /// Horizon of the revisit analysis of a clicked point, from the simulation time (hours)
+    pub hours: f64,
+    /// Simulated time between two visibility samples (seconds)
+    pub step_seconds: f64,
+    /// Elevation above the target's horizon a satellite needs to count as in view (degrees)
+    pub min_elevation_deg: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrailSettings {
+    /// How far back the selected satellite's trail reaches (simulated minutes)
+    pub minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelSettings {
+    /// Show 3D models of the satellites close to the camera
+    pub enabled: bool,
+    /// Camera distance to a satellite under which its model replaces its marker (km)
+    pub close_distance_km: f64,
+    /// Most models shown at once, nearest first (the selected satellite's always counts)
+    pub max_count: usize,
+    /// glTF models of payloads, rocket bodies and debris without a model of their own,
+    /// relative to the assets folder ("" keeps the marker)
+    pub payload: String,
+    pub rocket_body: String,
+    pub debris: String,
+    /// Models of specific objects: NORAD ID -> glTF model
+    pub norad_ids: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IssSettings {
+    /// Keep the ISS label on screen at any zoom
+    pub always_label: bool,
+    /// Compare the propagated ISS position with a live fix from `cross_check_url`
+    pub cross_check: bool,
+    /// Where The ISS At API endpoint of the ISS
+    pub cross_check_url: String,
+    /// Time between two cross-checks (real seconds)
+    pub cross_check_interval_seconds: f32,
+    /// Distance between the two positions above which they are reported as disagreeing (km)
+    pub cross_check_tolerance_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransmitterSettings {
+    /// Load SatNOGS transmitter data and list frequencies in the satellite info panel
+    pub enabled: bool,
+    /// SatNOGS DB API URL, or a JSON snapshot of it on disk
+    pub source: String,
+    /// Cache lifetime before the list is downloaded again
+    pub cache_max_age_hours: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SatcatSettings {
+    /// Load the satellite catalog: owner, launch date, object type and radar size in the
+    /// info panel, and for the `owner:`, `type:`, `rcs:` and `launch` filter terms
+    pub enabled: bool,
+    /// CSV export URL (CelesTrak or Space-Track layout), or the path of a CSV file
+    pub source: String,
+    /// Cache lifetime before the catalog is downloaded again
+    pub cache_max_age_hours: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSettings {
+    /// Save the camera, filter and selection on exit and restore them at startup
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BorderSettings {
+    /// Draw coastlines and country borders over the globe (toggle with D)
+    pub enabled: bool,
+    /// Natural Earth coastline GeoJSON, relative to the assets directory (empty: none)
+    pub coastlines: String,
+    /// Natural Earth country border GeoJSON, relative to the assets directory (empty: none)
+    pub borders: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegionSettings {
+    /// Country watched at startup, by name or ISO code (empty: use the box below)
+    pub country: String,
+    /// Natural Earth admin 0 countries GeoJSON, relative to the assets directory, for
+    /// countries by name and picked on the globe
+    pub countries: String,
+    /// Latitude/longitude box watched at startup (degrees); an empty box watches nothing
+    pub south_deg: f64,
+    pub north_deg: f64,
+    pub west_deg: f64,
+    pub east_deg: f64,
+    /// Only list satellites at or above this altitude (km, 0: no limit)
+    pub min_altitude_km: f64,
+    /// Only list satellites at or below this altitude (km, 0: no limit)
+    pub max_altitude_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchSiteSettings {
+    /// Mark the built-in launch sites on the globe
+    pub markers: bool,
+    /// Altitude of the circular parking orbit drawn by the launch planner (km)
+    pub parking_orbit_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamSettings {
+    /// Stream the propagated positions to external clients
+    pub enabled: bool,
+    pub protocol: StreamProtocol,
+    /// WebSocket: address to listen on. UDP: address the datagrams are sent to
+    pub address: String,
+    /// Position updates per second
+    pub rate_hz: f64,
+    pub frame: StreamFrame,
+}
+
+/// Transport of the position stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamProtocol {
+    /// One JSON message per update with every satellite, to each connected client
+    WebSocket,
+    /// One JSON datagram per satellite and update
+    Udp,
+}
+
+/// Coordinates of the streamed positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamFrame {
+    /// Latitude, longitude (degrees) and altitude (km) over WGS84
+    Geodetic,
+    /// TEME position (km) and velocity (km/s), the inertial frame SGP4 outputs
+    Teme,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestSettings {
+    /// Answer position and pass queries over HTTP (needs the `rest-api` build feature)
+    pub enabled: bool,
+    /// Address to listen on
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RotatorSettings {
+    /// Point an antenna rotator and tune a radio at the selected satellite while it is above
+    /// the station, when the clock runs live
+    pub enabled: bool,
+    /// Ground station: a name from ground_stations.json or "lat,lon[,alt_km]" (empty: the first
+    /// station in ground_stations.json)
+    pub station: String,
+    /// hamlib rotctld address (empty: no rotator)
+    pub rotctld: String,
+    /// hamlib rigctld address (empty: no radio)
+    pub rigctld: String,
+    /// Downlink frequency tuned before the Doppler correction (MHz, 0: the satellite's first
+    /// active SatNOGS downlink)
+    pub downlink_mhz: f64,
+    /// Commands per second
+    pub rate_hz: f64,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+        }
+    }
+}
+
+impl Default for SatelliteSettings {
+    fn default() -> Self {
+        Self {
+            max_count: 10000,
+            update_rate_hz: 10.0,
+            max_update_stride: 16,
+            culling: true,
+            cull_distance_km: 0.0,
+            marker: MarkerStyle::Sphere,
+            size: MarkerSize::Exaggerated,
+            pixel_size: 6.0,
+        }
+    }
+}
+
+impl Default for TleSettings {
+    fn default() -> Self {
+        Self {
+            cache_max_age_hours: 24,
+            groups: vec!["active".to_string()],
+            debris_groups: ["cosmos-1408-debris", "fengyun-1c-debris", "iridium-33-debris", "cosmos-2251-debris", "last-30-days"]
+                .iter()
+                .map(|group| group.to_string())
+                .collect(),
+            auto_refresh: true,
+            sources: vec![TleProvider::Celestrak],
+            files: Vec::new(),
+        }
+    }
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        // Europe
+        Self {
+            target_latitude_deg: 50.0,
+            target_longitude_deg: 10.0,
+            distance_km: 15000.0,
+            smoothing: 10.0,
+            damping: 5.0,
+            frame: CameraFrame::EarthFixed,
+        }
+    }
+}
+
+impl Default for LabelSettings {
+    fn default() -> Self {
+        Self {
+            offset_x: 12.0,
+            offset_y: 12.0,
+            leader_lines: true,
+        }
+    }
+}
+
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            acceleration: 1.0,
+            start: None,
+            paused: false,
+            time_bar: true,
+        }
+    }
+}
+
+impl Default for TextureSettings {
+    fn default() -> Self {
+        Self {
+            day: "earth_texture.jpg".to_string(),
+            night: "earth_night_texture.jpg".to_string(),
+            clouds: None,
+            quality: TextureQuality::Bundled,
+            download: false,
+            city_lights_max: 1.0,
+        }
+    }
+}
+
+impl Default for EarthSettings {
+    fn default() -> Self {
+        Self {
+            atmosphere: true,
+            ellipsoid: false,
+        }
+    }
+}
+
+impl Default for ConjunctionSettings {
+    fn default() -> Self {
+        Self {
+            threshold_km: 10.0,
+            interval_seconds: 5.0,
+        }
+    }
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            directory: "exports".to_string(),
+            track_duration_minutes: 180,
+            track_step_seconds: 30,
+            ephemeris_duration_minutes: 1440,
+            ephemeris_step_seconds: 60,
+            ephemeris_frame: EphemerisFrame::Teme,
+        }
+    }
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            directory: "captures".to_string(),
+            record_every_n_frames: 1,
+        }
+    }
+}
+
+impl Default for FootprintSettings {
+    fn default() -> Self {
+        Self { min_elevation_deg: 0.0 }
+    }
+}
+
+impl Default for SensorSettings {
+    fn default() -> Self {
+        Self { half_angle_deg: 15.0 }
+    }
+}
+
+impl Default for CoverageSettings {
+    fn default() -> Self {
+        Self {
+            hours: 24.0,
+            step_seconds: 60.0,
+            cell_deg: 2.0,
+        }
+    }
+}
+
+impl Default for RevisitSettings {
+    fn default() -> Self {
+        Self {
+            hours: 24.0,
+            step_seconds: 10.0,
+            min_elevation_deg: 10.0,
+        }
+    }
+}
+
+impl Default for TrailSettings {
+    fn default() -> Self {
+        Self { minutes: 20.0 }
+    }
+}
+
+impl Default for ModelSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            close_distance_km: 3000.0,
+            max_count: 16,
+            payload: "models/satellite.gltf".to_string(),
+            rocket_body: "models/rocket_body.gltf".to_string(),
+            debris: String::new(),
+            norad_ids: BTreeMap::from([
+                ("25544".to_string(), "models/iss.gltf".to_string()),
+                ("20580".to_string(), "models/hubble.gltf".to_string()),
+                ("48274".to_string(), "models/tiangong.gltf".to_string()),
+            ]),
+        }
+    }
+}
+
+impl Default for IssSettings {
+    fn default() -> Self {
+        Self {
+            always_label: true,
+            cross_check: false,
+            cross_check_url: crate::iss::WHERE_THE_ISS_AT_URL.to_string(),
+            cross_check_interval_seconds: 60.0,
+            cross_check_tolerance_km: 20.0,
+        }
+    }
+}
+
+impl Default for TransmitterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: crate::transmitters::SATNOGS_TRANSMITTERS_URL.to_string(),
+            cache_max_age_hours: 24 * 7,
+        }
+    }
+}
+
+impl Default for SatcatSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: crate::satcat::CELESTRAK_SATCAT_URL.to_string(),
+            cache_max_age_hours: 24 * 7,
+        }
+    }
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl Default for BorderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            coastlines: "geo/ne_110m_coastline.geojson".to_string(),
+            borders: "geo/ne_110m_admin_0_boundary_lines_land.geojson".to_string(),
+        }
+    }
+}
+
+impl Default for RegionSettings {
+    fn default() -> Self {
+        Self {
+            country: String::new(),
+            countries: "geo/ne_110m_admin_0_countries.geojson".to_string(),
+            south_deg: 0.0,
+            north_deg: 0.0,
+            west_deg: 0.0,
+            east_deg: 0.0,
+            min_altitude_km: 0.0,
+            max_altitude_km: 0.0,
+        }
+    }
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            desktop_notifications: false,
+            lead_minutes: 10.0,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            satellite: String::new(),
+            station: String::new(),
+            min_elevation_deg: 30.0,
+            within_hours: 12.0,
+        }
+    }
+}
+
+impl Default for LaunchSiteSettings {
+    fn default() -> Self {
+        Self {
+            markers: true,
+            parking_orbit_km: 300.0,
+        }
+    }
+}
+
+impl Default for StreamSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            protocol: StreamProtocol::WebSocket,
+            address: "127.0.0.1:8765".to_string(),
+            rate_hz: 1.0,
+            frame: StreamFrame::Geodetic,
+        }
+    }
+}
+
+impl Default for RotatorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            station: String::new(),
+            rotctld: "127.0.0.1:4533".to_string(),
+            rigctld: "127.0.0.1:4532".to_string(),
+            downlink_mhz: 0.0,
+            rate_hz: 1.0,
+        }
+    }
+}
+
+impl Default for RestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:8080".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from a TOML file, falling back to defaults if it is missing or invalid
+    pub fn load(path: &str) -> Self {
+        if !Path::new(path).exists() {
+            println!("No {} found, using default settings", path);
+            return Self::default();
+        }
+
+        match Self::load_from_file(path) {
+            Ok(settings) => {
+                println!("✓ Loaded settings from {}", path);
+                settings
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to load {}: {}. Using default settings", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
@@ -0,0 +1,377 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use chrono::{DateTime, Utc};
+use nalgebra::Vector3;
+use std::collections::HashMap;
+
+use crate::config::{ConjunctionSettings, Settings};
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{update_satellite_positions, PropagationSchedule, Satellite};
+use crate::text_input::InputFocus;
+
+/// Periodic close-approach screening, with a panel listing the closest pairs
+pub struct ConjunctionPlugin;
+
+impl Plugin for ConjunctionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let screening = ConjunctionScreening::new(&app.world().resource::<Settings>().conjunctions);
+
+        app.insert_resource(screening)
+            .add_systems(Startup, (setup_conjunction_panel, spawn_conjunction_line))
+            .add_systems(Update, (
+                screen_conjunctions.after(update_satellite_positions),
+                update_conjunction_panel,
+                select_conjunction_row,
+                toggle_conjunction_panel,
+                update_conjunction_line.after(update_satellite_positions),
+            ));
+    }
+}
+
+/// Rows shown in the close-approach panel
+const MAX_LISTED_APPROACHES: usize = 8;
+
+/// Two satellites found closer than the screening threshold
+#[derive(Debug, Clone, Copy)]
+pub struct CloseApproach {
+    pub first: Entity,
+    pub second: Entity,
+    pub distance_km: f64,
+    /// Propagation time of the positions that were compared
+    pub time: DateTime<Utc>,
+}
+
+/// Periodic pairwise distance screening of the propagated satellites
+#[derive(Resource)]
+pub struct ConjunctionScreening {
+    pub threshold_km: f64,
+    /// Close approaches from the last completed run, closest first
+    pub approaches: Vec<CloseApproach>,
+    /// Pair drawn with a connecting line (the closest one unless a row was clicked)
+    pub highlighted: Option<(Entity, Entity)>,
+    timer: Timer,
+    task: Option<Task<Vec<CloseApproach>>>,
+}
+
+impl ConjunctionScreening {
+    pub fn new(settings: &ConjunctionSettings) -> Self {
+        Self {
+            threshold_km: settings.threshold_km,
+            approaches: Vec::new(),
+            highlighted: None,
+            timer: Timer::from_seconds(settings.interval_seconds.max(0.1), TimerMode::Repeating),
+            task: None,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ConjunctionPanel;
+
+#[derive(Component)]
+pub struct ConjunctionHeader;
+
+/// Clickable row of the close-approach panel, showing `approaches[index]`
+#[derive(Component)]
+pub struct ConjunctionRow(pub usize);
+
+/// Line joining the highlighted pair
+#[derive(Component)]
+pub struct ConjunctionLine;
+
+/// Find all pairs of points closer than `threshold_km`, closest first
+///
+/// Points are bucketed into a uniform grid of `threshold_km` cells, so each point is only
+/// compared with the points of its own and the 26 neighboring cells.
+pub fn find_close_pairs(points: &[Vector3<f64>], threshold_km: f64) -> Vec<(usize, usize, f64)> {
+    if threshold_km <= 0.0 {
+        return Vec::new();
+    }
+
+    let cell_of = |p: &Vector3<f64>| {
+        (
+            (p.x / threshold_km).floor() as i64,
+            (p.y / threshold_km).floor() as i64,
+            (p.z / threshold_km).floor() as i64,
+        )
+    };
+    let mut grid: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+    for (index, point) in points.iter().enumerate() {
+        grid.entry(cell_of(point)).or_default().push(index);
+    }
+
+    let mut pairs = Vec::new();
+    for (i, point) in points.iter().enumerate() {
+        let (cx, cy, cz) = cell_of(point);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let cell = match grid.get(&(cx + dx, cy + dy, cz + dz)) {
+                        Some(cell) => cell,
+                        None => continue,
+                    };
+                    // Each pair is seen from both ends: keep it once
+                    for &j in cell.iter().filter(|&&j| j > i) {
+                        let distance = (points[j] - point).norm();
+                        if distance < threshold_km {
+                            pairs.push((i, j, distance));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pairs.sort_by(|a, b| a.2.total_cmp(&b.2));
+    pairs
+}
+
+/// Run the screening on a background thread every interval and collect its results
+pub fn screen_conjunctions(
+    time: Res<Time>,
+    mut screening: ResMut<ConjunctionScreening>,
+    schedule: Res<PropagationSchedule>,
+    satellite_query: Query<(Entity, &Satellite)>,
+) {
+    if let Some(task) = screening.task.as_mut() {
+        if let Some(approaches) = block_on(future::poll_once(task)) {
+            screening.task = None;
+            if !approaches.is_empty() {
+                println!(
+                    "Conjunction screening: {} pairs closer than {} km (closest {:.2} km)",
+                    approaches.len(),
+                    screening.threshold_km,
+                    approaches[0].distance_km,
+                );
+            }
+            // Keep a clicked pair highlighted while it is still flagged
+            let highlighted = screening.highlighted.filter(|&(first, second)| {
+                approaches.iter().any(|a| a.first == first && a.second == second)
+            });
+            screening.highlighted = highlighted.or_else(|| approaches.first().map(|a| (a.first, a.second)));
+            screening.approaches = approaches;
+        }
+        return;
+    }
+
+    if !screening.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    // Snapshot positions at the keyframe time; satellites that skip keyframes are propagated to it
+    let time = match schedule.keyframe_time() {
+        Some(time) => time,
+        None => return,
+    };
+    let mut entities = Vec::new();
+    let mut positions = Vec::new();
+    for (entity, satellite) in satellite_query.iter() {
+        if let Some(position) = satellite.position_at(time) {
+            entities.push(entity);
+            positions.push(position);
+        }
+    }
+
+    let threshold_km = screening.threshold_km;
+    screening.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        find_close_pairs(&positions, threshold_km)
+            .into_iter()
+            .map(|(i, j, distance_km)| CloseApproach {
+                first: entities[i],
+                second: entities[j],
+                distance_km,
+                time,
+            })
+            .collect()
+    }));
+}
+
+/// Close-approach panel (left, below the filter field), toggled with F4
+pub fn setup_conjunction_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(70.0),
+                width: Val::Px(400.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            ConjunctionPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                ConjunctionHeader,
+            ));
+            for index in 0..MAX_LISTED_APPROACHES {
+                parent.spawn((
+                    Node {
+                        padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)),
+                        display: Display::None,
+                        ..default()
+                    },
+                    Text::new(""),
+                    TextFont {
+                        font_size: 13.0,
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    Interaction::default(),
+                    ConjunctionRow(index),
+                ));
+            }
+        });
+}
+
+/// Refresh the close-approach list
+pub fn update_conjunction_panel(
+    screening: Res<ConjunctionScreening>,
+    keymap: Res<Keymap>,
+    satellite_query: Query<&Satellite>,
+    mut header_query: Query<&mut Text, (With<ConjunctionHeader>, Without<ConjunctionRow>)>,
+    mut row_query: Query<(&ConjunctionRow, &Interaction, &mut Text, &mut Node, &mut BackgroundColor)>,
+) {
+    let hide_key = keymap.label(Action::ToggleConjunctions);
+    for mut text in header_query.iter_mut() {
+        *text = Text::new(match screening.approaches.len() {
+            0 => format!("No close approaches (< {} km)  [{}] hide", screening.threshold_km, hide_key),
+            count => format!("Close approaches (< {} km): {}  [{}] hide", screening.threshold_km, count, hide_key),
+        });
+    }
+
+    let name = |entity: Entity| {
+        satellite_query
+            .get(entity)
+            .map_or("?".to_string(), |satellite| satellite.name.clone())
+    };
+
+    for (row, interaction, mut text, mut node, mut background) in row_query.iter_mut() {
+        let approach = match screening.approaches.get(row.0) {
+            Some(approach) => approach,
+            None => {
+                node.display = Display::None;
+                continue;
+            }
+        };
+        node.display = Display::Flex;
+        *text = Text::new(format!(
+            "{:.2} km  {} / {}  ({})",
+            approach.distance_km,
+            name(approach.first),
+            name(approach.second),
+            approach.time.format("%H:%M:%S"),
+        ));
+
+        let highlighted = screening.highlighted == Some((approach.first, approach.second));
+        background.0 = if highlighted {
+            Color::srgba(1.0, 0.3, 0.3, 0.35)
+        } else if *interaction == Interaction::Hovered {
+            Color::srgba(1.0, 1.0, 1.0, 0.1)
+        } else {
+            Color::NONE
+        };
+    }
+}
+
+/// Highlight the pair of a clicked row
+pub fn select_conjunction_row(
+    mut screening: ResMut<ConjunctionScreening>,
+    row_query: Query<(&ConjunctionRow, &Interaction), Changed<Interaction>>,
+) {
+    for (row, interaction) in row_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(approach) = screening.approaches.get(row.0).copied() {
+            screening.highlighted = Some((approach.first, approach.second));
+        }
+    }
+}
+
+/// Toggle the close-approach panel (and its line) with F4
+#[allow(clippy::type_complexity)]
+pub fn toggle_conjunction_panel(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut query: Query<&mut Visibility, Or<(With<ConjunctionPanel>, With<ConjunctionLine>)>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleConjunctions, &keyboard_input) {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn line_mesh(from: Vec3, to: Vec3) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![from.to_array(), to.to_array()]);
+    mesh
+}
+
+pub fn spawn_conjunction_line(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 0.2, 0.2),
+        unlit: true,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(meshes.add(line_mesh(Vec3::ZERO, Vec3::ZERO))),
+        MeshMaterial3d(material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::default(),
+        ConjunctionLine,
+        Name::new("ConjunctionLine"),
+    ));
+}
+
+/// Keep the connecting line on the highlighted pair's current (interpolated) positions
+#[allow(clippy::type_complexity)]
+pub fn update_conjunction_line(
+    screening: Res<ConjunctionScreening>,
+    satellite_query: Query<(&ScenePosition, &Visibility), With<Satellite>>,
+    mut line_query: Query<(&Mesh3d, &mut ScenePosition), (With<ConjunctionLine>, Without<Satellite>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let endpoints = screening.highlighted.and_then(|(first, second)| {
+        let (first, first_visibility) = satellite_query.get(first).ok()?;
+        let (second, second_visibility) = satellite_query.get(second).ok()?;
+        // Don't point at satellites the filter hides
+        if *first_visibility == Visibility::Hidden || *second_visibility == Visibility::Hidden {
+            return None;
+        }
+        Some((first.0, second.0))
+    });
+    // A degenerate line draws nothing
+    let (from, to) = endpoints.unwrap_or_default();
+
+    // The line starts at its entity, placed on the first satellite, so it stays precise up close
+    for (mesh_3d, mut position) in line_query.iter_mut() {
+        position.set_if_neq(ScenePosition(from));
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0; 3], (to - from).as_vec3().to_array()]);
+        }
+    }
+}
@@ -1,57 +1,116 @@
+use bevy::math::DVec3;
 use bevy::prelude::*;
+
+use crate::coords;
+use crate::floating_origin::FloatingOrigin;
+use crate::keymap::{Action, Keymap};
+use crate::map_view::MapView;
+use crate::satellite::Satellite;
+use crate::selection::Selected;
+use crate::sun::calculate_sun_direction;
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
 use nalgebra::Vector3;
 
-/// Convert TEME coordinates to Bevy coordinates with detailed debugging
-/// TEME: X (vernal equinox), Y (completes right-hand), Z (north pole)
-/// Bevy: X (right), Y (up), Z (forward)
-pub fn teme_to_bevy(pos: Vector3<f64>, name: &str, debug: bool) -> Vec3 {
-    if debug {
-        let distance = pos.magnitude();
-        let z_ratio = pos.z / distance;
-        
-        println!("[COORD] {} - TEME: X={:.2}, Y={:.2}, Z={:.2} km", 
-            name, pos.x, pos.y, pos.z);
-        println!("[COORD] {} - Distance: {:.2} km, Z-ratio: {:.4} (1.0=north, -1.0=south, 0.0=equator)", 
-            name, distance, z_ratio);
+/// Gizmo overlay for checking the frames: ECEF and TEME axes, the vernal equinox,
+/// the sun direction and each satellite's local orbit frame
+pub struct CoordinateDebugPlugin;
+
+impl Plugin for CoordinateDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoordinateDebug>()
+            .init_resource::<MapView>()
+            .add_systems(Update, (toggle_coordinate_debug, draw_coordinate_debug).chain());
     }
-    
-    // Current conversion: TEME Z (north) -> Bevy Y (up)
-    // This should work, but let's verify the conversion is correct
-    let bevy_pos = Vec3::new(pos.x as f32, pos.z as f32, -pos.y as f32);
-    
-    if debug {
-        println!("[COORD] {} - Bevy: X={:.2}, Y={:.2}, Z={:.2} km", 
-            name, bevy_pos.x, bevy_pos.y, bevy_pos.z);
-        println!("[COORD] {} - Bevy Y (up): {:.2} km (should vary for inclined orbits)", 
-            name, bevy_pos.y);
+}
+
+/// Length of the frame axes drawn through Earth (scene units)
+const AXIS_LENGTH: f32 = coords::km_to_scene(1.6 * coords::EARTH_RADIUS_KM as f32);
+/// Length of the sun direction arrow (scene units)
+const SUN_ARROW_LENGTH: f32 = coords::km_to_scene(2.2 * coords::EARTH_RADIUS_KM as f32);
+/// Length of the axes of a satellite frame (scene units)
+const SATELLITE_AXIS_LENGTH: f32 = coords::km_to_scene(600.0);
+/// Satellite frames drawn at most, besides the selected one
+const MAX_SATELLITE_FRAMES: usize = 200;
+
+const ECEF_COLORS: [Color; 3] = [Color::srgb(1.0, 0.2, 0.2), Color::srgb(0.2, 1.0, 0.2), Color::srgb(0.3, 0.4, 1.0)];
+const TEME_COLORS: [Color; 3] = [Color::srgb(1.0, 0.6, 0.6), Color::srgb(0.6, 1.0, 0.6), Color::srgb(0.6, 0.7, 1.0)];
+const EQUINOX_COLOR: Color = Color::srgb(1.0, 0.3, 1.0);
+const SUN_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+
+/// Whether the overlay is drawn (toggle with F2)
+#[derive(Resource, Default)]
+pub struct CoordinateDebug {
+    pub enabled: bool,
+}
+
+/// Toggle the overlay with F2
+pub fn toggle_coordinate_debug(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut debug: ResMut<CoordinateDebug>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleDebugOverlay, &keyboard_input) {
+        return;
     }
-    
-    bevy_pos
+    debug.enabled = !debug.enabled;
+    println!("Coordinate debug overlay {}", if debug.enabled { "on" } else { "off" });
 }
 
-/// Analyze coordinate ranges in a trajectory
-pub fn analyze_trajectory_coords(points: &[Vec3], name: &str) {
-    if points.is_empty() {
+/// Draw the frames at the simulation time
+///
+/// ECEF axes (red X to Greenwich, green Y to 90°E, blue Z north) are fixed to the globe;
+/// the paler TEME axes turn against it with sidereal time, their X toward the vernal
+/// equinox (magenta). Satellites show radial (red), along-track (green) and
+/// cross-track (blue) axes.
+pub fn draw_coordinate_debug(
+    debug: Res<CoordinateDebug>,
+    map_view: Res<MapView>,
+    sim_time: Res<TimeSimulation>,
+    satellite_query: Query<(&Satellite, &Transform, &Visibility, Has<Selected>)>,
+    origin: Res<FloatingOrigin>,
+    mut gizmos: Gizmos,
+) {
+    if !debug.enabled || map_view.enabled {
         return;
     }
-    
-    let y_min = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
-    let y_max = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
-    let x_min = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
-    let x_max = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
-    let z_min = points.iter().map(|p| p.z).fold(f32::INFINITY, f32::min);
-    let z_max = points.iter().map(|p| p.z).fold(f32::NEG_INFINITY, f32::max);
-    
-    println!("[TRAJ] {} - Y range (up/down): {:.2} to {:.2} km (span: {:.2} km)", 
-        name, y_min, y_max, (y_max - y_min));
-    println!("[TRAJ] {} - X range: {:.2} to {:.2} km", name, x_min, x_max);
-    println!("[TRAJ] {} - Z range: {:.2} to {:.2} km", name, z_min, z_max);
-    
-    // Check if Y range is suspiciously small
-    if (y_max - y_min).abs() < 100.0 {
-        println!("[WARNING] {} - Y range is very small! This suggests:", name);
-        println!("  1. All satellites are in equatorial orbits (unlikely), OR");
-        println!("  2. Coordinate conversion is incorrect (TEME Z -> Bevy Y might be wrong)");
+    let now = sim_time.current_time();
+    let unit_axes = [Vector3::x(), Vector3::y(), Vector3::z()];
+    // Gizmos are drawn in the render world, where Earth's center is off the origin
+    let center = origin.to_render(DVec3::ZERO);
+
+    for (axis, color) in unit_axes.iter().zip(ECEF_COLORS) {
+        let direction = coords::ecef_to_scene(*axis).normalize();
+        gizmos.arrow(center - direction * AXIS_LENGTH * 0.5, center + direction * AXIS_LENGTH, color);
+    }
+    for (axis, color) in unit_axes.iter().zip(TEME_COLORS) {
+        let direction = coords::teme_to_scene(*axis, now).normalize();
+        gizmos.line(center, center + direction * AXIS_LENGTH, color);
     }
-}
+    let equinox = coords::teme_to_scene(Vector3::x(), now).normalize();
+    gizmos.arrow(center + equinox * AXIS_LENGTH, center + equinox * AXIS_LENGTH * 1.25, EQUINOX_COLOR);
+    gizmos.arrow(center, center + calculate_sun_direction(now) * SUN_ARROW_LENGTH, SUN_COLOR);
 
+    // The selected satellite first, then visible ones up to the limit
+    let mut frames: Vec<_> = satellite_query
+        .iter()
+        .filter(|(satellite, _, visibility, selected)| {
+            satellite.position.is_some() && (*selected || **visibility != Visibility::Hidden)
+        })
+        .collect();
+    frames.sort_by_key(|(_, _, _, selected)| !selected);
+    for (satellite, transform, _, _) in frames.into_iter().take(MAX_SATELLITE_FRAMES + 1) {
+        let (Some(position), Some(velocity)) = (satellite.position, satellite.velocity) else { continue };
+        let time = satellite.last_update;
+        let scene_position = coords::teme_to_scene(position, time);
+        let radial = scene_position.normalize_or_zero();
+        let along = (coords::teme_to_scene(position + velocity, time) - scene_position).normalize_or_zero();
+        let cross = radial.cross(along).normalize_or_zero();
+
+        let base = transform.translation;
+        for (axis, color) in [radial, along, cross].into_iter().zip(ECEF_COLORS) {
+            gizmos.line(base, base + axis * SATELLITE_AXIS_LENGTH, color);
+        }
+    }
+}
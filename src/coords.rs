@@ -0,0 +1,484 @@
+//! Coordinate frame conversions shared by the propagation and rendering systems
+//!
+//! Frames used in this crate:
+//! - TEME: True Equator Mean Equinox, the inertial frame SGP4 outputs (km)
+//! - ECEF: Earth-Centered Earth-Fixed, rotates with the Earth (km)
+//! - Geodetic: WGS84 latitude/longitude (degrees) and height above the ellipsoid (km)
+//! - Scene: Bevy world space (Y-up), matching the Earth mesh texture mapping,
+//!   `WORLD_SCALE` units per km. ECEF (X, Y, Z) maps to scene (-X, Z, Y) so that
+//!   longitude 0° lands in the middle of the equirectangular texture. Scene
+//!   positions come in f32 (`Vec3`) and, for the simulation state, f64 (`DVec3`).
+//!   Scene conversions are only available with the `visualizer` feature.
+
+#[cfg(feature = "visualizer")]
+use bevy::math::{DVec3, Vec3};
+use chrono::{DateTime, TimeZone, Utc};
+use nalgebra::Vector3;
+
+/// Mean Earth radius (km), used for the rendered globe
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Earth's rotation rate relative to the stars (rad/s)
+pub const EARTH_ROTATION_RATE: f64 = 7.292115146706979e-5;
+
+/// WGS84 semi-major axis (km)
+pub const WGS84_A: f64 = 6378.137;
+/// WGS84 flattening
+pub const WGS84_F: f64 = 1.0 / 298.257223563;
+/// WGS84 first eccentricity squared
+pub const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Latitude/longitude (degrees) and altitude (km) of a point above the Earth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_km: f64,
+}
+
+impl Geodetic {
+    pub fn new(latitude_deg: f64, longitude_deg: f64, altitude_km: f64) -> Self {
+        Self {
+            latitude_deg,
+            longitude_deg,
+            altitude_km,
+        }
+    }
+}
+
+/// Greenwich mean sidereal time (radians) at the given UTC instant
+/// UTC is used as an approximation of UT1 (error below one second)
+pub fn gmst(time: DateTime<Utc>) -> f64 {
+    let j2000 = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+    let seconds = (time - j2000).num_milliseconds() as f64 / 1000.0;
+    let years_since_j2000 = seconds / (365.25 * 86400.0);
+    sgp4::iau_epoch_to_sidereal_time(years_since_j2000)
+}
+
+/// Rotate a TEME position into the Earth-fixed frame using GMST
+pub fn teme_to_ecef(teme: Vector3<f64>, time: DateTime<Utc>) -> Vector3<f64> {
+    let theta = gmst(time);
+    let (sin_t, cos_t) = theta.sin_cos();
+    Vector3::new(
+        cos_t * teme.x + sin_t * teme.y,
+        -sin_t * teme.x + cos_t * teme.y,
+        teme.z,
+    )
+}
+
+/// Velocity relative to the rotating Earth-fixed frame, from a TEME state
+pub fn teme_to_ecef_velocity(teme_position: Vector3<f64>, teme_velocity: Vector3<f64>, time: DateTime<Utc>) -> Vector3<f64> {
+    let position = teme_to_ecef(teme_position, time);
+    let velocity = teme_to_ecef(teme_velocity, time);
+    // Remove the frame rotation: v_ecef = R v_teme - ω × r_ecef
+    Vector3::new(
+        velocity.x + EARTH_ROTATION_RATE * position.y,
+        velocity.y - EARTH_ROTATION_RATE * position.x,
+        velocity.z,
+    )
+}
+
+/// Rotate an Earth-fixed position back into TEME using GMST
+pub fn ecef_to_teme(ecef: Vector3<f64>, time: DateTime<Utc>) -> Vector3<f64> {
+    let theta = gmst(time);
+    let (sin_t, cos_t) = theta.sin_cos();
+    Vector3::new(
+        cos_t * ecef.x - sin_t * ecef.y,
+        sin_t * ecef.x + cos_t * ecef.y,
+        ecef.z,
+    )
+}
+
+/// Convert an Earth-fixed position to WGS84 geodetic coordinates
+pub fn ecef_to_geodetic(ecef: Vector3<f64>) -> Geodetic {
+    let p = (ecef.x * ecef.x + ecef.y * ecef.y).sqrt();
+    let longitude = ecef.y.atan2(ecef.x);
+
+    // Iterate on latitude; converges to sub-millimetre in a handful of steps
+    let mut latitude = ecef.z.atan2(p * (1.0 - WGS84_E2));
+    let mut n = WGS84_A;
+    for _ in 0..8 {
+        let sin_lat = latitude.sin();
+        n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        latitude = (ecef.z + WGS84_E2 * n * sin_lat).atan2(p);
+    }
+
+    // Height formulation that stays well conditioned near the poles
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let altitude = p * cos_lat + (ecef.z + WGS84_E2 * n * sin_lat) * sin_lat - n;
+
+    Geodetic {
+        latitude_deg: latitude.to_degrees(),
+        longitude_deg: longitude.to_degrees(),
+        altitude_km: altitude,
+    }
+}
+
+/// Convert WGS84 geodetic coordinates to an Earth-fixed position
+pub fn geodetic_to_ecef(geodetic: Geodetic) -> Vector3<f64> {
+    let latitude = geodetic.latitude_deg.to_radians();
+    let longitude = geodetic.longitude_deg.to_radians();
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lon, cos_lon) = longitude.sin_cos();
+    let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+    let h = geodetic.altitude_km;
+
+    Vector3::new(
+        (n + h) * cos_lat * cos_lon,
+        (n + h) * cos_lat * sin_lon,
+        (n * (1.0 - WGS84_E2) + h) * sin_lat,
+    )
+}
+
+/// Scene units per kilometer
+///
+/// Positions enter and leave the scene through `ecef_to_scene` and `scene_to_ecef`, and
+/// every size or distance in the scene (meshes, camera distances, offsets) is a km value
+/// passed through `km_to_scene`, so the whole scene can be rescaled here.
+#[cfg(feature = "visualizer")]
+pub const WORLD_SCALE: f32 = 1.0;
+
+/// Scene length of a distance in km
+#[cfg(feature = "visualizer")]
+pub const fn km_to_scene(km: f32) -> f32 {
+    km * WORLD_SCALE
+}
+
+/// Distance in km of a scene length
+#[cfg(feature = "visualizer")]
+pub const fn scene_to_km(length: f32) -> f32 {
+    length / WORLD_SCALE
+}
+
+/// Map an Earth-fixed position to Bevy scene coordinates
+#[cfg(feature = "visualizer")]
+pub fn ecef_to_scene(ecef: Vector3<f64>) -> Vec3 {
+    ecef_to_scene_f64(ecef).as_vec3()
+}
+
+/// Map a Bevy scene position back to Earth-fixed coordinates
+#[cfg(feature = "visualizer")]
+pub fn scene_to_ecef(scene: Vec3) -> Vector3<f64> {
+    scene_to_ecef_f64(scene.as_dvec3())
+}
+
+/// `ecef_to_scene` in double precision
+#[cfg(feature = "visualizer")]
+pub fn ecef_to_scene_f64(ecef: Vector3<f64>) -> DVec3 {
+    DVec3::new(-ecef.x, ecef.z, ecef.y) * WORLD_SCALE as f64
+}
+
+/// `scene_to_ecef` in double precision
+#[cfg(feature = "visualizer")]
+pub fn scene_to_ecef_f64(scene: DVec3) -> Vector3<f64> {
+    let scene = scene / WORLD_SCALE as f64;
+    Vector3::new(-scene.x, scene.z, scene.y)
+}
+
+/// Shape of the rendered globe, chosen with `[earth] ellipsoid` and held by the viewer as a resource
+///
+/// Scene conversions of geodetic points and occlusion tests take the shape explicitly, so
+/// objects are placed on the globe that is drawn.
+#[cfg(feature = "visualizer")]
+#[derive(bevy::prelude::Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GlobeShape {
+    /// A sphere of `EARTH_RADIUS_KM`
+    #[default]
+    Sphere,
+    /// The WGS84 ellipsoid
+    Ellipsoid,
+}
+
+#[cfg(feature = "visualizer")]
+impl GlobeShape {
+    pub fn new(ellipsoid: bool) -> Self {
+        if ellipsoid { GlobeShape::Ellipsoid } else { GlobeShape::Sphere }
+    }
+
+    /// Equatorial and polar (scene Y) radii (km)
+    pub fn radii(self) -> (f64, f64) {
+        match self {
+            GlobeShape::Sphere => (EARTH_RADIUS_KM, EARTH_RADIUS_KM),
+            GlobeShape::Ellipsoid => (WGS84_A, WGS84_A * (1.0 - WGS84_F)),
+        }
+    }
+
+    /// Scale turning a sphere of `EARTH_RADIUS_KM` into this globe
+    pub fn scale(self) -> Vec3 {
+        let (equatorial, polar) = self.radii();
+        Vec3::new(equatorial as f32, polar as f32, equatorial as f32) / EARTH_RADIUS_KM as f32
+    }
+}
+
+/// Place a geodetic point on the rendered globe
+///
+/// On the WGS84 globe this is the exact Earth-fixed position. On the spherical
+/// globe the point is placed along its geodetic direction at the mean radius plus
+/// its altitude; this keeps surface features (ground stations, tracks) on the
+/// textured sphere.
+#[cfg(feature = "visualizer")]
+pub fn geodetic_to_scene(geodetic: Geodetic, globe: GlobeShape) -> Vec3 {
+    if globe == GlobeShape::Ellipsoid {
+        return ecef_to_scene(geodetic_to_ecef(geodetic));
+    }
+    let latitude = geodetic.latitude_deg.to_radians();
+    let longitude = geodetic.longitude_deg.to_radians();
+    let radius = EARTH_RADIUS_KM + geodetic.altitude_km;
+    let direction = Vector3::new(
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    );
+    ecef_to_scene(direction * radius)
+}
+
+/// Geodetic coordinates of a scene position, the inverse of `geodetic_to_scene`
+///
+/// On the spherical globe the latitude is taken from the direction on the sphere
+/// (not the WGS84 ellipsoid), so a point picked on the globe maps back to where
+/// it was drawn.
+#[cfg(feature = "visualizer")]
+pub fn scene_to_geodetic(scene: Vec3, globe: GlobeShape) -> Geodetic {
+    let ecef = scene_to_ecef(scene);
+    if globe == GlobeShape::Ellipsoid {
+        return ecef_to_geodetic(ecef);
+    }
+    let radius = ecef.norm();
+    Geodetic::new(
+        (ecef.z / radius).asin().to_degrees(),
+        ecef.y.atan2(ecef.x).to_degrees(),
+        radius - EARTH_RADIUS_KM,
+    )
+}
+
+/// Azimuth/elevation/range of a target as seen from an observer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookAngles {
+    /// Degrees clockwise from true north, in [0, 360)
+    pub azimuth_deg: f64,
+    /// Degrees above the local horizon
+    pub elevation_deg: f64,
+    pub range_km: f64,
+}
+
+/// Look angles from an observer on the ground to an Earth-fixed target position
+pub fn look_angles(observer: Geodetic, target_ecef: Vector3<f64>) -> LookAngles {
+    let observer_ecef = geodetic_to_ecef(observer);
+    let d = target_ecef - observer_ecef;
+
+    let (sin_lat, cos_lat) = observer.latitude_deg.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = observer.longitude_deg.to_radians().sin_cos();
+
+    // Rotate the line of sight into the local East-North-Up frame
+    let east = -sin_lon * d.x + cos_lon * d.y;
+    let north = -sin_lat * cos_lon * d.x - sin_lat * sin_lon * d.y + cos_lat * d.z;
+    let up = cos_lat * cos_lon * d.x + cos_lat * sin_lon * d.y + sin_lat * d.z;
+
+    let range = d.magnitude();
+    LookAngles {
+        azimuth_deg: east.atan2(north).to_degrees().rem_euclid(360.0),
+        elevation_deg: (up / range).asin().to_degrees(),
+        range_km: range,
+    }
+}
+
+/// Sub-satellite point and altitude of a TEME position at the given instant
+pub fn teme_to_geodetic(teme: Vector3<f64>, time: DateTime<Utc>) -> Geodetic {
+    ecef_to_geodetic(teme_to_ecef(teme, time))
+}
+
+/// Inclination of the orbit reached by launching along an azimuth from a latitude (degrees)
+///
+/// cos i = cos φ sin β, for the inertial azimuth (Earth's rotation ignored)
+pub fn launch_inclination_deg(latitude_deg: f64, azimuth_deg: f64) -> f64 {
+    let cos_i = latitude_deg.to_radians().cos() * azimuth_deg.to_radians().sin();
+    cos_i.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Launch azimuth reaching an inclination from a latitude, heading north or south of east
+/// (west for retrograde orbits), in [0, 360) degrees
+///
+/// None when the inclination is below the site's latitude: the orbit never passes over it.
+pub fn launch_azimuth_deg(latitude_deg: f64, inclination_deg: f64, northbound: bool) -> Option<f64> {
+    let sin_azimuth = inclination_deg.to_radians().cos() / latitude_deg.to_radians().cos();
+    if !(-1.0..=1.0).contains(&sin_azimuth) {
+        return None;
+    }
+    let azimuth = sin_azimuth.asin().to_degrees();
+    let azimuth = if northbound { azimuth } else { 180.0 - azimuth };
+    Some(azimuth.rem_euclid(360.0))
+}
+
+/// Scene position of a TEME position at the given instant
+#[cfg(feature = "visualizer")]
+pub fn teme_to_scene(teme: Vector3<f64>, time: DateTime<Utc>) -> Vec3 {
+    ecef_to_scene(teme_to_ecef(teme, time))
+}
+
+/// `teme_to_scene` in double precision
+#[cfg(feature = "visualizer")]
+pub fn teme_to_scene_f64(teme: Vector3<f64>, time: DateTime<Utc>) -> DVec3 {
+    ecef_to_scene_f64(teme_to_ecef(teme, time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {} ± {}, got {}",
+            expected,
+            tolerance,
+            actual
+        );
+    }
+
+    #[test]
+    fn gmst_at_j2000() {
+        // GMST at 2000-01-01 12:00 UT1 is 280.46061837°
+        let time = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+        assert_close(gmst(time).to_degrees(), 280.46061837, 1e-4);
+    }
+
+    #[test]
+    fn gmst_vallado_example() {
+        // Vallado, Example 3-5: 1992-08-20 12:14 UT1 -> GMST 152.578787810°
+        let time = Utc.with_ymd_and_hms(1992, 8, 20, 12, 14, 0).unwrap();
+        assert_close(gmst(time).to_degrees(), 152.578787810, 1e-3);
+    }
+
+    #[test]
+    fn ecef_to_geodetic_vallado_example() {
+        // Vallado, Example 3-3
+        let ecef = Vector3::new(6524.834, 6862.875, 6448.296);
+        let geodetic = ecef_to_geodetic(ecef);
+        assert_close(geodetic.latitude_deg, 34.352496, 1e-5);
+        assert_close(geodetic.longitude_deg, 46.4464, 1e-4);
+        assert_close(geodetic.altitude_km, 5085.22, 1e-2);
+    }
+
+    #[test]
+    fn ecef_to_geodetic_equator_and_pole() {
+        let equator = ecef_to_geodetic(Vector3::new(WGS84_A, 0.0, 0.0));
+        assert_close(equator.latitude_deg, 0.0, 1e-9);
+        assert_close(equator.longitude_deg, 0.0, 1e-9);
+        assert_close(equator.altitude_km, 0.0, 1e-6);
+
+        let polar_radius = WGS84_A * (1.0 - WGS84_F);
+        let pole = ecef_to_geodetic(Vector3::new(0.0, 0.0, polar_radius + 100.0));
+        assert_close(pole.latitude_deg, 90.0, 1e-9);
+        assert_close(pole.altitude_km, 100.0, 1e-6);
+    }
+
+    #[test]
+    fn geodetic_round_trip() {
+        for &(lat, lon, alt) in &[
+            (0.0, 0.0, 0.0),
+            (45.0, 10.0, 400.0),
+            (-33.9, 151.2, 0.05),
+            (89.9, -120.0, 35786.0),
+            (-70.0, -179.5, 1200.0),
+        ] {
+            let geodetic = Geodetic::new(lat, lon, alt);
+            let back = ecef_to_geodetic(geodetic_to_ecef(geodetic));
+            assert_close(back.latitude_deg, lat, 1e-8);
+            assert_close(back.longitude_deg, lon, 1e-8);
+            assert_close(back.altitude_km, alt, 1e-6);
+        }
+    }
+
+    #[test]
+    fn teme_ecef_round_trip_preserves_geometry() {
+        let time = Utc.with_ymd_and_hms(2024, 3, 20, 3, 6, 0).unwrap();
+        let teme = Vector3::new(-4500.0, 3200.0, 3900.0);
+        let ecef = teme_to_ecef(teme, time);
+        assert_close(ecef.z, teme.z, 1e-9);
+        assert_close(ecef.magnitude(), teme.magnitude(), 1e-9);
+
+        let back = ecef_to_teme(ecef, time);
+        assert_close((back - teme).magnitude(), 0.0, 1e-9);
+    }
+
+    #[test]
+    fn look_angles_zenith_and_north() {
+        let observer = Geodetic::new(45.0, 5.0, 0.0);
+
+        let overhead = geodetic_to_ecef(Geodetic::new(45.0, 5.0, 500.0));
+        let angles = look_angles(observer, overhead);
+        assert_close(angles.elevation_deg, 90.0, 1e-6);
+        assert_close(angles.range_km, 500.0, 1e-6);
+
+        // A point slightly north at the same height is close to azimuth 0
+        let north = geodetic_to_ecef(Geodetic::new(46.0, 5.0, 0.0));
+        let angles = look_angles(observer, north);
+        assert!(angles.azimuth_deg < 0.1 || angles.azimuth_deg > 359.9);
+        assert!(angles.elevation_deg < 0.0);
+
+        // Due east is azimuth 90
+        let east = geodetic_to_ecef(Geodetic::new(45.0, 6.0, 50.0));
+        let angles = look_angles(observer, east);
+        assert_close(angles.azimuth_deg, 90.0, 0.5);
+    }
+
+    #[test]
+    fn launch_azimuth_and_inclination() {
+        // Due east from Cape Canaveral gives the site's latitude
+        assert_close(launch_inclination_deg(28.5, 90.0), 28.5, 1e-9);
+
+        // ISS orbit from Baikonur: about 63° northbound, 117° southbound
+        let north = launch_azimuth_deg(45.965, 51.64, true).unwrap();
+        let south = launch_azimuth_deg(45.965, 51.64, false).unwrap();
+        assert_close(north, 63.3, 0.1);
+        assert_close(south, 180.0 - north, 1e-9);
+        assert_close(launch_inclination_deg(45.965, north), 51.64, 1e-9);
+        assert_close(launch_inclination_deg(45.965, south), 51.64, 1e-9);
+
+        // Sun-synchronous (retrograde) from Vandenberg heads west of north
+        let sso = launch_azimuth_deg(34.74, 97.5, false).unwrap();
+        assert!(sso > 180.0 && sso < 270.0, "{}", sso);
+
+        // A site can't reach an inclination below its latitude directly
+        assert!(launch_azimuth_deg(45.965, 28.5, true).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "visualizer")]
+    fn scene_mapping_matches_earth_texture() {
+        // Greenwich on the equator sits in the middle of the texture, which the
+        // Earth mesh places on the -X axis; the north pole points up (+Y)
+        let sphere = GlobeShape::Sphere;
+        let greenwich = geodetic_to_scene(Geodetic::new(0.0, 0.0, 0.0), sphere);
+        assert!((greenwich - Vec3::new(-EARTH_RADIUS_KM as f32, 0.0, 0.0) * WORLD_SCALE).length() < 1e-3);
+
+        let north_pole = geodetic_to_scene(Geodetic::new(90.0, 0.0, 0.0), sphere);
+        assert!((north_pole - Vec3::new(0.0, EARTH_RADIUS_KM as f32, 0.0) * WORLD_SCALE).length() < 1e-2);
+
+        let east = geodetic_to_scene(Geodetic::new(0.0, 90.0, 0.0), sphere);
+        assert!((east - Vec3::new(0.0, 0.0, EARTH_RADIUS_KM as f32) * WORLD_SCALE).length() < 1e-2);
+
+        // The WGS84 globe is wider at the equator and flatter at the poles
+        let ellipsoid = GlobeShape::Ellipsoid;
+        let greenwich = geodetic_to_scene(Geodetic::new(0.0, 0.0, 0.0), ellipsoid);
+        assert!((greenwich - Vec3::new(-WGS84_A as f32, 0.0, 0.0) * WORLD_SCALE).length() < 1e-3);
+        let north_pole = geodetic_to_scene(Geodetic::new(90.0, 0.0, 0.0), ellipsoid);
+        let polar_radius = (WGS84_A * (1.0 - WGS84_F)) as f32;
+        assert!((north_pole - Vec3::new(0.0, polar_radius, 0.0) * WORLD_SCALE).length() < 1e-2);
+
+        let ecef = Vector3::new(1000.0, -2000.0, 3000.0);
+        assert!((scene_to_ecef(ecef_to_scene(ecef)) - ecef).magnitude() < 1e-3);
+    }
+
+    #[test]
+    #[cfg(feature = "visualizer")]
+    fn scene_to_geodetic_round_trip() {
+        let paris = Geodetic::new(48.8566, 2.3522, 0.0);
+        for globe in [GlobeShape::Sphere, GlobeShape::Ellipsoid] {
+            let back = scene_to_geodetic(geodetic_to_scene(paris, globe), globe);
+            assert_close(back.latitude_deg, paris.latitude_deg, 1e-3);
+            assert_close(back.longitude_deg, paris.longitude_deg, 1e-3);
+            assert_close(back.altitude_km, 0.0, 1e-2);
+        }
+    }
+}
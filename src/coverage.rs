@@ -0,0 +1,398 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use chrono::{DateTime, Duration, Utc};
+use nalgebra::Vector3;
+
+use crate::config::{CoverageSettings, Settings};
+use crate::coords::{self, Geodetic, GlobeShape, EARTH_RADIUS_KM};
+use crate::floating_origin::ScenePosition;
+use crate::footprint::footprint_half_angle;
+use crate::groups::SatelliteGroups;
+use crate::keymap::{Action, Keymap};
+use crate::notifications::Notify;
+use crate::propagation::Propagator;
+use crate::satellite::Satellite;
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
+
+/// Coverage heatmap: how long each patch of the globe sees at least one of a set of satellites
+pub struct CoveragePlugin;
+
+impl Plugin for CoveragePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>();
+        let analysis = CoverageAnalysis {
+            settings: settings.coverage.clone(),
+            min_elevation_deg: settings.footprint.min_elevation_deg,
+            shown: false,
+            task: None,
+        };
+
+        app.insert_resource(analysis)
+            .add_systems(Startup, spawn_coverage_overlay)
+            .add_systems(Update, (toggle_coverage, show_coverage).chain());
+    }
+}
+
+/// Height of the overlay above the surface, under the footprints (km)
+const COVERAGE_LIFT_KM: f64 = 10.0;
+/// Opacity of the overlay cells
+const COVERAGE_ALPHA: f32 = 0.45;
+/// Color map from rarely (first) to always (last) covered
+const COVERAGE_COLORS: [Color; 4] = [
+    Color::srgb(0.0, 0.3, 1.0),
+    Color::srgb(0.0, 0.9, 0.3),
+    Color::srgb(1.0, 0.9, 0.0),
+    Color::srgb(1.0, 0.1, 0.0),
+];
+
+/// Coverage analysis settings and the run in progress
+#[derive(Resource)]
+pub struct CoverageAnalysis {
+    pub settings: CoverageSettings,
+    /// Elevation a ground point needs to see a satellite, shared with the footprint (degrees)
+    pub min_elevation_deg: f64,
+    /// Overlay displayed (or being computed)
+    pub shown: bool,
+    task: Option<Task<CoverageGrid>>,
+}
+
+/// Fraction of a time window each cell of a latitude/longitude grid had a satellite in view
+pub struct CoverageGrid {
+    pub cell_deg: f64,
+    pub rows: usize,
+    pub columns: usize,
+    /// Row-major from the south pole and the antimeridian, in [0, 1]
+    pub fraction: Vec<f32>,
+    /// Satellites, start and length of the window, for the summary
+    pub satellites: usize,
+    pub start: DateTime<Utc>,
+    pub hours: f64,
+}
+
+impl CoverageGrid {
+    /// Latitude and longitude (degrees) of the south-west corner of a cell
+    fn corner(&self, row: usize, column: usize) -> (f64, f64) {
+        (-90.0 + row as f64 * self.cell_deg, -180.0 + column as f64 * self.cell_deg)
+    }
+
+    /// Share of the globe's area seen at least once, and mean share of the window in view, weighted by cell area
+    pub fn summary(&self) -> (f64, f64) {
+        let mut area = 0.0;
+        let mut seen = 0.0;
+        let mut in_view = 0.0;
+        for row in 0..self.rows {
+            let (latitude, _) = self.corner(row, 0);
+            let weight = (latitude + self.cell_deg * 0.5).to_radians().cos();
+            for &fraction in &self.fraction[row * self.columns..(row + 1) * self.columns] {
+                area += weight;
+                in_view += weight * fraction as f64;
+                if fraction > 0.0 {
+                    seen += weight;
+                }
+            }
+        }
+        if area > 0.0 {
+            (seen / area, in_view / area)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+}
+
+/// Step through the window and count, per cell, the steps where its center sees a satellite
+/// at least `min_elevation_deg` above the horizon
+pub fn accumulate_coverage(
+    propagators: &[Propagator],
+    start: DateTime<Utc>,
+    settings: &CoverageSettings,
+    min_elevation_deg: f64,
+) -> CoverageGrid {
+    // Square cells that tile the globe exactly, as close to the requested size as that allows
+    let rows = (180.0 / settings.cell_deg.clamp(0.25, 30.0)).round() as usize;
+    let columns = 2 * rows;
+    let cell_deg = 180.0 / rows as f64;
+
+    // Cell centers as unit vectors, Earth-fixed
+    let centers: Vec<Vector3<f64>> = (0..rows * columns)
+        .map(|cell| {
+            let latitude = (-90.0 + (cell / columns) as f64 * cell_deg + cell_deg * 0.5).to_radians();
+            let longitude = (-180.0 + (cell % columns) as f64 * cell_deg + cell_deg * 0.5).to_radians();
+            Vector3::new(latitude.cos() * longitude.cos(), latitude.cos() * longitude.sin(), latitude.sin())
+        })
+        .collect();
+
+    let step = settings.step_seconds.max(1.0);
+    let steps = ((settings.hours.max(0.0) * 3600.0 / step).ceil() as usize).max(1);
+    let mut counts = vec![0u32; rows * columns];
+    // Step at which each cell was last counted, so overlapping satellites count once
+    let mut counted_at = vec![usize::MAX; rows * columns];
+
+    for index in 0..steps {
+        let time = start + Duration::milliseconds((index as f64 * step * 1000.0) as i64);
+        for propagator in propagators {
+            let Some(position) = propagator.propagate(time) else { continue };
+            let ecef = coords::teme_to_ecef(position, time);
+            let half_angle = footprint_half_angle(ecef.norm() - EARTH_RADIUS_KM, min_elevation_deg);
+            let sub_point = ecef.normalize();
+            let min_dot = half_angle.cos();
+
+            // Only the rows the footprint reaches
+            let latitude = sub_point.z.asin().to_degrees();
+            let first_row = ((latitude - half_angle.to_degrees() + 90.0) / cell_deg).floor().max(0.0) as usize;
+            let last_row = (((latitude + half_angle.to_degrees() + 90.0) / cell_deg).ceil() as usize).min(rows);
+            for cell in first_row * columns..last_row * columns {
+                if counted_at[cell] != index && centers[cell].dot(&sub_point) >= min_dot {
+                    counted_at[cell] = index;
+                    counts[cell] += 1;
+                }
+            }
+        }
+    }
+
+    CoverageGrid {
+        cell_deg,
+        rows,
+        columns,
+        fraction: counts.iter().map(|&count| count as f32 / steps as f32).collect(),
+        satellites: propagators.len(),
+        start,
+        hours: settings.hours,
+    }
+}
+
+/// Overlay color of a covered fraction
+fn coverage_color(fraction: f32) -> Color {
+    let scaled = fraction.clamp(0.0, 1.0) * (COVERAGE_COLORS.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(COVERAGE_COLORS.len() - 2);
+    let low = COVERAGE_COLORS[index].to_linear();
+    let high = COVERAGE_COLORS[index + 1].to_linear();
+    Color::from(low.mix(&high, scaled - index as f32)).with_alpha(COVERAGE_ALPHA)
+}
+
+/// One colored quad per cell seen at least once; cells never covered stay clear
+fn overlay_mesh(grid: &CoverageGrid, globe: GlobeShape) -> Mesh {
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    let point = |latitude: f64, longitude: f64| {
+        coords::geodetic_to_scene(Geodetic::new(latitude, longitude, COVERAGE_LIFT_KM), globe).to_array()
+    };
+    for row in 0..grid.rows {
+        for column in 0..grid.columns {
+            let fraction = grid.fraction[row * grid.columns + column];
+            if fraction <= 0.0 {
+                continue;
+            }
+            let (south, west) = grid.corner(row, column);
+            let (north, east) = (south + grid.cell_deg, west + grid.cell_deg);
+            positions.extend([
+                point(south, west),
+                point(south, east),
+                point(north, east),
+                point(south, west),
+                point(north, east),
+                point(north, west),
+            ]);
+            colors.extend([coverage_color(fraction).to_linear().to_f32_array(); 6]);
+        }
+    }
+    empty_if_degenerate(&mut positions, &mut colors);
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh
+}
+
+/// Keep at least one (zero-area) triangle so the mesh stays valid
+fn empty_if_degenerate(positions: &mut Vec<[f32; 3]>, colors: &mut Vec<[f32; 4]>) {
+    if positions.is_empty() {
+        positions.extend([[0.0; 3]; 3]);
+        colors.extend([[0.0; 4]; 3]);
+    }
+}
+
+/// Translucent grid of colored cells over the globe
+#[derive(Component)]
+pub struct CoverageOverlay;
+
+pub fn spawn_coverage_overlay(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    empty_if_degenerate(&mut positions, &mut colors);
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        cull_mode: None,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        CoverageOverlay,
+        Name::new("CoverageOverlay"),
+    ));
+}
+
+/// Propagators of the satellites an analysis covers: the members of the group shown with
+/// "show only", or else the selected satellite
+pub fn analyzed_propagators(
+    groups: &SatelliteGroups,
+    satellite_query: &Query<(&Satellite, Has<Selected>)>,
+) -> Vec<Propagator> {
+    let group = groups.show_only.as_deref().and_then(|name| groups.get(name));
+    satellite_query
+        .iter()
+        .filter(|(satellite, selected)| match group {
+            Some(group) => group.members.contains(&satellite.elements.norad_id),
+            None => *selected,
+        })
+        .map(|(satellite, _)| Propagator::new(&satellite.elements))
+        .collect()
+}
+
+/// Start a coverage run with Z, or hide the overlay
+///
+/// Covers the `analyzed_propagators` over `[coverage] hours` from the simulation time.
+#[allow(clippy::too_many_arguments)]
+pub fn toggle_coverage(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut analysis: ResMut<CoverageAnalysis>,
+    groups: Res<SatelliteGroups>,
+    sim_time: Res<TimeSimulation>,
+    satellite_query: Query<(&Satellite, Has<Selected>)>,
+    mut overlay_query: Query<&mut Visibility, With<CoverageOverlay>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleCoverage, &keyboard_input) {
+        return;
+    }
+
+    if analysis.shown {
+        analysis.shown = false;
+        // Dropping the task cancels a run still in progress
+        analysis.task = None;
+        for mut visibility in overlay_query.iter_mut() {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+        println!("Coverage map: off");
+        return;
+    }
+
+    let propagators = analyzed_propagators(&groups, &satellite_query);
+    if propagators.is_empty() {
+        println!("Select a satellite or show only a group to map its coverage");
+        return;
+    }
+
+    println!(
+        "Computing coverage of {} satellites over {} h...",
+        propagators.len(),
+        analysis.settings.hours
+    );
+    let start = sim_time.current_time();
+    let settings = analysis.settings.clone();
+    let min_elevation_deg = analysis.min_elevation_deg;
+    analysis.shown = true;
+    analysis.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        accumulate_coverage(&propagators, start, &settings, min_elevation_deg)
+    }));
+}
+
+/// Draw the overlay once a run finishes
+pub fn show_coverage(
+    mut analysis: ResMut<CoverageAnalysis>,
+    mut overlay_query: Query<(&Mesh3d, &mut Visibility), With<CoverageOverlay>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    globe: Res<GlobeShape>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let grid = match analysis.task.as_mut().and_then(|task| block_on(future::poll_once(task))) {
+        Some(grid) => grid,
+        None => return,
+    };
+    analysis.task = None;
+
+    let (seen, in_view) = grid.summary();
+    let summary = format!(
+        "Coverage of {} satellites over {} h from {}: {:.0}% of the globe seen, {:.1}% of the time on average",
+        grid.satellites,
+        grid.hours,
+        grid.start.format("%Y-%m-%d %H:%M UTC"),
+        seen * 100.0,
+        in_view * 100.0,
+    );
+    println!("✓ {}", summary);
+    notify_writer.write(Notify::info(summary));
+
+    for (mesh_3d, mut visibility) in overlay_query.iter_mut() {
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            *mesh = overlay_mesh(&grid, *globe);
+        }
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tle_loader::TleData;
+
+    fn settings(hours: f64, cell_deg: f64) -> CoverageSettings {
+        CoverageSettings {
+            hours,
+            step_seconds: 600.0,
+            cell_deg,
+        }
+    }
+
+    #[test]
+    fn grid_covers_the_whole_globe() {
+        for cell_deg in [0.25, 1.0, 5.0, 7.0, 11.0, 30.0, 45.0] {
+            let grid = accumulate_coverage(&[], Utc::now(), &settings(0.0, cell_deg), 0.0);
+            assert_eq!(grid.columns, 2 * grid.rows, "{}° cells", cell_deg);
+            assert!((grid.rows as f64 * grid.cell_deg - 180.0).abs() < 1e-9, "{}° cells", cell_deg);
+            assert!((grid.columns as f64 * grid.cell_deg - 360.0).abs() < 1e-9, "{}° cells", cell_deg);
+            assert_eq!(grid.fraction.len(), grid.rows * grid.columns);
+        }
+        // 7° doesn't divide 180°: 26 rows of 6.92° cells, and 52 columns to go all the way round
+        let grid = accumulate_coverage(&[], Utc::now(), &settings(0.0, 7.0), 0.0);
+        assert_eq!((grid.rows, grid.columns), (26, 52));
+    }
+
+    #[test]
+    fn a_geostationary_satellite_sees_its_hemisphere_all_the_time() {
+        let tle = TleData {
+            line1: "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190".to_string(),
+            line2: "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891".to_string(),
+            name: String::new(),
+            omm: None,
+        };
+        let elements = tle.to_elements().unwrap();
+        let start = elements.datetime.and_utc();
+        let grid = accumulate_coverage(&[Propagator::new(&elements)], start, &settings(6.0, 2.0), 0.0);
+
+        // The cap seen from GEO spans acos(R / r) = 81.3° around the sub-satellite point,
+        // (1 - cos 81.3°) / 2 = 42.4% of the globe, and never moves
+        let (seen, in_view) = grid.summary();
+        assert!((seen - 0.424).abs() < 0.01, "seen {}", seen);
+        assert!((in_view - seen).abs() < 0.005, "in view {} of {}", in_view, seen);
+    }
+}
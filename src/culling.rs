@@ -0,0 +1,128 @@
+use bevy::camera::primitives::{self, Frustum};
+use bevy::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::camera::CameraController;
+use crate::chase_view::ChaseView;
+use crate::config::Settings;
+use crate::coords;
+use crate::floating_origin::FloatingOrigin;
+use crate::map_view::MapView;
+use crate::observer_view::ObserverView;
+use crate::satellite::{PositionKeyframes, SatelliteSystems};
+use crate::selection::Selected;
+use crate::ui::SatelliteFilter;
+
+/// Skip propagating and moving satellites the camera can't see: those well outside the view,
+/// and, when zoomed into a region, those beyond a distance budget
+pub struct CullingPlugin;
+
+impl Plugin for CullingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = &app.world().resource::<Settings>().satellites;
+        let culling = SatelliteCulling {
+            enabled: settings.culling,
+            max_distance_km: settings.cull_distance_km as f32,
+        };
+
+        app.insert_resource(culling)
+            .init_resource::<MapView>()
+            .init_resource::<ObserverView>()
+            .init_resource::<ChaseView>()
+            .init_resource::<SatelliteFilter>()
+            .add_systems(Update, cull_satellites.in_set(SatelliteSystems::Cull));
+    }
+}
+
+/// Satellites within this distance of the view (km) are still updated, so they are in
+/// place when a turn of the camera brings them in
+const VIEW_MARGIN_KM: f32 = 1500.0;
+
+/// What culling skips
+#[derive(Resource)]
+pub struct SatelliteCulling {
+    pub enabled: bool,
+    /// Satellites farther than this from the camera are hidden and not propagated, while the
+    /// camera is closer than this to Earth's center (0 = off)
+    pub max_distance_km: f32,
+}
+
+/// Why a satellite is skipped by `update_satellite_positions`, if it is
+///
+/// Culled satellites are still propagated now and then (without interpolation) so the
+/// view test follows them as they move.
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Culled {
+    /// Well outside the camera frustum
+    pub outside_view: bool,
+    /// Beyond the distance budget; also hidden
+    pub beyond_range: bool,
+}
+
+impl Culled {
+    pub fn is_culled(&self) -> bool {
+        self.outside_view || self.beyond_range
+    }
+}
+
+/// Flag the satellites to skip this frame; the selected one is always updated
+///
+/// Only the orbit camera culls: the map, observer and chase views show satellites
+/// the orbit camera doesn't.
+#[allow(clippy::too_many_arguments)]
+pub fn cull_satellites(
+    culling: Res<SatelliteCulling>,
+    map_view: Res<MapView>,
+    observer_view: Res<ObserverView>,
+    chase_view: Res<ChaseView>,
+    camera_query: Query<(&GlobalTransform, &Frustum), With<CameraController>>,
+    mut satellite_query: Query<(&Transform, &mut Culled, &mut PositionKeyframes, Has<Selected>)>,
+    mut filter: ResMut<SatelliteFilter>,
+    origin: Res<FloatingOrigin>,
+) {
+    let active = culling.enabled
+        && !map_view.enabled
+        && observer_view.station.is_none()
+        && *chase_view == ChaseView::Off;
+    let camera = camera_query.iter().next().filter(|_| active);
+    let camera_position = camera.map(|(transform, _)| transform.translation());
+    let range = coords::km_to_scene(culling.max_distance_km);
+    let max_distance = camera_position
+        .filter(|&position| range > 0.0 && origin.to_scene(position).length() < range as f64)
+        .map(|_| range);
+
+    let range_changed = AtomicBool::new(false);
+    satellite_query.par_iter_mut().for_each(|(transform, mut culled, mut keyframes, selected)| {
+        let position = transform.translation;
+        let new = match (camera, camera_position) {
+            (Some((_, frustum)), Some(camera_position)) if !selected => Culled {
+                outside_view: !frustum.intersects_sphere(
+                    &primitives::Sphere {
+                        center: position.into(),
+                        radius: coords::km_to_scene(VIEW_MARGIN_KM),
+                    },
+                    false,
+                ),
+                beyond_range: max_distance.is_some_and(|max| position.distance(camera_position) > max),
+            },
+            _ => Culled::default(),
+        };
+        if *culled == new {
+            return;
+        }
+        if culled.is_culled() && !new.is_culled() {
+            // Back in view: snap to a fresh position rather than sliding from the stale one
+            keyframes.times = None;
+        }
+        if culled.beyond_range != new.beyond_range {
+            range_changed.store(true, Ordering::Relaxed);
+        }
+        *culled = new;
+    });
+
+    // Satellites beyond the distance budget are hidden by the filter
+    if range_changed.load(Ordering::Relaxed) {
+        filter.set_changed();
+    }
+}
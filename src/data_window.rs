@@ -0,0 +1,493 @@
+use bevy::camera::visibility::RenderLayers;
+use bevy::camera::RenderTarget;
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::WindowRef;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::conjunction::ConjunctionScreening;
+use crate::coords;
+use crate::ground_station::GroundStation;
+use crate::keymap::{Action, Keymap};
+use crate::passes::{self, Pass};
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::selection::{SelectSatellite, Selected};
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
+use crate::ui::ScrollArea;
+
+/// Second OS window with data panels (satellite table, pass schedule, close approaches),
+/// leaving the globe alone in the main window
+pub struct DataWindowPlugin;
+
+impl Plugin for DataWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataWindow>()
+            .add_message::<SelectSatellite>()
+            .add_systems(Update, (
+                toggle_data_window,
+                forget_closed_data_window,
+                update_data_panels,
+                select_table_row,
+            ).chain());
+    }
+}
+
+/// Render layer of the data window camera, so it draws none of the globe's 2D labels
+const DATA_WINDOW_LAYER: usize = 2;
+/// Rows of the satellite table
+const MAX_TABLE_ROWS: usize = 200;
+/// Passes listed for the selected satellite
+const MAX_LISTED_PASSES: usize = 10;
+/// Close approaches listed
+const MAX_LISTED_APPROACHES: usize = 10;
+/// How far ahead passes are predicted
+const PASS_WINDOW_HOURS: i64 = 24;
+/// Satellite table columns: header and width (px)
+const TABLE_COLUMNS: [(&str, f32); 6] = [
+    ("Name", 230.0),
+    ("Type", 90.0),
+    ("Lat", 70.0),
+    ("Lon", 80.0),
+    ("Alt (km)", 80.0),
+    ("Speed (km/s)", 100.0),
+];
+
+/// The data window, if open, and the pass schedule it shows
+#[derive(Resource)]
+pub struct DataWindow {
+    pub window: Option<Entity>,
+    /// Satellite and station the passes were predicted for
+    pass_key: Option<(Entity, Entity)>,
+    predicted_from: Option<DateTime<Utc>>,
+    passes: Vec<Pass>,
+    refresh: Timer,
+}
+
+impl Default for DataWindow {
+    fn default() -> Self {
+        Self {
+            window: None,
+            pass_key: None,
+            predicted_from: None,
+            passes: Vec::new(),
+            refresh: Timer::from_seconds(0.5, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Everything spawned for the data window besides the window itself
+#[derive(Component)]
+pub struct DataWindowEntity;
+
+#[derive(Component)]
+pub struct SatelliteTableHeader;
+
+/// Row of the satellite table, showing a satellite once filled
+#[derive(Component)]
+pub struct SatelliteTableRow {
+    pub index: usize,
+    pub satellite: Option<Entity>,
+}
+
+/// Cell of a satellite table row, by column
+#[derive(Component)]
+pub struct SatelliteTableCell(pub usize);
+
+#[derive(Component)]
+pub struct PassScheduleHeader;
+
+#[derive(Component)]
+pub struct PassScheduleRow(pub usize);
+
+#[derive(Component)]
+pub struct ApproachListHeader;
+
+#[derive(Component)]
+pub struct ApproachListRow(pub usize);
+
+/// Open or close the data window
+pub fn toggle_data_window(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut data_window: ResMut<DataWindow>,
+    spawned_query: Query<Entity, With<DataWindowEntity>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleDataWindow, &keyboard_input) {
+        return;
+    }
+
+    if let Some(window) = data_window.window.take() {
+        commands.entity(window).despawn();
+        for entity in spawned_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    data_window.window = Some(spawn_data_window(&mut commands));
+    data_window.pass_key = None;
+    // Fill the panels right away
+    let duration = data_window.refresh.duration();
+    data_window.refresh.set_elapsed(duration);
+}
+
+/// Clean up after the window was closed with its close button
+pub fn forget_closed_data_window(
+    mut commands: Commands,
+    mut data_window: ResMut<DataWindow>,
+    window_query: Query<(), With<Window>>,
+    spawned_query: Query<Entity, With<DataWindowEntity>>,
+) {
+    let Some(window) = data_window.window else { return };
+    if window_query.contains(window) {
+        return;
+    }
+    data_window.window = None;
+    for entity in spawned_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_data_window(commands: &mut Commands) -> Entity {
+    let window = commands
+        .spawn(Window {
+            title: "AI Space Tracker - Data".into(),
+            resolution: (720, 900).into(),
+            ..default()
+        })
+        .id();
+    let camera = commands
+        .spawn((
+            Camera2d,
+            Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                clear_color: ClearColorConfig::Custom(Color::srgb(0.06, 0.06, 0.08)),
+                ..default()
+            },
+            RenderLayers::layer(DATA_WINDOW_LAYER),
+            DataWindowEntity,
+        ))
+        .id();
+
+    let heading = || {
+        (
+            Text::new(""),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.85, 1.0)),
+        )
+    };
+    let line = || {
+        (
+            Node {
+                display: Display::None,
+                ..default()
+            },
+            Text::new(""),
+            TextFont {
+                font_size: 13.0,
+                ..default()
+            },
+        )
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            UiTargetCamera(camera),
+            DataWindowEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((heading(), SatelliteTableHeader));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|header| {
+                    for (title, width) in TABLE_COLUMNS {
+                        header.spawn((
+                            Node {
+                                width: Val::Px(width),
+                                ..default()
+                            },
+                            Text::new(title),
+                            TextFont {
+                                font_size: 13.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                        ));
+                    }
+                });
+            parent
+                .spawn((
+                    Node {
+                        height: Val::Percent(50.0),
+                        flex_direction: FlexDirection::Column,
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.03)),
+                    ScrollArea,
+                    RelativeCursorPosition::default(),
+                ))
+                .with_children(|table| {
+                    for index in 0..MAX_TABLE_ROWS {
+                        table
+                            .spawn((
+                                Node {
+                                    display: Display::None,
+                                    column_gap: Val::Px(4.0),
+                                    padding: UiRect::axes(Val::Px(0.0), Val::Px(1.0)),
+                                    flex_shrink: 0.0,
+                                    ..default()
+                                },
+                                BackgroundColor(Color::NONE),
+                                Interaction::default(),
+                                SatelliteTableRow { index, satellite: None },
+                            ))
+                            .with_children(|row| {
+                                for (column, (_, width)) in TABLE_COLUMNS.iter().enumerate() {
+                                    row.spawn((
+                                        Node {
+                                            width: Val::Px(*width),
+                                            overflow: Overflow::clip(),
+                                            ..default()
+                                        },
+                                        Text::new(""),
+                                        TextFont {
+                                            font_size: 13.0,
+                                            ..default()
+                                        },
+                                        SatelliteTableCell(column),
+                                    ));
+                                }
+                            });
+                    }
+                });
+
+            parent.spawn((heading(), PassScheduleHeader));
+            for index in 0..MAX_LISTED_PASSES {
+                parent.spawn((line(), PassScheduleRow(index)));
+            }
+
+            parent.spawn((heading(), ApproachListHeader));
+            for index in 0..MAX_LISTED_APPROACHES {
+                parent.spawn((line(), ApproachListRow(index)));
+            }
+        });
+
+    window
+}
+
+/// Refresh the three panels twice a second while the window is open
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_data_panels(
+    time: Res<Time>,
+    sim_time: Res<TimeSimulation>,
+    mut data_window: ResMut<DataWindow>,
+    screening: Option<Res<ConjunctionScreening>>,
+    satellite_query: Query<(Entity, &Satellite, &Visibility, Has<Selected>)>,
+    station_query: Query<(Entity, &GroundStation)>,
+    mut texts: ParamSet<(
+        Query<&mut Text, With<SatelliteTableHeader>>,
+        Query<(&SatelliteTableCell, &ChildOf, &mut Text)>,
+        Query<&mut Text, With<PassScheduleHeader>>,
+        Query<(&PassScheduleRow, &mut Text, &mut Node), Without<SatelliteTableRow>>,
+        Query<&mut Text, With<ApproachListHeader>>,
+        Query<(&ApproachListRow, &mut Text, &mut Node), Without<SatelliteTableRow>>,
+    )>,
+    mut row_query: Query<(&mut SatelliteTableRow, &Interaction, &mut Node, &mut BackgroundColor)>,
+    mut refreshed: MessageReader<TleRefreshed>,
+) {
+    // New elements give new passes
+    if TleRefreshed::any_changed(&mut refreshed) {
+        data_window.pass_key = None;
+    }
+    if data_window.window.is_none() || !data_window.refresh.tick(time.delta()).just_finished() {
+        return;
+    }
+    let now = sim_time.current_time();
+
+    // Satellite table: the shown satellites by name, the selected one highlighted
+    let mut shown: Vec<_> = satellite_query
+        .iter()
+        .filter(|(_, satellite, visibility, _)| satellite.position.is_some() && **visibility != Visibility::Hidden)
+        .collect();
+    shown.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    for mut text in texts.p0().iter_mut() {
+        *text = Text::new(if shown.len() > MAX_TABLE_ROWS {
+            format!("Satellites: first {} of {} shown", MAX_TABLE_ROWS, shown.len())
+        } else {
+            format!("Satellites: {}", shown.len())
+        });
+    }
+
+    let cells: Vec<[String; 6]> = shown
+        .iter()
+        .take(MAX_TABLE_ROWS)
+        .map(|(_, satellite, _, _)| {
+            let geodetic = satellite
+                .position
+                .map(|position| coords::teme_to_geodetic(position, satellite.last_update));
+            let [lat, lon, alt] = geodetic.map_or_else(
+                || ["-".to_string(), "-".to_string(), "-".to_string()],
+                |g| {
+                    [
+                        format!("{:.2}°", g.latitude_deg),
+                        format!("{:.2}°", g.longitude_deg),
+                        format!("{:.0}", g.altitude_km),
+                    ]
+                },
+            );
+            let speed = satellite.speed().map_or("-".to_string(), |speed| format!("{:.2}", speed));
+            [satellite.name.clone(), satellite.kind.label().to_string(), lat, lon, alt, speed]
+        })
+        .collect();
+    for (mut row, interaction, mut node, mut background) in row_query.iter_mut() {
+        let Some((satellite_entity, _, _, selected)) = shown.get(row.index) else {
+            row.satellite = None;
+            node.display = Display::None;
+            continue;
+        };
+        row.satellite = Some(*satellite_entity);
+        node.display = Display::Flex;
+        background.0 = if *selected {
+            Color::srgba(0.3, 0.6, 1.0, 0.35)
+        } else if *interaction == Interaction::Hovered {
+            Color::srgba(1.0, 1.0, 1.0, 0.1)
+        } else {
+            Color::NONE
+        };
+    }
+    for (cell, parent, mut text) in texts.p1().iter_mut() {
+        let value = row_query
+            .get(parent.parent())
+            .ok()
+            .and_then(|(row, ..)| cells.get(row.index))
+            .map(|values| &values[cell.0]);
+        if let Some(value) = value.filter(|value| **value != text.0) {
+            text.0 = value.clone();
+        }
+    }
+
+    // Pass schedule of the selected satellite over the first ground station
+    let selected = satellite_query.iter().find(|(_, _, _, selected)| *selected);
+    let station = station_query.iter().next();
+    let header = match (selected, station) {
+        (Some((satellite_entity, satellite, _, _)), Some((station_entity, station))) => {
+            let key = (satellite_entity, station_entity);
+            let stale = data_window.pass_key != Some(key)
+                || data_window
+                    .predicted_from
+                    .is_none_or(|from| now < from || now > from + Duration::hours(PASS_WINDOW_HOURS / 4));
+            if stale {
+                data_window.passes = passes::predict_passes(
+                    satellite.propagator(),
+                    station.geodetic(),
+                    &station.horizon(),
+                    now,
+                    now + Duration::hours(PASS_WINDOW_HOURS),
+                    Duration::seconds(passes::PASS_SEARCH_STEP_SECONDS),
+                );
+                data_window.pass_key = Some(key);
+                data_window.predicted_from = Some(now);
+            }
+            format!("Passes of {} over {} (next {} h)", satellite.name, station.name, PASS_WINDOW_HOURS)
+        }
+        (None, _) => {
+            data_window.pass_key = None;
+            data_window.passes.clear();
+            "Passes: select a satellite".to_string()
+        }
+        (_, None) => {
+            data_window.pass_key = None;
+            data_window.passes.clear();
+            "Passes: no ground station".to_string()
+        }
+    };
+    let upcoming: Vec<&Pass> = data_window.passes.iter().filter(|pass| pass.set_time >= now).collect();
+    for mut text in texts.p2().iter_mut() {
+        *text = Text::new(if data_window.pass_key.is_some() && upcoming.is_empty() {
+            format!("{}: none", header)
+        } else {
+            header.clone()
+        });
+    }
+    for (row, mut text, mut node) in texts.p3().iter_mut() {
+        let Some(pass) = upcoming.get(row.0) else {
+            node.display = Display::None;
+            continue;
+        };
+        node.display = Display::Flex;
+        let current = if pass.rise_time <= now { "  (now)" } else { "" };
+        *text = Text::new(format!(
+            "{}  rise az {:.0}°  max {:.1}° at {}  set {} az {:.0}°{}",
+            pass.rise_time.format("%m-%d %H:%M:%S"),
+            pass.rise_azimuth_deg,
+            pass.max_elevation_deg,
+            pass.max_elevation_time.format("%H:%M:%S"),
+            pass.set_time.format("%H:%M:%S"),
+            pass.set_azimuth_deg,
+            current,
+        ));
+    }
+
+    // Close approaches from the last screening run
+    let name = |entity: Entity| {
+        satellite_query
+            .get(entity)
+            .map_or("?".to_string(), |(_, satellite, _, _)| satellite.name.clone())
+    };
+    let approaches = screening.as_ref().map_or(&[][..], |screening| &screening.approaches[..]);
+    for mut text in texts.p4().iter_mut() {
+        *text = Text::new(match &screening {
+            Some(screening) => format!(
+                "Close approaches (< {} km): {}",
+                screening.threshold_km,
+                screening.approaches.len()
+            ),
+            None => "Close approaches: screening is off".to_string(),
+        });
+    }
+    for (row, mut text, mut node) in texts.p5().iter_mut() {
+        let Some(approach) = approaches.get(row.0) else {
+            node.display = Display::None;
+            continue;
+        };
+        node.display = Display::Flex;
+        *text = Text::new(format!(
+            "{:.2} km  {} / {}  ({})",
+            approach.distance_km,
+            name(approach.first),
+            name(approach.second),
+            approach.time.format("%H:%M:%S"),
+        ));
+    }
+}
+
+/// Select the satellite of a clicked table row
+pub fn select_table_row(
+    mut select_writer: MessageWriter<SelectSatellite>,
+    row_query: Query<(&SatelliteTableRow, &Interaction), Changed<Interaction>>,
+) {
+    for (row, interaction) in row_query.iter() {
+        if *interaction == Interaction::Pressed {
+            if let Some(satellite) = row.satellite {
+                select_writer.write(SelectSatellite(Some(satellite)));
+            }
+        }
+    }
+}
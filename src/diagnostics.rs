@@ -0,0 +1,150 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+};
+use bevy::prelude::*;
+use std::time::SystemTime;
+
+use crate::config::Settings;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{self, Satellite, TleLoadState, TleRefreshed};
+use crate::text_input::InputFocus;
+use crate::time_bar::TIME_BAR_STRIP;
+
+/// FPS and satellite statistics overlay
+pub struct DiagnosticsOverlayPlugin;
+
+impl Plugin for DiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        app.register_diagnostic(Diagnostic::new(VISIBLE_SATELLITES))
+            .add_systems(Startup, setup_diagnostics_overlay)
+            .add_systems(Update, (
+                measure_visible_satellites,
+                update_diagnostics_overlay,
+                toggle_diagnostics_overlay,
+            ));
+    }
+}
+
+/// Satellites with a valid position after the last propagation keyframe
+pub const PROPAGATED_SATELLITES: DiagnosticPath = DiagnosticPath::const_new("satellites/propagated");
+/// SGP4 propagations at the last keyframe (fewer than the satellites when slow movers skip it)
+pub const SGP4_CALLS: DiagnosticPath = DiagnosticPath::const_new("satellites/sgp4_calls");
+/// Satellites left visible by the filter
+pub const VISIBLE_SATELLITES: DiagnosticPath = DiagnosticPath::const_new("satellites/visible");
+/// Time spent in `update_satellite_positions` each frame (ms)
+pub const PROPAGATION_TIME: DiagnosticPath = DiagnosticPath::const_new("satellites/propagation_time");
+
+#[derive(Component)]
+pub struct DiagnosticsText;
+
+/// Diagnostics panel (bottom-right), toggled with F3
+pub fn setup_diagnostics_overlay(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(TIME_BAR_STRIP + 10.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        DiagnosticsText,
+    ));
+}
+
+/// Count satellites left visible by the filter
+pub fn measure_visible_satellites(
+    query: Query<&Visibility, With<Satellite>>,
+    mut diagnostics: Diagnostics,
+) {
+    diagnostics.add_measurement(&VISIBLE_SATELLITES, || {
+        query.iter().filter(|visibility| **visibility != Visibility::Hidden).count() as f64
+    });
+}
+
+/// Refresh the diagnostics panel text
+pub fn update_diagnostics_overlay(
+    store: Res<DiagnosticsStore>,
+    keymap: Res<Keymap>,
+    load_state: Option<Res<TleLoadState>>,
+    settings: Res<Settings>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    mut query: Query<(&mut Text, &Visibility), With<DiagnosticsText>>,
+    // Modification time of the TLE cache, read once the satellites are loaded and after each refresh
+    mut cache_time: Local<Option<SystemTime>>,
+) {
+    if refreshed.read().count() > 0 {
+        *cache_time = None;
+    }
+
+    let (mut text, visibility) = match query.single_mut() {
+        Ok(overlay) => overlay,
+        Err(_) => return,
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let smoothed = |path: &DiagnosticPath| store.get(path).and_then(|diagnostic| diagnostic.smoothed());
+    let latest = |path: &DiagnosticPath| store.get(path).and_then(|diagnostic| diagnostic.value());
+    let format_value = |value: Option<f64>, precision: usize| {
+        value.map_or("-".to_string(), |value| format!("{:.*}", precision, value))
+    };
+
+    let cache_age = match load_state.as_deref() {
+        Some(TleLoadState::Loaded(_)) => {
+            if cache_time.is_none() {
+                *cache_time = satellite::tle_loader(&settings).cache_modified();
+            }
+            match cache_time.and_then(|time| time.elapsed().ok()) {
+                Some(age) => format!("{:.1} h", age.as_secs_f64() / 3600.0),
+                None => "unknown".to_string(),
+            }
+        }
+        Some(TleLoadState::Failed(_)) => "unavailable".to_string(),
+        _ => "loading...".to_string(),
+    };
+
+    *text = Text::new(format!(
+        "FPS: {}\n\
+         Propagated satellites: {} ({} SGP4 calls per update)\n\
+         Visible satellites: {}\n\
+         Propagation: {} ms/frame\n\
+         TLE cache age: {}\n\
+         [{}] hide",
+        format_value(smoothed(&FrameTimeDiagnosticsPlugin::FPS), 0),
+        format_value(latest(&PROPAGATED_SATELLITES), 0),
+        format_value(latest(&SGP4_CALLS), 0),
+        format_value(latest(&VISIBLE_SATELLITES), 0),
+        format_value(smoothed(&PROPAGATION_TIME), 2),
+        cache_age,
+        keymap.label(Action::ToggleDiagnostics),
+    ));
+}
+
+/// Toggle the diagnostics panel with F3
+pub fn toggle_diagnostics_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut query: Query<&mut Visibility, With<DiagnosticsText>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleDiagnostics, &keyboard_input) {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
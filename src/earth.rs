@@ -1,5 +1,70 @@
 use bevy::prelude::*;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::shader::ShaderRef;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+
+use crate::config::{Settings, TextureQuality, TextureSettings};
+use crate::coords::{self, GlobeShape};
+use crate::earth_textures::{self, TexturePaths};
+use crate::floating_origin::ScenePosition;
+use crate::notifications::Notify;
 use crate::sun;
+use crate::time_simulation::TimeSimulation;
+
+/// Path of the Earth shader, relative to the assets directory
+const EARTH_SHADER_PATH: &str = "shaders/earth.wgsl";
+/// Cloud drift speed (texture widths per second of real time)
+const CLOUD_DRIFT_SPEED: f32 = 0.002;
+/// Strength of the sun glint on oceans
+const OCEAN_SPECULAR_STRENGTH: f32 = 0.6;
+/// Ambient light level, so the night side isn't pitch black
+const AMBIENT_LIGHT: f32 = 0.08;
+
+/// Textured Earth globe lit by the simulated sun
+pub struct EarthPlugin;
+
+impl Plugin for EarthPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .add_plugins(MaterialPlugin::<EarthMaterial>::default())
+            .add_systems(Startup, spawn_earth)
+            .add_systems(Update, (
+                check_earth_texture_loaded,
+                apply_downloaded_textures,
+                update_earth_material, // Feed the sun direction to the Earth shader
+            ));
+    }
+}
+
+/// Earth surface material: blends day and night textures per fragment using the real sun direction,
+/// adds an ocean specular highlight and an optional drifting cloud layer (see assets/shaders/earth.wgsl)
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct EarthMaterial {
+    /// Direction from Earth's center toward the sun, in scene coordinates (w unused)
+    #[uniform(0)]
+    pub sun_direction: Vec4,
+    /// x: clouds enabled (0/1), y: cloud drift speed, z: ocean specular strength, w: ambient light
+    #[uniform(0)]
+    pub params: Vec4,
+    /// x: city lights brightness cap (yzw unused)
+    #[uniform(0)]
+    pub night_params: Vec4,
+    #[texture(1)]
+    #[sampler(2)]
+    pub day_texture: Handle<Image>,
+    #[texture(3)]
+    #[sampler(4)]
+    pub night_texture: Handle<Image>,
+    #[texture(5)]
+    #[sampler(6)]
+    pub clouds_texture: Option<Handle<Image>>,
+}
+
+impl Material for EarthMaterial {
+    fn fragment_shader() -> ShaderRef {
+        EARTH_SHADER_PATH.into()
+    }
+}
 
 #[derive(Component)]
 pub struct EarthTexture {
@@ -10,8 +75,9 @@ pub struct EarthTexture {
 #[derive(Bundle)]
 pub struct EarthBundle {
     pub mesh: Mesh3d,
-    pub material: MeshMaterial3d<StandardMaterial>,
+    pub material: MeshMaterial3d<EarthMaterial>,
     pub transform: Transform,
+    pub position: ScenePosition,
     pub visibility: Visibility,
     pub earth_texture: EarthTexture,
 }
@@ -19,45 +85,50 @@ pub struct EarthBundle {
 impl EarthBundle {
     pub fn new(
         meshes: &mut ResMut<Assets<Mesh>>,
-        materials: &mut ResMut<Assets<StandardMaterial>>,
+        materials: &mut ResMut<Assets<EarthMaterial>>,
         asset_server: &Res<AssetServer>,
+        textures: &TextureSettings,
+        globe: GlobeShape,
     ) -> Self {
-        let earth_radius = 6371.0;
+        let earth_radius = coords::km_to_scene(coords::EARTH_RADIUS_KM as f32);
 
         // Create custom UV sphere for meaningful texture mapping
         // High resolution (64 sectors, 32 stacks) to ensure smooth poles and horizon
         let mesh_handle = meshes.add(create_uv_sphere(earth_radius, 64, 32));
 
         // Load Earth day and night textures
-        let day_texture_path = "earth_texture.jpg";
-        let night_texture_path = "earth_night_texture.jpg";
-        let day_texture_handle: Handle<Image> = asset_server.load(day_texture_path);
-        let night_texture_handle: Handle<Image> = asset_server.load(night_texture_path);
+        let day_texture_handle: Handle<Image> = asset_server.load(textures.day.clone());
+        let night_texture_handle: Handle<Image> = asset_server.load(textures.night.clone());
+        let clouds_texture_handle: Option<Handle<Image>> =
+            textures.clouds.as_ref().map(|path| asset_server.load(path.clone()));
         println!("Loading Earth textures:");
-        println!("  Day: {}", day_texture_path);
-        println!("  Night: {}", night_texture_path);
-
-        // Create material with day and night textures
-        // Use unlit: false so emissive texture works, but keep lighting uniform via ambient light
-        let material = materials.add(StandardMaterial {
-            // Day texture as base color (shows on lit side)
-            // Night texture as emissive (shows on dark/night side)
-            base_color_texture: Some(day_texture_handle.clone()),
-            emissive_texture: Some(night_texture_handle.clone()),
-            base_color: Color::srgb(1.0, 1.0, 1.0), // Normal brightness
-            metallic: 0.0,
-            perceptual_roughness: 0.7,
-            // Use unlit: false so emissive texture is visible
-            unlit: false,
-            alpha_mode: AlphaMode::Opaque,
-            emissive: LinearRgba::from(Color::srgb(0.4, 0.4, 0.5)), // Emissive for night texture visibility
-            ..default()
+        println!("  Day: {}", textures.day);
+        println!("  Night: {}", textures.night);
+        if let Some(clouds) = &textures.clouds {
+            println!("  Clouds: {}", clouds);
+        }
+
+        // Sun direction is filled in every frame by update_earth_material
+        let material = materials.add(EarthMaterial {
+            sun_direction: Vec4::X,
+            params: Vec4::new(
+                if clouds_texture_handle.is_some() { 1.0 } else { 0.0 },
+                CLOUD_DRIFT_SPEED,
+                OCEAN_SPECULAR_STRENGTH,
+                AMBIENT_LIGHT,
+            ),
+            night_params: Vec4::new(textures.city_lights_max.clamp(0.0, 1.0), 0.0, 0.0, 0.0),
+            day_texture: day_texture_handle.clone(),
+            night_texture: night_texture_handle.clone(),
+            clouds_texture: clouds_texture_handle,
         });
 
         Self {
             mesh: Mesh3d(mesh_handle),
             material: MeshMaterial3d(material),
-            transform: Transform::from_translation(Vec3::ZERO),
+            // Flattened to the WGS84 ellipsoid when `[earth] ellipsoid` is on
+            transform: Transform::from_scale(globe.scale()),
+            position: ScenePosition::default(),
             visibility: Visibility::default(),
             earth_texture: EarthTexture {
                 day_handle: day_texture_handle,
@@ -67,6 +138,98 @@ impl EarthBundle {
     }
 }
 
+/// Download of higher-resolution textures, swapped in when it finishes
+#[derive(Resource)]
+struct TextureDownload {
+    quality: TextureQuality,
+    task: Task<Result<TexturePaths, String>>,
+}
+
+fn spawn_earth(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut earth_materials: ResMut<Assets<EarthMaterial>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    globe: Res<GlobeShape>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let mut textures = settings.textures.clone();
+    let quality = textures.quality;
+    if let Some(paths) = TexturePaths::for_quality(quality) {
+        if paths.exist() {
+            textures.day = paths.day;
+            textures.night = paths.night;
+        } else if textures.download {
+            // Start with the bundled textures so the globe shows up right away
+            println!("Downloading {} Earth textures in the background...", quality.name());
+            let task = AsyncComputeTaskPool::get().spawn(async move { earth_textures::download(quality) });
+            commands.insert_resource(TextureDownload { quality, task });
+        } else {
+            let message = format!(
+                "{} Earth textures not found in assets/textures, using the bundled ones (set download = true under [textures] to fetch them)",
+                quality.name()
+            );
+            eprintln!("Warning: {}", message);
+            notify_writer.write(Notify::warning(message));
+        }
+    }
+
+    commands.spawn(EarthBundle::new(
+        &mut meshes,
+        &mut earth_materials,
+        &asset_server,
+        &textures,
+        *globe,
+    ));
+}
+
+/// Swap the downloaded textures into the Earth material once they are on disk
+fn apply_downloaded_textures(
+    mut commands: Commands,
+    download: Option<ResMut<TextureDownload>>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<EarthMaterial>>,
+    mut query: Query<(&MeshMaterial3d<EarthMaterial>, &mut EarthTexture)>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let mut download = match download {
+        Some(download) => download,
+        None => return,
+    };
+    let result = match block_on(future::poll_once(&mut download.task)) {
+        Some(result) => result,
+        None => return, // Still downloading
+    };
+    let quality = download.quality;
+    commands.remove_resource::<TextureDownload>();
+
+    match result {
+        Ok(paths) => {
+            let day_handle: Handle<Image> = asset_server.load(paths.day.clone());
+            let night_handle: Handle<Image> = asset_server.load(paths.night.clone());
+            for (material_3d, mut earth_texture) in query.iter_mut() {
+                if let Some(material) = materials.get_mut(&material_3d.0) {
+                    material.day_texture = day_handle.clone();
+                    material.night_texture = night_handle.clone();
+                }
+                earth_texture.day_handle = day_handle.clone();
+                earth_texture.night_handle = night_handle.clone();
+            }
+            println!("✓ Switched to {} Earth textures", quality.name());
+            notify_writer.write(Notify::info(format!("Switched to {} Earth textures", quality.name())));
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to download {} Earth textures: {}", quality.name(), e);
+            notify_writer.write(Notify::warning(format!(
+                "Failed to download {} Earth textures: {}",
+                quality.name(),
+                e
+            )));
+        }
+    }
+}
+
 /// Creates a UV Sphere mesh with correct texture coordinates for equirectangular projection
 /// Uses non-indexed geometry to avoid import issues with Indices
 fn create_uv_sphere(radius: f32, sectors: usize, stacks: usize) -> Mesh {
@@ -165,12 +328,11 @@ fn create_uv_sphere(radius: f32, sectors: usize, stacks: usize) -> Mesh {
 
 /// System to verify textures loaded and update material if needed
 pub fn check_earth_texture_loaded(
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut images: ResMut<Assets<Image>>,
-    query: Query<(&EarthTexture, &MeshMaterial3d<StandardMaterial>)>,
+    images: Res<Assets<Image>>,
+    query: Query<&EarthTexture>,
     mut has_logged: Local<bool>,
 ) {
-    for (earth_texture, material_3d) in query.iter() {
+    for earth_texture in query.iter() {
         let day_loaded = images.get(&earth_texture.day_handle).is_some();
         let night_loaded = images.get(&earth_texture.night_handle).is_some();
         
@@ -196,49 +358,20 @@ pub fn check_earth_texture_loaded(
         } else if !*has_logged {
             println!("⏳ Earth textures still loading...");
         }
-
-        // Ensure material is using the textures
-        if let Some(material) = materials.get_mut(&material_3d.0) {
-            if material.base_color_texture.is_none() {
-                material.base_color_texture = Some(earth_texture.day_handle.clone());
-            }
-            if material.emissive_texture.is_none() {
-                material.emissive_texture = Some(earth_texture.night_handle.clone());
-            }
-            material.base_color = Color::WHITE;
-        }
     }
 }
 
-/// System to blend day/night textures based on sun position
-/// Uses emissive texture intensity to show night texture when surface is in shadow
-/// The emissive texture (night) will be more visible when the surface is darker (facing away from sun)
-/// 
-/// Note: StandardMaterial doesn't support per-vertex emissive control, so we rely on the shader's
-/// automatic blending based on lighting. The emissive texture will be more visible in darker areas.
-/// If there's a day/night inversion, we may need to adjust the sun direction or material properties.
-pub fn blend_day_night_textures(
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    query: Query<(&EarthTexture, &MeshMaterial3d<StandardMaterial>)>,
-    _sun_query: Query<(&GlobalTransform, &Name), With<DirectionalLight>>,
-    _time: Res<Time>,
+/// Keep the Earth shader's sun direction in sync with the simulation clock
+pub fn update_earth_material(
+    mut materials: ResMut<Assets<EarthMaterial>>,
+    query: Query<&MeshMaterial3d<EarthMaterial>>,
+    sim_time: Res<TimeSimulation>,
 ) {
-    // The emissive texture blending is handled automatically by the shader based on lighting
-    // The emissive texture (night) will be more visible in darker areas (night side)
-    // If there's a day/night mix up, it's likely because:
-    // 1. The sun direction is inverted in update_sun_position (which we already negate)
-    // 2. The emissive texture needs to be inverted
-    
-    // Since StandardMaterial blends emissive based on lighting automatically,
-    // and the lighting is controlled by update_sun_position (which negates sun_direction),
-    // the emissive should automatically show on the night side.
-    // If it's showing on the day side, we might need to swap base_color_texture and emissive_texture
-    
-    for (_, material_3d) in query.iter() {
+    let sun_direction = sun::calculate_sun_direction(sim_time.current_time());
+
+    for material_3d in query.iter() {
         if let Some(material) = materials.get_mut(&material_3d.0) {
-            // Set emissive intensity to make night texture visible
-            // The shader will automatically make emissive more visible in darker areas (night side)
-            material.emissive = LinearRgba::from(Color::srgb(0.4, 0.4, 0.5)); // Higher emissive for visibility with uniform lighting
+            material.sun_direction = sun_direction.extend(0.0);
         }
     }
-}
\ No newline at end of file
+}
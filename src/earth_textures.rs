@@ -0,0 +1,142 @@
+//! Higher-resolution Earth textures from NASA Visible Earth
+//!
+//! Day: Blue Marble Next Generation (December 2004, with topography and bathymetry).
+//! Night: Black Marble 2016. The source images are downloaded, scaled down to the
+//! chosen quality and saved as JPEG under assets/textures/<quality>/.
+
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::ImageReader;
+
+use crate::config::TextureQuality;
+
+/// Bevy's asset directory, which texture paths are relative to
+const ASSETS_DIR: &str = "assets";
+/// Downloaded textures, relative to the assets directory
+const TEXTURES_DIR: &str = "textures";
+const JPEG_QUALITY: u8 = 90;
+/// The largest source images are tens of megabytes
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A NASA image and its width in pixels (equirectangular, so the height is half)
+struct SourceImage {
+    width: u32,
+    url: &'static str,
+}
+
+const DAY_SOURCES: &[SourceImage] = &[
+    SourceImage {
+        width: 5400,
+        url: "https://eoimages.gsfc.nasa.gov/images/imagerecords/73000/73909/world.topo.bathy.200412.3x5400x2700.jpg",
+    },
+    SourceImage {
+        width: 21600,
+        url: "https://eoimages.gsfc.nasa.gov/images/imagerecords/73000/73909/world.topo.bathy.200412.3x21600x10800.jpg",
+    },
+];
+
+const NIGHT_SOURCES: &[SourceImage] = &[
+    SourceImage {
+        width: 3600,
+        url: "https://eoimages.gsfc.nasa.gov/images/imagerecords/144000/144898/BlackMarble_2016_01deg.jpg",
+    },
+    SourceImage {
+        width: 13500,
+        url: "https://eoimages.gsfc.nasa.gov/images/imagerecords/144000/144898/BlackMarble_2016_3km.jpg",
+    },
+];
+
+/// Day and night texture paths, relative to the assets directory
+#[derive(Debug, Clone)]
+pub struct TexturePaths {
+    pub day: String,
+    pub night: String,
+}
+
+impl TexturePaths {
+    /// Where the textures for a quality level live (None for the bundled textures)
+    pub fn for_quality(quality: TextureQuality) -> Option<Self> {
+        quality.width()?;
+        let dir = format!("{}/{}", TEXTURES_DIR, quality.name());
+        Some(Self {
+            day: format!("{}/earth_day.jpg", dir),
+            night: format!("{}/earth_night.jpg", dir),
+        })
+    }
+
+    /// Whether both textures are on disk
+    pub fn exist(&self) -> bool {
+        asset_file(&self.day).exists() && asset_file(&self.night).exists()
+    }
+}
+
+fn asset_file(path: &str) -> PathBuf {
+    Path::new(ASSETS_DIR).join(path)
+}
+
+/// Smallest source at least `width` wide, or the largest one if none is
+fn pick_source(sources: &[SourceImage], width: u32) -> &SourceImage {
+    sources
+        .iter()
+        .find(|source| source.width >= width)
+        .unwrap_or_else(|| &sources[sources.len() - 1])
+}
+
+/// Download the day and night textures for a quality level (blocking; run off the main thread)
+pub fn download(quality: TextureQuality) -> Result<TexturePaths, String> {
+    let (paths, width) = match (TexturePaths::for_quality(quality), quality.width()) {
+        (Some(paths), Some(width)) => (paths, width),
+        _ => return Err("The bundled textures are not downloaded".to_string()),
+    };
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    fetch_scaled(&client, pick_source(DAY_SOURCES, width), width, &asset_file(&paths.day))?;
+    fetch_scaled(&client, pick_source(NIGHT_SOURCES, width), width, &asset_file(&paths.night))?;
+    Ok(paths)
+}
+
+/// Download one image, scale it down to `width` if it is wider, and save it as JPEG
+fn fetch_scaled(client: &reqwest::blocking::Client, source: &SourceImage, width: u32, dest: &Path) -> Result<(), String> {
+    if dest.exists() {
+        return Ok(());
+    }
+    println!("Downloading {}...", source.url);
+    let bytes = client
+        .get(source.url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.bytes())
+        .map_err(|e| format!("{}: {}", source.url, e))?;
+
+    // The largest images exceed the decoder's default memory limit
+    let mut reader = ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    reader.no_limits();
+    let mut image = reader.decode().map_err(|e| format!("{}: {}", source.url, e))?;
+    if image.width() > width {
+        image = image.resize_exact(width, width / 2, FilterType::Triangle);
+    }
+
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // Write under a temporary name so an interrupted download is not mistaken for a texture
+    let partial = dest.with_extension("part");
+    let file = fs::File::create(&partial).map_err(|e| e.to_string())?;
+    image
+        .into_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(BufWriter::new(file), JPEG_QUALITY))
+        .map_err(|e| e.to_string())?;
+    fs::rename(&partial, dest).map_err(|e| e.to_string())?;
+    println!("✓ Saved {} ({}x{})", dest.display(), width.min(source.width), width.min(source.width) / 2);
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::ephemeris::{self, Illumination};
+use crate::floating_origin::ScenePosition;
+use crate::groups::apply_group_colors;
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::{apply_selection_highlight, HighlightedMaterial};
+use crate::time_simulation::TimeSimulation;
+
+/// Sunlit / Earth-shadow state of every satellite, with shadowed satellites drawn dimmed
+pub struct EclipsePlugin;
+
+impl Plugin for EclipsePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EclipseMaterials>()
+            .add_systems(Update, (
+                update_illumination.after(update_satellite_positions),
+                apply_eclipse_colors.after(apply_group_colors).after(apply_selection_highlight),
+            ).chain());
+    }
+}
+
+/// Share of the lit color kept by a satellite in the penumbra
+const PENUMBRA_BRIGHTNESS: f32 = 0.5;
+/// Share of the lit color kept by a satellite in the umbra
+const UMBRA_BRIGHTNESS: f32 = 0.2;
+
+/// Whether a satellite currently sees the sun
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SatelliteIllumination(pub Illumination);
+
+/// Dimmed copies of the satellite materials, created on first use
+///
+/// Satellites share a few materials (the default one and one per group color),
+/// so each gets one darker copy per shadow state rather than one per satellite.
+#[derive(Resource, Default)]
+pub struct EclipseMaterials {
+    /// (lit material, shadow state) -> dimmed copy
+    dimmed: HashMap<(AssetId<StandardMaterial>, Illumination), Handle<StandardMaterial>>,
+    /// Dimmed copy -> the lit material it was made from
+    lit: HashMap<AssetId<StandardMaterial>, Handle<StandardMaterial>>,
+}
+
+impl EclipseMaterials {
+    /// Lit material behind `handle`, which may itself be a dimmed copy
+    fn lit_of(&self, handle: &Handle<StandardMaterial>) -> Handle<StandardMaterial> {
+        self.lit.get(&handle.id()).cloned().unwrap_or_else(|| handle.clone())
+    }
+
+    /// Material to draw a satellite wearing `lit` with
+    fn for_state(
+        &mut self,
+        lit: Handle<StandardMaterial>,
+        illumination: Illumination,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        let brightness = match illumination {
+            Illumination::Sunlit => return lit,
+            Illumination::Penumbra => PENUMBRA_BRIGHTNESS,
+            Illumination::Umbra => UMBRA_BRIGHTNESS,
+        };
+        if let Some(dimmed) = self.dimmed.get(&(lit.id(), illumination)) {
+            return dimmed.clone();
+        }
+
+        let mut material = match materials.get(&lit) {
+            Some(material) => material.clone(),
+            None => return lit,
+        };
+        let base = material.base_color.to_linear();
+        material.base_color =
+            LinearRgba::new(base.red * brightness, base.green * brightness, base.blue * brightness, base.alpha).into();
+        material.emissive *= brightness;
+        let dimmed = materials.add(material);
+
+        self.dimmed.insert((lit.id(), illumination), dimmed.clone());
+        self.lit.insert(dimmed.id(), lit);
+        dimmed
+    }
+}
+
+/// Check every satellite against Earth's shadow at the simulated time
+pub fn update_illumination(
+    sim_time: Res<TimeSimulation>,
+    mut satellite_query: Query<(&ScenePosition, &mut SatelliteIllumination), With<Satellite>>,
+) {
+    let sun_direction = ephemeris::sun_direction_ecef(sim_time.current_time());
+    for (position, mut illumination) in satellite_query.iter_mut() {
+        let state = ephemeris::illumination(position.ecef(), sun_direction);
+        illumination.set_if_neq(SatelliteIllumination(state));
+    }
+}
+
+/// Swap satellites to a dimmed material while they are in shadow
+pub fn apply_eclipse_colors(
+    mut eclipse_materials: ResMut<EclipseMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut satellite_query: Query<(
+        Ref<SatelliteIllumination>,
+        &mut MeshMaterial3d<StandardMaterial>,
+        Option<&mut HighlightedMaterial>,
+    )>,
+) {
+    for (illumination, mut material, highlighted) in satellite_query.iter_mut() {
+        // Group colors and the selection highlight swap materials too; follow whatever they picked
+        match highlighted {
+            // The selected satellite wears the highlight; dim what it goes back to instead
+            Some(mut highlighted) => {
+                let lit = eclipse_materials.lit_of(&highlighted.0);
+                let target = eclipse_materials.for_state(lit, illumination.0, &mut materials);
+                if highlighted.0 != target {
+                    highlighted.0 = target;
+                }
+            }
+            None => {
+                if !illumination.is_changed() && !material.is_changed() {
+                    continue;
+                }
+                let lit = eclipse_materials.lit_of(&material.0);
+                let target = eclipse_materials.for_state(lit, illumination.0, &mut materials);
+                if material.0 != target {
+                    material.0 = target;
+                }
+            }
+        }
+    }
+}
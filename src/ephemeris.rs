@@ -0,0 +1,220 @@
+//! Sun position (Astronomical Almanac low-precision formulas), good enough for lighting,
+//! day/night boundaries and eclipses
+
+use chrono::{DateTime, TimeZone, Timelike, Utc};
+use nalgebra::Vector3;
+
+use crate::coords::{Geodetic, EARTH_RADIUS_KM};
+
+/// Apparent position of the sun from the Astronomical Almanac's low-precision
+/// formulas, accurate to about 0.01° between 1950 and 2050
+struct SolarPosition {
+    /// Mean longitude, corrected for aberration (degrees)
+    mean_longitude_deg: f64,
+    /// Right ascension (degrees, 0..360)
+    right_ascension_deg: f64,
+    /// Declination (degrees)
+    declination_deg: f64,
+}
+
+fn solar_position(time: DateTime<Utc>) -> SolarPosition {
+    let j2000 = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+    let days = (time - j2000).num_milliseconds() as f64 / 86_400_000.0;
+
+    let mean_longitude_deg = (280.460 + 0.9856474 * days).rem_euclid(360.0);
+    let mean_anomaly = (357.528 + 0.9856003 * days).to_radians();
+    let ecliptic_longitude =
+        (mean_longitude_deg + 1.915 * mean_anomaly.sin() + 0.020 * (2.0 * mean_anomaly).sin()).to_radians();
+    let obliquity = (23.439 - 0.0000004 * days).to_radians();
+
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+
+    SolarPosition {
+        mean_longitude_deg,
+        right_ascension_deg: right_ascension.to_degrees().rem_euclid(360.0),
+        declination_deg: declination.to_degrees(),
+    }
+}
+
+/// Wrap an angle into [-180°, 180°)
+fn wrap_degrees(angle_deg: f64) -> f64 {
+    (angle_deg + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Equation of time (minutes): apparent solar time minus mean solar time
+///
+/// Positive when a sundial runs ahead of the clock, e.g. about +16 min in early
+/// November and about -14 min in mid-February.
+pub fn equation_of_time_minutes(time: DateTime<Utc>) -> f64 {
+    let sun = solar_position(time);
+    // 4 minutes of time per degree of hour angle
+    4.0 * wrap_degrees(sun.mean_longitude_deg - sun.right_ascension_deg)
+}
+
+/// Point on the Earth where the sun is directly overhead
+///
+/// The latitude is the solar declination. The longitude is where it is local
+/// apparent noon: mean solar noon is at longitude 0° at 12:00 UTC, shifted by
+/// the equation of time (up to about 4° either way).
+pub fn subsolar_point(time: DateTime<Utc>) -> Geodetic {
+    let sun = solar_position(time);
+    let equation_of_time_deg = wrap_degrees(sun.mean_longitude_deg - sun.right_ascension_deg);
+
+    // Solar hour angle = 15° per hour since solar noon; positive hour angle = west of the sun
+    let hours_since_midnight = time.hour() as f64
+        + time.minute() as f64 / 60.0
+        + (time.second() as f64 + time.nanosecond() as f64 / 1e9) / 3600.0;
+    let hour_angle_deg = (hours_since_midnight - 12.0) * 15.0 + equation_of_time_deg;
+
+    Geodetic::new(sun.declination_deg, wrap_degrees(-hour_angle_deg), 0.0)
+}
+
+/// Unit vector from Earth's center toward the sun, in the Earth-fixed (ECEF) frame
+pub fn sun_direction_ecef(time: DateTime<Utc>) -> Vector3<f64> {
+    let subsolar = subsolar_point(time);
+    let latitude = subsolar.latitude_deg.to_radians();
+    let longitude = subsolar.longitude_deg.to_radians();
+    Vector3::new(
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    )
+}
+
+/// Mean Earth-Sun distance (km)
+const SUN_DISTANCE_KM: f64 = 149_597_870.7;
+/// Radius of the sun (km)
+const SUN_RADIUS_KM: f64 = 696_000.0;
+
+/// How much of the sun a satellite can see
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Illumination {
+    #[default]
+    Sunlit,
+    /// Partly shadowed: the Earth covers part of the solar disc
+    Penumbra,
+    /// Fully in Earth's shadow
+    Umbra,
+}
+
+impl Illumination {
+    pub fn name(self) -> &'static str {
+        match self {
+            Illumination::Sunlit => "sunlit",
+            Illumination::Penumbra => "penumbra",
+            Illumination::Umbra => "eclipsed (umbra)",
+        }
+    }
+}
+
+/// Whether a point is sunlit or in Earth's shadow, using a conical shadow model
+///
+/// `position` and `sun_direction` must be in the same Earth-centered frame
+/// (`sun_direction` a unit vector toward the sun). The sun is taken at its mean
+/// distance, which moves the shadow edges by well under a kilometer at LEO.
+pub fn illumination(position: Vector3<f64>, sun_direction: Vector3<f64>) -> Illumination {
+    // Distance behind the Earth along the shadow axis; the day side is always lit
+    let behind = -position.dot(&sun_direction);
+    if behind <= 0.0 {
+        return Illumination::Sunlit;
+    }
+    let off_axis = (position + sun_direction * behind).norm();
+
+    // The umbra narrows to a point behind the Earth, the penumbra widens
+    let umbra_angle = ((SUN_RADIUS_KM - EARTH_RADIUS_KM) / SUN_DISTANCE_KM).asin();
+    let penumbra_angle = ((SUN_RADIUS_KM + EARTH_RADIUS_KM) / SUN_DISTANCE_KM).asin();
+    let umbra_radius = (EARTH_RADIUS_KM / umbra_angle.sin() - behind) * umbra_angle.tan();
+    let penumbra_radius = (EARTH_RADIUS_KM / penumbra_angle.sin() + behind) * penumbra_angle.tan();
+
+    if off_axis < umbra_radius {
+        Illumination::Umbra
+    } else if off_axis < penumbra_radius {
+        Illumination::Penumbra
+    } else {
+        Illumination::Sunlit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {} ± {}, got {}",
+            expected,
+            tolerance,
+            actual
+        );
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn declination_at_equinoxes_and_solstices() {
+        // 2024 equinox and solstice instants from the US Naval Observatory
+        assert_close(subsolar_point(utc(2024, 3, 20, 3, 6)).latitude_deg, 0.0, 0.02);
+        assert_close(subsolar_point(utc(2024, 6, 20, 20, 51)).latitude_deg, 23.44, 0.02);
+        assert_close(subsolar_point(utc(2024, 9, 22, 12, 44)).latitude_deg, 0.0, 0.02);
+        assert_close(subsolar_point(utc(2024, 12, 21, 9, 21)).latitude_deg, -23.44, 0.02);
+    }
+
+    #[test]
+    fn equation_of_time_extremes() {
+        // Almanac values: -14.2 min on 11 February, +16.4 min on 3 November
+        assert_close(equation_of_time_minutes(utc(2024, 2, 11, 12, 0)), -14.2, 0.1);
+        assert_close(equation_of_time_minutes(utc(2024, 11, 3, 12, 0)), 16.4, 0.1);
+    }
+
+    #[test]
+    fn equation_of_time_vanishes_four_times_a_year() {
+        for time in [
+            utc(2024, 4, 15, 12, 0),
+            utc(2024, 6, 13, 12, 0),
+            utc(2024, 9, 1, 12, 0),
+            utc(2024, 12, 25, 12, 0),
+        ] {
+            assert_close(equation_of_time_minutes(time), 0.0, 0.6);
+        }
+    }
+
+    #[test]
+    fn subsolar_longitude_includes_equation_of_time() {
+        // At 12:00 UTC on 3 November the sun runs 16.4 min ahead, so it is
+        // overhead about 4.1° west of Greenwich
+        let subsolar = subsolar_point(utc(2024, 11, 3, 12, 0));
+        assert_close(subsolar.latitude_deg, -15.3, 0.05);
+        assert_close(subsolar.longitude_deg, -4.1, 0.05);
+
+        // March equinox 2024: over the western Pacific, near 135.35°E
+        assert_close(subsolar_point(utc(2024, 3, 20, 3, 6)).longitude_deg, 135.35, 0.05);
+    }
+
+    #[test]
+    fn subsolar_point_at_j2000() {
+        // Astronomical Almanac 2000: declination -23.03°, equation of time -3.3 min
+        let subsolar = subsolar_point(utc(2000, 1, 1, 12, 0));
+        assert_close(subsolar.latitude_deg, -23.03, 0.02);
+        assert_close(subsolar.longitude_deg, 0.83, 0.05);
+    }
+
+    #[test]
+    fn sun_direction_is_a_unit_vector() {
+        let direction = sun_direction_ecef(utc(2024, 6, 20, 20, 51));
+        assert_close(direction.norm(), 1.0, 1e-12);
+        assert!(direction.z > 0.0, "June sun is over the northern hemisphere");
+    }
+
+    #[test]
+    fn illumination_day_side_and_shadow() {
+        let sun = Vector3::new(1.0, 0.0, 0.0);
+        let leo = EARTH_RADIUS_KM + 400.0;
+        assert_eq!(illumination(Vector3::new(leo, 0.0, 0.0), sun), Illumination::Sunlit);
+        assert_eq!(illumination(Vector3::new(-leo, 0.0, 0.0), sun), Illumination::Umbra);
+        assert_eq!(illumination(Vector3::new(-leo, 0.0, leo), sun), Illumination::Sunlit);
+    }
+}
@@ -0,0 +1,775 @@
+//! Export of ground tracks (GeoJSON, KML) for GIS tools such as QGIS or Google Earth,
+//! of ephemerides (CCSDS OEM, CSV) for flight dynamics tools, and of pass schedules
+//! (iCalendar) for calendar apps
+
+use chrono::{DateTime, Duration, DurationRound, SecondsFormat, Utc};
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sgp4::Elements;
+
+use crate::coords;
+use crate::passes::Pass;
+use crate::propagation::Propagator;
+
+/// File format of an exported ground track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackFormat {
+    GeoJson,
+    Kml,
+}
+
+impl TrackFormat {
+    /// Usual file extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            TrackFormat::GeoJson => "geojson",
+            TrackFormat::Kml => "kml",
+        }
+    }
+}
+
+/// Sub-satellite points of one satellite over a time window
+///
+/// The track is split into segments wherever it crosses the antimeridian (so
+/// GIS tools don't draw a line across the whole map) or propagation fails.
+pub struct GroundTrack {
+    pub name: String,
+    pub norad_id: u64,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Continuous runs of [longitude, latitude] points (degrees)
+    pub segments: Vec<Vec<[f64; 2]>>,
+}
+
+impl GroundTrack {
+    /// Sample the track from `start` to `end` every `step`
+    pub fn compute(
+        name: &str,
+        norad_id: u64,
+        propagator: &Propagator,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step: Duration,
+    ) -> Self {
+        let mut segments = Vec::new();
+        let mut segment: Vec<[f64; 2]> = Vec::new();
+
+        let mut time = start;
+        while time <= end {
+            let point = propagator.propagate(time).map(|position| {
+                let geodetic = coords::teme_to_geodetic(position, time);
+                [geodetic.longitude_deg, geodetic.latitude_deg]
+            });
+            match (point, segment.last().copied()) {
+                (Some(point), Some(previous)) if (point[0] - previous[0]).abs() > 180.0 => {
+                    // Close the segment on the antimeridian and continue from the other side
+                    let edge = if previous[0] > 0.0 { 180.0 } else { -180.0 };
+                    let unwrapped = point[0] + 2.0 * edge;
+                    let fraction = (edge - previous[0]) / (unwrapped - previous[0]);
+                    let latitude = previous[1] + fraction * (point[1] - previous[1]);
+                    segment.push([edge, latitude]);
+                    segments.push(std::mem::take(&mut segment));
+                    segment.push([-edge, latitude]);
+                    segment.push(point);
+                }
+                (Some(point), _) => segment.push(point),
+                (None, _) => {
+                    if segment.len() > 1 {
+                        segments.push(std::mem::take(&mut segment));
+                    }
+                    segment.clear();
+                }
+            }
+            time += step;
+        }
+        if segment.len() > 1 {
+            segments.push(segment);
+        }
+
+        Self {
+            name: name.to_string(),
+            norad_id,
+            start,
+            end,
+            segments,
+        }
+    }
+}
+
+/// Write tracks in the given format
+pub fn render_tracks(tracks: &[GroundTrack], format: TrackFormat) -> String {
+    match format {
+        TrackFormat::GeoJson => to_geojson(tracks),
+        TrackFormat::Kml => to_kml(tracks),
+    }
+}
+
+/// GeoJSON FeatureCollection with one LineString (or MultiLineString) feature per track
+pub fn to_geojson(tracks: &[GroundTrack]) -> String {
+    let features: Vec<_> = tracks
+        .iter()
+        .map(|track| {
+            let geometry = match track.segments.as_slice() {
+                [segment] => json!({ "type": "LineString", "coordinates": segment }),
+                segments => json!({ "type": "MultiLineString", "coordinates": segments }),
+            };
+            json!({
+                "type": "Feature",
+                "properties": {
+                    "name": track.name,
+                    "norad_id": track.norad_id,
+                    "start": track.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    "end": track.end.to_rfc3339_opts(SecondsFormat::Secs, true),
+                },
+                "geometry": geometry,
+            })
+        })
+        .collect();
+
+    let collection = json!({ "type": "FeatureCollection", "features": features });
+    serde_json::to_string_pretty(&collection).unwrap_or_default() + "\n"
+}
+
+/// KML document with one placemark per track, clamped to the ground
+pub fn to_kml(tracks: &[GroundTrack]) -> String {
+    let mut kml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n\
+         <Document>\n\
+         <Style id=\"track\"><LineStyle><color>ff00a5ff</color><width>2</width></LineStyle></Style>\n",
+    );
+    for track in tracks {
+        kml.push_str(&format!(
+            "<Placemark>\n<name>{}</name>\n<description>NORAD {}, {} to {}</description>\n<styleUrl>#track</styleUrl>\n<MultiGeometry>\n",
+            xml_escape(&track.name),
+            track.norad_id,
+            track.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+            track.end.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ));
+        for segment in &track.segments {
+            let coordinates: Vec<String> = segment
+                .iter()
+                .map(|[longitude, latitude]| format!("{:.5},{:.5},0", longitude, latitude))
+                .collect();
+            kml.push_str(&format!(
+                "<LineString><tessellate>1</tessellate><coordinates>{}</coordinates></LineString>\n",
+                coordinates.join(" "),
+            ));
+        }
+        kml.push_str("</MultiGeometry>\n</Placemark>\n");
+    }
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Reference frame of exported ephemeris states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EphemerisFrame {
+    /// True Equator Mean Equinox, the inertial frame SGP4 outputs
+    Teme,
+    /// Earth-fixed, with velocities relative to the rotating Earth
+    Ecef,
+}
+
+impl EphemerisFrame {
+    /// Parse "teme" or "ecef"
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "teme" => Ok(EphemerisFrame::Teme),
+            "ecef" => Ok(EphemerisFrame::Ecef),
+            other => Err(format!("Unknown frame \"{}\" (expected teme or ecef)", other)),
+        }
+    }
+
+    /// `REF_FRAME` value in an OEM (ECEF states are given in ITRF, of which WGS84 is a realization)
+    fn oem_name(self) -> &'static str {
+        match self {
+            EphemerisFrame::Teme => "TEME",
+            EphemerisFrame::Ecef => "ITRF",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            EphemerisFrame::Teme => "TEME",
+            EphemerisFrame::Ecef => "ECEF",
+        }
+    }
+}
+
+/// File format of an exported ephemeris
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EphemerisFormat {
+    /// CCSDS Orbit Ephemeris Message, keyword = value notation
+    Oem,
+    Csv,
+}
+
+impl EphemerisFormat {
+    /// Usual file extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            EphemerisFormat::Oem => "oem",
+            EphemerisFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Position (km) and velocity (km/s) at one epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateVector {
+    pub time: DateTime<Utc>,
+    pub position: Vector3<f64>,
+    pub velocity: Vector3<f64>,
+}
+
+/// Propagated states of one satellite over a time window
+pub struct Ephemeris {
+    pub name: String,
+    pub norad_id: u64,
+    /// COSPAR ID (e.g. "1998-067A"), if known
+    pub international_designator: Option<String>,
+    pub frame: EphemerisFrame,
+    /// States in time order; epochs where propagation fails are left out
+    pub states: Vec<StateVector>,
+}
+
+impl Ephemeris {
+    /// Sample states from `start` to `end` every `step`; identifiers are taken from `elements`
+    pub fn compute(
+        name: &str,
+        elements: &Elements,
+        propagator: &Propagator,
+        frame: EphemerisFrame,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step: Duration,
+    ) -> Self {
+        let mut states = Vec::new();
+        let mut time = start;
+        while time <= end {
+            if let Some((position, velocity)) = propagator.propagate_state(time) {
+                let (position, velocity) = match frame {
+                    EphemerisFrame::Teme => (position, velocity),
+                    EphemerisFrame::Ecef => (
+                        coords::teme_to_ecef(position, time),
+                        coords::teme_to_ecef_velocity(position, velocity, time),
+                    ),
+                };
+                states.push(StateVector { time, position, velocity });
+            }
+            time += step;
+        }
+
+        Self {
+            name: name.to_string(),
+            norad_id: elements.norad_id,
+            international_designator: elements.international_designator.clone(),
+            frame,
+            states,
+        }
+    }
+}
+
+/// Write ephemerides in the given format
+pub fn render_ephemerides(ephemerides: &[Ephemeris], format: EphemerisFormat) -> String {
+    match format {
+        EphemerisFormat::Oem => to_oem(ephemerides, Utc::now()),
+        EphemerisFormat::Csv => to_ephemeris_csv(ephemerides),
+    }
+}
+
+/// OEM epoch format (UTC, no zone suffix)
+fn oem_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S%.3f").to_string()
+}
+
+/// CCSDS OEM 2.0 (KVN), one metadata and data block per satellite
+///
+/// Satellites without any propagated state are left out.
+pub fn to_oem(ephemerides: &[Ephemeris], created: DateTime<Utc>) -> String {
+    let mut oem = format!(
+        "CCSDS_OEM_VERS = 2.0\n\
+         CREATION_DATE = {}\n\
+         ORIGINATOR = AI Space Tracker\n",
+        oem_time(created),
+    );
+    for ephemeris in ephemerides {
+        let (first, last) = match (ephemeris.states.first(), ephemeris.states.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => continue,
+        };
+        let object_id = ephemeris
+            .international_designator
+            .clone()
+            .unwrap_or_else(|| ephemeris.norad_id.to_string());
+
+        oem.push_str(&format!(
+            "\nMETA_START\n\
+             OBJECT_NAME = {}\n\
+             OBJECT_ID = {}\n\
+             CENTER_NAME = EARTH\n\
+             REF_FRAME = {}\n\
+             TIME_SYSTEM = UTC\n\
+             START_TIME = {}\n\
+             STOP_TIME = {}\n\
+             META_STOP\n\n\
+             COMMENT Propagated with SGP4 from TLE/OMM mean elements, NORAD {}\n",
+            ephemeris.name,
+            object_id,
+            ephemeris.frame.oem_name(),
+            oem_time(first.time),
+            oem_time(last.time),
+            ephemeris.norad_id,
+        ));
+        for state in &ephemeris.states {
+            oem.push_str(&format!(
+                "{} {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}\n",
+                oem_time(state.time),
+                state.position.x,
+                state.position.y,
+                state.position.z,
+                state.velocity.x,
+                state.velocity.y,
+                state.velocity.z,
+            ));
+        }
+    }
+    oem
+}
+
+/// CSV with one line per state: epoch, satellite, frame, position (km) and velocity (km/s)
+pub fn to_ephemeris_csv(ephemerides: &[Ephemeris]) -> String {
+    let mut csv = String::from("epoch,name,norad_id,frame,x_km,y_km,z_km,vx_km_s,vy_km_s,vz_km_s\n");
+    for ephemeris in ephemerides {
+        let name = if ephemeris.name.contains(',') || ephemeris.name.contains('"') {
+            format!("\"{}\"", ephemeris.name.replace('"', "\"\""))
+        } else {
+            ephemeris.name.clone()
+        };
+        for state in &ephemeris.states {
+            csv.push_str(&format!(
+                "{},{},{},{},{:.6},{:.6},{:.6},{:.9},{:.9},{:.9}\n",
+                state.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+                name,
+                ephemeris.norad_id,
+                ephemeris.frame.name(),
+                state.position.x,
+                state.position.y,
+                state.position.z,
+                state.velocity.x,
+                state.velocity.y,
+                state.velocity.z,
+            ));
+        }
+    }
+    csv
+}
+
+/// A pass of a satellite over a ground station, as a calendar event
+#[derive(Debug, Clone)]
+pub struct PassEvent {
+    pub satellite: String,
+    pub norad_id: u64,
+    pub station: String,
+    pub pass: Pass,
+}
+
+/// iCalendar date-time (UTC)
+fn ical_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape iCalendar TEXT values
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folded to 75 octets with CRLF endings as RFC 5545 asks
+fn push_ical_line(ical: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            ical.push_str("\r\n ");
+            width = 1;
+        }
+        ical.push(c);
+        width += c.len_utf8();
+    }
+    ical.push_str("\r\n");
+}
+
+/// Passes of one satellite over a station are at least an orbit apart, so culminations
+/// floored to this window tell them apart
+const PASS_UID_WINDOW_MINUTES: i64 = 15;
+
+/// Event UID from the satellite, the station and the culmination time floored to
+/// `PASS_UID_WINDOW_MINUTES`
+///
+/// A TLE refresh moves the predicted pass by a few seconds, so re-importing an export
+/// updates the events rather than duplicating them.
+fn pass_uid(event: &PassEvent) -> String {
+    let station: String = event
+        .station
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let culmination = event.pass.max_elevation_time;
+    let window = culmination
+        .duration_trunc(Duration::minutes(PASS_UID_WINDOW_MINUTES))
+        .unwrap_or(culmination);
+    format!("{}-{}-{}@ai-space-tracker", event.norad_id, station, window.format("%Y%m%dT%H%MZ"))
+}
+
+/// iCalendar (RFC 5545) with one event per pass, from rise (AOS) to set (LOS), the
+/// maximum elevation and azimuths in the description
+pub fn to_ical(events: &[PassEvent], created: DateTime<Utc>) -> String {
+    let mut ical = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//AI Space Tracker//Pass schedule//EN", "CALSCALE:GREGORIAN"] {
+        push_ical_line(&mut ical, line);
+    }
+    for event in events {
+        let pass = &event.pass;
+        let uid = pass_uid(event);
+        let description = format!(
+            "Max elevation {:.1}° at {} UTC\nAOS {} UTC, azimuth {:.0}°\nLOS {} UTC, azimuth {:.0}°\nNORAD {}",
+            pass.max_elevation_deg,
+            pass.max_elevation_time.format("%H:%M:%S"),
+            pass.rise_time.format("%H:%M:%S"),
+            pass.rise_azimuth_deg,
+            pass.set_time.format("%H:%M:%S"),
+            pass.set_azimuth_deg,
+            event.norad_id,
+        );
+        for line in [
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", uid),
+            format!("DTSTAMP:{}", ical_time(created)),
+            format!("DTSTART:{}", ical_time(pass.rise_time)),
+            format!("DTEND:{}", ical_time(pass.set_time)),
+            format!(
+                "SUMMARY:{}",
+                ical_text(&format!("{} over {} (max {:.0}°)", event.satellite, event.station, pass.max_elevation_deg))
+            ),
+            format!("DESCRIPTION:{}", ical_text(&description)),
+            format!("LOCATION:{}", ical_text(&event.station)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ] {
+            push_ical_line(&mut ical, &line);
+        }
+    }
+    push_ical_line(&mut ical, "END:VCALENDAR");
+    ical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::tle_loader::TleData;
+
+    /// ISS-like orbit over 51.8°N 10°E at its epoch, 2000-01-01 12:00 UTC, heading east
+    fn iss() -> (Elements, Propagator, DateTime<Utc>) {
+        let tle = TleData {
+            line1: "1 25544U 98067A   00001.50000000  .00000000  00000-0  00000-0 0  9996".to_string(),
+            line2: "2 25544  51.6400 200.4606 0001000   0.0000  90.0000 15.50000000    18".to_string(),
+            name: String::new(),
+            omm: None,
+        };
+        let elements = tle.to_elements().unwrap();
+        let propagator = Propagator::new(&elements);
+        let epoch = elements.datetime.and_utc();
+        (elements, propagator, epoch)
+    }
+
+    /// One orbit from the epoch, which crosses the antimeridian once
+    fn iss_track() -> GroundTrack {
+        let (_, propagator, epoch) = iss();
+        GroundTrack::compute("ISS <A&B>", 25544, &propagator, epoch, epoch + Duration::minutes(90), Duration::minutes(1))
+    }
+
+    fn event(station: &str, rise_time: DateTime<Utc>) -> PassEvent {
+        PassEvent {
+            satellite: "ISS (ZARYA)".to_string(),
+            norad_id: 25544,
+            station: station.to_string(),
+            pass: Pass {
+                rise_time,
+                rise_azimuth_deg: 200.0,
+                max_elevation_time: rise_time + Duration::minutes(5),
+                max_elevation_deg: 45.0,
+                set_time: rise_time + Duration::minutes(10),
+                set_azimuth_deg: 60.0,
+            },
+        }
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        assert_eq!(ical_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+        assert_eq!(ical_text("Toulouse"), "Toulouse");
+    }
+
+    #[test]
+    fn long_lines_are_folded_at_75_octets() {
+        let line = format!("DESCRIPTION:{}", "Max elevation 45.0° ".repeat(10));
+        let mut ical = String::new();
+        push_ical_line(&mut ical, &line);
+
+        assert!(ical.ends_with("\r\n"));
+        let physical: Vec<&str> = ical.trim_end_matches("\r\n").split("\r\n").collect();
+        assert!(physical.len() > 1);
+        for (i, part) in physical.iter().enumerate() {
+            assert!(part.len() <= 75, "line {} is {} octets", i, part.len());
+            assert_eq!(part.starts_with(' '), i > 0);
+        }
+        // Unfolding drops each CRLF and the space after it
+        assert_eq!(ical.trim_end_matches("\r\n").replace("\r\n ", ""), line);
+
+        let mut short = String::new();
+        push_ical_line(&mut short, "VERSION:2.0");
+        assert_eq!(short, "VERSION:2.0\r\n");
+    }
+
+    #[test]
+    fn uids_survive_a_tle_refresh() {
+        let rise = Utc.with_ymd_and_hms(2024, 3, 1, 12, 34, 10).unwrap();
+        let before = pass_uid(&event("Toulouse, FR", rise));
+        // Culmination at 12:39:10, in the 12:30 window
+        assert_eq!(before, "25544-toulouse--fr-20240301T1230Z@ai-space-tracker");
+        // New elements shift the predicted pass by seconds, not minutes
+        assert_eq!(pass_uid(&event("Toulouse, FR", rise + Duration::seconds(12))), before);
+        assert_ne!(pass_uid(&event("Kiruna", rise)), before);
+        assert_ne!(pass_uid(&event("Toulouse, FR", rise + Duration::minutes(95))), before);
+        assert_ne!(
+            pass_uid(&PassEvent { norad_id: 20580, ..event("Toulouse, FR", rise) }),
+            before
+        );
+    }
+
+    #[test]
+    fn uids_survive_a_shift_across_the_half_minute() {
+        // Rounded to the minute, these rises would be 12:34 and 12:35
+        let rise = Utc.with_ymd_and_hms(2024, 3, 1, 12, 34, 29).unwrap();
+        let before = pass_uid(&event("Toulouse, FR", rise));
+        assert_eq!(pass_uid(&event("Toulouse, FR", rise + Duration::seconds(2))), before);
+        assert_eq!(pass_uid(&event("Toulouse, FR", rise - Duration::seconds(29))), before);
+    }
+
+    #[test]
+    fn tracks_are_split_on_the_antimeridian() {
+        let track = iss_track();
+        assert_eq!(track.segments.len(), 2);
+        let (before, after) = (&track.segments[0], &track.segments[1]);
+        assert_eq!(before.len() + after.len(), 91 + 2);
+
+        // Eastbound: the first segment ends on +180° and the second picks up on -180° at the same latitude
+        let edge = before[before.len() - 1];
+        assert_eq!(edge[0], 180.0);
+        assert_eq!(after[0], [-180.0, edge[1]]);
+        // The edge latitude is interpolated between the samples either side of the crossing
+        let (last, next) = (before[before.len() - 2], after[1]);
+        assert!(last[0] > 0.0 && next[0] < 0.0);
+        assert!(edge[1] >= last[1].min(next[1]) && edge[1] <= last[1].max(next[1]));
+
+        for segment in &track.segments {
+            for pair in segment.windows(2) {
+                assert!((pair[1][0] - pair[0][0]).abs() < 180.0, "jump within a segment: {:?}", pair);
+            }
+        }
+        assert_eq!(before[0][1].round(), 52.0);
+        assert_eq!(before[0][0].round(), 10.0);
+    }
+
+    #[test]
+    fn propagation_failures_end_the_track() {
+        let (_, propagator, epoch) = iss();
+        // Propagation stops MAX_PROPAGATION_DAYS after the epoch
+        let start = epoch + Duration::days(crate::propagation::MAX_PROPAGATION_DAYS) - Duration::minutes(30);
+        let track = GroundTrack::compute("ISS", 25544, &propagator, start, start + Duration::hours(1), Duration::minutes(1));
+        let points: usize = track.segments.iter().map(Vec::len).sum();
+        assert!(points > 25 && points < 40, "{} points", points);
+        assert!(track.segments.iter().all(|segment| segment.len() > 1));
+    }
+
+    #[test]
+    fn geojson_has_one_feature_per_track() {
+        let split = iss_track();
+        let (_, propagator, epoch) = iss();
+        let short = GroundTrack::compute("ISS", 25544, &propagator, epoch, epoch + Duration::minutes(10), Duration::minutes(1));
+
+        let geojson: serde_json::Value = serde_json::from_str(&to_geojson(&[split, short])).unwrap();
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+
+        let feature = &features[0];
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(feature["properties"]["name"], "ISS <A&B>");
+        assert_eq!(feature["properties"]["norad_id"], 25544);
+        assert_eq!(feature["properties"]["start"], "2000-01-01T12:00:00Z");
+        assert_eq!(feature["properties"]["end"], "2000-01-01T13:30:00Z");
+        assert_eq!(feature["geometry"]["type"], "MultiLineString");
+        let lines = feature["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].as_array().unwrap().last().unwrap()[0], 180.0);
+        assert_eq!(lines[1][0][0], -180.0);
+
+        // A track that doesn't cross is a plain LineString of [longitude, latitude] positions
+        let geometry = &features[1]["geometry"];
+        assert_eq!(geometry["type"], "LineString");
+        let positions = geometry["coordinates"].as_array().unwrap();
+        assert_eq!(positions.len(), 11);
+        assert!(positions.iter().all(|position| position.as_array().unwrap().len() == 2));
+        assert_eq!(positions[0][0].as_f64().unwrap().round(), 10.0);
+    }
+
+    #[test]
+    fn kml_has_a_placemark_per_track_and_a_line_per_segment() {
+        let kml = to_kml(&[iss_track()]);
+        assert!(kml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n"));
+        assert!(kml.ends_with("</Document>\n</kml>\n"));
+        assert_eq!(kml.matches("<Placemark>").count(), 1);
+        assert_eq!(kml.matches("</Placemark>").count(), 1);
+        assert!(kml.contains("<name>ISS &lt;A&amp;B&gt;</name>"));
+        assert!(kml.contains("<description>NORAD 25544, 2000-01-01T12:00:00Z to 2000-01-01T13:30:00Z</description>"));
+        assert_eq!(kml.matches("<LineString>").count(), 2);
+
+        // Coordinates are longitude,latitude,altitude tuples, the first line ending on the antimeridian
+        let lines: Vec<&str> = kml
+            .split("<coordinates>")
+            .skip(1)
+            .map(|rest| rest.split("</coordinates>").next().unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            for tuple in line.split(' ') {
+                let values: Vec<f64> = tuple.split(',').map(|value| value.parse().unwrap()).collect();
+                assert_eq!(values.len(), 3);
+                assert_eq!(values[2], 0.0);
+            }
+        }
+        assert!(lines[0].rsplit(' ').next().unwrap().starts_with("180.00000,"));
+        assert!(lines[1].starts_with("-180.00000,"));
+    }
+
+    fn state(time: DateTime<Utc>, position: [f64; 3], velocity: [f64; 3]) -> StateVector {
+        StateVector {
+            time,
+            position: Vector3::from(position),
+            velocity: Vector3::from(velocity),
+        }
+    }
+
+    /// Two satellites with fixed states a minute apart, and one with none
+    fn ephemerides() -> Vec<Ephemeris> {
+        let epoch = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        vec![
+            Ephemeris {
+                name: "ISS (ZARYA)".to_string(),
+                norad_id: 25544,
+                international_designator: Some("1998-067A".to_string()),
+                frame: EphemerisFrame::Teme,
+                states: vec![
+                    state(epoch, [6678.137, 0.0, -12.5], [0.0, 4.7904, 6.0558]),
+                    state(epoch + Duration::seconds(60), [6655.2125, 287.1, 363.0005], [-0.7641, 4.7738, 6.0349]),
+                ],
+            },
+            Ephemeris {
+                name: "No state".to_string(),
+                norad_id: 1,
+                international_designator: None,
+                frame: EphemerisFrame::Teme,
+                states: Vec::new(),
+            },
+            Ephemeris {
+                name: "Debris, \"A\"".to_string(),
+                norad_id: 99999,
+                international_designator: None,
+                frame: EphemerisFrame::Ecef,
+                states: vec![state(epoch + Duration::milliseconds(500), [-42164.0, 1.0, 2.0], [0.1, -0.2, 0.3])],
+            },
+        ]
+    }
+
+    #[test]
+    fn oem_matches_the_ccsds_layout() {
+        let created = Utc.with_ymd_and_hms(2024, 3, 2, 8, 30, 15).unwrap();
+        let expected = "\
+CCSDS_OEM_VERS = 2.0
+CREATION_DATE = 2024-03-02T08:30:15.000
+ORIGINATOR = AI Space Tracker
+
+META_START
+OBJECT_NAME = ISS (ZARYA)
+OBJECT_ID = 1998-067A
+CENTER_NAME = EARTH
+REF_FRAME = TEME
+TIME_SYSTEM = UTC
+START_TIME = 2024-03-01T12:00:00.000
+STOP_TIME = 2024-03-01T12:01:00.000
+META_STOP
+
+COMMENT Propagated with SGP4 from TLE/OMM mean elements, NORAD 25544
+2024-03-01T12:00:00.000 6678.137000 0.000000 -12.500000 0.000000000 4.790400000 6.055800000
+2024-03-01T12:01:00.000 6655.212500 287.100000 363.000500 -0.764100000 4.773800000 6.034900000
+
+META_START
+OBJECT_NAME = Debris, \"A\"
+OBJECT_ID = 99999
+CENTER_NAME = EARTH
+REF_FRAME = ITRF
+TIME_SYSTEM = UTC
+START_TIME = 2024-03-01T12:00:00.500
+STOP_TIME = 2024-03-01T12:00:00.500
+META_STOP
+
+COMMENT Propagated with SGP4 from TLE/OMM mean elements, NORAD 99999
+2024-03-01T12:00:00.500 -42164.000000 1.000000 2.000000 0.100000000 -0.200000000 0.300000000
+";
+        assert_eq!(to_oem(&ephemerides(), created), expected);
+    }
+
+    #[test]
+    fn csv_has_one_line_per_state() {
+        let expected = "\
+epoch,name,norad_id,frame,x_km,y_km,z_km,vx_km_s,vy_km_s,vz_km_s
+2024-03-01T12:00:00.000Z,ISS (ZARYA),25544,TEME,6678.137000,0.000000,-12.500000,0.000000000,4.790400000,6.055800000
+2024-03-01T12:01:00.000Z,ISS (ZARYA),25544,TEME,6655.212500,287.100000,363.000500,-0.764100000,4.773800000,6.034900000
+2024-03-01T12:00:00.500Z,\"Debris, \"\"A\"\"\",99999,ECEF,-42164.000000,1.000000,2.000000,0.100000000,-0.200000000,0.300000000
+";
+        assert_eq!(to_ephemeris_csv(&ephemerides()), expected);
+    }
+
+    #[test]
+    fn ephemerides_are_sampled_from_the_elements() {
+        let (elements, propagator, epoch) = iss();
+        let end = epoch + Duration::minutes(2);
+        let teme = Ephemeris::compute("ISS", &elements, &propagator, EphemerisFrame::Teme, epoch, end, Duration::minutes(1));
+        assert_eq!(teme.norad_id, 25544);
+        assert_eq!(teme.international_designator.as_deref(), Some("1998-067A"));
+        let times: Vec<_> = teme.states.iter().map(|state| state.time).collect();
+        assert_eq!(times, [epoch, epoch + Duration::minutes(1), end]);
+        // About 6790 km from the center at 7.66 km/s
+        assert!((teme.states[0].position.norm() - 6788.0).abs() < 5.0);
+        assert!((teme.states[0].velocity.norm() - 7.66).abs() < 0.05);
+
+        // Same distance from the center in either frame, slower relative to the rotating Earth
+        let ecef = Ephemeris::compute("ISS", &elements, &propagator, EphemerisFrame::Ecef, epoch, end, Duration::minutes(1));
+        assert!((ecef.states[0].position.norm() - teme.states[0].position.norm()).abs() < 1e-6);
+        assert!(ecef.states[0].velocity.norm() < teme.states[0].velocity.norm());
+    }
+}
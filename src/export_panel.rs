@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use std::fs;
+use std::path::Path;
+
+use crate::config::{ExportSettings, Settings};
+use crate::export::{self, Ephemeris, EphemerisFormat, GroundTrack, TrackFormat};
+use crate::satellite::Satellite;
+
+/// Export buttons in the satellite info panel: ground track and ephemeris of the selected satellite
+pub struct ExportPanelPlugin;
+
+impl Plugin for ExportPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .init_resource::<ExportStatus>();
+    }
+}
+
+/// Background of the export buttons (lighter while hovered)
+pub const EXPORT_BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// What an export button writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    GroundTrack(TrackFormat),
+    Ephemeris(EphemerisFormat),
+}
+
+impl ExportKind {
+    /// Buttons shown in the info panel, in order
+    pub const ALL: [ExportKind; 4] = [
+        ExportKind::GroundTrack(TrackFormat::GeoJson),
+        ExportKind::GroundTrack(TrackFormat::Kml),
+        ExportKind::Ephemeris(EphemerisFormat::Oem),
+        ExportKind::Ephemeris(EphemerisFormat::Csv),
+    ];
+
+    /// Button label
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportKind::GroundTrack(TrackFormat::GeoJson) => "GeoJSON",
+            ExportKind::GroundTrack(TrackFormat::Kml) => "KML",
+            ExportKind::Ephemeris(EphemerisFormat::Oem) => "OEM",
+            ExportKind::Ephemeris(EphemerisFormat::Csv) => "CSV",
+        }
+    }
+}
+
+/// Outcome of the last export (the saved path or the error), shown under the export buttons
+#[derive(Resource, Default)]
+pub struct ExportStatus(pub Option<Result<String, String>>);
+
+/// Export satellites from the simulation time onward to a file named after `name`, returning its path
+pub fn write_export(
+    name: &str,
+    satellites: &[&Satellite],
+    kind: ExportKind,
+    start: DateTime<Utc>,
+    settings: &ExportSettings,
+) -> Result<String, String> {
+    let (contents, suffix, extension) = match kind {
+        ExportKind::GroundTrack(format) => {
+            let tracks: Vec<GroundTrack> = satellites
+                .iter()
+                .map(|satellite| {
+                    GroundTrack::compute(
+                        &satellite.name,
+                        satellite.elements.norad_id,
+                        satellite.propagator(),
+                        start,
+                        start + Duration::minutes(settings.track_duration_minutes),
+                        Duration::seconds(settings.track_step_seconds.max(1)),
+                    )
+                })
+                .filter(|track| !track.segments.is_empty())
+                .collect();
+            if tracks.is_empty() {
+                return Err("no satellite can be propagated over this window".to_string());
+            }
+            (export::render_tracks(&tracks, format), "track", format.extension())
+        }
+        ExportKind::Ephemeris(format) => {
+            let ephemerides: Vec<Ephemeris> = satellites
+                .iter()
+                .map(|satellite| {
+                    Ephemeris::compute(
+                        &satellite.name,
+                        &satellite.elements,
+                        satellite.propagator(),
+                        settings.ephemeris_frame,
+                        start,
+                        start + Duration::minutes(settings.ephemeris_duration_minutes),
+                        Duration::seconds(settings.ephemeris_step_seconds.max(1)),
+                    )
+                })
+                .filter(|ephemeris| !ephemeris.states.is_empty())
+                .collect();
+            if ephemerides.is_empty() {
+                return Err("no satellite can be propagated over this window".to_string());
+            }
+            (export::render_ephemerides(&ephemerides, format), "ephemeris", format.extension())
+        }
+    };
+
+    fs::create_dir_all(&settings.directory)
+        .map_err(|e| format!("Failed to create {}: {}", settings.directory, e))?;
+    let file_stem: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let path = Path::new(&settings.directory).join(format!(
+        "{}_{}_{}.{}",
+        file_stem,
+        suffix,
+        start.format("%Y%m%dT%H%M%SZ"),
+        extension,
+    ));
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path.display().to_string())
+}
+
+/// Export a satellite from the simulation time onward when its export button is clicked
+pub fn export_satellite(
+    satellite: &Satellite,
+    kind: ExportKind,
+    start: DateTime<Utc>,
+    settings: &ExportSettings,
+    status: &mut ExportStatus,
+) {
+    status.0 = Some(match write_export(&satellite.name, &[satellite], kind, start, settings) {
+        Ok(path) => {
+            println!("✓ Exported {} to {}", satellite.name, path);
+            Ok(path)
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to export {}: {}", satellite.name, e);
+            Err(e)
+        }
+    });
+}
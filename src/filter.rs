@@ -0,0 +1,386 @@
+//! Satellite filter expressions
+//!
+//! A filter is a space-separated list of terms that must all match:
+//!
+//! - `iss`, `name:iss` — name contains the text (case-insensitive)
+//! - `"space station"`, `name:"space station"` — double quotes keep spaces in a term
+//! - `25544` — a bare number also matches the NORAD catalog number
+//! - `group:starlink` — constellation starts with the text (case-insensitive), the same
+//!   constellations as the satellite browser (STARLINK-1234 → STARLINK)
+//! - `alt>500`, `alt<2000` — mean altitude in km
+//! - `inc>50` — inclination in degrees
+//! - `period<100` — orbital period in minutes
+//! - `norad:25544`, `id=25544` — NORAD catalog number
+//! - `owner:prc`, `country:us` — SATCAT owner code
+//! - `type:payload`, `type:rocket`, `type:debris` — object type, from the SATCAT when loaded
+//!   and from the name otherwise
+//! - `rcs:small`, `rcs:medium`, `rcs:large` — SATCAT radar cross section size
+//! - `launch>=2020` — SATCAT launch year
+//!
+//! Numeric terms accept `<`, `<=`, `>`, `>=`, `=` and `:` (equal). Satellites without
+//! SATCAT data never match the `owner`, `rcs` and `launch` terms.
+
+use chrono::Datelike;
+
+use crate::satcat::{RcsSize, SatcatObjectType};
+use crate::satellite::{ObjectKind, Satellite};
+
+/// Comparison operator of a numeric term
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    fn matches(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Less => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::Greater => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+            Comparison::Equal => (value - threshold).abs() < 1e-9,
+        }
+    }
+}
+
+/// Orbital quantity a numeric term compares against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    AltitudeKm,
+    InclinationDeg,
+    PeriodMinutes,
+    NoradId,
+    LaunchYear,
+}
+
+/// A single filter term
+#[derive(Debug, Clone, PartialEq)]
+pub enum Criterion {
+    /// Name contains the (lowercase) text
+    Name(String),
+    /// Bare number: catalog number equals it, or name contains it
+    NameOrNorad(String, u64),
+    /// Constellation starts with the (uppercase) text
+    Group(String),
+    /// SATCAT owner code equals the text (case-insensitive)
+    Owner(String),
+    ObjectType(SatcatObjectType),
+    Rcs(RcsSize),
+    Numeric {
+        field: Field,
+        comparison: Comparison,
+        value: f64,
+    },
+}
+
+/// Parsed filter: every criterion must match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterExpr {
+    pub criteria: Vec<Criterion>,
+}
+
+impl FilterExpr {
+    /// Parse a filter expression, returning an error message for malformed terms
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let criteria = split_terms(text)?
+            .iter()
+            .map(|term| parse_term(term))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { criteria })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.criteria.is_empty()
+    }
+
+    pub fn matches(&self, satellite: &Satellite) -> bool {
+        self.criteria.iter().all(|criterion| criterion.matches(satellite))
+    }
+}
+
+impl Criterion {
+    fn matches(&self, satellite: &Satellite) -> bool {
+        match self {
+            Criterion::Name(text) => satellite.name.to_lowercase().contains(text.as_str()),
+            Criterion::NameOrNorad(text, norad_id) => {
+                satellite.elements.norad_id == *norad_id || satellite.name.contains(text.as_str())
+            }
+            Criterion::Group(group) => constellation_of(&satellite.name).starts_with(group.as_str()),
+            Criterion::Owner(owner) => satellite.satcat.as_ref().is_some_and(|satcat| satcat.owner.eq_ignore_ascii_case(owner)),
+            Criterion::ObjectType(object_type) => object_type_of(satellite) == *object_type,
+            Criterion::Rcs(size) => satellite.satcat.as_ref().is_some_and(|satcat| satcat.rcs_size == Some(*size)),
+            Criterion::Numeric {
+                field,
+                comparison,
+                value,
+            } => comparison.matches(field_value(satellite, *field), *value),
+        }
+    }
+}
+
+fn field_value(satellite: &Satellite, field: Field) -> f64 {
+    match field {
+        Field::NoradId => satellite.elements.norad_id as f64,
+        // No launch date compares false with everything
+        Field::LaunchYear => satellite
+            .satcat
+            .as_ref()
+            .and_then(|satcat| satcat.launch_date)
+            .map_or(f64::NAN, |date| date.year() as f64),
+        _ => {
+            let params = satellite.orbital_parameters();
+            match field {
+                Field::AltitudeKm => (params.apogee_altitude_km + params.perigee_altitude_km) / 2.0,
+                Field::InclinationDeg => params.inclination_deg,
+                Field::PeriodMinutes => params.period_minutes,
+                Field::NoradId | Field::LaunchYear => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Object type from the SATCAT, or guessed from the name without it
+fn object_type_of(satellite: &Satellite) -> SatcatObjectType {
+    match &satellite.satcat {
+        Some(satcat) => satcat.object_type,
+        None => match satellite.kind {
+            ObjectKind::Payload => SatcatObjectType::Payload,
+            ObjectKind::RocketBody => SatcatObjectType::RocketBody,
+            ObjectKind::Debris => SatcatObjectType::Debris,
+        },
+    }
+}
+
+/// Constellation or family a satellite belongs to, from the first word of its name
+///
+/// "STARLINK-1234" and "ONEWEB-0012" give STARLINK and ONEWEB; "GPS BIIR-2 (PRN 13)"
+/// gives GPS; debris and rocket bodies ("COSMOS 2251 DEB") go with their parent family.
+pub fn constellation_of(name: &str) -> String {
+    let first = name
+        .split(|c: char| c.is_whitespace() || matches!(c, '-' | '(' | '/' | '_'))
+        .find(|word| !word.is_empty())
+        .unwrap_or(name);
+    first.to_uppercase()
+}
+
+/// Split a filter into terms at the whitespace outside double quotes, dropping the quotes
+fn split_terms(text: &str) -> Result<Vec<String>, String> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            c => term.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    Ok(terms)
+}
+
+fn parse_term(term: &str) -> Result<Criterion, String> {
+    // Operators are checked longest first so ">=" isn't read as ">"
+    const OPERATORS: [(&str, Comparison); 6] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+        (":", Comparison::Equal),
+    ];
+
+    let operator = term
+        .char_indices()
+        .find(|(_, c)| matches!(c, '<' | '>' | '=' | ':'))
+        .map(|(index, _)| index);
+    let index = match operator {
+        Some(index) => index,
+        None => {
+            return Ok(match term.parse::<u64>() {
+                Ok(norad_id) => Criterion::NameOrNorad(term.to_string(), norad_id),
+                Err(_) => Criterion::Name(term.to_lowercase()),
+            })
+        }
+    };
+
+    let key = term[..index].to_lowercase();
+    let rest = &term[index..];
+    let (op, comparison) = OPERATORS
+        .iter()
+        .find(|(op, _)| rest.starts_with(op))
+        .copied()
+        .ok_or_else(|| format!("Unknown operator in \"{}\"", term))?;
+    let value = &rest[op.len()..];
+    if value.is_empty() {
+        return Err(format!("Missing value in \"{}\"", term));
+    }
+
+    let field = match key.as_str() {
+        "name" | "group" | "constellation" => {
+            if comparison != Comparison::Equal {
+                return Err(format!("\"{}\" only supports ':'", key));
+            }
+            return Ok(if key == "name" {
+                Criterion::Name(value.to_lowercase())
+            } else {
+                Criterion::Group(value.to_uppercase())
+            });
+        }
+        "owner" | "country" => {
+            if comparison != Comparison::Equal {
+                return Err(format!("\"{}\" only supports ':'", key));
+            }
+            return Ok(Criterion::Owner(value.to_string()));
+        }
+        "type" => {
+            if comparison != Comparison::Equal {
+                return Err(format!("\"{}\" only supports ':'", key));
+            }
+            return match SatcatObjectType::parse(value) {
+                SatcatObjectType::Unknown if !value.eq_ignore_ascii_case("unknown") => {
+                    Err(format!("Unknown object type \"{}\" (payload, rocket, debris or unknown)", value))
+                }
+                object_type => Ok(Criterion::ObjectType(object_type)),
+            };
+        }
+        "rcs" => {
+            if comparison != Comparison::Equal {
+                return Err(format!("\"{}\" only supports ':'", key));
+            }
+            return RcsSize::parse(value)
+                .map(Criterion::Rcs)
+                .ok_or_else(|| format!("Unknown radar size \"{}\" (small, medium or large)", value));
+        }
+        "alt" | "altitude" => Field::AltitudeKm,
+        "inc" | "inclination" => Field::InclinationDeg,
+        "period" => Field::PeriodMinutes,
+        "norad" | "id" => Field::NoradId,
+        "launch" | "launched" => Field::LaunchYear,
+        _ => return Err(format!("Unknown filter key \"{}\"", key)),
+    };
+
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number in \"{}\"", term))?;
+    Ok(Criterion::Numeric {
+        field,
+        comparison,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tle_loader::TleData;
+
+    /// A satellite on an ISS-like orbit: about 420 km up, 51.64° inclination, 92.9 min period
+    fn satellite(name: &str) -> Satellite {
+        let tle = TleData {
+            line1: "1 25544U 98067A   00001.50000000  .00000000  00000-0  00000-0 0  9996".to_string(),
+            line2: "2 25544  51.6400 200.4606 0001000   0.0000  90.0000 15.50000000    18".to_string(),
+            name: name.to_string(),
+            omm: None,
+        };
+        Satellite::new(tle.name.clone(), tle.to_elements().unwrap())
+    }
+
+    fn matches(filter: &str, name: &str) -> bool {
+        FilterExpr::parse(filter).unwrap().matches(&satellite(name))
+    }
+
+    #[test]
+    fn all_terms_must_match() {
+        let filter = FilterExpr::parse(" iss  inc>50 norad:25544 ").unwrap();
+        assert_eq!(
+            filter.criteria,
+            vec![
+                Criterion::Name("iss".to_string()),
+                Criterion::Numeric {
+                    field: Field::InclinationDeg,
+                    comparison: Comparison::Greater,
+                    value: 50.0,
+                },
+                Criterion::Numeric {
+                    field: Field::NoradId,
+                    comparison: Comparison::Equal,
+                    value: 25544.0,
+                },
+            ]
+        );
+        assert!(filter.matches(&satellite("ISS (ZARYA)")));
+        assert!(!filter.matches(&satellite("TIANGONG")));
+        assert!(!matches("iss inc<50", "ISS (ZARYA)"));
+        assert!(matches("25544", "TIANGONG"));
+        assert!(FilterExpr::parse("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn numeric_terms_bound_a_range() {
+        assert!(matches("alt>400 alt<450", "ISS"));
+        assert!(!matches("alt>450 alt<2000", "ISS"));
+        assert!(matches("period>=92 period<=94", "ISS"));
+        assert!(!matches("period>=92 period<92.5", "ISS"));
+        assert!(matches("id=25544", "ISS"));
+        assert!(!matches("norad>25544", "ISS"));
+        // Without SATCAT data there is no launch year to compare
+        assert!(!matches("launch>=1900", "ISS"));
+        assert!(!matches("launch<3000", "ISS"));
+
+        assert_eq!(FilterExpr::parse("alt>abc"), Err("Invalid number in \"alt>abc\"".to_string()));
+        assert_eq!(FilterExpr::parse("alt>="), Err("Missing value in \"alt>=\"".to_string()));
+    }
+
+    #[test]
+    fn quotes_keep_spaces_in_a_term() {
+        let filter = FilterExpr::parse("name:\"Space Station\" alt<500").unwrap();
+        assert_eq!(filter.criteria[0], Criterion::Name("space station".to_string()));
+        assert_eq!(filter.criteria.len(), 2);
+        assert!(filter.matches(&satellite("INTERNATIONAL SPACE STATION")));
+        assert!(matches("\"space station\"", "INTERNATIONAL SPACE STATION"));
+        assert!(!matches("\"station space\"", "INTERNATIONAL SPACE STATION"));
+        assert_eq!(FilterExpr::parse("name:\"space station"), Err("Unterminated quote".to_string()));
+    }
+
+    #[test]
+    fn unknown_keys_and_values_are_errors() {
+        assert_eq!(FilterExpr::parse("iss color:red"), Err("Unknown filter key \"color\"".to_string()));
+        assert_eq!(FilterExpr::parse("name>iss"), Err("\"name\" only supports ':'".to_string()));
+        assert_eq!(FilterExpr::parse("owner>us"), Err("\"owner\" only supports ':'".to_string()));
+        assert!(FilterExpr::parse("type:station").is_err());
+        assert!(FilterExpr::parse("rcs:huge").is_err());
+        assert_eq!(
+            FilterExpr::parse("type:rocket rcs:LARGE").unwrap().criteria,
+            vec![Criterion::ObjectType(SatcatObjectType::RocketBody), Criterion::Rcs(RcsSize::Large)]
+        );
+    }
+
+    #[test]
+    fn groups_are_the_browser_constellations() {
+        assert_eq!(constellation_of("STARLINK-1234"), "STARLINK");
+        assert_eq!(constellation_of("GPS BIIR-2 (PRN 13)"), "GPS");
+        assert_eq!(constellation_of("COSMOS 2251 DEB"), "COSMOS");
+        assert_eq!(constellation_of("ONEWEB/0012"), "ONEWEB");
+        assert_eq!(constellation_of("noaa_19"), "NOAA");
+
+        assert!(matches("group:starlink", "STARLINK-1234"));
+        assert!(matches("group:GPS", "GPS BIIR-2 (PRN 13)"));
+        assert!(matches("constellation:star", "STARLINK-1234"));
+        assert!(!matches("group:link", "STARLINK-1234"));
+        assert!(!matches("group:prn", "GPS BIIR-2 (PRN 13)"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Double-precision scene positions and the origin the render world is placed around
+//!
+//! The simulation keeps positions in f64 (`ScenePosition`); transforms only hold f32
+//! offsets from the `FloatingOrigin`, so precision is spent where the camera looks
+//! rather than on the distance from Earth's center.
+//!
+//! Everything drawn in the 3D scene carries a `ScenePosition`: meshes built in scene
+//! coordinates sit at the default (Earth's center). Render-space values (transforms,
+//! camera rays, gizmos) go through `FloatingOrigin::to_scene` before being compared
+//! with scene positions or coordinate conversions, and back with `to_render`.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::transform::TransformSystems;
+use nalgebra::Vector3;
+
+use crate::camera::CameraController;
+use crate::coords;
+
+/// Derive the transforms of entities with a `ScenePosition` from the floating origin
+pub struct FloatingOriginPlugin;
+
+impl Plugin for FloatingOriginPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FloatingOrigin>()
+            .add_systems(PostUpdate, (
+                recenter_floating_origin,
+                place_scene_positions,
+            ).chain().before(TransformSystems::Propagate));
+    }
+}
+
+/// Distance the camera's focus may stray from the origin before the render world is re-centered (km)
+const RECENTER_DISTANCE_KM: f64 = 1000.0;
+
+/// Position of an entity in the scene frame, in double precision
+///
+/// The entity's `Transform` translation is this position relative to the `FloatingOrigin`.
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq)]
+pub struct ScenePosition(pub DVec3);
+
+impl ScenePosition {
+    /// Scene position in single precision, for geometry that doesn't need more
+    pub fn scene(&self) -> Vec3 {
+        self.0.as_vec3()
+    }
+
+    /// Earth-fixed position (km)
+    pub fn ecef(&self) -> Vector3<f64> {
+        coords::scene_to_ecef_f64(self.0)
+    }
+}
+
+/// Scene position that the render world's origin stands for
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct FloatingOrigin(pub DVec3);
+
+impl FloatingOrigin {
+    /// Render translation of a scene position
+    pub fn to_render(self, scene: DVec3) -> Vec3 {
+        (scene - self.0).as_vec3()
+    }
+
+    /// Scene position of a render translation
+    pub fn to_scene(self, render: Vec3) -> DVec3 {
+        self.0 + render.as_dvec3()
+    }
+}
+
+/// Move the origin onto the camera's orbit center once it gets far from the origin
+///
+/// The orbit center is what the camera closes in on: Earth's center by default, the followed
+/// satellite in chase mode. Runs before the transforms are placed, so the whole render world
+/// moves in the same frame.
+pub fn recenter_floating_origin(mut origin: ResMut<FloatingOrigin>, camera_query: Query<&CameraController>) {
+    let Some(controller) = camera_query.iter().next() else { return };
+    let threshold = RECENTER_DISTANCE_KM * coords::WORLD_SCALE as f64;
+    if controller.orbit_center.distance(origin.0) > threshold {
+        origin.0 = controller.orbit_center;
+    }
+}
+
+/// Move entities to their render translation when they or the origin moved
+pub fn place_scene_positions(
+    origin: Res<FloatingOrigin>,
+    mut query: Query<(Ref<ScenePosition>, &mut Transform)>,
+) {
+    let origin_moved = origin.is_changed();
+    query.par_iter_mut().for_each(|(position, mut transform)| {
+        if origin_moved || position.is_changed() {
+            let translation = origin.to_render(position.0);
+            if transform.translation != translation {
+                transform.translation = translation;
+            }
+        }
+    });
+}
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::config::Settings;
+use crate::coords::{self, Geodetic, GlobeShape, EARTH_RADIUS_KM};
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+
+/// Coverage footprint of the selected satellite on the globe
+pub struct FootprintPlugin;
+
+impl Plugin for FootprintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let min_elevation_deg = app.world().resource::<Settings>().footprint.min_elevation_deg;
+
+        app.insert_resource(FootprintDisplay {
+            enabled: true,
+            min_elevation_deg,
+        })
+        .add_systems(Startup, spawn_footprint)
+        .add_systems(Update, (
+            update_footprint.after(update_satellite_positions),
+            toggle_footprint,
+        ));
+    }
+}
+
+/// Concentric rings the cap is divided into, so it follows the curvature of the globe
+const FOOTPRINT_RINGS: usize = 8;
+/// Segments around the cap
+const FOOTPRINT_SEGMENTS: usize = 72;
+/// Height of the cap above the surface, to keep it from z-fighting with the globe (km)
+const FOOTPRINT_LIFT_KM: f64 = 15.0;
+
+/// Translucent cap covering the ground that sees the selected satellite
+#[derive(Component)]
+pub struct Footprint;
+
+/// Whether the selected satellite's footprint is drawn (toggle with F)
+#[derive(Resource)]
+pub struct FootprintDisplay {
+    pub enabled: bool,
+    /// Elevation above the horizon a ground point needs to count as covered (degrees)
+    pub min_elevation_deg: f64,
+}
+
+/// Earth central angle (radians) from the sub-satellite point to the edge of the footprint,
+/// for a satellite at `altitude_km` seen at least `min_elevation_deg` above the horizon
+pub fn footprint_half_angle(altitude_km: f64, min_elevation_deg: f64) -> f64 {
+    let elevation = min_elevation_deg.clamp(0.0, 90.0).to_radians();
+    let ratio = EARTH_RADIUS_KM / (EARTH_RADIUS_KM + altitude_km.max(0.0));
+    ((ratio * elevation.cos()).acos() - elevation).max(0.0)
+}
+
+/// Triangle list of a spherical cap of `half_angle` around the unit direction `center`
+pub fn cap_positions(center: Vec3, half_angle: f32, radius: f32) -> Vec<[f32; 3]> {
+    // Two directions spanning the plane perpendicular to the center
+    let (u, v) = center.any_orthonormal_pair();
+    let point = |ring: usize, segment: usize| {
+        let angle = half_angle * ring as f32 / FOOTPRINT_RINGS as f32;
+        let azimuth = segment as f32 / FOOTPRINT_SEGMENTS as f32 * std::f32::consts::TAU;
+        let direction = center * angle.cos() + (u * azimuth.cos() + v * azimuth.sin()) * angle.sin();
+        (direction * radius).to_array()
+    };
+
+    let mut positions = Vec::with_capacity(FOOTPRINT_RINGS * FOOTPRINT_SEGMENTS * 6);
+    for ring in 0..FOOTPRINT_RINGS {
+        for segment in 0..FOOTPRINT_SEGMENTS {
+            let (inner, outer) = (ring, ring + 1);
+            let next = segment + 1;
+            positions.extend([
+                point(inner, segment),
+                point(outer, segment),
+                point(outer, next),
+                point(inner, segment),
+                point(outer, next),
+                point(inner, next),
+            ]);
+        }
+    }
+    positions
+}
+
+pub fn spawn_footprint(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, cap_positions(Vec3::Y, 0.0, 0.0));
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(1.0, 0.85, 0.3, 0.2),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        cull_mode: None,
+        double_sided: true,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        Footprint,
+        Name::new("Footprint"),
+    ));
+}
+
+/// Center the cap under the selected satellite, sized from its altitude
+#[allow(clippy::type_complexity)]
+pub fn update_footprint(
+    display: Res<FootprintDisplay>,
+    selected_query: Query<(&Satellite, &ScenePosition), (With<Selected>, Without<Footprint>)>,
+    mut footprint_query: Query<(&Mesh3d, &mut Visibility), With<Footprint>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    globe: Res<GlobeShape>,
+) {
+    let cap = selected_query
+        .iter()
+        .next()
+        .filter(|(satellite, _)| display.enabled && satellite.position.is_some())
+        .map(|(_, position)| {
+            // Follow the drawn (interpolated) position rather than the last keyframe
+            let geodetic = coords::ecef_to_geodetic(position.ecef());
+            let center = coords::geodetic_to_scene(Geodetic::new(geodetic.latitude_deg, geodetic.longitude_deg, 0.0), *globe);
+            let half_angle = footprint_half_angle(geodetic.altitude_km, display.min_elevation_deg);
+            (center.normalize(), half_angle as f32)
+        });
+
+    for (mesh_3d, mut visibility) in footprint_query.iter_mut() {
+        let (center, half_angle) = match cap {
+            Some(cap) => cap,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+        visibility.set_if_neq(Visibility::Visible);
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            let radius = coords::km_to_scene((EARTH_RADIUS_KM + FOOTPRINT_LIFT_KM) as f32);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, cap_positions(center, half_angle, radius));
+        }
+    }
+}
+
+/// Toggle the footprint with F
+pub fn toggle_footprint(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<FootprintDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleFootprint, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
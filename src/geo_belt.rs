@@ -0,0 +1,369 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::camera::CameraController;
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::keymap::{Action, Keymap};
+use crate::labels::project_label;
+use crate::map_view::MapView;
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+
+/// Geostationary belt with longitude ticks, the slots GEO satellites occupy and the selected one's slot
+pub struct GeoBeltPlugin;
+
+impl Plugin for GeoBeltPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GeoBeltDisplay>()
+            .init_resource::<MapView>()
+            .add_systems(Startup, spawn_geo_belt)
+            .add_systems(Update, (
+                toggle_geo_belt,
+                update_geo_belt.after(update_satellite_positions),
+                update_geo_belt_labels,
+            ).chain());
+    }
+}
+
+/// Altitude of the geostationary orbit above the equator (km)
+pub const GEO_ALTITUDE_KM: f64 = 35786.0;
+/// Earth's sidereal rotation rate (revolutions per day), the mean motion of a GEO satellite
+const SIDEREAL_REVS_PER_DAY: f64 = 1.002_737_9;
+/// Orbits counted as geostationary: close to one sidereal revolution per day,
+/// near-circular and near-equatorial
+const GEO_MEAN_MOTION_TOLERANCE: f64 = 0.01;
+const GEO_MAX_ECCENTRICITY: f64 = 0.01;
+const GEO_MAX_INCLINATION_DEG: f64 = 15.0;
+/// Slot longitudes are snapped to this step (degrees)
+const SLOT_STEP_DEG: f64 = 0.1;
+/// Segments of the belt circle
+const BELT_SEGMENTS: usize = 360;
+/// Spacing of the longitude ticks; every third one is longer and labelled (degrees)
+const TICK_STEP_DEG: usize = 10;
+const LABELLED_TICK_STEP_DEG: usize = 30;
+/// Half-length of the longitude ticks across the belt (km)
+const TICK_HALF_LENGTH_KM: f32 = 600.0;
+const LABELLED_TICK_HALF_LENGTH_KM: f32 = 1500.0;
+/// Half-length of the ticks marking occupied slots (km)
+const SLOT_TICK_HALF_LENGTH_KM: f32 = 400.0;
+const SLOT_MARKER_RADIUS: f32 = coords::km_to_scene(250.0);
+const BELT_COLOR: Color = Color::srgb(0.6, 0.6, 0.8);
+const SLOT_COLOR: Color = Color::srgb(1.0, 0.75, 0.2);
+const SELECTED_SLOT_COLOR: Color = Color::srgb(0.2, 1.0, 1.0);
+
+/// Whether the GEO belt is drawn (toggle with E)
+#[derive(Resource, Default)]
+pub struct GeoBeltDisplay {
+    pub enabled: bool,
+}
+
+/// Belt circle and its longitude ticks
+#[derive(Component)]
+pub struct GeoBelt;
+
+/// Ticks at the longitude of every GEO satellite
+#[derive(Component)]
+pub struct GeoSlotTicks;
+
+/// Marker on the slot of the selected GEO satellite
+#[derive(Component)]
+pub struct SelectedSlotMarker;
+
+/// Longitude label along the belt (degrees east)
+#[derive(Component)]
+pub struct GeoBeltLabel(pub f64);
+
+/// Label of the selected GEO satellite's slot
+#[derive(Component)]
+pub struct SelectedSlotLabel;
+
+/// Whether a satellite with these elements stays over one point of the equator
+pub fn is_geostationary(satellite: &Satellite) -> bool {
+    let elements = &satellite.elements;
+    (elements.mean_motion - SIDEREAL_REVS_PER_DAY).abs() < GEO_MEAN_MOTION_TOLERANCE
+        && elements.eccentricity < GEO_MAX_ECCENTRICITY
+        && elements.inclination < GEO_MAX_INCLINATION_DEG
+}
+
+/// Longitude drift of a near-geostationary satellite (degrees per day, positive eastward)
+pub fn longitude_drift_deg_per_day(satellite: &Satellite) -> f64 {
+    (satellite.elements.mean_motion - SIDEREAL_REVS_PER_DAY) * 360.0
+}
+
+/// Snap a longitude to the slot grid, in (-180, 180]
+pub fn slot_longitude(longitude_deg: f64) -> f64 {
+    let snapped = (longitude_deg / SLOT_STEP_DEG).round() * SLOT_STEP_DEG;
+    if snapped <= -180.0 {
+        snapped + 360.0
+    } else {
+        snapped
+    }
+}
+
+/// "19.2°E", "75.0°W", "0.0°"
+pub fn format_longitude(longitude_deg: f64) -> String {
+    if longitude_deg.abs() < SLOT_STEP_DEG / 2.0 {
+        "0.0°".to_string()
+    } else if longitude_deg > 0.0 {
+        format!("{:.1}°E", longitude_deg)
+    } else {
+        format!("{:.1}°W", -longitude_deg)
+    }
+}
+
+/// Scene position on the belt at a longitude, `offset_km` farther from Earth's center
+fn belt_point(longitude_deg: f64, offset_km: f32, globe: GlobeShape) -> Vec3 {
+    coords::geodetic_to_scene(Geodetic::new(0.0, longitude_deg, GEO_ALTITUDE_KM + offset_km as f64), globe)
+}
+
+/// Radial tick across the belt as a line list pair
+fn tick(longitude_deg: f64, half_length_km: f32, globe: GlobeShape) -> [[f32; 3]; 2] {
+    [
+        belt_point(longitude_deg, -half_length_km, globe).to_array(),
+        belt_point(longitude_deg, half_length_km, globe).to_array(),
+    ]
+}
+
+/// Belt circle (as line list segments) and longitude ticks
+fn belt_positions(globe: GlobeShape) -> Vec<[f32; 3]> {
+    let mut positions = Vec::with_capacity(BELT_SEGMENTS * 2 + 72);
+    for i in 0..BELT_SEGMENTS {
+        let longitude = |i: usize| i as f64 / BELT_SEGMENTS as f64 * 360.0;
+        positions.push(belt_point(longitude(i), 0.0, globe).to_array());
+        positions.push(belt_point(longitude(i + 1), 0.0, globe).to_array());
+    }
+    for longitude in (0..360).step_by(TICK_STEP_DEG) {
+        let half_length = if longitude % LABELLED_TICK_STEP_DEG == 0 {
+            LABELLED_TICK_HALF_LENGTH_KM
+        } else {
+            TICK_HALF_LENGTH_KM
+        };
+        positions.extend(tick(longitude as f64, half_length, globe));
+    }
+    positions
+}
+
+pub fn spawn_geo_belt(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    globe: Res<GlobeShape>,
+) {
+    // The belt turns with the Earth, so it is built once in the Earth-fixed scene frame
+    let mut belt_mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    belt_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, belt_positions(*globe));
+    commands.spawn((
+        Mesh3d(meshes.add(belt_mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: BELT_COLOR,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        GeoBelt,
+        Name::new("GeoBelt"),
+    ));
+
+    let mut slot_mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    slot_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 2]);
+    commands.spawn((
+        Mesh3d(meshes.add(slot_mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: SLOT_COLOR,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        GeoSlotTicks,
+        Name::new("GeoSlotTicks"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(SLOT_MARKER_RADIUS).mesh().uv(12, 6))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: SELECTED_SLOT_COLOR,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        SelectedSlotMarker,
+        Name::new("SelectedSlotMarker"),
+    ));
+
+    for longitude in (0..360).step_by(LABELLED_TICK_STEP_DEG) {
+        let longitude = if longitude > 180 { longitude as f64 - 360.0 } else { longitude as f64 };
+        commands.spawn((
+            Text2d::new(format_longitude(longitude)),
+            TextFont {
+                font_size: 12.0,
+                ..default()
+            },
+            TextColor(BELT_COLOR),
+            Transform::default(),
+            Visibility::Hidden,
+            GeoBeltLabel(longitude),
+        ));
+    }
+    commands.spawn((
+        Text2d::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(SELECTED_SLOT_COLOR),
+        Transform::default(),
+        Visibility::Hidden,
+        SelectedSlotLabel,
+    ));
+}
+
+/// Show or hide the belt, and mark the slots occupied by GEO satellites and the selected one's slot
+#[allow(clippy::type_complexity)]
+pub fn update_geo_belt(
+    display: Res<GeoBeltDisplay>,
+    satellite_query: Query<(&Satellite, &ScenePosition, &Visibility, Has<Selected>)>,
+    mut belt_query: Query<&mut Visibility, (With<GeoBelt>, Without<Satellite>, Without<GeoSlotTicks>, Without<SelectedSlotMarker>)>,
+    mut ticks_query: Query<(&Mesh3d, &mut Visibility), (With<GeoSlotTicks>, Without<Satellite>, Without<SelectedSlotMarker>)>,
+    mut marker_query: Query<(&mut ScenePosition, &mut Visibility), (With<SelectedSlotMarker>, Without<Satellite>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    globe: Res<GlobeShape>,
+) {
+    let visibility = if display.enabled { Visibility::Visible } else { Visibility::Hidden };
+    for mut belt_visibility in belt_query.iter_mut() {
+        belt_visibility.set_if_neq(visibility);
+    }
+
+    let mut tick_positions = Vec::new();
+    let mut selected_slot = None;
+    if display.enabled {
+        for (satellite, position, satellite_visibility, is_selected) in satellite_query.iter() {
+            if *satellite_visibility == Visibility::Hidden || !is_geostationary(satellite) {
+                continue;
+            }
+            let longitude = coords::ecef_to_geodetic(position.ecef()).longitude_deg;
+            let slot = slot_longitude(longitude);
+            tick_positions.extend(tick(slot, SLOT_TICK_HALF_LENGTH_KM, *globe));
+            if is_selected {
+                selected_slot = Some(slot);
+            }
+        }
+    }
+
+    for (mesh_3d, mut ticks_visibility) in ticks_query.iter_mut() {
+        if tick_positions.is_empty() {
+            ticks_visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        ticks_visibility.set_if_neq(Visibility::Visible);
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, tick_positions.clone());
+        }
+    }
+
+    for (mut position, mut marker_visibility) in marker_query.iter_mut() {
+        match selected_slot {
+            Some(slot) => {
+                position.set_if_neq(ScenePosition(belt_point(slot, 0.0, *globe).as_dvec3()));
+                marker_visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                marker_visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+/// Place the longitude labels and the selected slot's label on screen
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_geo_belt_labels(
+    display: Res<GeoBeltDisplay>,
+    map_view: Res<MapView>,
+    selected_query: Query<(&Satellite, &ScenePosition), With<Selected>>,
+    mut longitude_query: Query<(&GeoBeltLabel, &mut Transform, &mut Visibility), (Without<Satellite>, Without<SelectedSlotLabel>)>,
+    mut slot_label_query: Query<(&mut Text2d, &mut Transform, &mut Visibility), (With<SelectedSlotLabel>, Without<Satellite>)>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    origin: Res<FloatingOrigin>,
+    globe: Res<GlobeShape>,
+) {
+    // Text2d draws over every view, so the map (which hides the 3D scene) drops the labels
+    let view = match (camera_query.iter().next(), windows.iter().next()) {
+        (Some(camera), Some(window)) if display.enabled && !map_view.enabled => {
+            Some((camera, Vec2::new(window.width(), window.height())))
+        }
+        _ => None,
+    };
+    let project = |position: Vec3| {
+        let ((camera, camera_global), window_size) = view?;
+        project_label(position, camera, camera_global, *origin, *globe, window_size)
+    };
+
+    for (label, mut transform, mut visibility) in longitude_query.iter_mut() {
+        match project(belt_point(label.0, 0.0, *globe)) {
+            Some(position) => {
+                transform.translation = position.extend(0.0);
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    let selected = selected_query
+        .iter()
+        .next()
+        .filter(|(satellite, _)| is_geostationary(satellite));
+    for (mut text, mut transform, mut visibility) in slot_label_query.iter_mut() {
+        let (satellite, satellite_position) = match selected {
+            Some(selected) => selected,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+        let longitude = coords::ecef_to_geodetic(satellite_position.ecef()).longitude_deg;
+        let slot = slot_longitude(longitude);
+        let position = match project(belt_point(slot, 0.0, *globe)) {
+            Some(position) => position,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+
+        let caption = format!(
+            "Slot {} (drift {:+.2}°/day)",
+            format_longitude(slot),
+            longitude_drift_deg_per_day(satellite),
+        );
+        if text.0 != caption {
+            text.0 = caption;
+        }
+        // Below the longitude labels, which sit on the same spot every 30°
+        transform.translation = (position - Vec2::new(0.0, 16.0)).extend(1.0);
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
+
+/// Toggle the GEO belt with E
+pub fn toggle_geo_belt(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<GeoBeltDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleGeoBelt, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
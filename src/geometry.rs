@@ -0,0 +1,158 @@
+//! Geometry shared by the viewer's systems: what the Earth hides
+
+use bevy::math::Vec3;
+
+use crate::coords::{self, GlobeShape};
+
+/// Whether the straight segment from `a` to `b` clears a spherical Earth of `earth_radius`
+/// centered on the origin
+///
+/// Any Earth-centered frame works (scene, ECEF, TEME), as long as both points are in the same
+/// one. A segment that only grazes the sphere counts as clear; a point inside it sees nothing.
+pub fn line_of_sight(a: Vec3, b: Vec3, earth_radius: f32) -> bool {
+    let segment = b - a;
+    let length_sq = segment.length_squared();
+    // Point of the segment closest to the Earth's center
+    let t = if length_sq > 0.0 {
+        ((-a).dot(segment) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (a + segment * t).length_squared() >= earth_radius * earth_radius
+}
+
+/// `line_of_sight` past an ellipsoid of revolution with its polar axis along scene Y
+pub fn line_of_sight_ellipsoid(a: Vec3, b: Vec3, equatorial_radius: f32, polar_radius: f32) -> bool {
+    // Stretching Y turns the ellipsoid into a sphere and keeps segments straight
+    let stretch = Vec3::new(1.0, equatorial_radius / polar_radius, 1.0);
+    line_of_sight(a * stretch, b * stretch, equatorial_radius)
+}
+
+/// Distance along a ray (unit `direction`) to where it enters an ellipsoid of revolution
+/// with its polar axis along scene Y, 0 if it starts inside, None if it misses
+pub fn ray_hits_ellipsoid(origin: Vec3, direction: Vec3, equatorial_radius: f32, polar_radius: f32) -> Option<f32> {
+    let stretch = Vec3::new(1.0, equatorial_radius / polar_radius, 1.0);
+    let (origin, direction) = (origin * stretch, direction * stretch);
+
+    // |origin + t direction|² = r², with t unchanged by the stretch
+    let a = direction.length_squared();
+    let half_b = origin.dot(direction);
+    let c = origin.length_squared() - equatorial_radius * equatorial_radius;
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let (t_entry, t_exit) = ((-half_b - root) / a, (-half_b + root) / a);
+    if t_entry > 0.0 {
+        Some(t_entry)
+    } else if t_exit > 0.0 {
+        Some(0.0)
+    } else {
+        None
+    }
+}
+
+/// Equatorial and polar radii of the rendered globe in scene units
+fn globe_radii(globe: GlobeShape) -> (f32, f32) {
+    let (equatorial, polar) = globe.radii();
+    (coords::km_to_scene(equatorial as f32), coords::km_to_scene(polar as f32))
+}
+
+/// `line_of_sight` past the rendered globe, in scene coordinates
+pub fn globe_line_of_sight(a: Vec3, b: Vec3, globe: GlobeShape) -> bool {
+    let (equatorial, polar) = globe_radii(globe);
+    line_of_sight_ellipsoid(a, b, equatorial, polar)
+}
+
+/// `ray_hits_ellipsoid` against the rendered globe, in scene coordinates
+pub fn ray_hits_globe(origin: Vec3, direction: Vec3, globe: GlobeShape) -> Option<f32> {
+    let (equatorial, polar) = globe_radii(globe);
+    ray_hits_ellipsoid(origin, direction, equatorial, polar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 6371.0;
+
+    #[test]
+    fn satellites_on_the_same_side_see_each_other() {
+        let a = Vec3::new(7000.0, 0.0, 0.0);
+        let b = Vec3::new(7000.0, 1000.0, 0.0);
+        assert!(line_of_sight(a, b, RADIUS));
+        assert!(line_of_sight(b, a, RADIUS));
+    }
+
+    #[test]
+    fn the_earth_blocks_opposite_points() {
+        let a = Vec3::new(7000.0, 0.0, 0.0);
+        assert!(!line_of_sight(a, -a, RADIUS));
+        // Far apart but still seeing each other above the limb
+        let b = Vec3::new(-7000.0, 7000.0, 0.0);
+        assert!(line_of_sight(Vec3::new(7000.0, 7000.0, 0.0), b, RADIUS));
+    }
+
+    #[test]
+    fn grazing_the_limb_is_clear() {
+        let a = Vec3::new(-10000.0, RADIUS, 0.0);
+        let b = Vec3::new(10000.0, RADIUS, 0.0);
+        assert!(line_of_sight(a, b, RADIUS));
+        assert!(!line_of_sight(a - Vec3::Y, b - Vec3::Y, RADIUS));
+    }
+
+    #[test]
+    fn segment_stopping_short_of_the_earth_is_clear() {
+        // The line through both points crosses the globe, the segment between them doesn't
+        let a = Vec3::new(20000.0, 0.0, 0.0);
+        let b = Vec3::new(10000.0, 0.0, 0.0);
+        assert!(line_of_sight(a, b, RADIUS));
+    }
+
+    #[test]
+    fn the_flattened_poles_let_more_through() {
+        // Just above the polar radius but well inside a sphere of the equatorial radius
+        let polar = RADIUS - 20.0;
+        let a = Vec3::new(-3000.0, polar + 5.0, 0.0);
+        let b = Vec3::new(3000.0, polar + 5.0, 0.0);
+        assert!(!line_of_sight(a, b, RADIUS));
+        assert!(line_of_sight_ellipsoid(a, b, RADIUS, polar));
+        // Across the equator the ellipsoid blocks like the sphere
+        assert!(!line_of_sight_ellipsoid(Vec3::new(7000.0, 0.0, 0.0), Vec3::new(-7000.0, 0.0, 0.0), RADIUS, polar));
+    }
+
+    #[test]
+    fn rays_enter_the_ellipsoid_at_its_surface() {
+        let polar = RADIUS - 20.0;
+        // Straight down onto the pole and onto the equator
+        let pole = ray_hits_ellipsoid(Vec3::new(0.0, 10000.0, 0.0), -Vec3::Y, RADIUS, polar).unwrap();
+        assert!((pole - (10000.0 - polar)).abs() < 0.01, "pole hit at {}", pole);
+        let equator = ray_hits_ellipsoid(Vec3::new(10000.0, 0.0, 0.0), -Vec3::X, RADIUS, polar).unwrap();
+        assert!((equator - (10000.0 - RADIUS)).abs() < 0.01, "equator hit at {}", equator);
+        // Pointing away, and starting inside
+        assert_eq!(ray_hits_ellipsoid(Vec3::new(10000.0, 0.0, 0.0), Vec3::X, RADIUS, polar), None);
+        assert_eq!(ray_hits_ellipsoid(Vec3::ZERO, Vec3::X, RADIUS, polar), Some(0.0));
+    }
+
+    #[test]
+    fn the_globe_shape_decides_what_the_poles_hide() {
+        // 6362 km above the center: over the WGS84 pole (6357 km) but inside the mean sphere
+        let height = coords::km_to_scene(6362.0);
+        let a = Vec3::new(-coords::km_to_scene(3000.0), height, 0.0);
+        let b = Vec3::new(coords::km_to_scene(3000.0), height, 0.0);
+        assert!(!globe_line_of_sight(a, b, GlobeShape::Sphere));
+        assert!(globe_line_of_sight(a, b, GlobeShape::Ellipsoid));
+
+        let above = Vec3::new(0.0, coords::km_to_scene(10000.0), 0.0);
+        let sphere = ray_hits_globe(above, -Vec3::Y, GlobeShape::Sphere).unwrap();
+        let ellipsoid = ray_hits_globe(above, -Vec3::Y, GlobeShape::Ellipsoid).unwrap();
+        assert!((ellipsoid - sphere - coords::km_to_scene(14.25)).abs() < coords::km_to_scene(0.1));
+    }
+
+    #[test]
+    fn points_inside_the_earth_are_hidden() {
+        assert!(!line_of_sight(Vec3::new(7000.0, 0.0, 0.0), Vec3::new(6000.0, 0.0, 0.0), RADIUS));
+        assert!(!line_of_sight(Vec3::ZERO, Vec3::ZERO, RADIUS));
+    }
+}
@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::camera::CameraController;
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::keymap::{Action, Keymap};
+use crate::labels::project_label;
+use crate::map_view::MapView;
+use crate::text_input::InputFocus;
+
+/// Latitude/longitude grid drawn just above the Earth's surface, with labelled parallels and meridians
+pub struct GraticulePlugin;
+
+impl Plugin for GraticulePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GraticuleDisplay>()
+            .init_resource::<MapView>()
+            .add_systems(Startup, spawn_graticule)
+            .add_systems(Update, (
+                toggle_graticule,
+                update_graticule,
+                update_graticule_labels,
+            ).chain());
+    }
+}
+
+/// Spacing of the grid lines (degrees)
+const LINE_STEP_DEG: i32 = 15;
+/// Spacing of the labelled lines (degrees)
+const LABEL_STEP_DEG: i32 = 30;
+/// Height of the grid above the surface, enough to stay clear of the globe mesh facets (km)
+const GRID_ALTITUDE_KM: f64 = 20.0;
+const GRID_COLOR: Color = Color::srgba(0.8, 0.8, 0.8, 0.35);
+const LABEL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+
+/// Whether the lat/lon grid is drawn (toggle with N)
+#[derive(Resource, Default)]
+pub struct GraticuleDisplay {
+    pub enabled: bool,
+}
+
+/// The grid lines
+#[derive(Component)]
+pub struct Graticule;
+
+/// Label of a parallel (degrees north), placed on the meridian nearest the camera
+#[derive(Component)]
+pub struct ParallelLabel(pub i32);
+
+/// Label of a meridian (degrees east), placed on the parallel nearest the camera
+#[derive(Component)]
+pub struct MeridianLabel(pub i32);
+
+/// "30°N", "45°S", "0°"
+pub fn format_latitude(latitude_deg: i32) -> String {
+    match latitude_deg {
+        0 => "0°".to_string(),
+        latitude if latitude > 0 => format!("{}°N", latitude),
+        latitude => format!("{}°S", -latitude),
+    }
+}
+
+/// "120°E", "60°W", "0°", "180°"
+pub fn format_meridian(longitude_deg: i32) -> String {
+    match longitude_deg {
+        0 | 180 | -180 => format!("{}°", longitude_deg.abs()),
+        longitude if longitude > 0 => format!("{}°E", longitude),
+        longitude => format!("{}°W", -longitude),
+    }
+}
+
+fn grid_point(latitude_deg: f64, longitude_deg: f64, globe: GlobeShape) -> Vec3 {
+    coords::geodetic_to_scene(Geodetic::new(latitude_deg, longitude_deg, GRID_ALTITUDE_KM), globe)
+}
+
+/// Parallels and meridians as line list segments
+fn grid_positions(globe: GlobeShape) -> Vec<[f32; 3]> {
+    let mut positions = Vec::new();
+
+    // Parallels, without the poles which are single points; one segment per degree
+    for latitude in (-90 + LINE_STEP_DEG..90).step_by(LINE_STEP_DEG as usize) {
+        for longitude in -180..180 {
+            positions.push(grid_point(latitude as f64, longitude as f64, globe).to_array());
+            positions.push(grid_point(latitude as f64, (longitude + 1) as f64, globe).to_array());
+        }
+    }
+
+    // Meridians, pole to pole
+    for longitude in (-180..180).step_by(LINE_STEP_DEG as usize) {
+        for latitude in -90..90 {
+            positions.push(grid_point(latitude as f64, longitude as f64, globe).to_array());
+            positions.push(grid_point((latitude + 1) as f64, longitude as f64, globe).to_array());
+        }
+    }
+    positions
+}
+
+pub fn spawn_graticule(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    globe: Res<GlobeShape>,
+) {
+    // The grid turns with the Earth, so it is built once in the Earth-fixed scene frame
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, grid_positions(*globe));
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: GRID_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        Graticule,
+        Name::new("Graticule"),
+    ));
+
+    let label = |text: String| {
+        (
+            Text2d::new(text),
+            TextFont {
+                font_size: 12.0,
+                ..default()
+            },
+            TextColor(LABEL_COLOR),
+            Transform::default(),
+            Visibility::Hidden,
+        )
+    };
+    for latitude in (-90 + LABEL_STEP_DEG..90).step_by(LABEL_STEP_DEG as usize) {
+        commands.spawn((label(format_latitude(latitude)), ParallelLabel(latitude)));
+    }
+    for longitude in (-180..180).step_by(LABEL_STEP_DEG as usize) {
+        commands.spawn((label(format_meridian(longitude)), MeridianLabel(longitude)));
+    }
+}
+
+/// Show or hide the grid lines
+pub fn update_graticule(
+    display: Res<GraticuleDisplay>,
+    mut grid_query: Query<&mut Visibility, With<Graticule>>,
+) {
+    if !display.is_changed() {
+        return;
+    }
+    let visibility = if display.enabled { Visibility::Visible } else { Visibility::Hidden };
+    for mut grid_visibility in grid_query.iter_mut() {
+        grid_visibility.set_if_neq(visibility);
+    }
+}
+
+/// Nearest labelled line to an angle, kept within `limit`
+fn nearest_labelled(angle_deg: f64, limit: i32) -> i32 {
+    let nearest = (angle_deg / LABEL_STEP_DEG as f64).round() as i32 * LABEL_STEP_DEG;
+    nearest.clamp(-limit, limit)
+}
+
+/// Place the labels on screen, along the grid lines facing the camera
+#[allow(clippy::too_many_arguments)]
+pub fn update_graticule_labels(
+    display: Res<GraticuleDisplay>,
+    map_view: Res<MapView>,
+    mut parallel_query: Query<(&ParallelLabel, &mut Transform, &mut Visibility), Without<MeridianLabel>>,
+    mut meridian_query: Query<(&MeridianLabel, &mut Transform, &mut Visibility), Without<ParallelLabel>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    origin: Res<FloatingOrigin>,
+    globe: Res<GlobeShape>,
+) {
+    // Text2d draws over every view, so the map (which hides the 3D scene) drops the labels
+    let view = match (camera_query.iter().next(), windows.iter().next()) {
+        (Some(camera), Some(window)) if display.enabled && !map_view.enabled => {
+            Some((camera, Vec2::new(window.width(), window.height())))
+        }
+        _ => None,
+    };
+    let project = |position: Vec3| {
+        let ((camera, camera_global), window_size) = view?;
+        project_label(position, camera, camera_global, *origin, *globe, window_size)
+    };
+
+    // Point on the ground under the camera, so the labels run across the visible side
+    let (label_longitude, label_latitude) = match view {
+        Some(((_, camera_global), _)) => {
+            let below = coords::ecef_to_geodetic(coords::scene_to_ecef_f64(origin.to_scene(camera_global.translation())));
+            (
+                nearest_labelled(below.longitude_deg, 180),
+                nearest_labelled(below.latitude_deg, 90 - LABEL_STEP_DEG),
+            )
+        }
+        None => (0, 0),
+    };
+
+    for (label, mut transform, mut visibility) in parallel_query.iter_mut() {
+        match project(grid_point(label.0 as f64, label_longitude as f64, *globe)) {
+            Some(position) => {
+                transform.translation = position.extend(0.0);
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    for (label, mut transform, mut visibility) in meridian_query.iter_mut() {
+        // The crossing with the labelled parallel already carries that parallel's label
+        if label.0 == label_longitude || label.0 == label_longitude - 360 {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        match project(grid_point(label_latitude as f64, label.0 as f64, *globe)) {
+            Some(position) => {
+                transform.translation = position.extend(0.0);
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+/// Toggle the lat/lon grid with N
+pub fn toggle_graticule(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<GraticuleDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleGraticule, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
@@ -0,0 +1,363 @@
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::ground_station::{self, GroundStation, StationAssets};
+use crate::measure::MeasureTool;
+use crate::passes::{self, Pass};
+use crate::region_watch::RegionWatch;
+use crate::revisit::RevisitRequest;
+use crate::satellite::Satellite;
+use crate::selection::{GroundClicked, Selected};
+use crate::time_bar::TIME_BAR_STRIP;
+use crate::time_simulation::TimeSimulation;
+
+/// Click the globe to read the latitude/longitude there, drop a temporary ground
+/// station marker that predicts the selected satellite's next pass, or ask for the
+/// point's revisit statistics (see `revisit`)
+pub struct GroundPickPlugin;
+
+impl Plugin for GroundPickPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GroundPick>()
+            .init_resource::<MeasureTool>()
+            .add_message::<RevisitRequest>()
+            .add_systems(Startup, setup_ground_pick_panel)
+            .add_systems(Update, (
+                record_ground_clicks,
+                handle_ground_pick_buttons,
+                update_ground_pick_panel,
+            ).chain());
+    }
+}
+
+/// Passes are searched this far ahead of the simulation time (hours)
+const PASS_SEARCH_HOURS: i64 = 24;
+/// Minimum elevation of the dropped marker (degrees), as for stations without one in the file
+const MARKER_MIN_ELEVATION_DEG: f64 = 10.0;
+const MARKER_COLOR: Color = Color::srgb(1.0, 0.5, 0.1);
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// The last point clicked on the globe and the marker dropped from it
+#[derive(Resource, Default)]
+pub struct GroundPick {
+    pub point: Option<Geodetic>,
+    /// Ad-hoc ground station, not saved to ground_stations.json
+    pub marker: Option<Entity>,
+}
+
+/// Panel showing the picked coordinates and the marker's next pass (bottom-center)
+#[derive(Component)]
+pub struct GroundPickPanel;
+
+#[derive(Component)]
+pub struct GroundPickText;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum GroundPickButton {
+    DropMarker,
+    RemoveMarker,
+    Revisit,
+    Close,
+}
+
+impl GroundPickButton {
+    fn label(self) -> &'static str {
+        match self {
+            GroundPickButton::DropMarker => "Drop marker",
+            GroundPickButton::RemoveMarker => "Remove marker",
+            GroundPickButton::Revisit => "Revisit",
+            GroundPickButton::Close => "x",
+        }
+    }
+}
+
+/// Material of the dropped marker, set apart from the configured stations
+#[derive(Resource)]
+struct MarkerMaterial(Handle<StandardMaterial>);
+
+/// "48.8566°N, 2.3522°E"
+pub fn format_coordinates(geodetic: Geodetic) -> String {
+    let hemisphere = |value: f64, positive: char, negative: char| {
+        format!("{:.4}°{}", value.abs(), if value >= 0.0 { positive } else { negative })
+    };
+    format!(
+        "{}, {}",
+        hemisphere(geodetic.latitude_deg, 'N', 'S'),
+        hemisphere(geodetic.longitude_deg, 'E', 'W'),
+    )
+}
+
+pub fn setup_ground_pick_panel(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    commands.insert_resource(MarkerMaterial(materials.add(StandardMaterial {
+        base_color: MARKER_COLOR,
+        unlit: true,
+        ..default()
+    })));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(40.0),
+                bottom: Val::Px(TIME_BAR_STRIP + 10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+            GroundPickPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                GroundPickText,
+            ));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for button in [
+                        GroundPickButton::DropMarker,
+                        GroundPickButton::RemoveMarker,
+                        GroundPickButton::Revisit,
+                        GroundPickButton::Close,
+                    ] {
+                        parent.spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            Text::new(button.label()),
+                            TextFont {
+                                font_size: 13.0,
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_COLOR),
+                            Interaction::default(),
+                            button,
+                        ));
+                    }
+                });
+        });
+}
+
+/// Remember where the globe was clicked (unless the click was picking a measurement end
+/// or a watched region)
+pub fn record_ground_clicks(
+    mut clicks: MessageReader<GroundClicked>,
+    mut pick: ResMut<GroundPick>,
+    measure: Res<MeasureTool>,
+    region_watch: Option<Res<RegionWatch>>,
+    globe: Res<GlobeShape>,
+) {
+    let last = clicks.read().last();
+    if measure.enabled || region_watch.is_some_and(|watch| watch.is_picking()) {
+        return;
+    }
+    if let Some(click) = last {
+        let mut point = coords::scene_to_geodetic(click.0, *globe);
+        point.altitude_km = 0.0;
+        pick.point = Some(point);
+    }
+}
+
+/// Drop or remove the marker, or close the panel
+#[allow(clippy::too_many_arguments)]
+fn handle_ground_pick_buttons(
+    mut commands: Commands,
+    mut button_query: Query<(&Interaction, &GroundPickButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut pick: ResMut<GroundPick>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Option<Res<StationAssets>>,
+    marker_material: Res<MarkerMaterial>,
+    globe: Res<GlobeShape>,
+    mut revisit_writer: MessageWriter<RevisitRequest>,
+) {
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            GroundPickButton::DropMarker => {
+                let (point, assets) = match (pick.point, assets.as_deref()) {
+                    (Some(point), Some(assets)) => (point, assets),
+                    _ => continue,
+                };
+                if let Some(old) = pick.marker.take() {
+                    commands.entity(old).try_despawn();
+                }
+                let station = GroundStation {
+                    name: "Marker".to_string(),
+                    latitude_deg: point.latitude_deg,
+                    longitude_deg: point.longitude_deg,
+                    altitude_km: 0.0,
+                    min_elevation_deg: MARKER_MIN_ELEVATION_DEG,
+                    elevation_mask: None,
+                    mask: None,
+                };
+                println!("Dropped marker at {}", format_coordinates(point));
+                pick.marker = Some(ground_station::spawn_station(
+                    &mut commands,
+                    &mut meshes,
+                    assets,
+                    station,
+                    marker_material.0.clone(),
+                    *globe,
+                ));
+            }
+            GroundPickButton::RemoveMarker => {
+                if let Some(marker) = pick.marker.take() {
+                    commands.entity(marker).try_despawn();
+                }
+            }
+            GroundPickButton::Revisit => {
+                if let Some(point) = pick.point {
+                    revisit_writer.write(RevisitRequest(point));
+                }
+            }
+            GroundPickButton::Close => pick.point = None,
+        }
+    }
+}
+
+/// Next pass of the selected satellite over the marker, kept until the clock leaves its window
+struct PassCache {
+    satellite: Entity,
+    marker: Entity,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+    next: Option<Pass>,
+}
+
+fn describe_pass(pass: &Pass, now: DateTime<Utc>) -> String {
+    let clock = |time: DateTime<Utc>| time.format("%H:%M:%S").to_string();
+    let rise = if pass.rise_time <= now {
+        "In view now".to_string()
+    } else {
+        format!("AOS {} UTC ({})", pass.rise_time.format("%Y-%m-%d %H:%M:%S"), compass(pass.rise_azimuth_deg))
+    };
+    format!(
+        "  {}\n  Max {:.1}° at {}\n  LOS {} ({})",
+        rise,
+        pass.max_elevation_deg,
+        clock(pass.max_elevation_time),
+        clock(pass.set_time),
+        compass(pass.set_azimuth_deg),
+    )
+}
+
+/// "NE" for an azimuth of 45°
+fn compass(azimuth_deg: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((azimuth_deg.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+/// Show the picked point and the marker's next pass
+#[allow(clippy::too_many_arguments)]
+fn update_ground_pick_panel(
+    pick: Res<GroundPick>,
+    sim_time: Res<TimeSimulation>,
+    selected_query: Query<(Entity, &Satellite), With<Selected>>,
+    station_query: Query<&GroundStation>,
+    mut panel_query: Query<&mut Node, With<GroundPickPanel>>,
+    mut text_query: Query<&mut Text, With<GroundPickText>>,
+    mut button_query: Query<(&GroundPickButton, &mut Node), Without<GroundPickPanel>>,
+    mut cache: Local<Option<PassCache>>,
+) {
+    let marker = pick
+        .marker
+        .and_then(|entity| station_query.get(entity).ok().map(|station| (entity, station)));
+
+    let shown = pick.point.is_some() || marker.is_some();
+    for mut node in panel_query.iter_mut() {
+        let display = if shown { Display::Flex } else { Display::None };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !shown {
+        return;
+    }
+    for (button, mut node) in button_query.iter_mut() {
+        let visible = match button {
+            GroundPickButton::DropMarker => pick.point.is_some(),
+            GroundPickButton::RemoveMarker => marker.is_some(),
+            GroundPickButton::Revisit | GroundPickButton::Close => pick.point.is_some(),
+        };
+        let display = if visible { Display::Flex } else { Display::None };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+
+    let mut lines = Vec::new();
+    if let Some(point) = pick.point {
+        lines.push(format!("Clicked {}", format_coordinates(point)));
+    }
+    if let Some((marker_entity, station)) = marker {
+        lines.push(format!(
+            "Marker {} (min elevation {:.0}°)",
+            format_coordinates(station.geodetic()),
+            station.min_elevation_deg
+        ));
+        match selected_query.iter().next() {
+            Some((satellite_entity, satellite)) => {
+                let now = sim_time.current_time();
+                let stale = cache.as_ref().is_none_or(|cached| {
+                    cached.satellite != satellite_entity
+                        || cached.marker != marker_entity
+                        || now < cached.from
+                        || now > cached.next.map_or(cached.until, |pass| pass.set_time)
+                });
+                if stale {
+                    let until = now + Duration::hours(PASS_SEARCH_HOURS);
+                    let next = passes::predict_passes(
+                        satellite.propagator(),
+                        station.geodetic(),
+                        &station.horizon(),
+                        now,
+                        until,
+                        Duration::seconds(passes::PASS_SEARCH_STEP_SECONDS),
+                    )
+                    .into_iter()
+                    .next();
+                    *cache = Some(PassCache {
+                        satellite: satellite_entity,
+                        marker: marker_entity,
+                        from: now,
+                        until,
+                        next,
+                    });
+                }
+
+                lines.push(format!("Next pass of {}:", satellite.name));
+                match cache.as_ref().and_then(|cached| cached.next) {
+                    Some(pass) => lines.push(describe_pass(&pass, now)),
+                    None => lines.push(format!("  None in the next {} h", PASS_SEARCH_HOURS)),
+                }
+            }
+            None => lines.push("Select a satellite to predict its passes".to_string()),
+        }
+    }
+
+    let content = lines.join("\n");
+    for mut text in text_query.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
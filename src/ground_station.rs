@@ -0,0 +1,313 @@
+use bevy::mesh::ConeAnchor;
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::coords::{self, Geodetic, GlobeShape, LookAngles, WGS84_A, WGS84_F};
+use crate::floating_origin::ScenePosition;
+use crate::geometry::line_of_sight;
+use crate::horizon::{ElevationMask, Horizon};
+use crate::satellite::Satellite;
+use crate::selection::Selected;
+
+/// Ground stations with visibility cones, lit up while the selected satellite is in view
+pub struct GroundStationPlugin;
+
+impl Plugin for GroundStationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (setup_station_assets, spawn_ground_stations).chain())
+            .add_systems(Update, update_station_visibility);
+    }
+}
+
+/// Default ground station configuration file
+pub const GROUND_STATIONS_FILE: &str = "ground_stations.json";
+
+/// Height of the rendered visibility cone above the station (km)
+/// Roughly LEO altitude: satellites inside the cone are above the station's minimum elevation
+const VISIBILITY_CONE_HEIGHT_KM: f32 = 1000.0;
+/// Azimuth step of the cones following an elevation mask (degrees)
+const MASKED_CONE_STEP_DEG: usize = 2;
+
+fn default_min_elevation() -> f64 {
+    10.0
+}
+
+/// A ground station on the Earth's surface
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct GroundStation {
+    pub name: String,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    #[serde(default)]
+    pub altitude_km: f64,
+    /// Minimum elevation above the horizon for a satellite to be considered visible
+    #[serde(default = "default_min_elevation")]
+    pub min_elevation_deg: f64,
+    /// CSV file of the terrain horizon ("azimuth_deg,elevation_deg" lines), relative to
+    /// the stations file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation_mask: Option<String>,
+    /// The horizon profile read from `elevation_mask`
+    #[serde(skip)]
+    pub mask: Option<ElevationMask>,
+}
+
+/// Visibility cone attached to a ground station
+#[derive(Component)]
+pub struct VisibilityCone;
+
+/// Meshes and materials shared by the station markers and their cones
+#[derive(Resource)]
+pub struct StationAssets {
+    pub marker_mesh: Handle<Mesh>,
+    pub marker_material: Handle<StandardMaterial>,
+    /// Cone materials: idle, and lit up while the selected satellite is in view
+    pub cone_idle: Handle<StandardMaterial>,
+    pub cone_in_view: Handle<StandardMaterial>,
+}
+
+impl GroundStation {
+    pub fn geodetic(&self) -> Geodetic {
+        Geodetic::new(self.latitude_deg, self.longitude_deg, self.altitude_km)
+    }
+
+    /// Azimuth/elevation/range to an Earth-fixed position
+    pub fn look_angles(&self, target_ecef: Vector3<f64>) -> LookAngles {
+        coords::look_angles(self.geodetic(), target_ecef)
+    }
+
+    /// Minimum elevation, raised by the terrain where an elevation mask is loaded
+    pub fn horizon(&self) -> Horizon {
+        Horizon {
+            min_elevation_deg: self.min_elevation_deg,
+            mask: self.mask.clone(),
+        }
+    }
+
+    /// Whether an Earth-fixed position is above the station's horizon, with the
+    /// Earth out of the way (which matters for a negative minimum elevation)
+    pub fn is_visible(&self, target_ecef: Vector3<f64>) -> bool {
+        // The largest sphere inside the ellipsoid, so stations on the ground are never under it
+        let polar_radius = coords::km_to_scene((WGS84_A * (1.0 - WGS84_F)) as f32);
+        let station = coords::ecef_to_scene(coords::geodetic_to_ecef(self.geodetic()));
+        self.horizon().is_clear(&self.look_angles(target_ecef))
+            && line_of_sight(station, coords::ecef_to_scene(target_ecef), polar_radius)
+    }
+}
+
+/// Load ground stations from a JSON file (an array of stations), with their elevation masks
+///
+/// A mask that can't be read is skipped with a warning, leaving the station's flat horizon.
+pub fn load_ground_stations(path: &str) -> Result<Vec<GroundStation>, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut stations: Vec<GroundStation> = serde_json::from_str(&contents)?;
+    let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+    for station in stations.iter_mut() {
+        let Some(mask_file) = station.elevation_mask.as_ref() else { continue };
+        match ElevationMask::load(directory.join(mask_file)) {
+            Ok(mask) => station.mask = Some(mask),
+            Err(e) => eprintln!("Warning: Failed to load elevation mask {} of {}: {}", mask_file, station.name, e),
+        }
+    }
+    Ok(stations)
+}
+
+/// A station given as "lat,lon[,alt_km]", or the name of one in ground_stations.json
+pub fn resolve_station(value: &str) -> Result<GroundStation, String> {
+    let coordinates: Vec<f64> = value.split(',').filter_map(|part| part.trim().parse().ok()).collect();
+    if coordinates.len() >= 2 && coordinates.len() == value.split(',').count() {
+        return Ok(GroundStation {
+            name: value.to_string(),
+            latitude_deg: coordinates[0],
+            longitude_deg: coordinates[1],
+            altitude_km: coordinates.get(2).copied().unwrap_or(0.0),
+            min_elevation_deg: default_min_elevation(),
+            elevation_mask: None,
+            mask: None,
+        });
+    }
+
+    let stations = load_ground_stations(GROUND_STATIONS_FILE)
+        .map_err(|e| format!("Failed to load {}: {}", GROUND_STATIONS_FILE, e))?;
+    stations
+        .into_iter()
+        .find(|station| station.name.eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("No ground station named \"{}\" in {}", value, GROUND_STATIONS_FILE))
+}
+
+pub fn setup_station_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let cone_material = |color: Color| StandardMaterial {
+        base_color: color,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    };
+    commands.insert_resource(StationAssets {
+        marker_mesh: meshes.add(Sphere::new(coords::km_to_scene(40.0)).mesh().uv(12, 6)),
+        marker_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.1, 1.0, 0.3),
+            unlit: true,
+            ..default()
+        }),
+        cone_idle: materials.add(cone_material(Color::srgba(0.1, 1.0, 0.3, 0.12))),
+        cone_in_view: materials.add(cone_material(Color::srgba(0.2, 1.0, 1.0, 0.3))),
+    });
+}
+
+/// Visibility cone bent to the station's horizon: a fan from the station to a rim
+/// `VISIBILITY_CONE_HEIGHT_KM` up, closed by a cap, in scene offsets from the station
+fn masked_cone_mesh(station: &GroundStation) -> Mesh {
+    let horizon = station.horizon();
+    let (sin_lat, cos_lat) = station.latitude_deg.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = station.longitude_deg.to_radians().sin_cos();
+    let east = Vector3::new(-sin_lon, cos_lon, 0.0);
+    let north = Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat);
+    let up = Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat);
+    let height = VISIBILITY_CONE_HEIGHT_KM as f64;
+
+    // Rim point at each azimuth, where the horizon's line of sight reaches the cone height
+    let rim: Vec<Vec3> = (0..=360)
+        .step_by(MASKED_CONE_STEP_DEG)
+        .map(|azimuth| {
+            let azimuth = (azimuth as f64).to_radians();
+            let elevation = horizon.min_elevation_at(azimuth.to_degrees()).clamp(1.0, 89.0).to_radians();
+            let horizontal = east * azimuth.sin() + north * azimuth.cos();
+            coords::ecef_to_scene(horizontal * (height / elevation.tan()) + up * height)
+        })
+        .collect();
+    let top = coords::ecef_to_scene(up * height);
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    for pair in rim.windows(2) {
+        positions.extend([Vec3::ZERO, pair[0], pair[1]].map(|point| point.to_array()));
+        positions.extend([top, pair[1], pair[0]].map(|point| point.to_array()));
+    }
+    let normal = top.normalize().to_array();
+    let normals = vec![normal; positions.len()];
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh
+}
+
+/// Spawn a station marker with its visibility cone
+pub fn spawn_station(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    assets: &StationAssets,
+    station: GroundStation,
+    marker_material: Handle<StandardMaterial>,
+    globe: GlobeShape,
+) -> Entity {
+    let position = coords::geodetic_to_scene(station.geodetic(), globe);
+    let zenith = position.normalize();
+
+    let (cone_mesh, cone_transform) = if station.mask.is_some() {
+        (meshes.add(masked_cone_mesh(&station)), Transform::IDENTITY)
+    } else {
+        // Cone opening: satellites above min elevation lie within (90° - min elevation) of zenith
+        let half_angle = (90.0 - station.min_elevation_deg).clamp(1.0, 89.0).to_radians() as f32;
+        let height = coords::km_to_scene(VISIBILITY_CONE_HEIGHT_KM);
+        let cone_radius = height * half_angle.tan();
+        let cone_mesh = meshes.add(
+            Cone::new(cone_radius, height)
+                .mesh()
+                .anchor(ConeAnchor::Tip)
+                .resolution(48),
+        );
+        // Cone tip at the station, base pointing along the local zenith
+        (cone_mesh, Transform::from_rotation(Quat::from_rotation_arc(Vec3::NEG_Y, zenith)))
+    };
+
+    let name = station.name.clone();
+    commands
+        .spawn((
+            Mesh3d(assets.marker_mesh.clone()),
+            MeshMaterial3d(marker_material),
+            Transform::from_translation(position),
+            ScenePosition(position.as_dvec3()),
+            Visibility::default(),
+            station,
+            Name::new(name),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Mesh3d(cone_mesh),
+                MeshMaterial3d(assets.cone_idle.clone()),
+                cone_transform,
+                VisibilityCone,
+            ));
+        })
+        .id()
+}
+
+/// Spawn ground station markers and their visibility cones from the config file
+pub fn spawn_ground_stations(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<StationAssets>,
+    globe: Res<GlobeShape>,
+) {
+    if !Path::new(GROUND_STATIONS_FILE).exists() {
+        println!("No {} found, no ground stations loaded", GROUND_STATIONS_FILE);
+        return;
+    }
+
+    let stations = match load_ground_stations(GROUND_STATIONS_FILE) {
+        Ok(stations) => stations,
+        Err(e) => {
+            eprintln!("Warning: Failed to load {}: {}", GROUND_STATIONS_FILE, e);
+            return;
+        }
+    };
+
+    for station in stations {
+        println!(
+            "Ground station {} at {:.3}°, {:.3}° (min elevation {:.1}°{})",
+            station.name,
+            station.latitude_deg,
+            station.longitude_deg,
+            station.min_elevation_deg,
+            if station.mask.is_some() { ", terrain mask" } else { "" }
+        );
+        spawn_station(&mut commands, &mut meshes, &assets, station, assets.marker_material.clone(), *globe);
+    }
+}
+
+/// Light up the visibility cones of stations that currently see the selected satellite
+pub fn update_station_visibility(
+    selected_query: Query<&Satellite, With<Selected>>,
+    station_query: Query<(&GroundStation, &Children)>,
+    mut cone_query: Query<&mut MeshMaterial3d<StandardMaterial>, With<VisibilityCone>>,
+    assets: Res<StationAssets>,
+) {
+
+    let satellite_ecef = selected_query
+        .iter()
+        .next()
+        .and_then(|satellite| Some(coords::teme_to_ecef(satellite.position?, satellite.last_update)));
+
+    for (station, children) in station_query.iter() {
+        let in_view = satellite_ecef.is_some_and(|ecef| station.is_visible(ecef));
+        let material = if in_view { &assets.cone_in_view } else { &assets.cone_idle };
+
+        for child in children.iter() {
+            if let Ok(mut cone_material) = cone_query.get_mut(child) {
+                if cone_material.0 != *material {
+                    cone_material.0 = material.clone();
+                }
+            }
+        }
+    }
+}
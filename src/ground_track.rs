@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::coords::{self, GlobeShape};
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::selection::Selected;
+use crate::time_simulation::TimeSimulation;
+use crate::text_input::InputFocus;
+
+/// Ground track of the selected satellite
+pub struct GroundTrackPlugin;
+
+impl Plugin for GroundTrackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GroundTrackDisplay>()
+            .add_systems(Update, (update_ground_track, toggle_ground_track));
+    }
+}
+
+/// Number of future orbits covered by the ground track
+const GROUND_TRACK_ORBITS: f64 = 3.0;
+/// Samples per orbit (one every ~0.4 min for a LEO satellite)
+const SAMPLES_PER_ORBIT: usize = 240;
+/// Height above the globe surface at which the track is drawn (km)
+const GROUND_TRACK_HEIGHT_KM: f64 = 10.0;
+/// Recompute the track when simulation time has moved this far (seconds), as do the map
+/// view and the launch planner
+pub const RECOMPUTE_INTERVAL_SECONDS: i64 = 60;
+
+/// Ground track line of the selected satellite
+#[derive(Component)]
+pub struct GroundTrack {
+    pub satellite: Entity,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Whether the selected satellite's ground track and trail are drawn (toggle with T)
+#[derive(Resource)]
+pub struct GroundTrackDisplay {
+    pub enabled: bool,
+}
+
+impl Default for GroundTrackDisplay {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Sample the sub-satellite points of a satellite starting at `start`
+/// Returns scene positions draped just above the globe
+pub fn compute_ground_track(satellite: &Satellite, start: DateTime<Utc>, orbits: f64, globe: GlobeShape) -> Vec<Vec3> {
+    let period_minutes = satellite.orbital_parameters().period_minutes;
+    let samples = (SAMPLES_PER_ORBIT as f64 * orbits).ceil() as usize;
+    let step_seconds = period_minutes * 60.0 * orbits / samples as f64;
+
+    (0..=samples)
+        .filter_map(|i| {
+            let time = start + Duration::milliseconds((i as f64 * step_seconds * 1000.0) as i64);
+            let position = satellite.propagate(time)?;
+            let mut geodetic = coords::teme_to_geodetic(position, time);
+            geodetic.altitude_km = GROUND_TRACK_HEIGHT_KM;
+            Some(coords::geodetic_to_scene(geodetic, globe))
+        })
+        .collect()
+}
+
+fn line_strip_mesh(points: &[Vec3]) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::LineStrip, Default::default());
+    let positions: Vec<[f32; 3]> = points.iter().map(|p| [p.x, p.y, p.z]).collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh
+}
+
+/// Keep a ground track line for the selected satellite up to date
+#[allow(clippy::too_many_arguments)]
+pub fn update_ground_track(
+    mut commands: Commands,
+    selected_query: Query<(Entity, &Satellite), With<Selected>>,
+    mut track_query: Query<(Entity, &mut GroundTrack, &mut Mesh3d)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    sim_time: Res<TimeSimulation>,
+    display: Res<GroundTrackDisplay>,
+    globe: Res<GlobeShape>,
+    mut refreshed: MessageReader<TleRefreshed>,
+) {
+    let now = sim_time.current_time();
+    let elements_changed = TleRefreshed::any_changed(&mut refreshed);
+
+    let (satellite_entity, satellite) = match selected_query.iter().next().filter(|_| display.enabled) {
+        Some(selected) => selected,
+        None => {
+            // Nothing selected (or tracks turned off): remove any existing track
+            for (entity, _, _) in track_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+
+    if let Some((_, mut track, mut mesh_3d)) = track_query.iter_mut().next() {
+        let up_to_date = !elements_changed
+            && track.satellite == satellite_entity
+            && (now - track.computed_at).num_seconds().abs() < RECOMPUTE_INTERVAL_SECONDS;
+        if up_to_date {
+            return;
+        }
+
+        let points = compute_ground_track(satellite, now, GROUND_TRACK_ORBITS, *globe);
+        *mesh_3d = Mesh3d(meshes.add(line_strip_mesh(&points)));
+        track.satellite = satellite_entity;
+        track.computed_at = now;
+        return;
+    }
+
+    let points = compute_ground_track(satellite, now, GROUND_TRACK_ORBITS, *globe);
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 1.0, 0.0),
+        unlit: true, // Always visible regardless of lighting
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(line_strip_mesh(&points))),
+        MeshMaterial3d(material),
+        Transform::from_translation(Vec3::ZERO),
+        ScenePosition::default(),
+        GroundTrack {
+            satellite: satellite_entity,
+            computed_at: now,
+        },
+        Name::new("GroundTrack"),
+    ));
+}
+
+/// Toggle the ground track (and the trail, see `trail`) with T
+pub fn toggle_ground_track(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<GroundTrackDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleTrails, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
@@ -0,0 +1,608 @@
+//! Named satellite groups ("my ham sats", "debris watch") with bulk operations
+//!
+//! Groups hold NORAD catalog numbers and are saved to `groups.toml` in the working
+//! directory whenever they change. From the groups panel a group can be shown alone,
+//! drawn in its own color, or exported (ground tracks and ephemerides of every member).
+//! Members are added one at a time from the selection, or all at once from the
+//! satellites the filter currently shows.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Settings;
+use crate::export::{EphemerisFormat, TrackFormat};
+use crate::export_panel::{write_export, ExportKind, EXPORT_BUTTON_COLOR};
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{Satellite, SatelliteAssets};
+use crate::selection::{HighlightedMaterial, Selected};
+use crate::shells::ShellView;
+use crate::time_simulation::TimeSimulation;
+use crate::text_input::{InputFocus, TextInput, TextSubmitted};
+
+/// Default groups file, read from and written to the working directory
+pub const GROUPS_FILE: &str = "groups.toml";
+
+/// Groups listed in the panel
+const MAX_LISTED_GROUPS: usize = 6;
+
+/// Colors given to new groups, in turn (and to constellation shells)
+pub const GROUP_COLORS: [Color; 8] = [
+    Color::srgb(0.3, 0.6, 1.0),
+    Color::srgb(1.0, 0.3, 0.3),
+    Color::srgb(0.3, 1.0, 0.4),
+    Color::srgb(1.0, 0.9, 0.2),
+    Color::srgb(0.8, 0.4, 1.0),
+    Color::srgb(0.2, 1.0, 0.9),
+    Color::srgb(1.0, 0.5, 0.8),
+    Color::srgb(0.9, 0.9, 0.9),
+];
+
+/// Satellite groups: panel, bulk operations and persistence
+pub struct GroupsPlugin;
+
+impl Plugin for GroupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SatelliteGroups::load(GROUPS_FILE))
+            .add_systems(Startup, (setup_group_materials, setup_groups_panel))
+            .add_systems(Update, (
+                group_name_input,
+                handle_group_buttons,
+                update_groups_panel,
+                apply_group_colors,
+                save_groups,
+                toggle_groups_panel,
+            ));
+    }
+}
+
+/// A named set of satellites
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SatelliteGroup {
+    pub name: String,
+    /// Index into the group palette
+    #[serde(default)]
+    pub color: usize,
+    /// Draw members in the group color
+    #[serde(default)]
+    pub colorize: bool,
+    /// NORAD catalog numbers of the members
+    #[serde(default)]
+    pub members: BTreeSet<u64>,
+}
+
+impl SatelliteGroup {
+    pub fn color(&self) -> Color {
+        GROUP_COLORS[self.color % GROUP_COLORS.len()]
+    }
+}
+
+/// All groups, in creation order
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SatelliteGroups {
+    pub groups: Vec<SatelliteGroup>,
+    /// Group whose members alone are shown (not saved)
+    #[serde(skip)]
+    pub show_only: Option<String>,
+}
+
+impl SatelliteGroups {
+    /// Load groups from a TOML file, starting empty if it is missing or invalid
+    pub fn load(path: &str) -> Self {
+        if !Path::new(path).exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str::<Self>(&contents).map_err(|e| e.to_string()))
+        {
+            Ok(groups) => {
+                println!("✓ Loaded {} satellite groups from {}", groups.groups.len(), path);
+                groups
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to load {}: {}. Starting without groups", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SatelliteGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut SatelliteGroup> {
+        self.groups.iter_mut().find(|group| group.name == name)
+    }
+
+    /// Add an empty group, picking the first palette color no other group uses
+    pub fn create(&mut self, name: &str) -> Result<&mut SatelliteGroup, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Group name is empty".to_string());
+        }
+        if self.get(name).is_some() {
+            return Err(format!("Group \"{}\" already exists", name));
+        }
+
+        let color = (0..GROUP_COLORS.len())
+            .find(|color| self.groups.iter().all(|group| group.color != *color))
+            .unwrap_or(self.groups.len() % GROUP_COLORS.len());
+        self.groups.push(SatelliteGroup {
+            name: name.to_string(),
+            color,
+            colorize: false,
+            members: BTreeSet::new(),
+        });
+        Ok(self.groups.last_mut().unwrap())
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.groups.retain(|group| group.name != name);
+        if self.show_only.as_deref() == Some(name) {
+            self.show_only = None;
+        }
+    }
+
+    /// Whether the "show only" group (if any) lets this satellite through
+    pub fn is_shown(&self, norad_id: u64) -> bool {
+        match self.show_only.as_deref().and_then(|name| self.get(name)) {
+            Some(group) => group.members.contains(&norad_id),
+            None => true,
+        }
+    }
+
+    /// Color of the first colorized group containing this satellite
+    pub fn color_of(&self, norad_id: u64) -> Option<usize> {
+        self.groups
+            .iter()
+            .find(|group| group.colorize && group.members.contains(&norad_id))
+            .map(|group| group.color)
+    }
+}
+
+/// Satellite material for each palette color, shared by all members so they still batch
+#[derive(Resource)]
+pub struct GroupMaterials(pub Vec<Handle<StandardMaterial>>);
+
+/// What a group row button does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupAction {
+    ShowOnly,
+    Colorize,
+    AddSelected,
+    RemoveSelected,
+    AddShown,
+    Export,
+    Delete,
+}
+
+impl GroupAction {
+    /// Buttons of each group row, in order
+    pub const ALL: [GroupAction; 7] = [
+        GroupAction::ShowOnly,
+        GroupAction::Colorize,
+        GroupAction::AddSelected,
+        GroupAction::RemoveSelected,
+        GroupAction::AddShown,
+        GroupAction::Export,
+        GroupAction::Delete,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupAction::ShowOnly => "Show only",
+            GroupAction::Colorize => "Color",
+            GroupAction::AddSelected => "+ Selected",
+            GroupAction::RemoveSelected => "- Selected",
+            GroupAction::AddShown => "+ Shown",
+            GroupAction::Export => "Export",
+            GroupAction::Delete => "Delete",
+        }
+    }
+}
+
+/// Groups panel (right side, below the info panel), toggled with G
+#[derive(Component)]
+pub struct GroupsPanel;
+
+#[derive(Component)]
+pub struct GroupsHeader;
+
+#[derive(Component)]
+pub struct GroupNameText;
+
+/// Row listing the group at this index
+#[derive(Component)]
+pub struct GroupRow(pub usize);
+
+#[derive(Component)]
+pub struct GroupRowText(pub usize);
+
+/// Button applying an action to the group of a row
+#[derive(Component)]
+pub struct GroupButton {
+    pub row: usize,
+    pub action: GroupAction,
+}
+
+/// Outcome of the last group operation
+#[derive(Component)]
+pub struct GroupStatusText;
+
+pub fn setup_group_materials(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let handles = GROUP_COLORS
+        .iter()
+        .map(|color| {
+            materials.add(StandardMaterial {
+                base_color: *color,
+                emissive: LinearRgba::from(*color) * 0.8,
+                ..default()
+            })
+        })
+        .collect();
+    commands.insert_resource(GroupMaterials(handles));
+}
+
+pub fn setup_groups_panel(mut commands: Commands) {
+    let small_font = TextFont {
+        font_size: 12.0,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                // Between the info panel (top-right) and the diagnostics overlay (bottom-right)
+                top: Val::Percent(45.0),
+                width: Val::Px(360.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            GroupsPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                GroupsHeader,
+            ));
+
+            // "New group" field: click and type a name, Enter to create (with the selected satellite)
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Percent(100.0),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((Text::new("New group:"), small_font.clone()));
+                    parent.spawn((
+                        Node {
+                            flex_grow: 1.0,
+                            ..default()
+                        },
+                        small_font.clone(),
+                        TextInput::default(),
+                        GroupNameText,
+                    ));
+                });
+
+            for row in 0..MAX_LISTED_GROUPS {
+                parent
+                    .spawn((
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(2.0),
+                            display: Display::None,
+                            ..default()
+                        },
+                        GroupRow(row),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((Text::new(""), TextFont {
+                            font_size: 13.0,
+                            ..default()
+                        }, GroupRowText(row)));
+                        parent
+                            .spawn(Node {
+                                flex_wrap: FlexWrap::Wrap,
+                                column_gap: Val::Px(4.0),
+                                row_gap: Val::Px(2.0),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for action in GroupAction::ALL {
+                                    parent.spawn((
+                                        Node {
+                                            padding: UiRect::axes(Val::Px(5.0), Val::Px(2.0)),
+                                            ..default()
+                                        },
+                                        Text::new(action.label()),
+                                        small_font.clone(),
+                                        BackgroundColor(EXPORT_BUTTON_COLOR),
+                                        Interaction::default(),
+                                        GroupButton { row, action },
+                                    ));
+                                }
+                            });
+                    });
+            }
+
+            parent.spawn((Text::new(""), small_font.clone(), GroupStatusText));
+        });
+}
+
+/// Create a group named by the "New group" field when Enter is pressed
+pub fn group_name_input(
+    mut submitted: MessageReader<TextSubmitted>,
+    mut groups: ResMut<SatelliteGroups>,
+    mut input_query: Query<&mut TextInput, With<GroupNameText>>,
+    selected_query: Query<&Satellite, With<Selected>>,
+) {
+    for TextSubmitted(field) in submitted.read() {
+        let mut input = match input_query.get_mut(*field) {
+            Ok(input) => input,
+            Err(_) => continue,
+        };
+        match groups.create(&input.value) {
+            Ok(group) => {
+                if let Some(satellite) = selected_query.iter().next() {
+                    group.members.insert(satellite.elements.norad_id);
+                }
+                println!("✓ Created group \"{}\"", group.name);
+                input.clear();
+            }
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+/// Apply a group row button: show only, colorize, edit members, export or delete
+pub fn handle_group_buttons(
+    button_query: Query<(&Interaction, &GroupButton), Changed<Interaction>>,
+    mut groups: ResMut<SatelliteGroups>,
+    satellite_query: Query<(&Satellite, &Visibility)>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    mut status_query: Query<(&mut Text, &mut TextColor), With<GroupStatusText>>,
+    sim_time: Res<TimeSimulation>,
+    settings: Res<Settings>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let name = match groups.groups.get(button.row) {
+            Some(group) => group.name.clone(),
+            None => continue,
+        };
+        let selected = selected_query.iter().next().map(|satellite| satellite.elements.norad_id);
+
+        let result = match button.action {
+            GroupAction::ShowOnly => {
+                groups.show_only = if groups.show_only.as_deref() == Some(name.as_str()) {
+                    None
+                } else {
+                    Some(name.clone())
+                };
+                Ok(None)
+            }
+            GroupAction::Colorize => {
+                if let Some(group) = groups.get_mut(&name) {
+                    group.colorize = !group.colorize;
+                }
+                Ok(None)
+            }
+            GroupAction::AddSelected | GroupAction::RemoveSelected => match (selected, groups.get_mut(&name)) {
+                (Some(norad_id), Some(group)) => {
+                    if button.action == GroupAction::AddSelected {
+                        group.members.insert(norad_id);
+                    } else {
+                        group.members.remove(&norad_id);
+                    }
+                    Ok(None)
+                }
+                _ => Err("No satellite selected".to_string()),
+            },
+            GroupAction::AddShown => {
+                let shown: Vec<u64> = satellite_query
+                    .iter()
+                    .filter(|(_, visibility)| **visibility != Visibility::Hidden)
+                    .map(|(satellite, _)| satellite.elements.norad_id)
+                    .collect();
+                let group = groups.get_mut(&name).unwrap();
+                let before = group.members.len();
+                group.members.extend(shown);
+                Ok(Some(format!("Added {} satellites to \"{}\"", group.members.len() - before, name)))
+            }
+            GroupAction::Export => export_group(&groups, &name, &satellite_query, &sim_time, &settings),
+            GroupAction::Delete => {
+                groups.remove(&name);
+                Ok(Some(format!("Deleted \"{}\"", name)))
+            }
+        };
+
+        let (message, color) = match result {
+            Ok(None) => continue,
+            Ok(Some(message)) => {
+                println!("✓ {}", message);
+                (message, Color::srgb(0.6, 1.0, 0.6))
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                (e, Color::srgb(1.0, 0.4, 0.4))
+            }
+        };
+        for (mut text, mut text_color) in status_query.iter_mut() {
+            *text = Text::new(message.clone());
+            text_color.0 = color;
+        }
+    }
+}
+
+/// Write the ground tracks (GeoJSON) and ephemerides (OEM) of every member of a group
+fn export_group(
+    groups: &SatelliteGroups,
+    name: &str,
+    satellite_query: &Query<(&Satellite, &Visibility)>,
+    sim_time: &TimeSimulation,
+    settings: &Settings,
+) -> Result<Option<String>, String> {
+    let group = groups.get(name).ok_or_else(|| format!("No group \"{}\"", name))?;
+    let members: Vec<&Satellite> = satellite_query
+        .iter()
+        .map(|(satellite, _)| satellite)
+        .filter(|satellite| group.members.contains(&satellite.elements.norad_id))
+        .collect();
+    if members.is_empty() {
+        return Err(format!("\"{}\" has no loaded members to export", name));
+    }
+
+    let paths = [
+        ExportKind::GroundTrack(TrackFormat::GeoJson),
+        ExportKind::Ephemeris(EphemerisFormat::Oem),
+    ]
+    .into_iter()
+    .map(|kind| write_export(name, &members, kind, sim_time.current_time(), &settings.export))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| format!("Failed to export \"{}\": {}", name, e))?;
+    Ok(Some(format!("Exported {} satellites to {}", members.len(), paths.join(", "))))
+}
+
+/// Refresh the group rows and the state of their buttons
+pub fn update_groups_panel(
+    groups: Res<SatelliteGroups>,
+    keymap: Res<Keymap>,
+    mut header_query: Query<&mut Text, (With<GroupsHeader>, Without<GroupRowText>)>,
+    mut row_query: Query<(&GroupRow, &mut Node)>,
+    mut row_text_query: Query<(&GroupRowText, &mut Text, &mut TextColor), Without<GroupsHeader>>,
+    mut button_query: Query<(&GroupButton, &Interaction, &mut BackgroundColor)>,
+) {
+    for mut text in header_query.iter_mut() {
+        let hidden = groups.groups.len().saturating_sub(MAX_LISTED_GROUPS);
+        let mut header = format!("Groups: {}  [{}] hide", groups.groups.len(), keymap.label(Action::ToggleGroups));
+        if hidden > 0 {
+            header.push_str(&format!("\n({} more not listed)", hidden));
+        }
+        *text = Text::new(header);
+    }
+
+    for (row, mut node) in row_query.iter_mut() {
+        node.display = if row.0 < groups.groups.len() { Display::Flex } else { Display::None };
+    }
+
+    for (row, mut text, mut text_color) in row_text_query.iter_mut() {
+        if let Some(group) = groups.groups.get(row.0) {
+            *text = Text::new(format!("{} ({})", group.name, group.members.len()));
+            text_color.0 = group.color();
+        }
+    }
+
+    for (button, interaction, mut background) in button_query.iter_mut() {
+        let group = match groups.groups.get(button.row) {
+            Some(group) => group,
+            None => continue,
+        };
+        let active = match button.action {
+            GroupAction::ShowOnly => groups.show_only.as_deref() == Some(group.name.as_str()),
+            GroupAction::Colorize => group.colorize,
+            _ => false,
+        };
+        let color = if active { group.color().darker(0.3) } else { EXPORT_BUTTON_COLOR };
+        background.set_if_neq(BackgroundColor(match interaction {
+            Interaction::None => color,
+            _ => color.lighter(0.1),
+        }));
+    }
+}
+
+/// Give members of colorized groups their group's material, and everyone else the default one;
+/// in the shell mode, constellation satellites wear their shell's color instead
+pub fn apply_group_colors(
+    groups: Res<SatelliteGroups>,
+    shells: Option<Res<ShellView>>,
+    group_materials: Option<Res<GroupMaterials>>,
+    satellite_assets: Option<Res<SatelliteAssets>>,
+    added: Query<(), Added<Satellite>>,
+    mut satellite_query: Query<(&Satellite, &mut MeshMaterial3d<StandardMaterial>, Option<&mut HighlightedMaterial>)>,
+) {
+    let (group_materials, satellite_assets) = match (group_materials, satellite_assets) {
+        (Some(group_materials), Some(satellite_assets)) => (group_materials, satellite_assets),
+        _ => return,
+    };
+    let shells_changed = shells.as_ref().is_some_and(|shells| shells.is_changed());
+    if !groups.is_changed() && !shells_changed && added.is_empty() {
+        return;
+    }
+
+    for (satellite, mut material, highlighted) in satellite_query.iter_mut() {
+        let norad_id = satellite.elements.norad_id;
+        let color = shells
+            .as_ref()
+            .and_then(|shells| shells.shell_of(norad_id))
+            .or_else(|| groups.color_of(norad_id));
+        let target = match color {
+            Some(color) => group_materials.0[color % group_materials.0.len()].clone(),
+            None => satellite_assets.material_for(satellite.kind),
+        };
+        // The selected satellite wears the highlight; change what it goes back to instead
+        match highlighted {
+            Some(mut highlighted) => highlighted.0 = target,
+            None => {
+                if material.0 != target {
+                    material.0 = target;
+                }
+            }
+        }
+    }
+}
+
+/// Write the groups to disk after every change
+pub fn save_groups(groups: Res<SatelliteGroups>) {
+    if !groups.is_changed() || groups.is_added() {
+        return;
+    }
+    if let Err(e) = groups.save(GROUPS_FILE) {
+        eprintln!("Error: Failed to save {}: {}", GROUPS_FILE, e);
+    }
+}
+
+/// Toggle the groups panel with G
+pub fn toggle_groups_panel(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut query: Query<&mut Visibility, With<GroupsPanel>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleGroups, &keyboard_input) {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
@@ -0,0 +1,152 @@
+//! Local horizon of an observer: a minimum elevation, raised by an optional per-azimuth
+//! elevation mask (the terrain or buildings around a ground station)
+
+use std::fs;
+use std::path::Path;
+
+use crate::coords::LookAngles;
+
+/// Horizon profile: elevation of the obstructions by azimuth, linearly interpolated
+/// between the points and around north
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElevationMask {
+    /// (azimuth, elevation) in degrees, by azimuth in [0, 360)
+    points: Vec<(f64, f64)>,
+}
+
+impl ElevationMask {
+    /// Build a mask from (azimuth, elevation) points in degrees, in any order
+    pub fn new(points: impl IntoIterator<Item = (f64, f64)>) -> Result<Self, String> {
+        let mut points: Vec<(f64, f64)> = points
+            .into_iter()
+            .map(|(azimuth, elevation)| (azimuth.rem_euclid(360.0), elevation))
+            .collect();
+        if points.is_empty() {
+            return Err("an elevation mask needs at least one point".to_string());
+        }
+        if points.iter().any(|(azimuth, elevation)| !azimuth.is_finite() || !elevation.is_finite()) {
+            return Err("elevation mask points must be numbers".to_string());
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { points })
+    }
+
+    /// Parse "azimuth_deg,elevation_deg" lines; blank lines, `#` comments and a header
+    /// line are skipped
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut points = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(',').map(str::trim);
+            let parsed = (fields.next().map(str::parse::<f64>), fields.next().map(str::parse::<f64>));
+            match parsed {
+                (Some(Ok(azimuth)), Some(Ok(elevation))) => points.push((azimuth, elevation)),
+                // A header ("azimuth,elevation") can only come first
+                _ if points.is_empty() && !line.starts_with(|c: char| c.is_ascii_digit() || c == '-') => {}
+                _ => return Err(format!("line {}: expected \"azimuth_deg,elevation_deg\"", number + 1)),
+            }
+        }
+        Self::new(points)
+    }
+
+    /// Read a mask from a CSV file (see `parse_csv`)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = fs::read_to_string(path)?;
+        Ok(Self::parse_csv(&contents)?)
+    }
+
+    /// Elevation of the horizon at an azimuth (degrees)
+    pub fn elevation_at(&self, azimuth_deg: f64) -> f64 {
+        let azimuth = azimuth_deg.rem_euclid(360.0);
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        // Between the last point and the first one, across north
+        let (before, after) = match self.points.iter().position(|(point_azimuth, _)| *point_azimuth > azimuth) {
+            Some(0) | None => (last, (first.0 + 360.0, first.1)),
+            Some(index) => (self.points[index - 1], self.points[index]),
+        };
+        let azimuth = if azimuth < before.0 { azimuth + 360.0 } else { azimuth };
+        let span = after.0 - before.0;
+        if span <= 0.0 {
+            return before.1;
+        }
+        before.1 + (after.1 - before.1) * (azimuth - before.0) / span
+    }
+}
+
+/// Where satellites become visible to an observer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Horizon {
+    /// Minimum elevation everywhere (degrees)
+    pub min_elevation_deg: f64,
+    /// Obstructions, where they are higher than the minimum elevation
+    pub mask: Option<ElevationMask>,
+}
+
+impl Horizon {
+    /// The same minimum elevation in every direction
+    pub fn flat(min_elevation_deg: f64) -> Self {
+        Self {
+            min_elevation_deg,
+            mask: None,
+        }
+    }
+
+    /// Elevation a satellite must clear at this azimuth (degrees)
+    pub fn min_elevation_at(&self, azimuth_deg: f64) -> f64 {
+        match &self.mask {
+            Some(mask) => mask.elevation_at(azimuth_deg).max(self.min_elevation_deg),
+            None => self.min_elevation_deg,
+        }
+    }
+
+    pub fn is_clear(&self, look: &LookAngles) -> bool {
+        look.elevation_deg >= self.min_elevation_at(look.azimuth_deg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_interpolate_around_north() {
+        let mask = ElevationMask::new([(90.0, 10.0), (270.0, 30.0), (0.0, 20.0)]).unwrap();
+        assert_eq!(mask.elevation_at(0.0), 20.0);
+        assert_eq!(mask.elevation_at(45.0), 15.0);
+        assert_eq!(mask.elevation_at(180.0), 20.0);
+        assert_eq!(mask.elevation_at(315.0), 25.0);
+        assert_eq!(mask.elevation_at(-45.0), 25.0);
+        assert_eq!(ElevationMask::new([(120.0, 7.0)]).unwrap().elevation_at(300.0), 7.0);
+        assert!(ElevationMask::new([]).is_err());
+    }
+
+    #[test]
+    fn masks_parse_from_csv() {
+        let mask = ElevationMask::parse_csv("# Pic du Midi to the south\nazimuth_deg,elevation_deg\n0,2\n180, 12.5\n\n").unwrap();
+        assert_eq!(mask.elevation_at(180.0), 12.5);
+        assert_eq!(mask.elevation_at(90.0), 7.25);
+        assert!(ElevationMask::parse_csv("0,2\nnorth,5\n").is_err());
+        assert!(ElevationMask::parse_csv("azimuth,elevation\n").is_err());
+    }
+
+    #[test]
+    fn horizons_keep_the_minimum_elevation() {
+        let horizon = Horizon {
+            min_elevation_deg: 10.0,
+            mask: Some(ElevationMask::new([(0.0, 5.0), (180.0, 25.0)]).unwrap()),
+        };
+        assert_eq!(horizon.min_elevation_at(0.0), 10.0);
+        assert_eq!(horizon.min_elevation_at(180.0), 25.0);
+        let look = |azimuth_deg, elevation_deg| LookAngles {
+            azimuth_deg,
+            elevation_deg,
+            range_km: 1000.0,
+        };
+        assert!(horizon.is_clear(&look(0.0, 12.0)));
+        assert!(!horizon.is_clear(&look(180.0, 20.0)));
+        assert!(Horizon::flat(10.0).is_clear(&look(180.0, 20.0)));
+    }
+}
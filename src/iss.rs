@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::camera::FocusCamera;
+use crate::config::{IssSettings, Settings};
+use crate::coords::{self, Geodetic};
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::labels::AlwaysLabeled;
+use crate::notifications::Notify;
+use crate::satellite::Satellite;
+use crate::selection::SelectSatellite;
+use crate::text_input::InputFocus;
+
+/// The ISS as a first-class object: pinned label, "go to ISS" shortcut and live cross-check
+pub struct IssPlugin;
+
+impl Plugin for IssPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().iss.clone();
+
+        let mut timer = Timer::from_seconds(settings.cross_check_interval_seconds.max(5.0), TimerMode::Repeating);
+        // The first check goes out right away
+        timer.set_elapsed(timer.duration());
+
+        app.insert_resource(IssCrossCheck {
+            settings,
+            timer,
+            task: None,
+            agreeing: None,
+            failing: false,
+        })
+        .add_systems(Update, (
+            pin_iss_label,
+            go_to_iss,
+            start_iss_cross_check,
+            finish_iss_cross_check,
+        ));
+    }
+}
+
+/// NORAD catalog number of the ISS (Zarya module)
+pub const ISS_NORAD_ID: u64 = 25544;
+/// Where The ISS At API: current position of the ISS
+pub const WHERE_THE_ISS_AT_URL: &str = "https://api.wheretheiss.at/v1/satellites/25544";
+
+/// Live position of the ISS from the API
+struct LiveFix {
+    position: Geodetic,
+    time: DateTime<Utc>,
+}
+
+/// Reply of the Where The ISS At API (altitude in km)
+#[derive(Deserialize)]
+struct Reply {
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    timestamp: i64,
+}
+
+/// Periodic comparison of the propagated ISS position with a live one
+#[derive(Resource)]
+pub struct IssCrossCheck {
+    pub settings: IssSettings,
+    timer: Timer,
+    task: Option<Task<Result<LiveFix, String>>>,
+    /// Outcome of the last comparison, so only changes are reported
+    agreeing: Option<bool>,
+    failing: bool,
+}
+
+fn fetch_live_fix(url: &str) -> Result<LiveFix, Box<dyn std::error::Error + Send + Sync>> {
+    let reply: Reply = reqwest::blocking::get(url)?.error_for_status()?.json()?;
+    let time = DateTime::from_timestamp(reply.timestamp, 0).ok_or("invalid timestamp")?;
+    Ok(LiveFix {
+        position: Geodetic::new(reply.latitude, reply.longitude, reply.altitude),
+        time,
+    })
+}
+
+fn pin_iss_label(
+    mut commands: Commands,
+    cross_check: Res<IssCrossCheck>,
+    satellite_query: Query<(Entity, &Satellite), Added<Satellite>>,
+) {
+    if !cross_check.settings.always_label {
+        return;
+    }
+    for (entity, satellite) in satellite_query.iter() {
+        if satellite.elements.norad_id == ISS_NORAD_ID {
+            commands.entity(entity).insert(AlwaysLabeled);
+        }
+    }
+}
+
+/// Select the ISS and fly to it (F6 by default)
+fn go_to_iss(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    satellite_query: Query<(Entity, &Satellite, &ScenePosition)>,
+    mut select_writer: MessageWriter<SelectSatellite>,
+    mut focus_writer: MessageWriter<FocusCamera>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::GoToIss, &keyboard_input) {
+        return;
+    }
+    match satellite_query.iter().find(|(_, satellite, _)| satellite.elements.norad_id == ISS_NORAD_ID) {
+        Some((entity, satellite, position)) => {
+            println!("Focusing on {}", satellite.name);
+            select_writer.write(SelectSatellite(Some(entity)));
+            focus_writer.write(FocusCamera {
+                target: position.scene(),
+            });
+        }
+        None => println!("The ISS is not in the loaded catalog"),
+    }
+}
+
+/// Ask the API for the live position in the background, every `cross_check_interval_seconds`
+fn start_iss_cross_check(time: Res<Time>, mut cross_check: ResMut<IssCrossCheck>) {
+    if !cross_check.settings.cross_check || cross_check.task.is_some() {
+        return;
+    }
+    if !cross_check.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let url = cross_check.settings.cross_check_url.clone();
+    cross_check.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        fetch_live_fix(&url).map_err(|e| e.to_string())
+    }));
+}
+
+/// Compare the live fix with the ISS propagated to the same instant
+fn finish_iss_cross_check(
+    mut cross_check: ResMut<IssCrossCheck>,
+    satellite_query: Query<&Satellite>,
+    mut notify: MessageWriter<Notify>,
+) {
+    let result = match cross_check.task.as_mut().and_then(|task| block_on(future::poll_once(task))) {
+        Some(result) => result,
+        None => return,
+    };
+    cross_check.task = None;
+
+    let fix = match result {
+        Ok(fix) => fix,
+        Err(e) => {
+            if !cross_check.failing {
+                eprintln!("Warning: ISS cross-check failed: {}", e);
+            }
+            cross_check.failing = true;
+            return;
+        }
+    };
+    cross_check.failing = false;
+
+    let Some(propagated) = satellite_query
+        .iter()
+        .find(|satellite| satellite.elements.norad_id == ISS_NORAD_ID)
+        .and_then(|satellite| satellite.position_at(fix.time))
+    else {
+        return;
+    };
+    let difference = (coords::teme_to_ecef(propagated, fix.time) - coords::geodetic_to_ecef(fix.position)).norm();
+    let agreeing = difference <= cross_check.settings.cross_check_tolerance_km;
+    if cross_check.agreeing != Some(agreeing) {
+        if agreeing {
+            println!("✓ ISS position agrees with Where The ISS At ({:.1} km apart)", difference);
+        } else {
+            let message = format!("ISS position is {:.0} km from Where The ISS At; the TLE may be stale", difference);
+            eprintln!("Warning: {}", message);
+            notify.write(Notify::warning(message));
+        }
+    }
+    cross_check.agreeing = Some(agreeing);
+}
@@ -0,0 +1,452 @@
+//! Keyboard shortcuts, defined in one place and rebindable from the `[keys]` section of settings.toml
+//!
+//! ```toml
+//! [keys]
+//! toggle_pause = ["P"]
+//! toggle_fullscreen = ["F11", "Alt+Enter"]
+//! ```
+//!
+//! Keys are named like Bevy's `KeyCode` ("KeyA", "Digit1", "ArrowLeft", "F3"), or as a plain
+//! letter or digit ("A", "1"). Modifiers are written as prefixes: "Ctrl+", "Shift+", "Alt+".
+//! Text editing keys (Backspace, Enter, Escape) inside the text fields are not remappable.
+
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// Something a shortcut can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    ToggleFullscreen,
+    TogglePause,
+    Faster,
+    Slower,
+    ToggleReverse,
+    FocusSearch,
+    ToggleLabels,
+    ToggleTrails,
+    CycleCameraMode,
+    RecenterCamera,
+    OrbitLeft,
+    OrbitRight,
+    OrbitUp,
+    OrbitDown,
+    ZoomIn,
+    ZoomOut,
+    ToggleAtmosphere,
+    ToggleOrbit,
+    ToggleVelocity,
+    ToggleDiagnostics,
+    ToggleConjunctions,
+    Screenshot,
+    ToggleRecording,
+    ToggleGroups,
+    ToggleFootprint,
+    ToggleMapView,
+    CycleChaseView,
+    CycleObserverView,
+    ToggleBrowser,
+    ToggleGeoBelt,
+    ToggleGraticule,
+    ToggleBorders,
+    ToggleMeasure,
+    ToggleLaunchPlanner,
+    ToggleShells,
+    ToggleWatchList,
+    ToggleDebugOverlay,
+    ToggleDataWindow,
+    ToggleCameraFrame,
+    ToggleSensor,
+    ToggleCoverage,
+    GoToIss,
+    ToggleCatalogTimeline,
+    ToggleStatistics,
+    ToggleRegionWatch,
+    ToggleSkyPlot,
+    ToggleTimeBar,
+    Bookmark1,
+    Bookmark2,
+    Bookmark3,
+    Bookmark4,
+    Bookmark5,
+    Bookmark6,
+    Bookmark7,
+    Bookmark8,
+    Bookmark9,
+}
+
+impl Action {
+    pub const ALL: [Action; 56] = [
+        Action::ToggleFullscreen,
+        Action::TogglePause,
+        Action::Faster,
+        Action::Slower,
+        Action::ToggleReverse,
+        Action::FocusSearch,
+        Action::ToggleLabels,
+        Action::ToggleTrails,
+        Action::CycleCameraMode,
+        Action::RecenterCamera,
+        Action::OrbitLeft,
+        Action::OrbitRight,
+        Action::OrbitUp,
+        Action::OrbitDown,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleAtmosphere,
+        Action::ToggleOrbit,
+        Action::ToggleVelocity,
+        Action::ToggleDiagnostics,
+        Action::ToggleConjunctions,
+        Action::Screenshot,
+        Action::ToggleRecording,
+        Action::ToggleGroups,
+        Action::ToggleFootprint,
+        Action::ToggleMapView,
+        Action::CycleChaseView,
+        Action::CycleObserverView,
+        Action::ToggleBrowser,
+        Action::ToggleGeoBelt,
+        Action::ToggleGraticule,
+        Action::ToggleBorders,
+        Action::ToggleMeasure,
+        Action::ToggleLaunchPlanner,
+        Action::ToggleShells,
+        Action::ToggleWatchList,
+        Action::ToggleDebugOverlay,
+        Action::ToggleDataWindow,
+        Action::ToggleCameraFrame,
+        Action::ToggleSensor,
+        Action::ToggleCoverage,
+        Action::GoToIss,
+        Action::ToggleCatalogTimeline,
+        Action::ToggleStatistics,
+        Action::ToggleRegionWatch,
+        Action::ToggleSkyPlot,
+        Action::ToggleTimeBar,
+        Action::Bookmark1,
+        Action::Bookmark2,
+        Action::Bookmark3,
+        Action::Bookmark4,
+        Action::Bookmark5,
+        Action::Bookmark6,
+        Action::Bookmark7,
+        Action::Bookmark8,
+        Action::Bookmark9,
+    ];
+
+    /// Camera bookmarks, in the order of the bookmark list
+    pub const BOOKMARKS: [Action; 9] = [
+        Action::Bookmark1,
+        Action::Bookmark2,
+        Action::Bookmark3,
+        Action::Bookmark4,
+        Action::Bookmark5,
+        Action::Bookmark6,
+        Action::Bookmark7,
+        Action::Bookmark8,
+        Action::Bookmark9,
+    ];
+
+    /// Name used in the `[keys]` settings section
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::ToggleFullscreen => "toggle_fullscreen",
+            Action::TogglePause => "toggle_pause",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::ToggleReverse => "toggle_reverse",
+            Action::FocusSearch => "focus_search",
+            Action::ToggleLabels => "toggle_labels",
+            Action::ToggleTrails => "toggle_trails",
+            Action::CycleCameraMode => "cycle_camera_mode",
+            Action::RecenterCamera => "recenter_camera",
+            Action::OrbitLeft => "orbit_left",
+            Action::OrbitRight => "orbit_right",
+            Action::OrbitUp => "orbit_up",
+            Action::OrbitDown => "orbit_down",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::ToggleAtmosphere => "toggle_atmosphere",
+            Action::ToggleOrbit => "toggle_orbit",
+            Action::ToggleVelocity => "toggle_velocity",
+            Action::ToggleDiagnostics => "toggle_diagnostics",
+            Action::ToggleConjunctions => "toggle_conjunctions",
+            Action::Screenshot => "screenshot",
+            Action::ToggleRecording => "toggle_recording",
+            Action::ToggleGroups => "toggle_groups",
+            Action::ToggleFootprint => "toggle_footprint",
+            Action::ToggleMapView => "toggle_map_view",
+            Action::CycleChaseView => "cycle_chase_view",
+            Action::CycleObserverView => "cycle_observer_view",
+            Action::ToggleBrowser => "toggle_browser",
+            Action::ToggleGeoBelt => "toggle_geo_belt",
+            Action::ToggleGraticule => "toggle_graticule",
+            Action::ToggleBorders => "toggle_borders",
+            Action::ToggleMeasure => "toggle_measure",
+            Action::ToggleLaunchPlanner => "toggle_launch_planner",
+            Action::ToggleShells => "toggle_shells",
+            Action::ToggleWatchList => "toggle_watch_list",
+            Action::ToggleDebugOverlay => "toggle_debug_overlay",
+            Action::ToggleDataWindow => "toggle_data_window",
+            Action::ToggleCameraFrame => "toggle_camera_frame",
+            Action::ToggleSensor => "toggle_sensor",
+            Action::ToggleCoverage => "toggle_coverage",
+            Action::GoToIss => "go_to_iss",
+            Action::ToggleCatalogTimeline => "toggle_catalog_timeline",
+            Action::ToggleStatistics => "toggle_statistics",
+            Action::ToggleRegionWatch => "toggle_region_watch",
+            Action::ToggleSkyPlot => "toggle_sky_plot",
+            Action::ToggleTimeBar => "toggle_time_bar",
+            Action::Bookmark1 => "bookmark_1",
+            Action::Bookmark2 => "bookmark_2",
+            Action::Bookmark3 => "bookmark_3",
+            Action::Bookmark4 => "bookmark_4",
+            Action::Bookmark5 => "bookmark_5",
+            Action::Bookmark6 => "bookmark_6",
+            Action::Bookmark7 => "bookmark_7",
+            Action::Bookmark8 => "bookmark_8",
+            Action::Bookmark9 => "bookmark_9",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Built-in bindings, in the settings file syntax
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::ToggleFullscreen => &["F11", "Alt+Enter"],
+            Action::TogglePause => &["Space"],
+            Action::Faster => &["Equal", "NumpadAdd"],
+            Action::Slower => &["Minus", "NumpadSubtract"],
+            Action::ToggleReverse => &["R"],
+            Action::FocusSearch => &["Slash"],
+            Action::ToggleLabels => &["L"],
+            Action::ToggleTrails => &["T"],
+            Action::CycleCameraMode => &["C"],
+            Action::RecenterCamera => &["Home"],
+            Action::OrbitLeft => &["ArrowLeft"],
+            Action::OrbitRight => &["ArrowRight"],
+            Action::OrbitUp => &["ArrowUp"],
+            Action::OrbitDown => &["ArrowDown"],
+            Action::ZoomIn => &["W"],
+            Action::ZoomOut => &["S"],
+            Action::ToggleAtmosphere => &["A"],
+            Action::ToggleOrbit => &["O"],
+            Action::ToggleVelocity => &["V"],
+            Action::ToggleDiagnostics => &["F3"],
+            Action::ToggleConjunctions => &["F4"],
+            Action::Screenshot => &["F12"],
+            Action::ToggleRecording => &["F9"],
+            Action::ToggleGroups => &["G"],
+            Action::ToggleFootprint => &["F"],
+            Action::ToggleMapView => &["M"],
+            Action::CycleChaseView => &["P"],
+            Action::CycleObserverView => &["H"],
+            Action::ToggleBrowser => &["B"],
+            Action::ToggleGeoBelt => &["E"],
+            Action::ToggleGraticule => &["N"],
+            Action::ToggleBorders => &["D"],
+            Action::ToggleMeasure => &["K"],
+            Action::ToggleLaunchPlanner => &["U"],
+            Action::ToggleShells => &["J"],
+            Action::ToggleWatchList => &["Y"],
+            Action::ToggleDebugOverlay => &["F2"],
+            Action::ToggleDataWindow => &["X"],
+            Action::ToggleCameraFrame => &["I"],
+            Action::ToggleSensor => &["Q"],
+            Action::ToggleCoverage => &["Z"],
+            Action::GoToIss => &["F6"],
+            Action::ToggleCatalogTimeline => &["F7"],
+            Action::ToggleStatistics => &["F8"],
+            Action::ToggleRegionWatch => &["F10"],
+            Action::ToggleSkyPlot => &["F5"],
+            Action::ToggleTimeBar => &["F1"],
+            Action::Bookmark1 => &["1"],
+            Action::Bookmark2 => &["2"],
+            Action::Bookmark3 => &["3"],
+            Action::Bookmark4 => &["4"],
+            Action::Bookmark5 => &["5"],
+            Action::Bookmark6 => &["6"],
+            Action::Bookmark7 => &["7"],
+            Action::Bookmark8 => &["8"],
+            Action::Bookmark9 => &["9"],
+        }
+    }
+}
+
+/// A key plus the modifiers that must be held with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    /// Parse "Alt+Enter", "F3", "a", "KeyA", ...
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut binding = KeyBinding {
+            key: KeyCode::Space,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        };
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty()).ok_or_else(|| format!("Missing key in \"{}\"", text))?;
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => return Err(format!("Unknown modifier \"{}\" in \"{}\"", modifier, text)),
+            }
+        }
+        binding.key = parse_key(key).ok_or_else(|| format!("Unknown key \"{}\"", key))?;
+        Ok(binding)
+    }
+
+    fn modifiers_held(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        let held = |left, right| keys.pressed(left) || keys.pressed(right);
+        (!self.ctrl || held(KeyCode::ControlLeft, KeyCode::ControlRight))
+            && (!self.shift || held(KeyCode::ShiftLeft, KeyCode::ShiftRight))
+            && (!self.alt || held(KeyCode::AltLeft, KeyCode::AltRight))
+    }
+
+    /// Short label for on-screen hints ("Alt+Enter", "F3", "R", "+")
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.ctrl {
+            label.push_str("Ctrl+");
+        }
+        if self.shift {
+            label.push_str("Shift+");
+        }
+        if self.alt {
+            label.push_str("Alt+");
+        }
+        let name = format!("{:?}", self.key);
+        let name = match self.key {
+            KeyCode::Equal => "+",
+            KeyCode::Minus => "-",
+            KeyCode::Slash => "/",
+            _ => name.trim_start_matches("Key").trim_start_matches("Digit"),
+        };
+        label.push_str(name);
+        label
+    }
+}
+
+/// Resolve a key name; letters and digits may be given bare
+fn parse_key(name: &str) -> Option<KeyCode> {
+    if name.len() == 1 {
+        let c = name.chars().next()?.to_ascii_uppercase();
+        return match c {
+            'A'..='Z' => parse_key(&format!("Key{}", c)),
+            '0'..='9' => parse_key(&format!("Digit{}", c)),
+            _ => None,
+        };
+    }
+
+    const KEYS: [KeyCode; 80] = [
+        KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE,
+        KeyCode::KeyF, KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ,
+        KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO,
+        KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR, KeyCode::KeyS, KeyCode::KeyT,
+        KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY,
+        KeyCode::KeyZ,
+        KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+        KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+        KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+        KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+        KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+        KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown, KeyCode::Insert,
+        KeyCode::Delete, KeyCode::ArrowLeft, KeyCode::ArrowRight, KeyCode::ArrowUp,
+        KeyCode::ArrowDown, KeyCode::Minus, KeyCode::Equal, KeyCode::Slash, KeyCode::Backslash,
+        KeyCode::Comma, KeyCode::Period, KeyCode::Semicolon, KeyCode::Quote,
+        KeyCode::BracketLeft, KeyCode::BracketRight, KeyCode::Backquote,
+        KeyCode::NumpadAdd, KeyCode::NumpadSubtract, KeyCode::NumpadMultiply,
+        KeyCode::NumpadDivide, KeyCode::NumpadEnter, KeyCode::NumpadDecimal,
+    ];
+    KEYS.into_iter()
+        .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+}
+
+/// Shortcut bindings for every `Action`
+#[derive(Resource)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_settings(&BTreeMap::new())
+    }
+}
+
+impl Keymap {
+    /// Default bindings, with any action listed in `overrides` rebound to the given keys
+    ///
+    /// Unknown actions and keys are reported and ignored, so a typo doesn't lose the other bindings.
+    pub fn from_settings(overrides: &BTreeMap<String, Vec<String>>) -> Self {
+        let mut bindings = HashMap::new();
+        for action in Action::ALL {
+            let parsed = action
+                .default_keys()
+                .iter()
+                .filter_map(|key| KeyBinding::parse(key).ok())
+                .collect();
+            bindings.insert(action, parsed);
+        }
+
+        for (name, keys) in overrides {
+            let action = match Action::from_name(name) {
+                Some(action) => action,
+                None => {
+                    eprintln!("Warning: Unknown action \"{}\" in [keys]", name);
+                    continue;
+                }
+            };
+            let parsed = keys
+                .iter()
+                .filter_map(|key| match KeyBinding::parse(key) {
+                    Ok(binding) => Some(binding),
+                    Err(e) => {
+                        eprintln!("Warning: {} (in [keys] {})", e, name);
+                        None
+                    }
+                })
+                .collect();
+            bindings.insert(action, parsed);
+        }
+
+        Self { bindings }
+    }
+
+    fn bindings(&self, action: Action) -> &[KeyBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether a binding of the action was pressed this frame
+    pub fn just_pressed(&self, action: Action, keys: &ButtonInput<KeyCode>) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| keys.just_pressed(binding.key) && binding.modifiers_held(keys))
+    }
+
+    /// Whether a binding of the action is held down
+    pub fn pressed(&self, action: Action, keys: &ButtonInput<KeyCode>) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| keys.pressed(binding.key) && binding.modifiers_held(keys))
+    }
+
+    /// Label of the action's first binding, for on-screen hints
+    pub fn label(&self, action: Action) -> String {
+        self.bindings(action)
+            .first()
+            .map_or("unbound".to_string(), KeyBinding::label)
+    }
+}
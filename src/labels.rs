@@ -0,0 +1,354 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::collections::HashMap;
+
+use crate::camera::CameraController;
+use crate::config::Settings;
+use crate::coords::{self, GlobeShape};
+use crate::floating_origin::FloatingOrigin;
+use crate::geometry::globe_line_of_sight;
+use crate::keymap::{Action, Keymap};
+use crate::map_view::MapView;
+use crate::satellite::{update_satellite_positions, ObjectKind, Satellite, SatelliteLabel, SatelliteLabelParent};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+
+/// Satellite name labels that follow the satellites on screen
+pub struct LabelsPlugin;
+
+impl Plugin for LabelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = &app.world().resource::<Settings>().labels;
+        let layout = LabelLayout {
+            // Settings count down from the top of the screen, 2D camera space counts up
+            offset: Vec2::new(settings.offset_x, -settings.offset_y),
+            leader_lines: settings.leader_lines,
+        };
+
+        app.insert_resource(layout)
+            .init_resource::<LabelDisplay>()
+            .init_resource::<MapView>()
+            .add_systems(Update, (
+                update_satellite_labels.after(update_satellite_positions),
+                toggle_labels,
+            ));
+    }
+}
+
+/// Labels of unselected satellites only appear once the camera is closer than this (km)
+const LABEL_ZOOM_THRESHOLD: f32 = coords::km_to_scene(30000.0);
+/// Maximum number of labels drawn at once (the selected label always shows)
+const MAX_VISIBLE_LABELS: usize = 150;
+/// Scale of unselected labels relative to the selected one
+const SMALL_LABEL_SCALE: f32 = 0.5;
+/// Approximate on-screen glyph size of a full-scale label (pixels)
+const GLYPH_WIDTH: f32 = 12.0;
+const GLYPH_HEIGHT: f32 = 26.0;
+/// Extra spacing kept around each label so neighbours stay readable (pixels)
+const LABEL_MARGIN: f32 = 4.0;
+/// Other labels (orbit apsides, grid lines, ranges) are drawn this far below their point (pixels)
+const LABEL_OFFSET_PX: f32 = 16.0;
+/// Camera distance at which labels are drawn at their nominal size (km)
+const LABEL_REFERENCE_DISTANCE_KM: f32 = 8000.0;
+/// Bounds of the distance scaling, so far labels stay readable and near ones don't swamp the view
+const MIN_DISTANCE_SCALE: f32 = 0.7;
+const MAX_DISTANCE_SCALE: f32 = 1.4;
+/// Leader line color
+const LEADER_LINE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.4);
+
+/// Where satellite labels go relative to their satellite, from `[labels]` in settings.toml
+#[derive(Resource, Clone, Copy)]
+pub struct LabelLayout {
+    /// From the satellite to the label's nearest corner, in 2D camera space (pixels, Y up)
+    pub offset: Vec2,
+    pub leader_lines: bool,
+}
+
+/// Satellite whose label shows at any zoom, like the selected one's
+#[derive(Component)]
+pub struct AlwaysLabeled;
+
+/// Whether satellite labels are drawn at all (toggle with L)
+#[derive(Resource)]
+pub struct LabelDisplay {
+    pub enabled: bool,
+}
+
+impl Default for LabelDisplay {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// A label that passed occlusion and frustum checks this frame
+struct LabelCandidate {
+    label: Entity,
+    /// Satellite position in the scene
+    satellite: Vec3,
+    /// Satellite position in 2D camera space (origin at screen center, Y up)
+    anchor: Vec2,
+    scale: f32,
+    /// Estimated size in pixels at the label's scale
+    size: Vec2,
+    camera_distance: f32,
+    is_selected: bool,
+    /// Selected or `AlwaysLabeled`: shown at any zoom and never decluttered away
+    is_pinned: bool,
+    /// Payloads are labelled before debris and rocket bodies
+    is_payload: bool,
+}
+
+/// Where `declutter` put a label
+struct Placement {
+    /// Index into the candidates
+    candidate: usize,
+    /// From the satellite to the label's nearest corner (2D camera space)
+    offset: Vec2,
+    /// Label center (2D camera space)
+    position: Vec2,
+}
+
+/// Project a label for a scene position to 2D camera space, a few pixels below the point,
+/// or None if the point is behind Earth or off screen
+pub fn project_label(
+    position: Vec3,
+    camera: &Camera,
+    camera_global: &GlobalTransform,
+    origin: FloatingOrigin,
+    globe: GlobeShape,
+    window_size: Vec2,
+) -> Option<Vec2> {
+    let point = origin.to_render(position.as_dvec3());
+    project_point(point, camera, camera_global, origin, globe, window_size).map(|point| point - Vec2::Y * LABEL_OFFSET_PX)
+}
+
+/// Project a point of the render world to 2D camera space, or None if it is behind Earth or off screen
+pub fn project_point(
+    point: Vec3,
+    camera: &Camera,
+    camera_global: &GlobalTransform,
+    origin: FloatingOrigin,
+    globe: GlobeShape,
+    window_size: Vec2,
+) -> Option<Vec2> {
+    // Behind (or inside) the Earth, which is centered in scene coordinates
+    let scene = |point: Vec3| origin.to_scene(point).as_vec3();
+    if !globe_line_of_sight(scene(camera_global.translation()), scene(point), globe) {
+        return None;
+    }
+
+    let ndc = camera.world_to_ndc(camera_global, point)?;
+    if ndc.z > 1.0 || ndc.z < -1.0 || ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+        // Behind the camera or outside the view frustum
+        return None;
+    }
+
+    // Text2d uses 2D camera space: centered on the window, Y up
+    Some(Vec2::new(ndc.x * 0.5 * window_size.x, ndc.y * 0.5 * window_size.y))
+}
+
+/// Center of a label of `size` whose nearest corner (or edge, along an axis with no offset) is
+/// `offset` away from `anchor`
+fn label_center(anchor: Vec2, offset: Vec2, size: Vec2) -> Vec2 {
+    let direction = Vec2::select(offset.cmpeq(Vec2::ZERO), Vec2::ZERO, offset.signum());
+    anchor + offset + direction * size * 0.5
+}
+
+/// Point of the world at the depth of `depth_of` that is drawn at `screen` (2D camera space)
+fn unproject(screen: Vec2, depth_of: Vec3, camera: &Camera, camera_global: &GlobalTransform, window_size: Vec2) -> Option<Vec3> {
+    let viewport = Vec2::new(screen.x + window_size.x * 0.5, window_size.y * 0.5 - screen.y);
+    let ray = camera.viewport_to_world(camera_global, viewport).ok()?;
+    let forward = camera_global.forward();
+    let along = ray.direction.dot(*forward);
+    if along <= 0.0 {
+        return None;
+    }
+    Some(ray.get_point((depth_of - ray.origin).dot(*forward) / along))
+}
+
+/// Sides of the satellite a label is tried on: the one it had last frame (so labels don't flip
+/// back and forth), the configured one, then mirrored left-right, up-down and both
+fn placement_offsets(offset: Vec2, previous: Option<Vec2>) -> [Vec2; 5] {
+    [
+        previous.unwrap_or(offset),
+        offset,
+        Vec2::new(-offset.x, offset.y),
+        Vec2::new(offset.x, -offset.y),
+        -offset,
+    ]
+}
+
+fn on_screen(position: Vec2, size: Vec2, window_size: Vec2) -> bool {
+    let limit = (window_size - size) * 0.5;
+    position.x.abs() <= limit.x && position.y.abs() <= limit.y
+}
+
+/// Greedy screen-space decluttering of labels sorted by priority
+///
+/// Each label goes on the first side of its satellite where it stays on screen and overlaps no
+/// label placed before it; labels with no free side are left out, so in a dense cluster such as a
+/// Starlink train only the most important ones show. Pinned labels are always placed.
+fn declutter(
+    candidates: &[LabelCandidate],
+    offset: Vec2,
+    previous: &HashMap<Entity, Vec2>,
+    window_size: Vec2,
+) -> Vec<Placement> {
+    let mut placements: Vec<Placement> = Vec::new();
+    let mut placed: Vec<(Vec2, Vec2)> = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        if placed.len() >= MAX_VISIBLE_LABELS && !candidate.is_pinned {
+            break;
+        }
+        let free = placement_offsets(offset, previous.get(&candidate.label).copied()).into_iter().find_map(|offset| {
+            let position = label_center(candidate.anchor, offset, candidate.size);
+            let blocked = !on_screen(position, candidate.size, window_size)
+                || placed.iter().any(|(other, size)| overlaps(position, candidate.size, *other, *size));
+            (!blocked).then_some((offset, position))
+        });
+        let (offset, position) = match free {
+            Some(spot) => spot,
+            None if candidate.is_pinned => (offset, label_center(candidate.anchor, offset, candidate.size)),
+            None => continue,
+        };
+        placed.push((position, candidate.size));
+        placements.push(Placement {
+            candidate: index,
+            offset,
+            position,
+        });
+    }
+    placements
+}
+
+fn overlaps(a_pos: Vec2, a_size: Vec2, b_pos: Vec2, b_size: Vec2) -> bool {
+    let half_extent = (a_size + b_size) * 0.5 + Vec2::splat(LABEL_MARGIN);
+    let delta = (a_pos - b_pos).abs();
+    delta.x < half_extent.x && delta.y < half_extent.y
+}
+
+/// Position satellite labels and decide which ones are drawn
+///
+/// Drawing a `Text2d` for thousands of satellites is unreadable and slow, so labels are culled:
+/// - the selected satellite's and `AlwaysLabeled` labels are always shown (unless occluded)
+/// - other labels only appear when zoomed in past `LABEL_ZOOM_THRESHOLD`
+/// - the selected satellite, then pinned ones, payloads and nearest satellites are placed first, each on a
+///   side of its satellite where it overlaps no label already placed (see `declutter`)
+/// - at most `MAX_VISIBLE_LABELS` labels are drawn
+///
+/// Labels sit a fixed number of pixels from their satellite whatever the zoom, optionally with
+/// a leader line back to it, and grow a little as the camera closes in.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_satellite_labels(
+    mut label_query: Query<(Entity, &SatelliteLabel, &SatelliteLabelParent, &mut Transform, &mut Visibility)>,
+    satellite_query: Query<(&Satellite, &GlobalTransform, &Visibility, Has<Selected>, Has<AlwaysLabeled>), Without<SatelliteLabel>>,
+    camera_query: Query<(&Camera, &GlobalTransform, &CameraController), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    display: Res<LabelDisplay>,
+    layout: Res<LabelLayout>,
+    map_view: Res<MapView>,
+    origin: Res<FloatingOrigin>,
+    globe: Res<GlobeShape>,
+    mut gizmos: Gizmos,
+    mut sides: Local<HashMap<Entity, Vec2>>,
+) {
+    // Labels follow the 3D view, which the map replaces
+    if !display.enabled || map_view.enabled {
+        for (_, _, _, _, mut visibility) in label_query.iter_mut() {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+        return;
+    }
+
+    let (camera, camera_global, controller) = match camera_query.iter().next() {
+        Some(c) => c,
+        None => return,
+    };
+    let window = match windows.iter().next() {
+        Some(w) => w,
+        None => return,
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    let camera_pos = camera_global.translation();
+    let zoomed_in = controller.distance < LABEL_ZOOM_THRESHOLD;
+
+    let mut candidates = Vec::new();
+    for (label_entity, label, parent, _, mut visibility) in label_query.iter_mut() {
+        // Hidden unless it earns a place below
+        visibility.set_if_neq(Visibility::Hidden);
+
+        let (satellite, sat_global, sat_visibility, is_selected, always_labeled) = match satellite_query.get(parent.0) {
+            Ok(satellite) => satellite,
+            Err(_) => continue,
+        };
+        let is_pinned = is_selected || always_labeled;
+        // Filtered-out satellites never get a label
+        if *sat_visibility == Visibility::Hidden || !(is_pinned || zoomed_in) {
+            continue;
+        }
+
+        let sat_pos = sat_global.translation();
+        if let Some(anchor) = project_point(sat_pos, camera, camera_global, *origin, *globe, window_size) {
+            let camera_distance = (sat_pos - camera_pos).length();
+            let distance_scale = (LABEL_REFERENCE_DISTANCE_KM / coords::scene_to_km(camera_distance)).clamp(MIN_DISTANCE_SCALE, MAX_DISTANCE_SCALE);
+            let scale = distance_scale * if is_selected { 1.0 } else { SMALL_LABEL_SCALE };
+            let size = Vec2::new(label.name.chars().count() as f32 * GLYPH_WIDTH, GLYPH_HEIGHT) * scale;
+            candidates.push(LabelCandidate {
+                label: label_entity,
+                satellite: sat_pos,
+                anchor,
+                scale,
+                size,
+                camera_distance,
+                is_selected,
+                is_pinned,
+                is_payload: satellite.kind == ObjectKind::Payload,
+            });
+        }
+    }
+
+    // Selected first, then pinned, payloads, and nearest to the camera
+    candidates.sort_by(|a, b| {
+        b.is_selected
+            .cmp(&a.is_selected)
+            .then(b.is_pinned.cmp(&a.is_pinned))
+            .then(b.is_payload.cmp(&a.is_payload))
+            .then(a.camera_distance.total_cmp(&b.camera_distance))
+    });
+
+    let placements = declutter(&candidates, layout.offset, &sides, window_size);
+    sides.clear();
+    for placement in placements {
+        let candidate = &candidates[placement.candidate];
+        sides.insert(candidate.label, placement.offset);
+        if let Ok((_, _, _, mut transform, mut visibility)) = label_query.get_mut(candidate.label) {
+            // Keep the selected label on top of overlapping labels
+            let z = if candidate.is_selected { 1.0 } else { 0.0 };
+            transform.translation = placement.position.extend(z);
+            transform.scale = Vec3::splat(candidate.scale);
+            visibility.set_if_neq(Visibility::Visible);
+
+            // The line is drawn in the 3D scene, ending where the label's corner is on screen
+            if layout.leader_lines && placement.offset != Vec2::ZERO {
+                let corner = candidate.anchor + placement.offset;
+                if let Some(end) = unproject(corner, candidate.satellite, camera, camera_global, window_size) {
+                    gizmos.line(candidate.satellite, end, LEADER_LINE_COLOR);
+                }
+            }
+        }
+    }
+}
+
+/// Toggle satellite labels with L
+pub fn toggle_labels(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<LabelDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleLabels, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
@@ -0,0 +1,420 @@
+use bevy::mesh::ConeAnchor;
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use chrono::{DateTime, Duration, Utc};
+use nalgebra::Vector3;
+
+use crate::config::Settings;
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::floating_origin::ScenePosition;
+use crate::ground_pick::format_coordinates;
+use crate::ground_track::RECOMPUTE_INTERVAL_SECONDS;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::EARTH_MU;
+use crate::text_input::InputFocus;
+use crate::time_bar::TIME_BAR_STRIP;
+use crate::time_simulation::TimeSimulation;
+
+/// Built-in launch sites on the globe, and a planner drawing the ground track
+/// of a launch along a chosen azimuth
+pub struct LaunchSitesPlugin;
+
+impl Plugin for LaunchSitesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = &app.world().resource::<Settings>().launch_sites;
+        let planner = LaunchPlanner {
+            enabled: false,
+            site: 0,
+            azimuth_deg: 90.0,
+            parking_orbit_km: settings.parking_orbit_km,
+        };
+
+        app.insert_resource(planner)
+            .add_systems(Startup, setup_launch_sites)
+            .add_systems(Update, (
+                toggle_launch_planner,
+                handle_launch_planner_buttons,
+                update_launch_planner,
+            ).chain());
+    }
+}
+
+/// A launch site (degrees)
+pub struct LaunchSite {
+    pub name: &'static str,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+}
+
+impl LaunchSite {
+    pub fn geodetic(&self) -> Geodetic {
+        Geodetic::new(self.latitude_deg, self.longitude_deg, 0.0)
+    }
+}
+
+const fn site(name: &'static str, latitude_deg: f64, longitude_deg: f64) -> LaunchSite {
+    LaunchSite { name, latitude_deg, longitude_deg }
+}
+
+/// Orbital launch sites in use
+pub const LAUNCH_SITES: &[LaunchSite] = &[
+    site("Cape Canaveral / Kennedy", 28.573, -80.649),
+    site("Vandenberg", 34.742, -120.572),
+    site("Wallops", 37.940, -75.466),
+    site("Starbase", 25.997, -97.155),
+    site("Kourou", 5.236, -52.769),
+    site("Baikonur", 45.965, 63.305),
+    site("Plesetsk", 62.925, 40.577),
+    site("Vostochny", 51.884, 128.334),
+    site("Jiuquan", 40.958, 100.291),
+    site("Taiyuan", 38.849, 111.608),
+    site("Xichang", 28.246, 102.027),
+    site("Wenchang", 19.614, 110.951),
+    site("Satish Dhawan", 13.720, 80.230),
+    site("Tanegashima", 30.400, 130.970),
+    site("Naro", 34.432, 127.535),
+    site("Mahia", -39.262, 177.865),
+    site("Palmachim", 31.884, 34.690),
+    site("Semnan", 35.234, 53.921),
+];
+
+const SITE_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+const TRACK_COLOR: Color = Color::srgb(1.0, 0.6, 0.6);
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+/// Size of the site markers (km)
+const SITE_MARKER_SIZE: f32 = coords::km_to_scene(80.0);
+/// Orbits covered by the planned ground track
+const TRACK_ORBITS: f64 = 2.0;
+/// Samples per orbit of the planned ground track
+const SAMPLES_PER_ORBIT: usize = 240;
+/// Height above the globe surface at which the track is drawn (km)
+const TRACK_HEIGHT_KM: f64 = 10.0;
+
+/// Launch planner (toggle with U): site, launch azimuth and parking orbit of the drawn track
+#[derive(Resource)]
+pub struct LaunchPlanner {
+    pub enabled: bool,
+    /// Index in `LAUNCH_SITES`
+    pub site: usize,
+    /// Degrees clockwise from north
+    pub azimuth_deg: f64,
+    pub parking_orbit_km: f64,
+}
+
+impl LaunchPlanner {
+    pub fn site(&self) -> &'static LaunchSite {
+        &LAUNCH_SITES[self.site % LAUNCH_SITES.len()]
+    }
+
+    pub fn inclination_deg(&self) -> f64 {
+        coords::launch_inclination_deg(self.site().latitude_deg, self.azimuth_deg)
+    }
+
+    /// Aim for another inclination on the same side (north or south of east/west);
+    /// ignored when the site can't reach it directly
+    fn set_inclination(&mut self, inclination_deg: f64) {
+        let azimuth = self.azimuth_deg.rem_euclid(360.0);
+        let northbound = !(90.0..270.0).contains(&azimuth);
+        if let Some(azimuth) = coords::launch_azimuth_deg(self.site().latitude_deg, inclination_deg, northbound) {
+            self.azimuth_deg = azimuth;
+        }
+    }
+}
+
+/// Marker of a launch site
+#[derive(Component)]
+pub struct LaunchSiteMarker;
+
+/// Planned ground track line
+#[derive(Component)]
+pub struct LaunchTrack;
+
+#[derive(Component)]
+pub struct LaunchPlannerPanel;
+
+#[derive(Component)]
+pub struct LaunchPlannerText;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchPlannerButton {
+    PreviousSite,
+    NextSite,
+    AzimuthDown,
+    AzimuthUp,
+    InclinationDown,
+    InclinationUp,
+}
+
+impl LaunchPlannerButton {
+    fn label(self) -> &'static str {
+        match self {
+            LaunchPlannerButton::PreviousSite => "< Site",
+            LaunchPlannerButton::NextSite => "Site >",
+            LaunchPlannerButton::AzimuthDown => "Az -",
+            LaunchPlannerButton::AzimuthUp => "Az +",
+            LaunchPlannerButton::InclinationDown => "Incl -",
+            LaunchPlannerButton::InclinationUp => "Incl +",
+        }
+    }
+}
+
+pub fn setup_launch_sites(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    globe: Res<GlobeShape>,
+) {
+    let site_material = materials.add(StandardMaterial {
+        base_color: SITE_COLOR,
+        unlit: true,
+        ..default()
+    });
+
+    if settings.launch_sites.markers {
+        // Small pyramids standing on the surface, pointing up
+        let marker_mesh = meshes.add(
+            Cone::new(SITE_MARKER_SIZE / 2.0, SITE_MARKER_SIZE)
+                .mesh()
+                .anchor(ConeAnchor::Base)
+                .resolution(4),
+        );
+        for site in LAUNCH_SITES {
+            let position = coords::geodetic_to_scene(site.geodetic(), *globe);
+            commands.spawn((
+                Mesh3d(marker_mesh.clone()),
+                MeshMaterial3d(site_material.clone()),
+                Transform::from_translation(position)
+                    .with_rotation(Quat::from_rotation_arc(Vec3::Y, position.normalize())),
+                ScenePosition(position.as_dvec3()),
+                LaunchSiteMarker,
+                Name::new(site.name),
+            ));
+        }
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::LineStrip, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 2]);
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: TRACK_COLOR,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        LaunchTrack,
+        Name::new("LaunchTrack"),
+    ));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(420.0),
+                bottom: Val::Px(TIME_BAR_STRIP + 80.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+            LaunchPlannerPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(TRACK_COLOR),
+                LaunchPlannerText,
+            ));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for button in [
+                        LaunchPlannerButton::PreviousSite,
+                        LaunchPlannerButton::NextSite,
+                        LaunchPlannerButton::AzimuthDown,
+                        LaunchPlannerButton::AzimuthUp,
+                        LaunchPlannerButton::InclinationDown,
+                        LaunchPlannerButton::InclinationUp,
+                    ] {
+                        parent.spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            Text::new(button.label()),
+                            TextFont {
+                                font_size: 13.0,
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_COLOR),
+                            Interaction::default(),
+                            button,
+                        ));
+                    }
+                });
+        });
+}
+
+/// Open or close the launch planner with U
+pub fn toggle_launch_planner(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut planner: ResMut<LaunchPlanner>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleLaunchPlanner, &keyboard_input) {
+        return;
+    }
+    planner.enabled = !planner.enabled;
+}
+
+/// Pick the site, and step the azimuth or the target inclination by one degree
+fn handle_launch_planner_buttons(
+    mut button_query: Query<(&Interaction, &LaunchPlannerButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut planner: ResMut<LaunchPlanner>,
+) {
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            LaunchPlannerButton::PreviousSite => planner.site = (planner.site + LAUNCH_SITES.len() - 1) % LAUNCH_SITES.len(),
+            LaunchPlannerButton::NextSite => planner.site = (planner.site + 1) % LAUNCH_SITES.len(),
+            LaunchPlannerButton::AzimuthDown => planner.azimuth_deg = (planner.azimuth_deg - 1.0).rem_euclid(360.0),
+            LaunchPlannerButton::AzimuthUp => planner.azimuth_deg = (planner.azimuth_deg + 1.0).rem_euclid(360.0),
+            LaunchPlannerButton::InclinationDown => {
+                let inclination = planner.inclination_deg().round() - 1.0;
+                planner.set_inclination(inclination);
+            }
+            LaunchPlannerButton::InclinationUp => {
+                let inclination = planner.inclination_deg().round() + 1.0;
+                planner.set_inclination(inclination);
+            }
+        }
+    }
+}
+
+/// Ground track of a circular orbit entered right above the site along an azimuth
+///
+/// The orbit plane holds the site and the launch direction, fixed in inertial space
+/// while Earth turns below it, so the track drifts west on each orbit.
+pub fn launch_ground_track(
+    site: Geodetic,
+    azimuth_deg: f64,
+    altitude_km: f64,
+    launch_time: DateTime<Utc>,
+    orbits: f64,
+    globe: GlobeShape,
+) -> Vec<Vec3> {
+    let (sin_lat, cos_lat) = site.latitude_deg.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = site.longitude_deg.to_radians().sin_cos();
+    let (sin_az, cos_az) = azimuth_deg.to_radians().sin_cos();
+
+    // Local up and launch direction, Earth-fixed then inertial at launch
+    let up = Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat);
+    let east = Vector3::new(-sin_lon, cos_lon, 0.0);
+    let north = Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat);
+    let r0 = coords::ecef_to_teme(up, launch_time);
+    let v0 = coords::ecef_to_teme(east * sin_az + north * cos_az, launch_time);
+
+    let radius = coords::EARTH_RADIUS_KM + altitude_km;
+    let mean_motion = (EARTH_MU / radius.powi(3)).sqrt();
+    let period_seconds = std::f64::consts::TAU / mean_motion;
+    let samples = (SAMPLES_PER_ORBIT as f64 * orbits).ceil() as usize;
+    let step_seconds = period_seconds * orbits / samples as f64;
+
+    (0..=samples)
+        .map(|i| {
+            let elapsed = i as f64 * step_seconds;
+            let (sin_theta, cos_theta) = (mean_motion * elapsed).sin_cos();
+            let position = (r0 * cos_theta + v0 * sin_theta) * radius;
+            let time = launch_time + Duration::milliseconds((elapsed * 1000.0) as i64);
+            let mut geodetic = coords::teme_to_geodetic(position, time);
+            geodetic.altitude_km = TRACK_HEIGHT_KM;
+            coords::geodetic_to_scene(geodetic, globe)
+        })
+        .collect()
+}
+
+/// Redraw the planned track (launching at the simulation time) and refresh the panel
+#[allow(clippy::too_many_arguments)]
+fn update_launch_planner(
+    planner: Res<LaunchPlanner>,
+    sim_time: Res<TimeSimulation>,
+    mut track_query: Query<(&Mesh3d, &mut Visibility), With<LaunchTrack>>,
+    mut panel_query: Query<&mut Node, With<LaunchPlannerPanel>>,
+    mut text_query: Query<&mut Text, With<LaunchPlannerText>>,
+    keymap: Res<Keymap>,
+    globe: Res<GlobeShape>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut computed_at: Local<Option<DateTime<Utc>>>,
+) {
+    let display = if planner.enabled { Display::Flex } else { Display::None };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+
+    let now = sim_time.current_time();
+    let stale = planner.is_changed()
+        || computed_at.is_none_or(|time| (now - time).num_seconds().abs() >= RECOMPUTE_INTERVAL_SECONDS);
+    for (mesh_3d, mut visibility) in track_query.iter_mut() {
+        visibility.set_if_neq(if planner.enabled { Visibility::Visible } else { Visibility::Hidden });
+        if !planner.enabled || !stale {
+            continue;
+        }
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            let points = launch_ground_track(
+                planner.site().geodetic(),
+                planner.azimuth_deg,
+                planner.parking_orbit_km,
+                now,
+                TRACK_ORBITS,
+                *globe,
+            );
+            let positions: Vec<[f32; 3]> = points.iter().map(|point| point.to_array()).collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            *computed_at = Some(now);
+        }
+    }
+
+    if !planner.enabled || !planner.is_changed() {
+        return;
+    }
+    let site = planner.site();
+    let content = format!(
+        "Launch from {} ({})\n\
+         Azimuth {:.0}° → inclination {:.1}°\n\
+         {:.0} km parking orbit, launching now; [{}] close",
+        site.name,
+        format_coordinates(site.geodetic()),
+        planner.azimuth_deg,
+        planner.inclination_deg(),
+        planner.parking_orbit_km,
+        keymap.label(Action::ToggleLaunchPlanner),
+    );
+    for mut text in text_query.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
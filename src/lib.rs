@@ -0,0 +1,154 @@
+//! Satellite tracking without the viewer: TLE loading and caching, SGP4 propagation,
+//! coordinate frame conversions, sun position, pass prediction over terrain horizons,
+//! ground track export, regions of interest, catalog changes and statistics, SATCAT
+//! metadata and SatNOGS transmitter data
+//!
+//! Depend on the crate with `default-features = false` to leave out Bevy:
+//!
+//! ```toml
+//! ai-space-tracker = { path = "...", default-features = false }
+//! ```
+//!
+//! The `visualizer` feature (on by default) builds the 3D viewer binary and adds
+//! the Bevy scene-frame conversions to `coords`, the scene `geometry` and the viewer's
+//! plugins (`satellite::SatellitePlugin`, `ui::UiPlugin`, ...), so an app can pick the
+//! ones it wants.
+
+pub mod catalog;
+pub mod coords;
+pub mod ephemeris;
+pub mod export;
+#[cfg(feature = "visualizer")]
+pub mod geometry;
+pub mod horizon;
+pub mod passes;
+pub mod propagation;
+pub mod region;
+pub mod satcat;
+pub mod statistics;
+pub mod tle_loader;
+pub mod transmitters;
+
+// The 3D viewer: its Bevy plugins and the modules they share, assembled by the binary
+#[cfg(feature = "visualizer")]
+pub mod alerts;
+#[cfg(feature = "visualizer")]
+pub mod atmosphere;
+#[cfg(feature = "visualizer")]
+pub mod bookmarks;
+#[cfg(feature = "visualizer")]
+pub mod borders;
+#[cfg(feature = "visualizer")]
+pub mod browser;
+#[cfg(feature = "visualizer")]
+pub mod camera;
+#[cfg(feature = "visualizer")]
+pub mod capture;
+#[cfg(feature = "visualizer")]
+pub mod catalog_timeline;
+#[cfg(feature = "visualizer")]
+pub mod chase_view;
+#[cfg(feature = "visualizer")]
+pub mod cli;
+#[cfg(feature = "visualizer")]
+pub mod config;
+#[cfg(feature = "visualizer")]
+pub mod conjunction;
+#[cfg(feature = "visualizer")]
+pub mod coordinate_debug;
+#[cfg(feature = "visualizer")]
+pub mod coverage;
+#[cfg(feature = "visualizer")]
+pub mod culling;
+#[cfg(feature = "visualizer")]
+pub mod data_window;
+#[cfg(feature = "visualizer")]
+pub mod diagnostics;
+#[cfg(feature = "visualizer")]
+pub mod earth;
+#[cfg(feature = "visualizer")]
+pub mod earth_textures;
+#[cfg(feature = "visualizer")]
+pub mod eclipse;
+#[cfg(feature = "visualizer")]
+pub mod export_panel;
+#[cfg(feature = "visualizer")]
+pub mod filter;
+#[cfg(feature = "visualizer")]
+pub mod floating_origin;
+#[cfg(feature = "visualizer")]
+pub mod footprint;
+#[cfg(feature = "visualizer")]
+pub mod geo_belt;
+#[cfg(feature = "visualizer")]
+pub mod graticule;
+#[cfg(feature = "visualizer")]
+pub mod ground_pick;
+#[cfg(feature = "visualizer")]
+pub mod ground_station;
+#[cfg(feature = "visualizer")]
+pub mod ground_track;
+#[cfg(feature = "visualizer")]
+pub mod groups;
+#[cfg(feature = "visualizer")]
+pub mod iss;
+#[cfg(feature = "visualizer")]
+pub mod keymap;
+#[cfg(feature = "visualizer")]
+pub mod labels;
+#[cfg(feature = "visualizer")]
+pub mod launch_sites;
+#[cfg(feature = "visualizer")]
+pub mod map_view;
+#[cfg(feature = "visualizer")]
+pub mod measure;
+#[cfg(feature = "visualizer")]
+pub mod metadata;
+#[cfg(feature = "visualizer")]
+pub mod notifications;
+#[cfg(feature = "visualizer")]
+pub mod observer_view;
+#[cfg(feature = "visualizer")]
+pub mod orbit;
+#[cfg(feature = "visualizer")]
+pub mod radio;
+#[cfg(feature = "visualizer")]
+pub mod region_watch;
+#[cfg(feature = "rest-api")]
+pub mod rest;
+#[cfg(feature = "visualizer")]
+pub mod revisit;
+#[cfg(feature = "visualizer")]
+pub mod rotator;
+#[cfg(feature = "visualizer")]
+pub mod satellite;
+#[cfg(feature = "visualizer")]
+pub mod satellite_model;
+#[cfg(feature = "visualizer")]
+pub mod selection;
+#[cfg(feature = "visualizer")]
+pub mod sensor;
+#[cfg(feature = "visualizer")]
+pub mod session;
+#[cfg(feature = "visualizer")]
+pub mod shells;
+#[cfg(feature = "visualizer")]
+pub mod sky_plot;
+#[cfg(feature = "visualizer")]
+pub mod statistics_panel;
+#[cfg(feature = "visualizer")]
+pub mod stream;
+#[cfg(feature = "visualizer")]
+pub mod sun;
+#[cfg(feature = "visualizer")]
+pub mod text_input;
+#[cfg(feature = "visualizer")]
+pub mod time_bar;
+#[cfg(feature = "visualizer")]
+pub mod time_simulation;
+#[cfg(feature = "visualizer")]
+pub mod trail;
+#[cfg(feature = "visualizer")]
+pub mod ui;
+#[cfg(feature = "visualizer")]
+pub mod velocity;
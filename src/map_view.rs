@@ -0,0 +1,343 @@
+use bevy::camera::visibility::RenderLayers;
+use bevy::camera::ScalingMode;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::render::render_resource::PrimitiveTopology;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::camera::CameraController;
+use crate::config::Settings;
+use crate::coords::{self, Geodetic};
+use crate::export::GroundTrack;
+use crate::ground_track::RECOMPUTE_INTERVAL_SECONDS;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{update_satellite_positions, Satellite, TleRefreshed};
+use crate::selection::{SelectSatellite, Selected};
+use crate::time_simulation::TimeSimulation;
+use crate::text_input::InputFocus;
+
+/// Flat world map (equirectangular) showing satellites and the selected ground track
+///
+/// The map lives on its own render layer, drawn by a 2D camera that replaces the
+/// 3D view while map mode is on (toggle with M).
+pub struct MapViewPlugin;
+
+impl Plugin for MapViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MapView>()
+            .add_systems(Startup, spawn_map_view)
+            .add_systems(Update, (
+                toggle_map_view,
+                update_map_satellites.after(update_satellite_positions),
+                update_map_ground_track,
+                pick_map_satellite,
+            ));
+    }
+}
+
+/// Render layer of the map and everything drawn on it
+pub const MAP_LAYER: usize = 1;
+/// Size of the map in 2D world units (10 per degree)
+pub const MAP_WIDTH: f32 = 3600.0;
+pub const MAP_HEIGHT: f32 = 1800.0;
+/// Side of a satellite marker (map units)
+const MARKER_SIZE: f32 = 6.0;
+/// Side of the selected satellite's marker (map units)
+const SELECTED_MARKER_SIZE: f32 = 16.0;
+/// Orbits covered by the ground track on the map
+const GROUND_TRACK_ORBITS: f64 = 3.0;
+/// Sampling step of the ground track (seconds)
+const GROUND_TRACK_STEP_SECONDS: i64 = 30;
+/// How close a click must land to a satellite marker to select it (map units)
+const PICK_RADIUS: f32 = 20.0;
+
+/// Whether the 2D map replaces the 3D view
+#[derive(Resource, Default)]
+pub struct MapView {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+pub struct MapCamera;
+
+/// One quad per visible satellite, in a single mesh
+#[derive(Component)]
+pub struct MapSatellites;
+
+#[derive(Component)]
+pub struct MapSelectedMarker;
+
+#[derive(Component)]
+pub struct MapGroundTrack;
+
+/// Project geodetic coordinates onto the map (x east, y north, origin at 0°, 0°)
+pub fn geodetic_to_map(geodetic: Geodetic) -> Vec2 {
+    Vec2::new(
+        geodetic.longitude_deg as f32 / 180.0 * MAP_WIDTH / 2.0,
+        geodetic.latitude_deg as f32 / 90.0 * MAP_HEIGHT / 2.0,
+    )
+}
+
+/// Map position of a satellite drawn at `translation` in the 3D scene
+fn scene_to_map(translation: Vec3) -> Vec2 {
+    geodetic_to_map(coords::ecef_to_geodetic(coords::scene_to_ecef(translation)))
+}
+
+/// Two triangles per square marker, at height `z`
+fn marker_positions(centers: impl Iterator<Item = Vec2>, size: f32, z: f32) -> Vec<[f32; 3]> {
+    let half = size / 2.0;
+    centers
+        .flat_map(|center| {
+            let (left, right) = (center.x - half, center.x + half);
+            let (bottom, top) = (center.y - half, center.y + half);
+            [
+                [left, bottom, z],
+                [right, bottom, z],
+                [right, top, z],
+                [left, bottom, z],
+                [right, top, z],
+                [left, top, z],
+            ]
+        })
+        .collect()
+}
+
+fn empty_mesh(topology: PrimitiveTopology) -> Mesh {
+    let mut mesh = Mesh::new(topology, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new());
+    mesh
+}
+
+pub fn spawn_map_view(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+) {
+    let layer = RenderLayers::layer(MAP_LAYER);
+
+    // Drawn over the 3D scene (order 0), under the chase inset and the UI, and only while map mode is on
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: 1,
+            is_active: false,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        Projection::Orthographic(OrthographicProjection {
+            // Fit the whole map in the window, letterboxed
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: MAP_WIDTH,
+                min_height: MAP_HEIGHT,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        layer.clone(),
+        MapCamera,
+        Name::new("MapCamera"),
+    ));
+
+    // The day texture is already equirectangular: longitude -180° on the left, north up
+    commands.spawn((
+        Sprite {
+            image: asset_server.load(settings.textures.day.clone()),
+            custom_size: Some(Vec2::new(MAP_WIDTH, MAP_HEIGHT)),
+            ..default()
+        },
+        Transform::default(),
+        layer.clone(),
+        Name::new("MapBackground"),
+    ));
+
+    commands.spawn((
+        Mesh2d(meshes.add(empty_mesh(PrimitiveTopology::LineList))),
+        MeshMaterial2d(materials.add(Color::srgb(1.0, 1.0, 0.3))),
+        Transform::default(),
+        layer.clone(),
+        MapGroundTrack,
+        Name::new("MapGroundTrack"),
+    ));
+    commands.spawn((
+        Mesh2d(meshes.add(empty_mesh(PrimitiveTopology::TriangleList))),
+        MeshMaterial2d(materials.add(Color::srgb(1.0, 0.5, 0.0))),
+        Transform::default(),
+        layer.clone(),
+        MapSatellites,
+        Name::new("MapSatellites"),
+    ));
+    commands.spawn((
+        Mesh2d(meshes.add(empty_mesh(PrimitiveTopology::TriangleList))),
+        MeshMaterial2d(materials.add(Color::srgb(0.2, 1.0, 1.0))),
+        Transform::default(),
+        layer,
+        MapSelectedMarker,
+        Name::new("MapSelectedMarker"),
+    ));
+}
+
+/// Switch between the 3D globe and the 2D map with M
+pub fn toggle_map_view(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut map_view: ResMut<MapView>,
+    mut map_camera_query: Query<&mut Camera, (With<MapCamera>, Without<CameraController>)>,
+    mut scene_camera_query: Query<&mut Camera, With<CameraController>>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleMapView, &keyboard_input) {
+        return;
+    }
+    map_view.enabled = !map_view.enabled;
+
+    // The globe is fully covered by the map, so don't render it meanwhile
+    for mut camera in map_camera_query.iter_mut() {
+        camera.is_active = map_view.enabled;
+    }
+    for mut camera in scene_camera_query.iter_mut() {
+        camera.is_active = !map_view.enabled;
+    }
+}
+
+/// Place a marker for every visible satellite, and a larger one for the selected satellite
+#[allow(clippy::type_complexity)]
+pub fn update_map_satellites(
+    map_view: Res<MapView>,
+    satellite_query: Query<(&Transform, &Visibility, Has<Selected>), With<Satellite>>,
+    marker_query: Query<(&Mesh2d, Has<MapSelectedMarker>), Or<(With<MapSatellites>, With<MapSelectedMarker>)>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    if !map_view.enabled {
+        return;
+    }
+
+    let visible = || {
+        satellite_query
+            .iter()
+            .filter(|(_, visibility, _)| **visibility != Visibility::Hidden)
+    };
+    let markers = marker_positions(visible().map(|(transform, _, _)| scene_to_map(transform.translation)), MARKER_SIZE, 1.0);
+    let selected = marker_positions(
+        visible()
+            .filter(|(_, _, is_selected)| *is_selected)
+            .map(|(transform, _, _)| scene_to_map(transform.translation)),
+        SELECTED_MARKER_SIZE,
+        3.0,
+    );
+
+    for (mesh_2d, is_selected_marker) in marker_query.iter() {
+        if let Some(mesh) = meshes.get_mut(&mesh_2d.0) {
+            let positions = if is_selected_marker { selected.clone() } else { markers.clone() };
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        }
+    }
+}
+
+/// Draw the selected satellite's upcoming ground track, split where it crosses the map edge
+pub fn update_map_ground_track(
+    map_view: Res<MapView>,
+    sim_time: Res<TimeSimulation>,
+    selected_query: Query<(Entity, &Satellite), With<Selected>>,
+    track_query: Query<&Mesh2d, With<MapGroundTrack>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    // Satellite and simulation time the current track was computed for
+    mut computed: Local<Option<(Entity, DateTime<Utc>)>>,
+) {
+    if TleRefreshed::any_changed(&mut refreshed) {
+        *computed = None;
+    }
+    if !map_view.enabled {
+        return;
+    }
+
+    let now = sim_time.current_time();
+    let selected = selected_query.iter().next();
+    let up_to_date = match (selected, *computed) {
+        (Some((entity, _)), Some((computed_entity, computed_at))) => {
+            entity == computed_entity && (now - computed_at).num_seconds().abs() < RECOMPUTE_INTERVAL_SECONDS
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    if up_to_date {
+        return;
+    }
+
+    let positions: Vec<[f32; 3]> = match selected {
+        Some((entity, satellite)) => {
+            *computed = Some((entity, now));
+            let period_minutes = satellite.orbital_parameters().period_minutes;
+            let track = GroundTrack::compute(
+                &satellite.name,
+                satellite.elements.norad_id,
+                satellite.propagator(),
+                now,
+                now + Duration::seconds((period_minutes * 60.0 * GROUND_TRACK_ORBITS) as i64),
+                Duration::seconds(GROUND_TRACK_STEP_SECONDS),
+            );
+            // Line list: each consecutive pair of points within a segment
+            track
+                .segments
+                .iter()
+                .flat_map(|segment| segment.windows(2))
+                .flat_map(|pair| {
+                    pair.iter().map(|[longitude, latitude]| {
+                        geodetic_to_map(Geodetic::new(*latitude, *longitude, 0.0)).extend(2.0).to_array()
+                    })
+                })
+                .collect()
+        }
+        None => {
+            *computed = None;
+            Vec::new()
+        }
+    };
+
+    for mesh_2d in track_query.iter() {
+        if let Some(mesh) = meshes.get_mut(&mesh_2d.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions.clone());
+        }
+    }
+}
+
+/// Select the satellite nearest to a click on the map (clicking empty map clears the selection)
+pub fn pick_map_satellite(
+    map_view: Res<MapView>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MapCamera>>,
+    satellite_query: Query<(Entity, &Transform, &Visibility), With<Satellite>>,
+    ui_interactions: Query<&Interaction>,
+    mut select_writer: MessageWriter<SelectSatellite>,
+) {
+    if !map_view.enabled || !mouse_button.just_released(MouseButton::Left) {
+        return;
+    }
+    // Ignore clicks on UI widgets
+    if ui_interactions.iter().any(|interaction| *interaction != Interaction::None) {
+        return;
+    }
+    let cursor = match windows.iter().next().and_then(|window| window.cursor_position()) {
+        Some(cursor) => cursor,
+        None => return,
+    };
+    let (camera, camera_transform) = match camera_query.iter().next() {
+        Some(camera) => camera,
+        None => return,
+    };
+    let click = match camera.viewport_to_world_2d(camera_transform, cursor) {
+        Ok(click) => click,
+        Err(_) => return,
+    };
+
+    let picked = satellite_query
+        .iter()
+        .filter(|(_, _, visibility)| **visibility != Visibility::Hidden)
+        .map(|(entity, transform, _)| (entity, scene_to_map(transform.translation).distance(click)))
+        .filter(|(_, distance)| *distance <= PICK_RADIUS)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity);
+    select_writer.write(SelectSatellite(picked));
+}
@@ -0,0 +1,323 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::render::render_resource::PrimitiveTopology;
+use nalgebra::Vector3;
+
+use crate::camera::CameraController;
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::ground_pick::format_coordinates;
+use crate::keymap::{Action, Keymap};
+use crate::labels::project_label;
+use crate::map_view::MapView;
+use crate::satellite::Satellite;
+use crate::selection::{GroundClicked, SelectSatellite};
+use crate::text_input::InputFocus;
+use crate::time_bar::TIME_BAR_STRIP;
+
+/// Measure the range between two satellites, or a satellite and a ground point, live as time runs
+pub struct MeasurePlugin;
+
+impl Plugin for MeasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeasureTool>()
+            .init_resource::<MapView>()
+            .add_systems(Startup, setup_measure)
+            .add_systems(Update, (
+                toggle_measure,
+                record_measure_clicks,
+                update_measurement,
+            ).chain());
+    }
+}
+
+const MEASURE_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+/// Radius of the sphere marking a ground end (km)
+const GROUND_END_RADIUS: f32 = coords::km_to_scene(50.0);
+
+/// One end of a measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeasureEnd {
+    Satellite(Entity),
+    /// Point on the ground (altitude ignored)
+    Ground(Geodetic),
+}
+
+/// Measurement mode (toggle with K): the next two clicks pick the ends
+#[derive(Resource, Default)]
+pub struct MeasureTool {
+    pub enabled: bool,
+    pub ends: Vec<MeasureEnd>,
+}
+
+impl MeasureTool {
+    /// Add an end; a third click starts a new measurement
+    fn push(&mut self, end: MeasureEnd) {
+        if self.ends.len() >= 2 {
+            self.ends.clear();
+        }
+        self.ends.push(end);
+    }
+}
+
+/// Line between the two ends
+#[derive(Component)]
+pub struct MeasureLine;
+
+/// Sphere on a ground end
+#[derive(Component)]
+pub struct MeasureGroundMarker;
+
+/// Range readout at the middle of the line
+#[derive(Component)]
+pub struct MeasureLabel;
+
+/// Instructions and the full measurement (bottom, right of the browser, above the time bar)
+#[derive(Component)]
+pub struct MeasurePanelText;
+
+pub fn setup_measure(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: MEASURE_COLOR,
+        unlit: true,
+        ..default()
+    });
+
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 2]);
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material.clone()),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        MeasureLine,
+        Name::new("MeasureLine"),
+    ));
+
+    let marker_mesh = meshes.add(Sphere::new(GROUND_END_RADIUS).mesh().uv(12, 6));
+    for _ in 0..2 {
+        commands.spawn((
+            Mesh3d(marker_mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::default(),
+            ScenePosition::default(),
+            Visibility::Hidden,
+            MeasureGroundMarker,
+        ));
+    }
+
+    commands.spawn((
+        Text2d::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(MEASURE_COLOR),
+        Transform::default(),
+        Visibility::Hidden,
+        MeasureLabel,
+    ));
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(420.0),
+            bottom: Val::Px(TIME_BAR_STRIP + 10.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            display: Display::None,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(MEASURE_COLOR),
+        MeasurePanelText,
+    ));
+}
+
+/// Turn measurement mode on or off with K
+pub fn toggle_measure(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut tool: ResMut<MeasureTool>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleMeasure, &keyboard_input) {
+        return;
+    }
+    tool.enabled = !tool.enabled;
+    tool.ends.clear();
+}
+
+/// Take the ends from clicks on satellites and on the globe; a click on empty space starts over
+pub fn record_measure_clicks(
+    mut tool: ResMut<MeasureTool>,
+    mut selections: MessageReader<SelectSatellite>,
+    mut ground_clicks: MessageReader<GroundClicked>,
+    globe: Res<GlobeShape>,
+) {
+    if !tool.enabled {
+        selections.clear();
+        ground_clicks.clear();
+        return;
+    }
+    for selection in selections.read() {
+        match selection.0 {
+            Some(entity) => tool.push(MeasureEnd::Satellite(entity)),
+            None => tool.ends.clear(),
+        }
+    }
+    for click in ground_clicks.read() {
+        let mut point = coords::scene_to_geodetic(click.0, *globe);
+        point.altitude_km = 0.0;
+        tool.push(MeasureEnd::Ground(point));
+    }
+}
+
+/// "1234.5 km" under 10000 km, "12345 km" above
+fn format_range(range_km: f64) -> String {
+    if range_km < 10000.0 {
+        format!("{:.1} km", range_km)
+    } else {
+        format!("{:.0} km", range_km)
+    }
+}
+
+/// Draw the line and show the range and angles between the two ends
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_measurement(
+    tool: Res<MeasureTool>,
+    map_view: Res<MapView>,
+    satellite_query: Query<(&Satellite, &ScenePosition)>,
+    mut line_query: Query<(&Mesh3d, &mut Visibility), (With<MeasureLine>, Without<MeasureGroundMarker>, Without<MeasureLabel>)>,
+    mut marker_query: Query<(&mut ScenePosition, &mut Visibility), (With<MeasureGroundMarker>, Without<Satellite>, Without<MeasureLabel>)>,
+    mut label_query: Query<(&mut Text2d, &mut Transform, &mut Visibility), (With<MeasureLabel>, Without<Satellite>)>,
+    mut panel_query: Query<(&mut Text, &mut Node), With<MeasurePanelText>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    origin: Res<FloatingOrigin>,
+    globe: Res<GlobeShape>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    // Scene position (as drawn), Earth-fixed position (on the WGS84 ellipsoid for ground points)
+    // and name of each end; a satellite gone after a reload drops out of the measurement
+    let resolve = |end: &MeasureEnd| match *end {
+        MeasureEnd::Satellite(entity) => satellite_query.get(entity).ok().map(|(satellite, position)| {
+            (position.scene(), position.ecef(), satellite.name.clone())
+        }),
+        MeasureEnd::Ground(point) => Some((
+            coords::geodetic_to_scene(point, *globe),
+            coords::geodetic_to_ecef(point),
+            format_coordinates(point),
+        )),
+    };
+    let ends: Vec<(Vec3, Vector3<f64>, String)> = tool.ends.iter().filter_map(resolve).collect();
+    let complete = tool.enabled && ends.len() == 2 && tool.ends.len() == 2;
+
+    // Range and angles, or the next step when the measurement isn't complete
+    let summary = if !tool.enabled {
+        None
+    } else if !complete {
+        Some(match ends.len() {
+            1 => format!("Measure from {}: click a satellite or a point on the globe", ends[0].2),
+            _ => "Measure: click a satellite, or a point on the globe".to_string(),
+        })
+    } else {
+        let (from, to) = (ends[0].1, ends[1].1);
+        let mut summary = format!("{} → {}\nRange {}", ends[0].2, ends[1].2, format_range((to - from).norm()));
+        match (tool.ends[0], tool.ends[1]) {
+            (MeasureEnd::Ground(point), MeasureEnd::Satellite(_)) | (MeasureEnd::Satellite(_), MeasureEnd::Ground(point)) => {
+                let target = if matches!(tool.ends[0], MeasureEnd::Ground(_)) { to } else { from };
+                let look = coords::look_angles(point, target);
+                summary.push_str(&format!(
+                    "\nAzimuth {:.1}°, elevation {:.1}°{}",
+                    look.azimuth_deg,
+                    look.elevation_deg,
+                    if look.elevation_deg < 0.0 { " (below the horizon)" } else { "" },
+                ));
+            }
+            _ => {
+                summary.push_str(&format!("\nSeparation seen from Earth's center {:.2}°", angle_between(from, to)));
+            }
+        }
+        Some(summary)
+    };
+
+    for (mesh_3d, mut visibility) in line_query.iter_mut() {
+        visibility.set_if_neq(if complete { Visibility::Visible } else { Visibility::Hidden });
+        if complete {
+            if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+                mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![ends[0].0.to_array(), ends[1].0.to_array()]);
+            }
+        }
+    }
+
+    let mut ground_ends = tool
+        .ends
+        .iter()
+        .filter_map(|end| match end {
+            MeasureEnd::Ground(point) if tool.enabled => Some(coords::geodetic_to_scene(*point, *globe)),
+            _ => None,
+        });
+    for (mut marker, mut visibility) in marker_query.iter_mut() {
+        match ground_ends.next() {
+            Some(position) => {
+                marker.set_if_neq(ScenePosition(position.as_dvec3()));
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    // Short range label at the middle of the line; Text2d draws over the map, so it is dropped there
+    let view = match (camera_query.iter().next(), windows.iter().next()) {
+        (Some(camera), Some(window)) if complete && !map_view.enabled => Some((camera, Vec2::new(window.width(), window.height()))),
+        _ => None,
+    };
+    for (mut text, mut transform, mut visibility) in label_query.iter_mut() {
+        let projected = view.and_then(|((camera, camera_global), window_size)| {
+            let middle = (ends[0].0 + ends[1].0) / 2.0;
+            project_label(middle, camera, camera_global, *origin, *globe, window_size)
+        });
+        match projected {
+            Some(position) => {
+                let range = format_range((ends[1].1 - ends[0].1).norm());
+                if text.0 != range {
+                    text.0 = range;
+                }
+                transform.translation = position.extend(1.0);
+                visibility.set_if_neq(Visibility::Visible);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    for (mut text, mut node) in panel_query.iter_mut() {
+        let display = if summary.is_some() { Display::Flex } else { Display::None };
+        if node.display != display {
+            node.display = display;
+        }
+        if let Some(summary) = &summary {
+            if text.0 != *summary {
+                text.0 = summary.clone();
+            }
+        }
+    }
+}
+
+/// Angle between two positions seen from Earth's center (degrees)
+fn angle_between(from: Vector3<f64>, to: Vector3<f64>) -> f64 {
+    from.angle(&to).to_degrees()
+}
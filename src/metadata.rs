@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+
+use crate::config::Settings;
+use crate::notifications::{Notify, Retry, RetryAction};
+use crate::satcat::{SatcatEntry, SatcatLoader, SatcatSet};
+use crate::satellite::Satellite;
+use crate::statistics_panel::StatisticsView;
+use crate::ui::SatelliteFilter;
+
+/// Owner, launch date, object type and radar size of each satellite from the SATCAT,
+/// loaded in the background
+pub struct MetadataPlugin;
+
+impl Plugin for MetadataPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        if !app.world().resource::<Settings>().satcat.enabled {
+            return;
+        }
+
+        app.add_systems(Startup, start_satcat_loading)
+            .add_systems(Update, (retry_satcat_loading, finish_satcat_loading, apply_satcat).chain());
+    }
+}
+
+/// SATCAT entries by NORAD ID, present once loading has finished
+#[derive(Resource)]
+pub struct Satcat(pub SatcatSet);
+
+/// Download or file read running on the async compute pool
+#[derive(Resource)]
+struct SatcatLoad(Task<Result<SatcatSet, String>>);
+
+fn start_satcat_loading(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(load_in_background(&settings));
+}
+
+fn load_in_background(settings: &Settings) -> SatcatLoad {
+    let loader = SatcatLoader::new()
+        .with_source(settings.satcat.source.clone())
+        .with_cache_max_age_hours(settings.satcat.cache_max_age_hours);
+    SatcatLoad(AsyncComputeTaskPool::get().spawn(async move { loader.load().map_err(|e| e.to_string()) }))
+}
+
+/// Load again when the failure notification's Retry button is clicked
+fn retry_satcat_loading(
+    mut commands: Commands,
+    mut retries: MessageReader<Retry>,
+    load: Option<Res<SatcatLoad>>,
+    settings: Res<Settings>,
+) {
+    let retried = retries.read().any(|retry| retry.0 == RetryAction::LoadSatcat);
+    if retried && load.is_none() {
+        commands.insert_resource(load_in_background(&settings));
+    }
+}
+
+fn finish_satcat_loading(
+    mut commands: Commands,
+    load: Option<ResMut<SatcatLoad>>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let mut load = match load {
+        Some(load) => load,
+        None => return,
+    };
+    let result = match block_on(future::poll_once(&mut load.0)) {
+        Some(result) => result,
+        None => return, // Still loading
+    };
+    commands.remove_resource::<SatcatLoad>();
+
+    match result {
+        Ok(satcat) => {
+            println!("✓ Loaded SATCAT data for {} objects", satcat.len());
+            commands.insert_resource(Satcat(satcat));
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to load SATCAT data: {}", e);
+            notify_writer.write(
+                Notify::error(format!("Failed to load SATCAT data: {}", e)).with_retry(RetryAction::LoadSatcat),
+            );
+        }
+    }
+}
+
+/// Attach the SATCAT entries to the satellites when the catalog arrives and as satellites
+/// are spawned, and hand the owners to the statistics
+fn apply_satcat(
+    satcat: Option<Res<Satcat>>,
+    mut filter: ResMut<SatelliteFilter>,
+    mut statistics: ResMut<StatisticsView>,
+    mut satellite_query: Query<&mut Satellite>,
+    added: Query<(), Added<Satellite>>,
+) {
+    let Some(satcat) = satcat else { return };
+    if !satcat.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let mut applied = 0;
+    for mut satellite in satellite_query.iter_mut() {
+        let norad_id = satellite.elements.norad_id;
+        let entry = satcat.0.get(&norad_id);
+        if satellite.satcat.as_ref() == entry {
+            continue;
+        }
+        satellite.satcat = entry.cloned();
+        if let Some(SatcatEntry { owner, .. }) = entry.filter(|entry| !entry.owner.is_empty()) {
+            statistics.countries.insert(norad_id, owner.clone());
+        }
+        applied += 1;
+    }
+    if applied == 0 {
+        return;
+    }
+
+    // Filter terms on SATCAT fields and the owner chart see the new data
+    filter.set_changed();
+    statistics.statistics = None;
+}
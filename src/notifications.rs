@@ -0,0 +1,291 @@
+use bevy::prelude::*;
+
+/// On-screen notifications (toasts) for errors and warnings that used to only reach the console
+pub struct NotificationsPlugin;
+
+impl Plugin for NotificationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<Notify>()
+            .add_message::<Retry>()
+            .add_systems(Startup, setup_notification_area)
+            .add_systems(Update, (
+                show_notifications,
+                handle_toast_buttons,
+                expire_notifications,
+            ).chain());
+    }
+}
+
+/// Toasts on screen at once; the oldest make way for new ones
+const MAX_TOASTS: usize = 5;
+const TOAST_BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn color(self) -> Color {
+        match self {
+            NotificationLevel::Info => Color::srgb(0.4, 0.8, 1.0),
+            NotificationLevel::Warning => Color::srgb(1.0, 0.8, 0.2),
+            NotificationLevel::Error => Color::srgb(1.0, 0.35, 0.35),
+        }
+    }
+
+    /// How long a toast stays up (seconds); errors stay until dismissed
+    fn lifetime(self) -> Option<f32> {
+        match self {
+            NotificationLevel::Info => Some(5.0),
+            NotificationLevel::Warning => Some(12.0),
+            NotificationLevel::Error => None,
+        }
+    }
+}
+
+/// Something that failed and can be tried again from its toast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryAction {
+    /// Initial TLE download or cache read
+    LoadTle,
+    /// Background TLE refresh
+    RefreshTle,
+    /// SatNOGS transmitter list
+    LoadTransmitters,
+    /// Satellite catalog metadata
+    LoadSatcat,
+}
+
+/// Request to show a notification
+///
+/// Repeats of a notification still on screen bump its count instead of stacking up.
+#[derive(Message, Debug, Clone)]
+pub struct Notify {
+    pub level: NotificationLevel,
+    pub text: String,
+    pub retry: Option<RetryAction>,
+}
+
+impl Notify {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self { level: NotificationLevel::Info, text: text.into(), retry: None }
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self { level: NotificationLevel::Warning, text: text.into(), retry: None }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self { level: NotificationLevel::Error, text: text.into(), retry: None }
+    }
+
+    /// Offer a Retry button on the toast
+    pub fn with_retry(mut self, action: RetryAction) -> Self {
+        self.retry = Some(action);
+        self
+    }
+}
+
+/// Sent when a toast's Retry button is clicked
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Retry(pub RetryAction);
+
+/// Column the toasts are stacked in (top-center, under the loading indicator)
+#[derive(Component)]
+pub struct NotificationArea;
+
+/// One notification on screen
+#[derive(Component)]
+pub struct Toast {
+    pub level: NotificationLevel,
+    pub text: String,
+    /// Times the notification was raised while on screen
+    pub count: usize,
+    /// Seconds left before it disappears (None: until dismissed)
+    pub remaining: Option<f32>,
+    /// Text entity showing the message and count
+    label: Entity,
+    /// Order the toasts were raised in
+    sequence: u64,
+}
+
+/// Message followed by how many times it was raised, if more than once
+fn caption(text: &str, count: usize) -> String {
+    if count > 1 {
+        format!("{} (x{})", text, count)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Button on a toast
+#[derive(Component, Clone, Copy)]
+pub struct ToastButton {
+    pub toast: Entity,
+    pub retry: Option<RetryAction>,
+}
+
+pub fn setup_notification_area(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            left: Val::Percent(30.0),
+            right: Val::Percent(30.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        NotificationArea,
+    ));
+}
+
+fn spawn_button(parent: &mut ChildSpawnerCommands, label: &str, button: ToastButton) {
+    parent.spawn((
+        Node {
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+            ..default()
+        },
+        Text::new(label),
+        TextFont {
+            font_size: 13.0,
+            ..default()
+        },
+        BackgroundColor(TOAST_BUTTON_COLOR),
+        Interaction::default(),
+        button,
+    ));
+}
+
+/// Spawn toasts for new notifications, or bump the count of identical ones on screen
+pub fn show_notifications(
+    mut commands: Commands,
+    mut requests: MessageReader<Notify>,
+    area_query: Query<Entity, With<NotificationArea>>,
+    mut toast_query: Query<(Entity, &mut Toast)>,
+    mut text_query: Query<&mut Text>,
+    mut next_sequence: Local<u64>,
+) {
+    let area = match area_query.iter().next() {
+        Some(area) => area,
+        None => return,
+    };
+
+    // Coalesce repeats within the frame too, since new toasts only exist once commands apply
+    let mut new: Vec<(Notify, usize)> = Vec::new();
+    for request in requests.read() {
+        let on_screen = toast_query
+            .iter_mut()
+            .find(|(_, toast)| toast.level == request.level && toast.text == request.text);
+        if let Some((_, mut toast)) = on_screen {
+            toast.count += 1;
+            toast.remaining = request.level.lifetime();
+            if let Ok(mut text) = text_query.get_mut(toast.label) {
+                text.0 = caption(&toast.text, toast.count);
+            }
+            continue;
+        }
+        match new.iter_mut().find(|(pending, _)| pending.level == request.level && pending.text == request.text) {
+            Some((_, count)) => *count += 1,
+            None => new.push((request.clone(), 1)),
+        }
+    }
+    if new.is_empty() {
+        return;
+    }
+
+    let mut existing: Vec<(u64, Entity)> = toast_query.iter().map(|(entity, toast)| (toast.sequence, entity)).collect();
+    existing.sort();
+    let overflow = (existing.len() + new.len()).saturating_sub(MAX_TOASTS);
+    for (_, entity) in existing.into_iter().take(overflow) {
+        commands.entity(entity).despawn();
+    }
+
+    for (request, count) in new.into_iter().rev().take(MAX_TOASTS).rev() {
+        let color = request.level.color();
+        *next_sequence += 1;
+        let toast = commands.spawn_empty().id();
+        let label = commands
+            .spawn((
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+                Text::new(caption(&request.text, count)),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(color),
+            ))
+            .id();
+
+        commands
+            .entity(toast)
+            .insert((
+                Node {
+                    width: Val::Percent(100.0),
+                    column_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    padding: UiRect::axes(Val::Px(8.0), Val::Px(6.0)),
+                    border: UiRect::left(Val::Px(4.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+                BorderColor::all(color),
+                Toast {
+                    level: request.level,
+                    text: request.text,
+                    count,
+                    remaining: request.level.lifetime(),
+                    label,
+                    sequence: *next_sequence,
+                },
+            ))
+            .add_child(label)
+            .with_children(|parent| {
+                if let Some(action) = request.retry {
+                    spawn_button(parent, "Retry", ToastButton { toast, retry: Some(action) });
+                }
+                spawn_button(parent, "x", ToastButton { toast, retry: None });
+            });
+        commands.entity(area).add_child(toast);
+    }
+}
+
+/// Retry or dismiss from a toast's buttons
+pub fn handle_toast_buttons(
+    mut commands: Commands,
+    mut button_query: Query<(&Interaction, &ToastButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut retry_writer: MessageWriter<Retry>,
+) {
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => TOAST_BUTTON_COLOR,
+            _ => TOAST_BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(action) = button.retry {
+            retry_writer.write(Retry(action));
+        }
+        commands.entity(button.toast).try_despawn();
+    }
+}
+
+/// Remove toasts whose time is up
+pub fn expire_notifications(mut commands: Commands, time: Res<Time>, mut toast_query: Query<(Entity, &mut Toast)>) {
+    for (entity, mut toast) in toast_query.iter_mut() {
+        if let Some(remaining) = toast.remaining.as_mut() {
+            *remaining -= time.delta_secs();
+            if *remaining <= 0.0 {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use nalgebra::Vector3;
+
+use crate::camera::{camera_controller_system, CameraController};
+use crate::coords::{self, GlobeShape};
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::ground_station::GroundStation;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{scale_markers, Satellite, SATELLITE_RADIUS};
+use crate::selection::{Selected, SELECTED_SCALE};
+use crate::text_input::InputFocus;
+
+/// Sky as seen from a ground station, with an azimuth/elevation grid (cycle stations with H)
+pub struct ObserverViewPlugin;
+
+impl Plugin for ObserverViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ObserverView>()
+            .add_systems(Startup, spawn_sky_grid)
+            .add_systems(Update, (
+                cycle_observer_view,
+                look_around,
+                update_observer_camera.after(camera_controller_system),
+                // Overrides the marker size while observing
+                scale_sky_points.after(scale_markers),
+            ).chain());
+    }
+}
+
+/// Height of the observer's eye above the station (km)
+const EYE_HEIGHT_KM: f32 = 1.0;
+/// Distance from the observer to the drawn az/el grid (km)
+const GRID_RADIUS_KM: f32 = 300.0;
+/// Spacing of the elevation circles and azimuth lines (degrees)
+const GRID_STEP_DEG: usize = 30;
+/// Apparent size of a satellite in the observer's sky (radians across)
+const SKY_POINT_SIZE: f32 = 0.006;
+/// Gaze turn rate for the orbit keys (degrees per second)
+const LOOK_KEY_SPEED: f32 = 45.0;
+/// Gaze turn per pixel of left-drag (degrees)
+const LOOK_DRAG_SPEED: f32 = 0.1;
+
+/// Ground station the camera is standing on, and where it is looking
+#[derive(Resource)]
+pub struct ObserverView {
+    /// Station entity, or None when the view is off
+    pub station: Option<Entity>,
+    /// Degrees clockwise from true north
+    pub azimuth_deg: f32,
+    /// Degrees above the horizon
+    pub elevation_deg: f32,
+}
+
+impl Default for ObserverView {
+    fn default() -> Self {
+        Self {
+            station: None,
+            azimuth_deg: 180.0,
+            elevation_deg: 30.0,
+        }
+    }
+}
+
+/// Azimuth/elevation grid around the observer, in the station's East-Up-South frame
+#[derive(Component)]
+pub struct SkyGrid;
+
+/// Unit direction of an azimuth/elevation in the local frame (x east, y up, z south)
+fn sky_direction(azimuth_deg: f32, elevation_deg: f32) -> Vec3 {
+    let (sin_az, cos_az) = azimuth_deg.to_radians().sin_cos();
+    let (sin_el, cos_el) = elevation_deg.to_radians().sin_cos();
+    Vec3::new(sin_az * cos_el, sin_el, -cos_az * cos_el)
+}
+
+/// Line list of elevation circles and azimuth lines on a dome of `GRID_RADIUS_KM`
+fn sky_grid_positions(azimuths: impl Iterator<Item = usize>, with_circles: bool) -> Vec<[f32; 3]> {
+    let point = |azimuth: usize, elevation: usize| (sky_direction(azimuth as f32, elevation as f32) * coords::km_to_scene(GRID_RADIUS_KM)).to_array();
+    let mut positions = Vec::new();
+    if with_circles {
+        for elevation in (0..90).step_by(GRID_STEP_DEG) {
+            for azimuth in (0..360).step_by(5) {
+                positions.extend([point(azimuth, elevation), point(azimuth + 5, elevation)]);
+            }
+        }
+    }
+    for azimuth in azimuths {
+        for elevation in (0..90).step_by(5) {
+            positions.extend([point(azimuth, elevation), point(azimuth, elevation + 5)]);
+        }
+    }
+    positions
+}
+
+pub fn spawn_sky_grid(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let lines = |positions: Vec<[f32; 3]>| {
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh
+    };
+    let grid_mesh = meshes.add(lines(sky_grid_positions((GRID_STEP_DEG..360).step_by(GRID_STEP_DEG), true)));
+    // North stands out so the view can be oriented at a glance
+    let north_mesh = meshes.add(lines(sky_grid_positions(std::iter::once(0), false)));
+    let material = |color: Color| StandardMaterial {
+        base_color: color,
+        unlit: true,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Mesh3d(grid_mesh),
+            MeshMaterial3d(materials.add(material(Color::srgb(0.3, 0.6, 0.9)))),
+            Transform::default(),
+            ScenePosition::default(),
+            Visibility::Hidden,
+            SkyGrid,
+            Name::new("SkyGrid"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Mesh3d(north_mesh),
+                MeshMaterial3d(materials.add(material(Color::srgb(1.0, 0.3, 0.3)))),
+                Transform::default(),
+            ));
+        });
+}
+
+/// Scene-frame rotation from a station's East-Up-South frame
+fn local_frame(station: &GroundStation) -> Quat {
+    let (sin_lat, cos_lat) = station.latitude_deg.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = station.longitude_deg.to_radians().sin_cos();
+    let east = coords::ecef_to_scene(Vector3::new(-sin_lon, cos_lon, 0.0));
+    let north = coords::ecef_to_scene(Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat));
+    let up = coords::ecef_to_scene(Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat));
+    Quat::from_mat3(&Mat3::from_cols(east, up, -north))
+}
+
+/// Step through the ground stations (sorted by name), then back to the normal camera
+pub fn cycle_observer_view(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut observer: ResMut<ObserverView>,
+    station_query: Query<(Entity, &GroundStation)>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::CycleObserverView, &keyboard_input) {
+        return;
+    }
+
+    let mut stations: Vec<(Entity, &GroundStation)> = station_query.iter().collect();
+    if stations.is_empty() {
+        println!("No ground stations loaded; add one to ground_stations.json to use the observer view");
+        return;
+    }
+    stations.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+
+    let next = match observer.station {
+        None => Some(0),
+        Some(current) => stations
+            .iter()
+            .position(|(entity, _)| *entity == current)
+            .map(|index| index + 1)
+            .filter(|&index| index < stations.len()),
+    };
+    observer.station = next.map(|index| stations[index].0);
+    match next {
+        Some(index) => {
+            let station = stations[index].1;
+            println!(
+                "Observer view: {} ({:.3}°, {:.3}°)",
+                station.name, station.latitude_deg, station.longitude_deg
+            );
+        }
+        None => println!("Observer view: off"),
+    }
+}
+
+/// Turn the observer's gaze with the orbit keys or a left-drag
+pub fn look_around(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    time: Res<Time>,
+    mut observer: ResMut<ObserverView>,
+) {
+    if observer.station.is_none() {
+        return;
+    }
+    let key_held = |action| !focus.is_focused() && keymap.pressed(action, &keyboard_input);
+
+    let step = LOOK_KEY_SPEED * time.delta_secs();
+    let mut turn = Vec2::ZERO;
+    if key_held(Action::OrbitLeft) {
+        turn.x -= step;
+    }
+    if key_held(Action::OrbitRight) {
+        turn.x += step;
+    }
+    if key_held(Action::OrbitUp) {
+        turn.y += step;
+    }
+    if key_held(Action::OrbitDown) {
+        turn.y -= step;
+    }
+    // Drag the sky: moving the mouse right turns the gaze left
+    if mouse_button.pressed(MouseButton::Left) {
+        turn += Vec2::new(-mouse_motion.delta.x, mouse_motion.delta.y) * LOOK_DRAG_SPEED;
+    }
+    if turn == Vec2::ZERO {
+        return;
+    }
+
+    observer.azimuth_deg = (observer.azimuth_deg + turn.x).rem_euclid(360.0);
+    observer.elevation_deg = (observer.elevation_deg + turn.y).clamp(-10.0, 89.0);
+}
+
+/// Stand the main camera on the station, looking along the gaze, and place the grid around it
+///
+/// Runs after the orbit controller and overrides its pose, so leaving the view
+/// returns the camera to where it was.
+#[allow(clippy::type_complexity)]
+pub fn update_observer_camera(
+    observer: Res<ObserverView>,
+    station_query: Query<&GroundStation>,
+    origin: Res<FloatingOrigin>,
+    globe: Res<GlobeShape>,
+    mut camera_query: Query<(&mut Transform, &mut ScenePosition), (With<CameraController>, Without<SkyGrid>)>,
+    mut grid_query: Query<(&mut Transform, &mut ScenePosition, &mut Visibility), With<SkyGrid>>,
+) {
+    let pose = observer.station.and_then(|entity| station_query.get(entity).ok()).map(|station| {
+        let frame = local_frame(station);
+        let eye = coords::geodetic_to_scene(station.geodetic(), *globe) + frame * Vec3::Y * coords::km_to_scene(EYE_HEIGHT_KM);
+        (eye, frame)
+    });
+
+    for (mut transform, mut position, mut visibility) in grid_query.iter_mut() {
+        match pose {
+            Some((eye, frame)) => {
+                position.0 = eye.as_dvec3();
+                *transform = Transform::from_translation(origin.to_render(position.0)).with_rotation(frame);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    if let Some((eye, frame)) = pose {
+        let gaze = frame * sky_direction(observer.azimuth_deg, observer.elevation_deg);
+        for (mut transform, mut position) in camera_query.iter_mut() {
+            position.0 = eye.as_dvec3();
+            *transform = Transform::from_translation(origin.to_render(position.0)).looking_to(gaze, frame * Vec3::Y);
+        }
+    }
+}
+
+/// Shrink satellites to points of constant apparent size while observing,
+/// and give them back their normal size afterwards
+pub fn scale_sky_points(
+    observer: Res<ObserverView>,
+    mut was_observing: Local<bool>,
+    camera_query: Query<&Transform, (With<CameraController>, Without<Satellite>)>,
+    mut satellite_query: Query<(&mut Transform, Has<Selected>), With<Satellite>>,
+) {
+    let observing = observer.station.is_some();
+    if !observing && !*was_observing {
+        return;
+    }
+    *was_observing = observing;
+
+    let eye = camera_query.iter().next().map(|transform| transform.translation);
+    for (mut transform, selected) in satellite_query.iter_mut() {
+        let base = if selected { SELECTED_SCALE } else { 1.0 };
+        let scale = match eye {
+            Some(eye) if observing => {
+                base * transform.translation.distance(eye) * SKY_POINT_SIZE / (2.0 * SATELLITE_RADIUS)
+            }
+            _ => base,
+        };
+        transform.scale = Vec3::splat(scale);
+    }
+}
@@ -0,0 +1,425 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::render::render_resource::PrimitiveTopology;
+use chrono::{DateTime, Duration, Utc};
+use nalgebra::Vector3;
+
+use crate::camera::CameraController;
+use crate::coords::{self, GlobeShape};
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::keymap::{Action, Keymap};
+use crate::labels::project_label;
+use crate::map_view::MapView;
+use crate::satellite::{Satellite, EARTH_MU};
+use crate::selection::Selected;
+use crate::time_simulation::TimeSimulation;
+use crate::text_input::InputFocus;
+
+/// Osculating orbit ellipse and plane of the selected satellite, with apogee and perigee markers
+pub struct OrbitPlugin;
+
+impl Plugin for OrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OrbitDisplay>()
+            .init_resource::<MapView>()
+            .add_systems(Startup, (spawn_orbit_display, spawn_apsis_markers))
+            .add_systems(Update, (update_orbit_display, update_apsis_markers, toggle_orbit_display));
+    }
+}
+
+/// Points sampled around the orbit ellipse
+const ORBIT_SAMPLES: usize = 180;
+/// Propagated samples over one period when looking for apogee and perigee
+const APSIS_SAMPLES: usize = 180;
+/// Ternary search steps refining an apsis between the samples around it
+const APSIS_REFINE_STEPS: usize = 30;
+const APSIS_MARKER_RADIUS: f32 = coords::km_to_scene(60.0);
+const APOGEE_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+const PERIGEE_COLOR: Color = Color::srgb(0.5, 1.0, 0.4);
+
+/// Keplerian ellipse matching a satellite's instantaneous (osculating) position and velocity
+pub struct OsculatingOrbit {
+    /// Semi-latus rectum (km)
+    pub semi_latus_rectum: f64,
+    pub eccentricity: f64,
+    /// Unit vector toward periapsis (TEME)
+    pub periapsis: Vector3<f64>,
+    /// Unit vector 90° ahead of periapsis in the direction of motion (TEME)
+    pub perpendicular: Vector3<f64>,
+}
+
+impl OsculatingOrbit {
+    /// Derive the orbit from a TEME state vector; None for degenerate or unbound (e ≥ 1) states
+    pub fn from_state(position: Vector3<f64>, velocity: Vector3<f64>) -> Option<Self> {
+        let angular_momentum = position.cross(&velocity);
+        let h = angular_momentum.norm();
+        let r = position.norm();
+        if h < 1e-9 || r < 1e-9 {
+            return None;
+        }
+
+        let eccentricity_vector = velocity.cross(&angular_momentum) / EARTH_MU - position / r;
+        let eccentricity = eccentricity_vector.norm();
+        if eccentricity >= 1.0 {
+            return None;
+        }
+
+        let normal = angular_momentum / h;
+        // Circular orbits have no periapsis: measure from the current position instead
+        let periapsis = if eccentricity > 1e-8 {
+            eccentricity_vector / eccentricity
+        } else {
+            position / r
+        };
+
+        Some(Self {
+            semi_latus_rectum: h * h / EARTH_MU,
+            eccentricity,
+            periapsis,
+            perpendicular: normal.cross(&periapsis),
+        })
+    }
+
+    /// TEME points around the ellipse, the first point repeated at the end to close it
+    pub fn points(&self, samples: usize) -> Vec<Vector3<f64>> {
+        (0..=samples)
+            .map(|i| {
+                let true_anomaly = i as f64 / samples as f64 * std::f64::consts::TAU;
+                let radius = self.semi_latus_rectum / (1.0 + self.eccentricity * true_anomaly.cos());
+                (self.periapsis * true_anomaly.cos() + self.perpendicular * true_anomaly.sin()) * radius
+            })
+            .collect()
+    }
+}
+
+/// Ellipse line of the selected satellite's orbit
+#[derive(Component)]
+pub struct OrbitEllipse;
+
+/// Translucent disk filling the selected satellite's orbital plane
+#[derive(Component)]
+pub struct OrbitPlane;
+
+/// Farthest or closest point of the selected satellite's orbit
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum Apsis {
+    Apogee,
+    Perigee,
+}
+
+impl Apsis {
+    pub fn label(self) -> &'static str {
+        match self {
+            Apsis::Apogee => "Apogee",
+            Apsis::Perigee => "Perigee",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Apsis::Apogee => APOGEE_COLOR,
+            Apsis::Perigee => PERIGEE_COLOR,
+        }
+    }
+}
+
+/// Altitude label drawn under an apsis marker
+#[derive(Component)]
+pub struct ApsisLabel(pub Apsis);
+
+/// When and where the propagated orbit reaches an apsis
+pub struct ApsisPoint {
+    pub time: DateTime<Utc>,
+    /// TEME position (km)
+    pub position: Vector3<f64>,
+}
+
+/// Find the apogee and perigee over one period from `start`
+///
+/// Unlike the osculating ellipse this follows the propagated (perturbed) orbit: the
+/// distance from Earth's center is sampled over the period, then the extreme samples
+/// are refined between their neighbours.
+pub fn find_apsides(
+    propagate: impl Fn(DateTime<Utc>) -> Option<Vector3<f64>>,
+    start: DateTime<Utc>,
+    period_seconds: f64,
+) -> Option<(ApsisPoint, ApsisPoint)> {
+    let step = period_seconds / APSIS_SAMPLES as f64;
+    let at = |seconds: f64| start + Duration::milliseconds((seconds * 1000.0) as i64);
+    let radius = |seconds: f64| propagate(at(seconds)).map(|position| position.norm());
+
+    let radii = (0..=APSIS_SAMPLES)
+        .map(|i| radius(i as f64 * step))
+        .collect::<Option<Vec<f64>>>()?;
+    let farthest = (0..radii.len()).max_by(|a, b| radii[*a].total_cmp(&radii[*b]))?;
+    let closest = (0..radii.len()).min_by(|a, b| radii[*a].total_cmp(&radii[*b]))?;
+
+    let refine = |index: usize, maximum: bool| -> Option<ApsisPoint> {
+        let mut low = index.saturating_sub(1) as f64 * step;
+        let mut high = (index + 1).min(APSIS_SAMPLES) as f64 * step;
+        for _ in 0..APSIS_REFINE_STEPS {
+            let a = low + (high - low) / 3.0;
+            let b = high - (high - low) / 3.0;
+            // Keep the third the extremum can't be in
+            if (radius(a)? < radius(b)?) == maximum {
+                low = a;
+            } else {
+                high = b;
+            }
+        }
+        let time = at((low + high) / 2.0);
+        Some(ApsisPoint { time, position: propagate(time)? })
+    };
+
+    Some((refine(farthest, true)?, refine(closest, false)?))
+}
+
+/// Whether the orbit of the selected satellite is drawn (toggle with O)
+#[derive(Resource)]
+pub struct OrbitDisplay {
+    pub enabled: bool,
+}
+
+impl Default for OrbitDisplay {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Mesh with every vertex at the origin, filled in once a satellite is selected
+fn empty_mesh(topology: PrimitiveTopology, vertices: usize) -> Mesh {
+    let mut mesh = Mesh::new(topology, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; vertices]);
+    mesh
+}
+
+pub fn spawn_orbit_display(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let ellipse_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.3, 0.8, 1.0),
+        unlit: true,
+        ..default()
+    });
+    let plane_material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.3, 0.8, 1.0, 0.12),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        // Visible from both sides of the plane
+        cull_mode: None,
+        double_sided: true,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(empty_mesh(PrimitiveTopology::LineStrip, 2))),
+        MeshMaterial3d(ellipse_material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        OrbitEllipse,
+        Name::new("OrbitEllipse"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(empty_mesh(PrimitiveTopology::TriangleList, 3))),
+        MeshMaterial3d(plane_material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        OrbitPlane,
+        Name::new("OrbitPlane"),
+    ));
+}
+
+pub fn spawn_apsis_markers(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Sphere::new(APSIS_MARKER_RADIUS).mesh().uv(12, 6));
+    for apsis in [Apsis::Apogee, Apsis::Perigee] {
+        let material = materials.add(StandardMaterial {
+            base_color: apsis.color(),
+            unlit: true,
+            ..default()
+        });
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material),
+            Transform::default(),
+            ScenePosition::default(),
+            Visibility::Hidden,
+            apsis,
+            Name::new(apsis.label()),
+        ));
+        commands.spawn((
+            Text2d::new(""),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(apsis.color()),
+            Transform::default(),
+            Visibility::Hidden,
+            ApsisLabel(apsis),
+        ));
+    }
+}
+
+/// Scene positions of the ellipse at `time`, or None if the state can't be propagated
+fn orbit_scene_points(satellite: &Satellite, time: DateTime<Utc>) -> Option<Vec<Vec3>> {
+    let (position, velocity) = satellite.propagate_state(time)?;
+    let orbit = OsculatingOrbit::from_state(position, velocity)?;
+    // The ellipse is fixed in inertial space, so it turns with the sky relative to the globe
+    Some(
+        orbit
+            .points(ORBIT_SAMPLES)
+            .into_iter()
+            .map(|point| coords::teme_to_scene(point, time))
+            .collect(),
+    )
+}
+
+/// Rebuild the selected satellite's osculating orbit every frame
+#[allow(clippy::type_complexity)]
+pub fn update_orbit_display(
+    display: Res<OrbitDisplay>,
+    sim_time: Res<TimeSimulation>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    mut ellipse_query: Query<(&Mesh3d, &mut Visibility), (With<OrbitEllipse>, Without<OrbitPlane>)>,
+    mut plane_query: Query<(&Mesh3d, &mut Visibility), (With<OrbitPlane>, Without<OrbitEllipse>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let points = selected_query
+        .iter()
+        .next()
+        .filter(|_| display.enabled)
+        .and_then(|satellite| orbit_scene_points(satellite, sim_time.current_time()));
+
+    let visibility = if points.is_some() { Visibility::Visible } else { Visibility::Hidden };
+    for (_, mut ellipse_visibility) in ellipse_query.iter_mut() {
+        ellipse_visibility.set_if_neq(visibility);
+    }
+    for (_, mut plane_visibility) in plane_query.iter_mut() {
+        plane_visibility.set_if_neq(visibility);
+    }
+    let points = match points {
+        Some(points) => points,
+        None => return,
+    };
+
+    for (mesh_3d, _) in ellipse_query.iter() {
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            let positions: Vec<[f32; 3]> = points.iter().map(|p| p.to_array()).collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        }
+    }
+
+    // Triangle fan from Earth's center, which is the ellipse's focus
+    for (mesh_3d, _) in plane_query.iter() {
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            let positions: Vec<[f32; 3]> = points
+                .windows(2)
+                .flat_map(|pair| [Vec3::ZERO.to_array(), pair[0].to_array(), pair[1].to_array()])
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        }
+    }
+}
+
+/// Place the apogee and perigee markers of the selected satellite and label them with their altitudes
+#[allow(clippy::too_many_arguments)]
+pub fn update_apsis_markers(
+    display: Res<OrbitDisplay>,
+    map_view: Res<MapView>,
+    sim_time: Res<TimeSimulation>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    mut marker_query: Query<(&Apsis, &mut ScenePosition, &mut Visibility), Without<ApsisLabel>>,
+    mut label_query: Query<(&ApsisLabel, &mut Text2d, &mut Transform, &mut Visibility), Without<Apsis>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    origin: Res<FloatingOrigin>,
+    globe: Res<GlobeShape>,
+) {
+    let now = sim_time.current_time();
+    let apsides = selected_query
+        .iter()
+        .next()
+        .filter(|_| display.enabled)
+        .and_then(|satellite| {
+            let period_seconds = satellite.orbital_parameters().period_minutes * 60.0;
+            find_apsides(|time| satellite.propagate_state(time).map(|(position, _)| position), now, period_seconds)
+        });
+
+    let (apogee, perigee) = match apsides {
+        Some(apsides) => apsides,
+        None => {
+            for (_, _, mut visibility) in marker_query.iter_mut() {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+            for (_, _, _, mut visibility) in label_query.iter_mut() {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+            return;
+        }
+    };
+    let point_of = |apsis: Apsis| match apsis {
+        Apsis::Apogee => &apogee,
+        Apsis::Perigee => &perigee,
+    };
+
+    // Drawn on the ellipse, which is fixed in inertial space like the plane it lies in
+    for (apsis, mut position, mut visibility) in marker_query.iter_mut() {
+        position.0 = coords::teme_to_scene_f64(point_of(*apsis).position, now);
+        visibility.set_if_neq(Visibility::Visible);
+    }
+
+    let camera = camera_query.iter().next();
+    let window_size = windows.iter().next().map(|window| Vec2::new(window.width(), window.height()));
+    for (label, mut text, mut transform, mut visibility) in label_query.iter_mut() {
+        let point = point_of(label.0);
+        let scene_position = coords::teme_to_scene(point.position, now);
+        // Text2d draws over every view, so the map (which hides the 3D scene) drops the labels
+        let projected = match (camera, window_size) {
+            (Some((camera, camera_global)), Some(window_size)) if !map_view.enabled => project_label(
+                scene_position,
+                camera,
+                camera_global,
+                *origin,
+                *globe,
+                window_size,
+            ),
+            _ => None,
+        };
+        let position = match projected {
+            Some(position) => position,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+
+        let altitude_km = coords::teme_to_geodetic(point.position, point.time).altitude_km;
+        let caption = format!("{} {:.0} km", label.0.label(), altitude_km);
+        if text.0 != caption {
+            text.0 = caption;
+        }
+        transform.translation = position.extend(0.0);
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
+
+/// Toggle the orbit display with O
+pub fn toggle_orbit_display(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<OrbitDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleOrbit, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
@@ -0,0 +1,478 @@
+//! Prediction of satellite passes over a ground observer
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::coords::{self, Geodetic, LookAngles};
+use crate::horizon::Horizon;
+use crate::propagation::Propagator;
+
+/// Speed of light (km/s)
+const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
+
+/// Rise and set times are refined to this precision (seconds)
+const PASS_TIME_PRECISION_SECONDS: i64 = 1;
+
+/// Elevation sampling step of the pass searches (seconds): crossings are bisected to
+/// the second, so it only bounds how short a pass can be and still be found
+pub const PASS_SEARCH_STEP_SECONDS: i64 = 10;
+
+/// One pass of a satellite above an observer's horizon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pass {
+    pub rise_time: DateTime<Utc>,
+    pub rise_azimuth_deg: f64,
+    pub max_elevation_time: DateTime<Utc>,
+    pub max_elevation_deg: f64,
+    pub set_time: DateTime<Utc>,
+    pub set_azimuth_deg: f64,
+}
+
+/// Look angles from the observer to the satellite, or None if it can't be propagated
+pub fn look_angles_at(propagator: &Propagator, observer: Geodetic, time: DateTime<Utc>) -> Option<LookAngles> {
+    let position = propagator.propagate(time)?;
+    Some(coords::look_angles(observer, coords::teme_to_ecef(position, time)))
+}
+
+/// Rate of change of the observer-satellite range (km/s, positive while receding), from the
+/// ranges a second either side of `time`
+pub fn range_rate_at(propagator: &Propagator, observer: Geodetic, time: DateTime<Utc>) -> Option<f64> {
+    let before = look_angles_at(propagator, observer, time - Duration::seconds(1))?;
+    let after = look_angles_at(propagator, observer, time + Duration::seconds(1))?;
+    Some((after.range_km - before.range_km) / 2.0)
+}
+
+/// Frequency received from a transmitter on `frequency_hz` moving away at `range_rate_km_s`
+pub fn doppler_shifted(frequency_hz: f64, range_rate_km_s: f64) -> f64 {
+    frequency_hz * (1.0 - range_rate_km_s / SPEED_OF_LIGHT_KM_S)
+}
+
+/// Bisect the time at which the satellite crosses the horizon, between `before` and `after`
+fn refine_crossing(
+    propagator: &Propagator,
+    observer: Geodetic,
+    horizon: &Horizon,
+    mut before: DateTime<Utc>,
+    mut after: DateTime<Utc>,
+) -> DateTime<Utc> {
+    let visible_at = |time| {
+        look_angles_at(propagator, observer, time).is_some_and(|look| horizon.is_clear(&look))
+    };
+    let visible_before = visible_at(before);
+
+    while (after - before).num_seconds() > PASS_TIME_PRECISION_SECONDS {
+        let middle = before + (after - before) / 2;
+        if visible_at(middle) == visible_before {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    after
+}
+
+/// Passes above `horizon` between `start` and `end`, sampling the elevation every `step`
+///
+/// A pass already in progress at `start` is reported from `start`; one still in
+/// progress at `end`, or cut by a propagation failure, is dropped. Rise and set times
+/// are refined by bisection, the maximum elevation is the highest sample. Where the
+/// satellite can't be propagated it counts as below the horizon, so a pass under way
+/// when propagation resumes rises at the first instant it can be propagated.
+pub fn predict_passes(
+    propagator: &Propagator,
+    observer: Geodetic,
+    horizon: &Horizon,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: Duration,
+) -> Vec<Pass> {
+    let mut passes = Vec::new();
+    let mut previous: Option<(DateTime<Utc>, bool)> = None;
+    // Rise time and azimuth, plus the highest point so far, while a pass is in progress
+    let mut current: Option<(DateTime<Utc>, f64, DateTime<Utc>, f64)> = None;
+    let azimuth_at = |time, fallback: f64| {
+        look_angles_at(propagator, observer, time).map_or(fallback, |look| look.azimuth_deg)
+    };
+
+    let mut time = start;
+    while time <= end {
+        let look = match look_angles_at(propagator, observer, time) {
+            Some(look) => look,
+            None => {
+                // Propagation failed (e.g. too far from the element epoch): drop any partial pass,
+                // and bisect the next rise from here
+                previous = Some((time, false));
+                current = None;
+                time += step;
+                continue;
+            }
+        };
+        let visible = horizon.is_clear(&look);
+
+        match (previous, visible) {
+            // Rising (a pass already in progress at the start is reported from the start time)
+            (Some((_, false)), true) | (None, true) => {
+                let rise = match previous {
+                    Some((previous_time, _)) => refine_crossing(propagator, observer, horizon, previous_time, time),
+                    None => time,
+                };
+                current = Some((rise, azimuth_at(rise, look.azimuth_deg), time, look.elevation_deg));
+            }
+            (Some((previous_time, true)), false) => {
+                if let Some((rise_time, rise_azimuth_deg, max_elevation_time, max_elevation_deg)) = current.take() {
+                    let set_time = refine_crossing(propagator, observer, horizon, previous_time, time);
+                    passes.push(Pass {
+                        rise_time,
+                        rise_azimuth_deg,
+                        max_elevation_time,
+                        max_elevation_deg,
+                        set_time,
+                        set_azimuth_deg: azimuth_at(set_time, look.azimuth_deg),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        if visible {
+            if let Some((_, _, max_time, max_elevation)) = current.as_mut() {
+                if look.elevation_deg > *max_elevation {
+                    *max_elevation = look.elevation_deg;
+                    *max_time = time;
+                }
+            }
+        }
+
+        previous = Some((time, visible));
+        time += step;
+    }
+
+    passes
+}
+
+/// Look angles along a pass, every `step` from rise to set (both included)
+pub fn pass_track(
+    propagator: &Propagator,
+    observer: Geodetic,
+    pass: &Pass,
+    step: Duration,
+) -> Vec<(DateTime<Utc>, LookAngles)> {
+    let mut track = Vec::new();
+    let mut time = pass.rise_time;
+    while time < pass.set_time {
+        track.extend(look_angles_at(propagator, observer, time).map(|look| (time, look)));
+        time += step;
+    }
+    track.extend(look_angles_at(propagator, observer, pass.set_time).map(|look| (pass.set_time, look)));
+    track
+}
+
+/// Intervals between `start` and `end` during which at least one of the satellites is at
+/// least `min_elevation_deg` above the observer's horizon, sampling every `step`
+///
+/// Unlike `predict_passes`, accesses of several satellites are merged, and an access still
+/// in progress at either end of the window is cut there rather than dropped. Times are
+/// those of the samples, so they are accurate to `step`.
+pub fn access_intervals(
+    propagators: &[Propagator],
+    observer: Geodetic,
+    min_elevation_deg: f64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: Duration,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut intervals = Vec::new();
+    let mut access_start: Option<DateTime<Utc>> = None;
+
+    let mut time = start;
+    while time <= end {
+        let visible = propagators.iter().any(|propagator| {
+            look_angles_at(propagator, observer, time).is_some_and(|look| look.elevation_deg >= min_elevation_deg)
+        });
+        match (access_start, visible) {
+            (None, true) => access_start = Some(time),
+            (Some(rise), false) => {
+                intervals.push((rise, time));
+                access_start = None;
+            }
+            _ => {}
+        }
+        time += step;
+    }
+    if let Some(rise) = access_start {
+        intervals.push((rise, end));
+    }
+
+    intervals
+}
+
+/// How often and how long a ground target goes unseen over a window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RevisitStatistics {
+    /// Number of accesses (merged over all satellites)
+    pub accesses: usize,
+    /// Share of the window with a satellite in view, in [0, 1]
+    pub coverage: f64,
+    /// Wait from the start of the window to the first access (None if there is none)
+    pub first_access: Option<Duration>,
+    /// Longest time without a satellite in view, including before the first and after
+    /// the last access (the whole window if there is none)
+    pub max_gap: Duration,
+    /// Mean length of the gaps between two accesses (None with fewer than two accesses)
+    pub mean_gap: Option<Duration>,
+    /// Mean time from the start of one access to the start of the next (None with fewer
+    /// than two accesses)
+    pub mean_revisit: Option<Duration>,
+    /// Mean length of an access (None if there is none)
+    pub mean_access: Option<Duration>,
+}
+
+/// Gap and revisit statistics of the `accesses` (sorted, not overlapping) within `start`..`end`
+pub fn revisit_statistics(
+    accesses: &[(DateTime<Utc>, DateTime<Utc>)],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> RevisitStatistics {
+    let window = end - start;
+    let mean = |durations: &[Duration]| {
+        (!durations.is_empty()).then(|| durations.iter().fold(Duration::zero(), |sum, &d| sum + d) / durations.len() as i32)
+    };
+
+    let in_view: Vec<Duration> = accesses.iter().map(|&(rise, set)| set - rise).collect();
+    let gaps: Vec<Duration> = accesses.windows(2).map(|pair| pair[1].0 - pair[0].1).collect();
+    let revisits: Vec<Duration> = accesses.windows(2).map(|pair| pair[1].0 - pair[0].0).collect();
+
+    // The gaps at the edges of the window count toward the longest wait but not the mean
+    let leading = accesses.first().map_or(window, |&(rise, _)| rise - start);
+    let trailing = accesses.last().map_or(window, |&(_, set)| end - set);
+    let max_gap = gaps.iter().copied().chain([leading, trailing]).max().unwrap_or(window);
+
+    let covered = in_view.iter().fold(Duration::zero(), |sum, &d| sum + d);
+    RevisitStatistics {
+        accesses: accesses.len(),
+        coverage: if window > Duration::zero() {
+            covered.num_milliseconds() as f64 / window.num_milliseconds() as f64
+        } else {
+            0.0
+        },
+        first_access: accesses.first().map(|&(rise, _)| rise - start),
+        max_gap,
+        mean_gap: mean(&gaps),
+        mean_revisit: mean(&revisits),
+        mean_access: mean(&in_view),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::propagation::MAX_PROPAGATION_DAYS;
+    use crate::tle_loader::TleData;
+
+    fn propagator(line1: &str, line2: &str) -> (Propagator, DateTime<Utc>) {
+        let tle = TleData {
+            line1: line1.to_string(),
+            line2: line2.to_string(),
+            name: String::new(),
+            omm: None,
+        };
+        let elements = tle.to_elements().unwrap();
+        (Propagator::new(&elements), elements.datetime.and_utc())
+    }
+
+    /// ISS-like orbit at its northernmost point over 51.8°N 10°E at the epoch
+    fn iss() -> (Propagator, DateTime<Utc>) {
+        propagator(
+            "1 25544U 98067A   00001.50000000  .00000000  00000-0  00000-0 0  9996",
+            "2 25544  51.6400 200.4606 0001000   0.0000  90.0000 15.50000000    18",
+        )
+    }
+
+    /// Ground point under the satellite at `time`
+    fn under_satellite(propagator: &Propagator, time: DateTime<Utc>) -> Geodetic {
+        let mut point = coords::teme_to_geodetic(propagator.propagate(time).unwrap(), time);
+        point.altitude_km = 0.0;
+        point
+    }
+
+    fn minutes(start: DateTime<Utc>, accesses: &[(i64, i64)]) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        accesses
+            .iter()
+            .map(|&(rise, set)| (start + Duration::minutes(rise), start + Duration::minutes(set)))
+            .collect()
+    }
+
+    #[test]
+    fn accesses_are_clipped_at_the_window_ends() {
+        let (propagator, epoch) = iss();
+        let observer = Geodetic::new(51.8, 10.0, 0.0);
+        let step = Duration::seconds(10);
+        let access = |start: DateTime<Utc>, end: DateTime<Utc>| {
+            access_intervals(std::slice::from_ref(&propagator), observer, 10.0, start, end, step)
+        };
+
+        // Overhead when the window opens, and gone within the pass
+        let opening = epoch - Duration::seconds(5);
+        let intervals = access(opening, epoch + Duration::minutes(30));
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(intervals[0].0, opening);
+        assert!(intervals[0].1 - epoch < Duration::minutes(6), "set at {}", intervals[0].1);
+
+        // Still overhead when it closes
+        let closing = epoch + Duration::seconds(5);
+        let intervals = access(epoch - Duration::minutes(30), closing);
+        assert_eq!(intervals.len(), 1);
+        assert!(epoch - intervals[0].0 < Duration::minutes(6), "rise at {}", intervals[0].0);
+        assert_eq!(intervals[0].1, closing);
+
+        // Never above the antipode during the pass
+        let antipode = Geodetic::new(-51.8, -170.0, 0.0);
+        let never = access_intervals(&[propagator], antipode, 10.0, epoch - Duration::minutes(5), epoch + Duration::minutes(5), step);
+        assert!(never.is_empty());
+    }
+
+    #[test]
+    fn no_access_leaves_one_gap_as_long_as_the_window() {
+        let start = Utc::now();
+        let statistics = revisit_statistics(&[], start, start + Duration::hours(2));
+        assert_eq!(statistics.accesses, 0);
+        assert_eq!(statistics.coverage, 0.0);
+        assert_eq!(statistics.first_access, None);
+        assert_eq!(statistics.max_gap, Duration::hours(2));
+        assert_eq!(statistics.mean_gap, None);
+        assert_eq!(statistics.mean_revisit, None);
+        assert_eq!(statistics.mean_access, None);
+    }
+
+    #[test]
+    fn a_single_access_has_no_mean_gap_or_revisit() {
+        let start = Utc::now();
+        let statistics = revisit_statistics(&minutes(start, &[(30, 40)]), start, start + Duration::minutes(100));
+        assert_eq!(statistics.accesses, 1);
+        assert!((statistics.coverage - 0.1).abs() < 1e-9);
+        assert_eq!(statistics.first_access, Some(Duration::minutes(30)));
+        // The 60 minutes after the access are the longest wait
+        assert_eq!(statistics.max_gap, Duration::minutes(60));
+        assert_eq!(statistics.mean_gap, None);
+        assert_eq!(statistics.mean_revisit, None);
+        assert_eq!(statistics.mean_access, Some(Duration::minutes(10)));
+    }
+
+    #[test]
+    fn edge_gaps_count_toward_the_longest_wait_but_not_the_mean() {
+        let start = Utc::now();
+        let accesses = minutes(start, &[(10, 20), (30, 40), (60, 70)]);
+        let statistics = revisit_statistics(&accesses, start, start + Duration::minutes(100));
+        assert_eq!(statistics.max_gap, Duration::minutes(30));
+        assert_eq!(statistics.mean_gap, Some(Duration::minutes(15)));
+        assert_eq!(statistics.mean_revisit, Some(Duration::minutes(25)));
+        assert_eq!(statistics.mean_access, Some(Duration::minutes(10)));
+        assert!((statistics.coverage - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn accesses_at_the_window_ends_leave_no_edge_gaps() {
+        let start = Utc::now();
+        let accesses = minutes(start, &[(0, 10), (50, 100)]);
+        let statistics = revisit_statistics(&accesses, start, start + Duration::minutes(100));
+        assert_eq!(statistics.first_access, Some(Duration::zero()));
+        assert_eq!(statistics.max_gap, Duration::minutes(40));
+        assert_eq!(statistics.mean_gap, Some(Duration::minutes(40)));
+        assert!((statistics.coverage - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn approaching_satellites_are_heard_higher() {
+        // 437 MHz (UHF amateur band) from a satellite closing at 7 km/s: about +10.2 kHz
+        let frequency_hz = 437e6;
+        let approaching = doppler_shifted(frequency_hz, -7.0) - frequency_hz;
+        assert!((approaching - 10_204.5).abs() < 1.0, "shift {} Hz", approaching);
+        let receding = doppler_shifted(frequency_hz, 7.0) - frequency_hz;
+        assert!((receding + approaching).abs() < 1e-6);
+        assert_eq!(doppler_shifted(frequency_hz, 0.0), frequency_hz);
+
+        // The range shrinks while the satellite comes over the observer and grows after
+        let (propagator, epoch) = iss();
+        let observer = Geodetic::new(51.8, 10.0, 0.0);
+        assert!(range_rate_at(&propagator, observer, epoch - Duration::minutes(3)).unwrap() < -3.0);
+        assert!(range_rate_at(&propagator, observer, epoch).unwrap().abs() < 1.0);
+        assert!(range_rate_at(&propagator, observer, epoch + Duration::minutes(3)).unwrap() > 3.0);
+    }
+
+    #[test]
+    fn crossings_are_bisected_to_the_second() {
+        let (propagator, epoch) = iss();
+        let observer = Geodetic::new(51.8, 10.0, 0.0);
+        let horizon = Horizon::flat(10.0);
+        let visible = |time| look_angles_at(&propagator, observer, time).is_some_and(|look| horizon.is_clear(&look));
+        let precision = Duration::seconds(PASS_TIME_PRECISION_SECONDS);
+
+        // Overhead at the epoch, below the horizon ten minutes either side
+        let rise = refine_crossing(&propagator, observer, &horizon, epoch - Duration::minutes(10), epoch);
+        assert!(visible(rise) && !visible(rise - precision), "rise at {}", rise);
+        let set = refine_crossing(&propagator, observer, &horizon, epoch, epoch + Duration::minutes(10));
+        assert!(!visible(set) && visible(set - precision), "set at {}", set);
+        assert!(epoch - rise > Duration::minutes(3) && set - epoch > Duration::minutes(3));
+    }
+
+    #[test]
+    fn refined_passes_match_a_pass_sampled_every_second() {
+        let (propagator, epoch) = iss();
+        let observer = Geodetic::new(51.8, 10.0, 0.0);
+        let horizon = Horizon::flat(10.0);
+        let (start, end) = (epoch - Duration::minutes(30), epoch + Duration::minutes(30));
+
+        let passes = predict_passes(&propagator, observer, &horizon, start, end, Duration::seconds(60));
+        let reference = predict_passes(&propagator, observer, &horizon, start, end, Duration::seconds(1));
+        assert_eq!(passes.len(), 1);
+        assert_eq!(reference.len(), 1);
+        let (pass, reference) = (passes[0], reference[0]);
+
+        assert!((pass.rise_time - reference.rise_time).num_seconds().abs() <= PASS_TIME_PRECISION_SECONDS);
+        assert!((pass.set_time - reference.set_time).num_seconds().abs() <= PASS_TIME_PRECISION_SECONDS);
+        assert!((pass.rise_azimuth_deg - reference.rise_azimuth_deg).abs() < 0.5);
+        assert!((pass.set_azimuth_deg - reference.set_azimuth_deg).abs() < 0.5);
+        // The highest of the coarse samples is the one at the epoch, straight overhead
+        assert_eq!(pass.max_elevation_time, epoch);
+        assert!(pass.max_elevation_deg > 85.0 && reference.max_elevation_deg >= pass.max_elevation_deg);
+    }
+
+    #[test]
+    fn a_pass_in_progress_at_the_start_rises_at_the_start() {
+        let (propagator, epoch) = iss();
+        let observer = Geodetic::new(51.8, 10.0, 0.0);
+        let horizon = Horizon::flat(10.0);
+        let step = Duration::seconds(60);
+
+        let passes = predict_passes(&propagator, observer, &horizon, epoch, epoch + Duration::minutes(30), step);
+        let whole = predict_passes(&propagator, observer, &horizon, epoch - Duration::minutes(30), epoch + Duration::minutes(30), step);
+        assert_eq!(passes.len(), 1);
+        assert_eq!(passes[0].rise_time, epoch);
+        assert_eq!(passes[0].set_time, whole[0].set_time);
+        assert_eq!(passes[0].max_elevation_time, epoch);
+    }
+
+    #[test]
+    fn propagation_failures_cut_passes_and_the_next_rise_is_bisected() {
+        let (propagator, epoch) = iss();
+        let horizon = Horizon::flat(10.0);
+        let step = Duration::seconds(60);
+        let limit = Duration::days(MAX_PROPAGATION_DAYS);
+
+        // Propagation resumes two minutes before the satellite passes overhead, between two
+        // samples: the rise is the first instant it can be propagated, not the next sample
+        let first = epoch - limit;
+        let observer = under_satellite(&propagator, first + Duration::minutes(2));
+        let start = first - Duration::minutes(10) - Duration::seconds(30);
+        let passes = predict_passes(&propagator, observer, &horizon, start, start + Duration::minutes(30), step);
+        assert_eq!(passes.len(), 1);
+        let rise = passes[0].rise_time - first;
+        assert!(rise >= Duration::zero() && rise <= Duration::seconds(PASS_TIME_PRECISION_SECONDS), "rise {} after", rise);
+        assert!(passes[0].set_time - first > Duration::minutes(5));
+
+        // A pass still in progress when propagation stops is dropped
+        let last = epoch + limit;
+        let observer = under_satellite(&propagator, last - Duration::minutes(2));
+        let passes = predict_passes(&propagator, observer, &horizon, last - Duration::minutes(30), last + Duration::minutes(30), step);
+        assert!(passes.is_empty(), "{:?}", passes);
+    }
+}
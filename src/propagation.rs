@@ -0,0 +1,91 @@
+//! SGP4 propagation of TLE mean elements
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use nalgebra::Vector3;
+use sgp4::Elements;
+
+/// Furthest from its TLE epoch a satellite is propagated (days); SGP4 errors grow quickly beyond this
+pub const MAX_PROPAGATION_DAYS: i64 = 7;
+
+/// Earth's gravitational parameter (km³/s²)
+pub const EARTH_MU: f64 = 398600.4418;
+/// Earth's equatorial radius (km), used for apogee/perigee altitudes
+const EARTH_EQUATORIAL_RADIUS: f64 = 6378.137;
+
+/// SGP4 propagator for one satellite, initialized once from its elements
+pub struct Propagator {
+    epoch: NaiveDateTime,
+    /// None if the elements are unusable
+    constants: Option<sgp4::Constants<'static>>,
+}
+
+impl Propagator {
+    pub fn new(elements: &Elements) -> Self {
+        // Initializing the propagator is far more expensive than a single propagation,
+        // so do it once here instead of for every time step
+        let constants = std::panic::catch_unwind(|| sgp4::Constants::from_elements(elements).ok())
+            .unwrap_or_default();
+
+        Self {
+            epoch: elements.datetime,
+            constants,
+        }
+    }
+
+    /// TEME position (km) at the given time
+    pub fn propagate(&self, time: DateTime<Utc>) -> Option<Vector3<f64>> {
+        self.propagate_state(time).map(|(position, _)| position)
+    }
+
+    /// TEME position (km) and velocity (km/s) at the given time
+    ///
+    /// None if the elements are unusable, SGP4 fails, or `time` is more than
+    /// `MAX_PROPAGATION_DAYS` from the epoch.
+    pub fn propagate_state(&self, time: DateTime<Utc>) -> Option<(Vector3<f64>, Vector3<f64>)> {
+        let constants = self.constants.as_ref()?;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let duration = time.naive_utc().signed_duration_since(self.epoch);
+            let minutes_since_epoch = duration.num_seconds() as f64 / 60.0;
+            
+            if minutes_since_epoch.abs() > (MAX_PROPAGATION_DAYS * 24 * 60) as f64 {
+                return None;
+            }
+            
+            match constants.propagate(minutes_since_epoch) {
+                Ok(state) => Some((
+                    Vector3::new(state.position[0], state.position[1], state.position[2]),
+                    Vector3::new(state.velocity[0], state.velocity[1], state.velocity[2]),
+                )),
+                Err(_) => None,
+            }
+        }));
+        
+        result.unwrap_or_default()
+    }
+}
+
+/// Classical orbit description extracted from TLE mean elements
+pub struct OrbitalParameters {
+    pub inclination_deg: f64,
+    pub eccentricity: f64,
+    pub period_minutes: f64,
+    pub apogee_altitude_km: f64,
+    pub perigee_altitude_km: f64,
+}
+
+impl OrbitalParameters {
+    pub fn from_elements(elements: &Elements) -> Self {
+        // Mean motion is given in revolutions per day
+        let period_minutes = 1440.0 / elements.mean_motion;
+        let mean_motion_rad_s = elements.mean_motion * 2.0 * std::f64::consts::PI / 86400.0;
+        let semi_major_axis = (EARTH_MU / (mean_motion_rad_s * mean_motion_rad_s)).cbrt();
+
+        Self {
+            inclination_deg: elements.inclination,
+            eccentricity: elements.eccentricity,
+            period_minutes,
+            apogee_altitude_km: semi_major_axis * (1.0 + elements.eccentricity) - EARTH_EQUATORIAL_RADIUS,
+            perigee_altitude_km: semi_major_axis * (1.0 - elements.eccentricity) - EARTH_EQUATORIAL_RADIUS,
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+
+use crate::config::Settings;
+use crate::notifications::{Notify, Retry, RetryAction};
+use crate::transmitters::{Transmitter, TransmitterLoader, TransmitterSet};
+
+/// Radio transmitters of each satellite from the SatNOGS DB, loaded in the background
+pub struct RadioPlugin;
+
+impl Plugin for RadioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        if !app.world().resource::<Settings>().transmitters.enabled {
+            return;
+        }
+
+        app.add_systems(Startup, start_transmitter_loading)
+            .add_systems(Update, (retry_transmitter_loading, finish_transmitter_loading).chain());
+    }
+}
+
+/// Transmitters listed in the info panel before the rest are summarized
+const MAX_LISTED_TRANSMITTERS: usize = 4;
+
+/// Transmitters by NORAD ID, present once loading has finished
+#[derive(Resource)]
+pub struct Transmitters(pub TransmitterSet);
+
+/// Download or snapshot read running on the async compute pool
+#[derive(Resource)]
+struct TransmitterLoad(Task<Result<TransmitterSet, String>>);
+
+fn start_transmitter_loading(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(load_in_background(&settings));
+}
+
+fn load_in_background(settings: &Settings) -> TransmitterLoad {
+    let loader = TransmitterLoader::new()
+        .with_source(settings.transmitters.source.clone())
+        .with_cache_max_age_hours(settings.transmitters.cache_max_age_hours);
+    TransmitterLoad(AsyncComputeTaskPool::get().spawn(async move { loader.load().map_err(|e| e.to_string()) }))
+}
+
+/// Load again when the failure notification's Retry button is clicked
+fn retry_transmitter_loading(
+    mut commands: Commands,
+    mut retries: MessageReader<Retry>,
+    load: Option<Res<TransmitterLoad>>,
+    settings: Res<Settings>,
+) {
+    let retried = retries.read().any(|retry| retry.0 == RetryAction::LoadTransmitters);
+    if retried && load.is_none() {
+        commands.insert_resource(load_in_background(&settings));
+    }
+}
+
+fn finish_transmitter_loading(
+    mut commands: Commands,
+    load: Option<ResMut<TransmitterLoad>>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let mut load = match load {
+        Some(load) => load,
+        None => return,
+    };
+    let result = match block_on(future::poll_once(&mut load.0)) {
+        Some(result) => result,
+        None => return, // Still loading
+    };
+    commands.remove_resource::<TransmitterLoad>();
+
+    match result {
+        Ok(transmitters) => {
+            let count: usize = transmitters.values().map(Vec::len).sum();
+            println!("✓ Loaded {} transmitters for {} satellites", count, transmitters.len());
+            commands.insert_resource(Transmitters(transmitters));
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to load transmitter data: {}", e);
+            notify_writer.write(
+                Notify::error(format!("Failed to load transmitter data: {}", e))
+                    .with_retry(RetryAction::LoadTransmitters),
+            );
+        }
+    }
+}
+
+fn describe(transmitter: &Transmitter) -> String {
+    let mut line = if transmitter.description.is_empty() {
+        "Transmitter".to_string()
+    } else {
+        transmitter.description.clone()
+    };
+    if !transmitter.is_active() {
+        line.push_str(" (inactive)");
+    }
+    if let Some(downlink) = transmitter.downlink() {
+        line.push_str(&format!("\n  Downlink {}", downlink));
+        if let Some(mode) = &transmitter.mode {
+            line.push_str(&format!(" {}", mode));
+        }
+    }
+    if let Some(uplink) = transmitter.uplink() {
+        line.push_str(&format!("\n  Uplink {}", uplink));
+        if let Some(mode) = &transmitter.uplink_mode {
+            line.push_str(&format!(" {}", mode));
+        }
+    }
+    line
+}
+
+/// Info panel lines for a satellite's transmitters, or None if SatNOGS lists none
+pub fn transmitter_summary(transmitters: &Transmitters, norad_id: u64) -> Option<String> {
+    let list = transmitters.0.get(&norad_id).filter(|list| !list.is_empty())?;
+    let mut summary = String::from("Transmitters:");
+    for transmitter in list.iter().take(MAX_LISTED_TRANSMITTERS) {
+        summary.push('\n');
+        summary.push_str(&describe(transmitter));
+    }
+    if list.len() > MAX_LISTED_TRANSMITTERS {
+        summary.push_str(&format!("\n... and {} more", list.len() - MAX_LISTED_TRANSMITTERS));
+    }
+    Some(summary)
+}
@@ -0,0 +1,266 @@
+//! Regions of the Earth's surface, as latitude/longitude boxes or country outlines, and
+//! whether a sub-satellite point is inside one
+
+use serde_json::Value;
+
+use crate::coords::Geodetic;
+
+/// Latitude/longitude box (degrees); a box whose west edge is east of its east edge wraps
+/// across the antimeridian
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub south_deg: f64,
+    pub north_deg: f64,
+    pub west_deg: f64,
+    pub east_deg: f64,
+}
+
+impl BoundingBox {
+    /// The box with these two points at opposite corners, spanning the shorter way in longitude
+    pub fn from_corners(a: Geodetic, b: Geodetic) -> Self {
+        let (mut west_deg, mut east_deg) = (a.longitude_deg.min(b.longitude_deg), a.longitude_deg.max(b.longitude_deg));
+        if east_deg - west_deg > 180.0 {
+            std::mem::swap(&mut west_deg, &mut east_deg);
+        }
+        Self {
+            south_deg: a.latitude_deg.min(b.latitude_deg),
+            north_deg: a.latitude_deg.max(b.latitude_deg),
+            west_deg,
+            east_deg,
+        }
+    }
+
+    pub fn contains(&self, latitude_deg: f64, longitude_deg: f64) -> bool {
+        if latitude_deg < self.south_deg || latitude_deg > self.north_deg {
+            return false;
+        }
+        let longitude_deg = wrap_longitude(longitude_deg);
+        if self.west_deg <= self.east_deg {
+            longitude_deg >= self.west_deg && longitude_deg <= self.east_deg
+        } else {
+            longitude_deg >= self.west_deg || longitude_deg <= self.east_deg
+        }
+    }
+
+    /// Longitude span (degrees), across the antimeridian if the box wraps
+    pub fn width_deg(&self) -> f64 {
+        (self.east_deg - self.west_deg).rem_euclid(360.0)
+    }
+}
+
+/// Longitude in [-180, 180)
+fn wrap_longitude(longitude_deg: f64) -> f64 {
+    (longitude_deg + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Polylines or rings of (longitude, latitude) points (degrees)
+pub type Rings = Vec<Vec<(f64, f64)>>;
+
+/// Area a region covers
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegionShape {
+    Box(BoundingBox),
+    /// Outer rings, with holes as rings of their own (even-odd rule)
+    Polygons(Rings),
+}
+
+/// A named area with optional altitude limits on the satellites over it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub name: String,
+    pub shape: RegionShape,
+    pub min_altitude_km: Option<f64>,
+    pub max_altitude_km: Option<f64>,
+}
+
+impl Region {
+    /// Whether a satellite at this position is over the region and within the altitude limits
+    pub fn contains(&self, position: Geodetic) -> bool {
+        if self.min_altitude_km.is_some_and(|min| position.altitude_km < min)
+            || self.max_altitude_km.is_some_and(|max| position.altitude_km > max)
+        {
+            return false;
+        }
+        let (latitude, longitude) = (position.latitude_deg, wrap_longitude(position.longitude_deg));
+        match &self.shape {
+            RegionShape::Box(bounds) => bounds.contains(latitude, longitude),
+            RegionShape::Polygons(rings) => {
+                rings.iter().filter(|ring| ring_contains(ring, longitude, latitude)).count() % 2 == 1
+            }
+        }
+    }
+
+    /// Outline of the region as polylines
+    pub fn outline(&self) -> Rings {
+        match &self.shape {
+            RegionShape::Box(bounds) => {
+                // Edges are sampled every degree or so to follow the globe
+                let steps = bounds.width_deg().ceil().max(1.0) as usize;
+                let longitude = |i: usize| bounds.west_deg + bounds.width_deg() * i as f64 / steps as f64;
+                let mut ring: Vec<(f64, f64)> = (0..=steps).map(|i| (longitude(i), bounds.south_deg)).collect();
+                ring.extend((0..=steps).rev().map(|i| (longitude(i), bounds.north_deg)));
+                ring.push(ring[0]);
+                vec![ring]
+            }
+            RegionShape::Polygons(rings) => rings.clone(),
+        }
+    }
+}
+
+/// Ray casting test of a point against one ring
+fn ring_contains(ring: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut previous = match ring.last() {
+        Some(point) => *point,
+        None => return false,
+    };
+    for &point in ring {
+        let ((x1, y1), (x2, y2)) = (previous, point);
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) / (y2 - y1) * (x2 - x1) {
+            inside = !inside;
+        }
+        previous = point;
+    }
+    inside
+}
+
+/// Outline rings of the country whose name or ISO code is `name`, from a GeoJSON feature
+/// collection of country polygons (Natural Earth admin 0 countries)
+pub fn country_rings(contents: &str, name: &str) -> Result<Option<(String, Rings)>, String> {
+    let document: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    Ok(document["features"].as_array().into_iter().flatten().find_map(|feature| {
+        let properties = &feature["properties"];
+        let matches = ["NAME", "NAME_LONG", "ADMIN", "ISO_A2", "ISO_A3", "name"]
+            .iter()
+            .filter_map(|key| properties[key].as_str())
+            .any(|value| value.eq_ignore_ascii_case(name.trim()));
+        if !matches {
+            return None;
+        }
+        let display_name = feature_name(properties).unwrap_or_else(|| name.to_string());
+        Some((display_name, geometry_rings(&feature["geometry"])))
+    }))
+}
+
+/// Name of the country containing a point, from the same GeoJSON as `country_rings`
+pub fn country_at(contents: &str, latitude_deg: f64, longitude_deg: f64) -> Result<Option<String>, String> {
+    let document: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    let longitude_deg = wrap_longitude(longitude_deg);
+    Ok(document["features"].as_array().into_iter().flatten().find_map(|feature| {
+        let rings = geometry_rings(&feature["geometry"]);
+        let inside = rings.iter().filter(|ring| ring_contains(ring, longitude_deg, latitude_deg)).count() % 2 == 1;
+        if !inside {
+            return None;
+        }
+        feature_name(&feature["properties"])
+    }))
+}
+
+/// Display name of a country feature
+fn feature_name(properties: &Value) -> Option<String> {
+    ["NAME", "ADMIN", "name"]
+        .iter()
+        .find_map(|key| properties[key].as_str())
+        .map(str::to_string)
+}
+
+/// Rings of a Polygon or MultiPolygon geometry
+fn geometry_rings(geometry: &Value) -> Rings {
+    let ring = |value: &Value| -> Option<Vec<(f64, f64)>> {
+        let points: Vec<(f64, f64)> = value
+            .as_array()?
+            .iter()
+            .filter_map(|point| Some((point.get(0)?.as_f64()?, point.get(1)?.as_f64()?)))
+            .collect();
+        (points.len() >= 3).then_some(points)
+    };
+    let coordinates = &geometry["coordinates"];
+    match geometry["type"].as_str() {
+        Some("Polygon") => coordinates.as_array().into_iter().flatten().filter_map(ring).collect(),
+        Some("MultiPolygon") => coordinates
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|polygon| polygon.as_array().into_iter().flatten().filter_map(ring))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(latitude_deg: f64, longitude_deg: f64, altitude_km: f64) -> Geodetic {
+        Geodetic::new(latitude_deg, longitude_deg, altitude_km)
+    }
+
+    fn region(shape: RegionShape) -> Region {
+        Region {
+            name: "Test".to_string(),
+            shape,
+            min_altitude_km: None,
+            max_altitude_km: None,
+        }
+    }
+
+    #[test]
+    fn boxes_wrap_across_the_antimeridian() {
+        let pacific = BoundingBox::from_corners(point(-20.0, 170.0, 0.0), point(10.0, -170.0, 0.0));
+        assert_eq!((pacific.west_deg, pacific.east_deg), (170.0, -170.0));
+        assert_eq!(pacific.width_deg(), 20.0);
+        assert!(pacific.contains(0.0, 180.0));
+        assert!(pacific.contains(0.0, -175.0));
+        assert!(!pacific.contains(0.0, 0.0));
+        assert!(!pacific.contains(15.0, 175.0));
+
+        let europe = BoundingBox::from_corners(point(35.0, -10.0, 0.0), point(60.0, 30.0, 0.0));
+        assert!(europe.contains(48.8, 2.3));
+        assert!(!europe.contains(48.8, 40.0));
+    }
+
+    #[test]
+    fn altitude_limits_apply() {
+        let mut europe = region(RegionShape::Box(BoundingBox::from_corners(point(35.0, -10.0, 0.0), point(60.0, 30.0, 0.0))));
+        europe.max_altitude_km = Some(2000.0);
+        assert!(europe.contains(point(45.0, 5.0, 550.0)));
+        assert!(!europe.contains(point(45.0, 5.0, 20000.0)));
+        europe.min_altitude_km = Some(600.0);
+        assert!(!europe.contains(point(45.0, 5.0, 550.0)));
+    }
+
+    #[test]
+    fn polygons_use_the_even_odd_rule() {
+        let outer = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let hole = vec![(4.0, 4.0), (6.0, 4.0), (6.0, 6.0), (4.0, 6.0)];
+        let square = region(RegionShape::Polygons(vec![outer, hole]));
+        assert!(square.contains(point(2.0, 2.0, 500.0)));
+        assert!(!square.contains(point(5.0, 5.0, 500.0)));
+        assert!(!square.contains(point(12.0, 5.0, 500.0)));
+    }
+
+    #[test]
+    fn countries_are_found_by_name_and_by_point() {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"NAME": "Squareland", "ISO_A2": "SQ"},
+             "geometry": {"type": "MultiPolygon", "coordinates": [[[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]]}}
+        ]}"#;
+        let (name, rings) = country_rings(geojson, "sq").unwrap().unwrap();
+        assert_eq!(name, "Squareland");
+        assert_eq!(rings.len(), 1);
+        assert_eq!(country_rings(geojson, "France").unwrap(), None);
+        assert_eq!(country_at(geojson, 5.0, 5.0).unwrap().as_deref(), Some("Squareland"));
+        assert_eq!(country_at(geojson, 5.0, 50.0).unwrap(), None);
+    }
+
+    #[test]
+    fn the_bundled_countries_resolve() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/geo/ne_110m_admin_0_countries.geojson");
+        let geojson = std::fs::read_to_string(path).unwrap();
+        let (name, _) = country_rings(&geojson, "FRA").unwrap().unwrap();
+        assert_eq!(name, "France");
+        assert_eq!(country_at(&geojson, 48.86, 2.35).unwrap().as_deref(), Some("France"));
+        assert_eq!(country_at(&geojson, -33.87, 151.21).unwrap().as_deref(), Some("Australia"));
+        assert_eq!(country_at(&geojson, 0.0, -30.0).unwrap(), None);
+    }
+}
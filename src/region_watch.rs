@@ -0,0 +1,498 @@
+//! Region-of-interest watch: the satellites whose sub-satellite point is over a country or
+//! a latitude/longitude box right now, optionally within an altitude band
+//!
+//! The region comes from the `[region]` settings, or is picked on the globe: two clicks
+//! for the corners of a box, or one click on a country.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use std::fs;
+use std::path::Path;
+
+use crate::config::{RegionSettings, Settings};
+use crate::coords::{self, Geodetic, GlobeShape};
+use crate::floating_origin::ScenePosition;
+use crate::ground_pick::format_coordinates;
+use crate::keymap::{Action, Keymap};
+use crate::region::{self, BoundingBox, Region, RegionShape};
+use crate::satellite::Satellite;
+use crate::selection::GroundClicked;
+use crate::text_input::InputFocus;
+use crate::time_bar::TIME_BAR_STRIP;
+
+/// Satellites over a region, listed in a panel, with the region outlined on the globe
+pub struct RegionWatchPlugin;
+
+impl Plugin for RegionWatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().region.clone();
+
+        app.insert_resource(RegionWatch {
+            enabled: false,
+            region: None,
+            revision: 0,
+            picking: None,
+            first_corner: None,
+            satellites: Vec::new(),
+            error: None,
+            timer: Timer::from_seconds(UPDATE_INTERVAL_SECONDS, TimerMode::Repeating),
+            settings,
+        })
+        .add_systems(Startup, (load_configured_region, setup_region_watch))
+        .add_systems(Update, (
+            toggle_region_watch,
+            handle_region_buttons,
+            record_region_clicks,
+            update_region_satellites,
+            update_region_outline,
+            update_region_panel,
+        ).chain());
+    }
+}
+
+/// Bevy's asset directory, which the countries GeoJSON path is relative to
+const ASSETS_DIR: &str = "assets";
+/// Real seconds between updates of the list
+const UPDATE_INTERVAL_SECONDS: f32 = 0.5;
+/// Satellites listed in the panel
+const MAX_LISTED_SATELLITES: usize = 20;
+/// Height of the outline above the surface (km), above the country borders
+const OUTLINE_ALTITUDE_KM: f64 = 20.0;
+const OUTLINE_COLOR: Color = Color::srgb(1.0, 0.3, 0.6);
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+const ACTIVE_BUTTON_COLOR: Color = Color::srgb(0.5, 0.2, 0.35);
+
+/// What the next clicks on the globe pick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionPicking {
+    Box,
+    Country,
+}
+
+/// A satellite over the region
+pub struct RegionSatellite {
+    pub name: String,
+    pub norad_id: u64,
+    pub position: Geodetic,
+}
+
+/// Watch mode (toggle with F10), the region and the satellites over it
+#[derive(Resource)]
+pub struct RegionWatch {
+    pub enabled: bool,
+    pub region: Option<Region>,
+    /// Bumped whenever the region changes, to redraw the outline
+    revision: u32,
+    pub picking: Option<RegionPicking>,
+    /// First corner of a box being picked
+    first_corner: Option<Geodetic>,
+    /// By name
+    pub satellites: Vec<RegionSatellite>,
+    /// Why the last region couldn't be set, shown in the panel until another one is
+    pub error: Option<String>,
+    timer: Timer,
+    settings: RegionSettings,
+}
+
+impl RegionWatch {
+    /// Whether globe clicks are picking a region rather than a point
+    pub fn is_picking(&self) -> bool {
+        self.enabled && self.picking.is_some()
+    }
+
+    fn set_region(&mut self, shape: RegionShape, name: String) {
+        let limit = |km: f64| (km > 0.0).then_some(km);
+        println!("✓ Watching {}", name);
+        self.region = Some(Region {
+            name,
+            shape,
+            min_altitude_km: limit(self.settings.min_altitude_km),
+            max_altitude_km: limit(self.settings.max_altitude_km),
+        });
+        self.revision += 1;
+        self.satellites.clear();
+        self.error = None;
+        // The list catches up with the new region right away
+        let duration = self.timer.duration();
+        self.timer.set_elapsed(duration);
+    }
+
+    /// Set the watched region to a country of the countries GeoJSON, by name or ISO code
+    fn watch_country(&mut self, name: &str) -> Result<(), String> {
+        let contents = read_countries(&self.settings.countries)?;
+        let (name, rings) = region::country_rings(&contents, name)?
+            .ok_or_else(|| format!("no country \"{}\" in {}", name, self.settings.countries))?;
+        self.set_region(RegionShape::Polygons(rings), name);
+        Ok(())
+    }
+
+    /// Log a failure to set the region and keep it for the panel
+    fn report(&mut self, message: String) {
+        eprintln!("Warning: {}", message);
+        self.error = Some(message);
+    }
+}
+
+fn read_countries(path: &str) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("no countries GeoJSON set under [region]".to_string());
+    }
+    let file = Path::new(ASSETS_DIR).join(path);
+    fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))
+}
+
+/// Outline of the region on the globe
+#[derive(Component)]
+pub struct RegionOutline;
+
+#[derive(Component)]
+pub struct RegionPanel;
+
+#[derive(Component)]
+pub struct RegionText;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum RegionButton {
+    PickBox,
+    PickCountry,
+    Clear,
+}
+
+impl RegionButton {
+    fn label(self) -> &'static str {
+        match self {
+            RegionButton::PickBox => "Draw box",
+            RegionButton::PickCountry => "Pick country",
+            RegionButton::Clear => "Clear",
+        }
+    }
+
+    fn picking(self) -> Option<RegionPicking> {
+        match self {
+            RegionButton::PickBox => Some(RegionPicking::Box),
+            RegionButton::PickCountry => Some(RegionPicking::Country),
+            RegionButton::Clear => None,
+        }
+    }
+}
+
+/// Watch the country or box of the `[region]` settings
+fn load_configured_region(mut watch: ResMut<RegionWatch>) {
+    let settings = watch.settings.clone();
+    if !settings.country.is_empty() {
+        if let Err(e) = watch.watch_country(&settings.country) {
+            watch.report(format!("Region not loaded: {}", e));
+        }
+        return;
+    }
+    let bounds = BoundingBox {
+        south_deg: settings.south_deg,
+        north_deg: settings.north_deg,
+        west_deg: settings.west_deg,
+        east_deg: settings.east_deg,
+    };
+    if bounds.north_deg > bounds.south_deg && bounds.width_deg() > 0.0 {
+        watch.set_region(RegionShape::Box(bounds), "Box from settings".to_string());
+    }
+}
+
+pub fn setup_region_watch(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new());
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: OUTLINE_COLOR,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        RegionOutline,
+        Name::new("RegionOutline"),
+    ));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Left of the alerts
+                right: Val::Px(380.0),
+                bottom: Val::Px(TIME_BAR_STRIP + 150.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+            RegionPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for button in [RegionButton::PickBox, RegionButton::PickCountry, RegionButton::Clear] {
+                        parent.spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            Text::new(button.label()),
+                            TextFont {
+                                font_size: 13.0,
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_COLOR),
+                            Interaction::default(),
+                            button,
+                        ));
+                    }
+                });
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                RegionText,
+            ));
+        });
+}
+
+/// Toggle the region watch with F10
+pub fn toggle_region_watch(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut watch: ResMut<RegionWatch>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleRegionWatch, &keyboard_input) {
+        return;
+    }
+    watch.enabled = !watch.enabled;
+    watch.picking = None;
+    watch.first_corner = None;
+}
+
+/// Picking tools stand out while active
+fn button_color(button: RegionButton, watch: &RegionWatch) -> Color {
+    if button.picking().is_some() && button.picking() == watch.picking {
+        ACTIVE_BUTTON_COLOR
+    } else {
+        BUTTON_COLOR
+    }
+}
+
+/// Start picking a box or a country on the globe, or stop watching
+fn handle_region_buttons(
+    mut button_query: Query<(&Interaction, &RegionButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut watch: ResMut<RegionWatch>,
+) {
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => button_color(*button, &watch),
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        watch.first_corner = None;
+        match button {
+            RegionButton::Clear => {
+                watch.picking = None;
+                watch.region = None;
+                watch.revision += 1;
+                watch.satellites.clear();
+                watch.error = None;
+            }
+            _ => {
+                // Pressing the active tool again cancels it
+                watch.picking = if watch.picking == button.picking() { None } else { button.picking() };
+            }
+        }
+    }
+}
+
+/// Take the box corners or the country from clicks on the globe
+fn record_region_clicks(
+    mut clicks: MessageReader<GroundClicked>,
+    mut watch: ResMut<RegionWatch>,
+    globe: Res<GlobeShape>,
+) {
+    if !watch.is_picking() {
+        clicks.clear();
+        return;
+    }
+    for click in clicks.read() {
+        let point = coords::scene_to_geodetic(click.0, *globe);
+        match watch.picking {
+            Some(RegionPicking::Box) => match watch.first_corner.take() {
+                None => watch.first_corner = Some(point),
+                Some(first) => {
+                    let bounds = BoundingBox::from_corners(first, point);
+                    let name = format!("{} to {}", format_coordinates(first), format_coordinates(point));
+                    watch.set_region(RegionShape::Box(bounds), name);
+                    watch.picking = None;
+                }
+            },
+            Some(RegionPicking::Country) => {
+                let country = read_countries(&watch.settings.countries)
+                    .and_then(|contents| region::country_at(&contents, point.latitude_deg, point.longitude_deg));
+                match country {
+                    Ok(Some(name)) => {
+                        if let Err(e) = watch.watch_country(&name) {
+                            watch.report(format!("Region not loaded: {}", e));
+                        }
+                        watch.picking = None;
+                    }
+                    Ok(None) => println!("No country at {}", format_coordinates(point)),
+                    Err(e) => {
+                        watch.report(format!("Countries not loaded: {}", e));
+                        watch.picking = None;
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// List the satellites over the region every `UPDATE_INTERVAL_SECONDS`
+fn update_region_satellites(
+    time: Res<Time>,
+    mut watch: ResMut<RegionWatch>,
+    satellite_query: Query<&Satellite>,
+) {
+    if !watch.enabled || !watch.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(region) = &watch.region else { return };
+
+    let mut satellites: Vec<RegionSatellite> = satellite_query
+        .iter()
+        .filter_map(|satellite| {
+            let position = coords::teme_to_geodetic(satellite.position?, satellite.last_update);
+            region.contains(position).then(|| RegionSatellite {
+                name: satellite.name.clone(),
+                norad_id: satellite.elements.norad_id,
+                position,
+            })
+        })
+        .collect();
+    satellites.sort_by(|a, b| a.name.cmp(&b.name).then(a.norad_id.cmp(&b.norad_id)));
+    watch.satellites = satellites;
+}
+
+/// Redraw the outline when the region changes, and show it while watching
+fn update_region_outline(
+    watch: Res<RegionWatch>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut outline_query: Query<(&Mesh3d, &mut Visibility), With<RegionOutline>>,
+    globe: Res<GlobeShape>,
+    mut drawn: Local<Option<u32>>,
+) {
+    if !watch.is_changed() {
+        return;
+    }
+    for (mesh_3d, mut visibility) in outline_query.iter_mut() {
+        let shown = watch.enabled && watch.region.is_some();
+        visibility.set_if_neq(if shown { Visibility::Visible } else { Visibility::Hidden });
+        if *drawn == Some(watch.revision) {
+            continue;
+        }
+        let mut positions = Vec::new();
+        for line in watch.region.iter().flat_map(Region::outline) {
+            for pair in line.windows(2) {
+                for &(longitude, latitude) in pair {
+                    let point = Geodetic::new(latitude, longitude, OUTLINE_ALTITUDE_KM);
+                    positions.push(coords::geodetic_to_scene(point, *globe).to_array());
+                }
+            }
+        }
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        }
+        *drawn = Some(watch.revision);
+    }
+}
+
+fn update_region_panel(
+    watch: Res<RegionWatch>,
+    keymap: Res<Keymap>,
+    mut panel_query: Query<&mut Node, With<RegionPanel>>,
+    mut text_query: Query<&mut Text, With<RegionText>>,
+    mut button_query: Query<(&RegionButton, &Interaction, &mut BackgroundColor)>,
+) {
+    if !watch.is_changed() {
+        return;
+    }
+    for (button, interaction, mut background) in button_query.iter_mut() {
+        if *interaction == Interaction::None {
+            background.0 = button_color(*button, &watch);
+        }
+    }
+    let display = if watch.enabled { Display::Flex } else { Display::None };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !watch.enabled {
+        return;
+    }
+
+    let mut lines = Vec::new();
+    match watch.picking {
+        Some(RegionPicking::Box) if watch.first_corner.is_some() => lines.push("Click the opposite corner".to_string()),
+        Some(RegionPicking::Box) => lines.push("Click a corner of the box on the globe".to_string()),
+        Some(RegionPicking::Country) => lines.push("Click a country on the globe".to_string()),
+        None => {}
+    }
+    match &watch.region {
+        Some(region) => {
+            let mut header = format!("Over {}: {} satellites", region.name, watch.satellites.len());
+            match (region.min_altitude_km, region.max_altitude_km) {
+                (Some(min), Some(max)) => header.push_str(&format!(" ({:.0}-{:.0} km)", min, max)),
+                (Some(min), None) => header.push_str(&format!(" (above {:.0} km)", min)),
+                (None, Some(max)) => header.push_str(&format!(" (below {:.0} km)", max)),
+                (None, None) => {}
+            }
+            header.push_str(&format!("  [{}] hide", keymap.label(Action::ToggleRegionWatch)));
+            lines.push(header);
+            lines.extend(watch.satellites.iter().take(MAX_LISTED_SATELLITES).map(|satellite| {
+                format!(
+                    "{} ({})  {:.0} km  {}",
+                    satellite.name,
+                    satellite.norad_id,
+                    satellite.position.altitude_km,
+                    format_coordinates(satellite.position)
+                )
+            }));
+            if watch.satellites.len() > MAX_LISTED_SATELLITES {
+                lines.push(format!("({} more not listed)", watch.satellites.len() - MAX_LISTED_SATELLITES));
+            }
+        }
+        None => lines.push(format!("No region watched  [{}] hide", keymap.label(Action::ToggleRegionWatch))),
+    }
+    if let Some(error) = &watch.error {
+        lines.push(error.clone());
+    }
+
+    let content = lines.join("\n");
+    for mut text in text_query.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
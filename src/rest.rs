@@ -0,0 +1,397 @@
+//! Local HTTP query service: positions and passes from the same propagation the viewer uses
+//!
+//! Built with the `rest-api` feature and enabled with `[rest]` in the settings.
+//!
+//! ```text
+//! GET /satellites                                  NORAD id, name and element epoch of each loaded satellite
+//! GET /satellite/25544/position?at=<time>          Geodetic and TEME state at a time
+//! GET /passes?station=Toulouse&norad=25544&hours=24&min_elevation=10
+//!                                                  Passes over a station (a name from ground_stations.json
+//!                                                  or "lat,lon[,alt_km]"); all satellites without `norad`
+//! ```
+//!
+//! Times are RFC 3339 or "now" (wall clock); they default to the simulation time. Answers are
+//! JSON, errors are `{"error": "..."}` with a 4xx status.
+
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::config::Settings;
+use crate::coords;
+use crate::ground_station;
+use crate::passes;
+use crate::propagation::Propagator;
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::time_simulation::TimeSimulation;
+
+/// Answer position and pass queries over HTTP
+pub struct RestPlugin;
+
+impl Plugin for RestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().rest.clone();
+        if !settings.enabled {
+            return;
+        }
+
+        let catalog = RestCatalog::default();
+        match start_server(&settings.address, catalog.0.clone()) {
+            Ok(()) => {
+                app.insert_resource(catalog).add_systems(Update, update_rest_catalog);
+            }
+            Err(e) => eprintln!("Error: Failed to start the REST API on {}: {}", settings.address, e),
+        }
+    }
+}
+
+/// A client that doesn't send its request within this time is dropped
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// Pass search window when `hours` is not given
+const DEFAULT_PASS_HOURS: i64 = 24;
+/// Longest pass search window, as in headless mode
+const MAX_PASS_HOURS: i64 = 14 * 24;
+
+/// What the server answers from: a propagator per satellite and the simulation time
+struct CatalogEntry {
+    norad_id: u64,
+    name: String,
+    epoch: DateTime<Utc>,
+    propagator: Propagator,
+}
+
+struct Catalog {
+    /// Rebuilt when satellites are loaded or their elements refreshed; requests keep
+    /// the list they started with
+    satellites: Arc<Vec<CatalogEntry>>,
+    time: DateTime<Utc>,
+}
+
+/// Catalog shared with the server threads
+#[derive(Resource, Clone)]
+pub struct RestCatalog(Arc<Mutex<Catalog>>);
+
+impl Default for RestCatalog {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Catalog {
+            satellites: Arc::default(),
+            time: Utc::now(),
+        })))
+    }
+}
+
+/// Request failure: HTTP status and message
+struct HttpError(u16, String);
+
+impl HttpError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(400, message.into())
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self(404, message.into())
+    }
+}
+
+#[derive(Serialize)]
+struct PositionReply {
+    norad_id: u64,
+    name: String,
+    time: String,
+    latitude_deg: f64,
+    longitude_deg: f64,
+    altitude_km: f64,
+    /// TEME position (km) and velocity (km/s)
+    position_km: [f64; 3],
+    velocity_km_s: [f64; 3],
+}
+
+#[derive(Serialize)]
+struct PassReply {
+    norad_id: u64,
+    name: String,
+    station: String,
+    rise_time: String,
+    rise_azimuth_deg: f64,
+    max_elevation_time: String,
+    max_elevation_deg: f64,
+    set_time: String,
+    set_azimuth_deg: f64,
+}
+
+fn start_server(address: &str, catalog: Arc<Mutex<Catalog>>) -> Result<(), String> {
+    let listener = TcpListener::bind(address).map_err(|e| e.to_string())?;
+    println!("✓ REST API listening on http://{}/", address);
+
+    thread::spawn(move || {
+        for connection in listener.incoming().flatten() {
+            // Pass searches over many satellites take a while; don't hold up other clients
+            let catalog = catalog.clone();
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(connection, &catalog) {
+                    eprintln!("Warning: REST request failed: {}", e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Read one request and answer it (no keep-alive)
+fn handle_connection(mut stream: TcpStream, catalog: &Mutex<Catalog>) -> Result<(), String> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(|e| e.to_string())?;
+    // Headers are not needed, but must be read before answering
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method, target),
+        _ => return Err("Malformed request".to_string()),
+    };
+    let reply = if method == "GET" {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        route(path, &parse_query(query), catalog)
+    } else {
+        Err(HttpError(405, format!("Method {} not allowed", method)))
+    };
+
+    let (status, body) = match reply {
+        Ok(body) => (200, body),
+        Err(HttpError(status, message)) => (status, json!({ "error": message })),
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
+
+/// "a=1&b=x%20y" -> {a: "1", b: "x y"}
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode %XX escapes and '+' as a space
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let escaped = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match escaped {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn route(path: &str, query: &HashMap<String, String>, catalog: &Mutex<Catalog>) -> Result<Value, HttpError> {
+    // Take the current list and time, then work without holding the lock
+    let (satellites, now) = {
+        let catalog = catalog.lock().unwrap();
+        (catalog.satellites.clone(), catalog.time)
+    };
+
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    match segments.as_slice() {
+        ["satellites"] => Ok(Value::Array(
+            satellites
+                .iter()
+                .map(|entry| {
+                    json!({
+                        "norad_id": entry.norad_id,
+                        "name": entry.name,
+                        "epoch": format_time(entry.epoch),
+                    })
+                })
+                .collect(),
+        )),
+        ["satellite", norad_id, "position"] => {
+            let entry = find_satellite(&satellites, norad_id)?;
+            let time = parse_time(query.get("at"), now)?;
+            position(entry, time).map(|reply| json!(reply))
+        }
+        ["passes"] => passes_over_station(&satellites, query, now).map(|replies| json!(replies)),
+        _ => Err(HttpError::not_found(format!("No endpoint {}", path))),
+    }
+}
+
+fn find_satellite<'a>(satellites: &'a [CatalogEntry], norad_id: &str) -> Result<&'a CatalogEntry, HttpError> {
+    let norad_id: u64 = norad_id
+        .parse()
+        .map_err(|_| HttpError::bad_request(format!("Invalid NORAD id \"{}\"", norad_id)))?;
+    satellites
+        .iter()
+        .find(|entry| entry.norad_id == norad_id)
+        .ok_or_else(|| HttpError::not_found(format!("No satellite {} loaded", norad_id)))
+}
+
+/// RFC 3339 or "now"; the simulation time if not given
+fn parse_time(value: Option<&String>, simulation_time: DateTime<Utc>) -> Result<DateTime<Utc>, HttpError> {
+    match value.map(String::as_str) {
+        None => Ok(simulation_time),
+        Some("now") => Ok(Utc::now()),
+        Some(value) => DateTime::parse_from_rfc3339(value)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|e| HttpError::bad_request(format!("Invalid time \"{}\": {}", value, e))),
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn position(entry: &CatalogEntry, time: DateTime<Utc>) -> Result<PositionReply, HttpError> {
+    let (position, velocity) = entry
+        .propagator
+        .propagate_state(time)
+        .ok_or_else(|| HttpError(422, format!("{} can't be propagated to {}", entry.name, format_time(time))))?;
+    let geodetic = coords::teme_to_geodetic(position, time);
+    Ok(PositionReply {
+        norad_id: entry.norad_id,
+        name: entry.name.clone(),
+        time: format_time(time),
+        latitude_deg: geodetic.latitude_deg,
+        longitude_deg: geodetic.longitude_deg,
+        altitude_km: geodetic.altitude_km,
+        position_km: [position.x, position.y, position.z],
+        velocity_km_s: [velocity.x, velocity.y, velocity.z],
+    })
+}
+
+fn passes_over_station(
+    satellites: &[CatalogEntry],
+    query: &HashMap<String, String>,
+    now: DateTime<Utc>,
+) -> Result<Vec<PassReply>, HttpError> {
+    let mut station = query
+        .get("station")
+        .ok_or_else(|| HttpError::bad_request("station is required"))
+        .and_then(|value| ground_station::resolve_station(value).map_err(HttpError::not_found))?;
+    if let Some(value) = query.get("min_elevation") {
+        station.min_elevation_deg = value
+            .parse()
+            .map_err(|_| HttpError::bad_request(format!("Invalid min_elevation \"{}\"", value)))?;
+    }
+    let hours = match query.get("hours") {
+        Some(value) => value
+            .parse::<i64>()
+            .ok()
+            .filter(|hours| (1..=MAX_PASS_HOURS).contains(hours))
+            .ok_or_else(|| HttpError::bad_request(format!("hours must be between 1 and {}", MAX_PASS_HOURS)))?,
+        None => DEFAULT_PASS_HOURS,
+    };
+    let start = parse_time(query.get("from"), now)?;
+    let selected: Vec<&CatalogEntry> = match query.get("norad") {
+        Some(norad_id) => vec![find_satellite(satellites, norad_id)?],
+        None => satellites.iter().collect(),
+    };
+
+    let mut replies = Vec::new();
+    for entry in selected {
+        let passes = passes::predict_passes(
+            &entry.propagator,
+            station.geodetic(),
+            &station.horizon(),
+            start,
+            start + Duration::hours(hours),
+            Duration::seconds(passes::PASS_SEARCH_STEP_SECONDS),
+        );
+        replies.extend(passes.into_iter().map(|pass| PassReply {
+            norad_id: entry.norad_id,
+            name: entry.name.clone(),
+            station: station.name.clone(),
+            rise_time: format_time(pass.rise_time),
+            rise_azimuth_deg: pass.rise_azimuth_deg,
+            max_elevation_time: format_time(pass.max_elevation_time),
+            max_elevation_deg: pass.max_elevation_deg,
+            set_time: format_time(pass.set_time),
+            set_azimuth_deg: pass.set_azimuth_deg,
+        }));
+    }
+    replies.sort_by(|a, b| a.rise_time.cmp(&b.rise_time));
+    Ok(replies)
+}
+
+/// Keep the simulation time current and rebuild the satellite list when it changes
+pub fn update_rest_catalog(
+    catalog: Res<RestCatalog>,
+    sim_time: Res<TimeSimulation>,
+    added: Query<(), Added<Satellite>>,
+    mut removed: RemovedComponents<Satellite>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    satellite_query: Query<&Satellite>,
+) {
+    let was_removed = removed.read().count() > 0;
+    let was_refreshed = TleRefreshed::any_changed(&mut refreshed);
+    let satellites = (!added.is_empty() || was_removed || was_refreshed).then(|| {
+        Arc::new(
+            satellite_query
+                .iter()
+                .map(|satellite| CatalogEntry {
+                    norad_id: satellite.elements.norad_id,
+                    name: satellite.name.clone(),
+                    epoch: satellite.elements.datetime.and_utc(),
+                    propagator: Propagator::new(&satellite.elements),
+                })
+                .collect::<Vec<_>>(),
+        )
+    });
+
+    let mut catalog = catalog.0.lock().unwrap();
+    catalog.time = sim_time.current_time();
+    if let Some(satellites) = satellites {
+        catalog.satellites = satellites;
+    }
+}
@@ -0,0 +1,287 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::{RevisitSettings, Settings};
+use crate::coords::Geodetic;
+use crate::coverage::analyzed_propagators;
+use crate::ground_pick::format_coordinates;
+use crate::groups::SatelliteGroups;
+use crate::passes::{self, RevisitStatistics};
+use crate::satellite::Satellite;
+use crate::selection::Selected;
+use crate::time_bar::TIME_BAR_STRIP;
+use crate::time_simulation::TimeSimulation;
+
+/// Gap and revisit statistics of a point on the ground, for a group of satellites
+pub struct RevisitPlugin;
+
+impl Plugin for RevisitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().revisit.clone();
+
+        app.insert_resource(RevisitAnalysis {
+            settings,
+            task: None,
+            report: None,
+        })
+        .add_message::<RevisitRequest>()
+        .add_systems(Startup, setup_revisit_panel)
+        .add_systems(Update, (
+            start_revisit_analysis,
+            finish_revisit_analysis,
+            handle_revisit_close,
+            update_revisit_panel,
+        ).chain());
+    }
+}
+
+/// Longest gaps listed in the panel
+const LISTED_GAPS: usize = 5;
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// Ask for the revisit statistics of a ground point (sent by the clicked point's panel)
+#[derive(Message, Clone, Copy)]
+pub struct RevisitRequest(pub Geodetic);
+
+/// Revisit analysis settings, the run in progress and the last result
+#[derive(Resource)]
+pub struct RevisitAnalysis {
+    pub settings: RevisitSettings,
+    task: Option<(Geodetic, Task<RevisitReport>)>,
+    pub report: Option<RevisitReport>,
+}
+
+/// Result of a revisit analysis
+pub struct RevisitReport {
+    pub target: Geodetic,
+    pub satellites: usize,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub min_elevation_deg: f64,
+    /// Times with at least one satellite in view, in order
+    pub accesses: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    pub statistics: RevisitStatistics,
+}
+
+impl RevisitReport {
+    /// Gaps between accesses and at the ends of the window, longest first
+    fn longest_gaps(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let bounds: Vec<DateTime<Utc>> = std::iter::once(self.start)
+            .chain(self.accesses.iter().flat_map(|&(rise, set)| [rise, set]))
+            .chain(std::iter::once(self.end))
+            .collect();
+        let mut gaps: Vec<_> = bounds
+            .chunks(2)
+            .map(|pair| (pair[0], pair[1]))
+            .filter(|(from, to)| to > from)
+            .collect();
+        gaps.sort_by_key(|&(from, to)| std::cmp::Reverse(to - from));
+        gaps.truncate(LISTED_GAPS);
+        gaps
+    }
+}
+
+/// Panel with the statistics of the last analysis (above the clicked point's panel)
+#[derive(Component)]
+pub struct RevisitPanel;
+
+#[derive(Component)]
+pub struct RevisitText;
+
+#[derive(Component)]
+pub struct RevisitCloseButton;
+
+pub fn setup_revisit_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(40.0),
+                bottom: Val::Px(TIME_BAR_STRIP + 150.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexStart,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+            RevisitPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                RevisitText,
+            ));
+            parent.spawn((
+                Node {
+                    padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                    ..default()
+                },
+                Text::new("Close"),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                Interaction::default(),
+                RevisitCloseButton,
+            ));
+        });
+}
+
+/// Sweep the satellites' visibility from the requested point on a background thread
+///
+/// Covers the `analyzed_propagators` over `[revisit] hours` from the simulation time; a new
+/// request replaces a run still in progress.
+pub fn start_revisit_analysis(
+    mut requests: MessageReader<RevisitRequest>,
+    mut analysis: ResMut<RevisitAnalysis>,
+    groups: Res<SatelliteGroups>,
+    sim_time: Res<TimeSimulation>,
+    satellite_query: Query<(&Satellite, Has<Selected>)>,
+) {
+    let Some(&RevisitRequest(target)) = requests.read().last() else {
+        return;
+    };
+
+    let propagators = analyzed_propagators(&groups, &satellite_query);
+    if propagators.is_empty() {
+        println!("Select a satellite or show only a group to compute revisit statistics");
+        return;
+    }
+
+    let settings = analysis.settings.clone();
+    let start = sim_time.current_time();
+    let end = start + Duration::milliseconds((settings.hours.max(0.0) * 3_600_000.0) as i64);
+    let step = Duration::milliseconds((settings.step_seconds.max(1.0) * 1000.0) as i64);
+    println!(
+        "Computing revisits of {} by {} satellites over {} h...",
+        format_coordinates(target),
+        propagators.len(),
+        settings.hours
+    );
+
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let accesses = passes::access_intervals(&propagators, target, settings.min_elevation_deg, start, end, step);
+        RevisitReport {
+            target,
+            satellites: propagators.len(),
+            start,
+            end,
+            min_elevation_deg: settings.min_elevation_deg,
+            statistics: passes::revisit_statistics(&accesses, start, end),
+            accesses,
+        }
+    });
+    // Dropping a previous task cancels it
+    analysis.task = Some((target, task));
+    analysis.report = None;
+}
+
+/// Keep the report once the sweep finishes
+pub fn finish_revisit_analysis(mut analysis: ResMut<RevisitAnalysis>) {
+    let report = match analysis.task.as_mut().and_then(|(_, task)| block_on(future::poll_once(task))) {
+        Some(report) => report,
+        None => return,
+    };
+    analysis.task = None;
+    println!(
+        "✓ Revisits of {}: {} accesses, max gap {}",
+        format_coordinates(report.target),
+        report.statistics.accesses,
+        format_span(report.statistics.max_gap)
+    );
+    analysis.report = Some(report);
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_revisit_close(
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<RevisitCloseButton>)>,
+    mut analysis: ResMut<RevisitAnalysis>,
+) {
+    for (interaction, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction == Interaction::Pressed {
+            analysis.task = None;
+            analysis.report = None;
+        }
+    }
+}
+
+/// "2 h 05 min", "14 min 30 s"
+fn format_span(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    if seconds >= 3600 {
+        format!("{} h {:02} min", seconds / 3600, seconds % 3600 / 60)
+    } else {
+        format!("{} min {:02} s", seconds / 60, seconds % 60)
+    }
+}
+
+fn describe_report(report: &RevisitReport) -> String {
+    let statistics = &report.statistics;
+    let optional = |duration: Option<Duration>| duration.map_or("-".to_string(), format_span);
+    let clock = |time: DateTime<Utc>| time.format("%m-%d %H:%M").to_string();
+
+    let mut lines = vec![
+        format!("Revisits of {}", format_coordinates(report.target)),
+        format!(
+            "{} satellites, {} to {} UTC, above {:.0}°",
+            report.satellites,
+            report.start.format("%Y-%m-%d %H:%M"),
+            clock(report.end),
+            report.min_elevation_deg
+        ),
+        format!("  Accesses       {}", statistics.accesses),
+        format!("  Time in view   {:.1}%", statistics.coverage * 100.0),
+        format!("  First access   {}", optional(statistics.first_access)),
+        format!("  Mean access    {}", optional(statistics.mean_access)),
+        format!("  Mean revisit   {}", optional(statistics.mean_revisit)),
+        format!("  Mean gap       {}", optional(statistics.mean_gap)),
+        format!("  Max gap        {}", format_span(statistics.max_gap)),
+        "Longest gaps:".to_string(),
+    ];
+    for (from, to) in report.longest_gaps() {
+        lines.push(format!("  {} - {}  {}", clock(from), clock(to), format_span(to - from)));
+    }
+    lines.join("\n")
+}
+
+/// Show the panel while an analysis runs or has a result
+fn update_revisit_panel(
+    analysis: Res<RevisitAnalysis>,
+    mut panel_query: Query<&mut Node, With<RevisitPanel>>,
+    mut text_query: Query<&mut Text, With<RevisitText>>,
+) {
+    let shown = analysis.task.is_some() || analysis.report.is_some();
+    for mut node in panel_query.iter_mut() {
+        let display = if shown { Display::Flex } else { Display::None };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !shown {
+        return;
+    }
+
+    let content = match (&analysis.task, &analysis.report) {
+        (Some((target, _)), _) => format!("Computing revisits of {}...", format_coordinates(*target)),
+        (None, Some(report)) => describe_report(report),
+        (None, None) => String::new(),
+    };
+    for mut text in text_query.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Ground station control through hamlib: while the selected satellite is above the
+//! station, its azimuth/elevation goes to a rotctld daemon (`P <az> <el>`) and the
+//! Doppler-corrected downlink to a rigctld daemon (`F <hz>`), a few times per second
+//!
+//! Only a live clock (real time at 1x) drives the hardware, so scrubbing or fast-forwarding
+//! the simulation never swings the antenna. The daemons are reached on their own thread,
+//! which reconnects after a failure; commands are dropped while it is still busy.
+
+use bevy::prelude::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::{RotatorSettings, Settings};
+use crate::ground_station::{self, GroundStation, GROUND_STATIONS_FILE};
+use crate::notifications::Notify;
+use crate::passes;
+use crate::radio::Transmitters;
+use crate::satellite::Satellite;
+use crate::selection::Selected;
+use crate::time_simulation::TimeSimulation;
+
+/// Point a rotator and tune a radio at the selected satellite during its passes
+pub struct RotatorPlugin;
+
+impl Plugin for RotatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().rotator.clone();
+        if !settings.enabled {
+            return;
+        }
+
+        match RotatorControl::start(&settings) {
+            Ok(control) => {
+                app.insert_resource(control).add_systems(Update, drive_rotator);
+            }
+            Err(e) => eprintln!("Error: Failed to start the rotator control: {}", e),
+        }
+    }
+}
+
+/// Time allowed to connect to, write to and hear back from a daemon
+const HAMLIB_TIMEOUT: Duration = Duration::from_secs(2);
+/// The clock counts as live within this many seconds of the real time
+const LIVE_TOLERANCE_SECONDS: i64 = 2;
+
+/// Pointing and tuning sent to the daemons
+struct ControlCommand {
+    azimuth_deg: f64,
+    elevation_deg: f64,
+    frequency_hz: Option<f64>,
+}
+
+/// Hands commands to the hamlib thread at the configured rate
+#[derive(Resource)]
+pub struct RotatorControl {
+    sender: SyncSender<ControlCommand>,
+    station: GroundStation,
+    downlink_hz: Option<f64>,
+    /// Satellite being tracked, while one is above the station
+    tracking: Option<Entity>,
+    timer: Timer,
+}
+
+impl RotatorControl {
+    fn start(settings: &RotatorSettings) -> Result<Self, String> {
+        let station = if settings.station.trim().is_empty() {
+            ground_station::load_ground_stations(GROUND_STATIONS_FILE)
+                .map_err(|e| format!("Failed to load {}: {}", GROUND_STATIONS_FILE, e))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("No ground station in {}", GROUND_STATIONS_FILE))?
+        } else {
+            ground_station::resolve_station(&settings.station)?
+        };
+        let rotator = HamlibLink::new("rotctld", &settings.rotctld)?;
+        let radio = HamlibLink::new("rigctld", &settings.rigctld)?;
+        if rotator.is_none() && radio.is_none() {
+            return Err("neither a rotctld nor a rigctld address is set".to_string());
+        }
+
+        // One command in flight; newer ones are dropped until the daemons answer
+        let (sender, receiver) = mpsc::sync_channel::<ControlCommand>(1);
+        thread::spawn(move || run_hamlib_links(receiver, rotator, radio));
+        println!("✓ Rotator control for {}", station.name);
+
+        let period = 1.0 / settings.rate_hz.max(0.1);
+        Ok(Self {
+            sender,
+            station,
+            downlink_hz: (settings.downlink_mhz > 0.0).then_some(settings.downlink_mhz * 1e6),
+            tracking: None,
+            timer: Timer::from_seconds(period as f32, TimerMode::Repeating),
+        })
+    }
+}
+
+/// Connection to one hamlib daemon, opened again after a failure
+struct HamlibLink {
+    name: &'static str,
+    address: SocketAddr,
+    connection: Option<BufReader<TcpStream>>,
+    /// Whether the connection failure was reported, so a daemon that is down is reported once
+    connection_warned: bool,
+    /// Whether a refused command was reported, until the daemon accepts one again
+    refusal_warned: bool,
+}
+
+impl HamlibLink {
+    /// None for an empty address
+    fn new(name: &'static str, address: &str) -> Result<Option<Self>, String> {
+        if address.trim().is_empty() {
+            return Ok(None);
+        }
+        let address = address
+            .to_socket_addrs()
+            .map_err(|e| format!("Invalid {} address {}: {}", name, address, e))?
+            .next()
+            .ok_or_else(|| format!("Invalid {} address {}", name, address))?;
+        Ok(Some(Self {
+            name,
+            address,
+            connection: None,
+            connection_warned: false,
+            refusal_warned: false,
+        }))
+    }
+
+    /// Send one command line and wait for its `RPRT` reply
+    fn send(&mut self, command: &str) {
+        if let Err(e) = self.try_send(command) {
+            self.connection = None;
+            if !self.connection_warned {
+                eprintln!("Warning: {} at {}: {}", self.name, self.address, e);
+                self.connection_warned = true;
+            }
+        }
+    }
+
+    fn try_send(&mut self, command: &str) -> Result<(), String> {
+        if self.connection.is_none() {
+            let stream = TcpStream::connect_timeout(&self.address, HAMLIB_TIMEOUT).map_err(|e| e.to_string())?;
+            stream.set_read_timeout(Some(HAMLIB_TIMEOUT)).map_err(|e| e.to_string())?;
+            stream.set_write_timeout(Some(HAMLIB_TIMEOUT)).map_err(|e| e.to_string())?;
+            println!("✓ Connected to {} at {}", self.name, self.address);
+            self.connection = Some(BufReader::new(stream));
+            self.connection_warned = false;
+        }
+        let Some(connection) = self.connection.as_mut() else { return Ok(()) };
+
+        connection.get_mut().write_all(format!("{}\n", command).as_bytes()).map_err(|e| e.to_string())?;
+        let mut reply = String::new();
+        if connection.read_line(&mut reply).map_err(|e| e.to_string())? == 0 {
+            return Err("connection closed".to_string());
+        }
+        // "RPRT 0" is success; a negative code is a hamlib error, reported but not fatal
+        match reply.trim().strip_prefix("RPRT ").and_then(|code| code.parse::<i32>().ok()) {
+            Some(0) => self.refusal_warned = false,
+            Some(code) if code < 0 && !self.refusal_warned => {
+                eprintln!("Warning: {} at {} refused \"{}\" (RPRT {})", self.name, self.address, command, code);
+                self.refusal_warned = true;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn run_hamlib_links(receiver: Receiver<ControlCommand>, mut rotator: Option<HamlibLink>, mut radio: Option<HamlibLink>) {
+    for command in receiver {
+        if let Some(rotator) = rotator.as_mut() {
+            rotator.send(&format!("P {:.1} {:.1}", command.azimuth_deg, command.elevation_deg));
+        }
+        if let (Some(radio), Some(frequency_hz)) = (radio.as_mut(), command.frequency_hz) {
+            radio.send(&format!("F {:.0}", frequency_hz));
+        }
+    }
+}
+
+/// Send the selected satellite's pointing and Doppler-corrected downlink while it is above
+/// the station's horizon and the clock is live
+pub fn drive_rotator(
+    time: Res<Time>,
+    sim_time: Res<TimeSimulation>,
+    mut control: ResMut<RotatorControl>,
+    selected_query: Query<(Entity, &Satellite), With<Selected>>,
+    transmitters: Option<Res<Transmitters>>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    if !control.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let now = sim_time.real_time();
+    let live = sim_time.rate() == 1.0 && (sim_time.current_time() - now).num_seconds().abs() <= LIVE_TOLERANCE_SECONDS;
+    let observer = control.station.geodetic();
+    let in_view = selected_query.iter().next().filter(|_| live).and_then(|(entity, satellite)| {
+        let look = passes::look_angles_at(satellite.propagator(), observer, now)?;
+        control.station.horizon().is_clear(&look).then_some((entity, satellite, look))
+    });
+
+    let Some((entity, satellite, look)) = in_view else {
+        if control.tracking.take().is_some() {
+            println!("Rotator control idle");
+        }
+        return;
+    };
+    if control.tracking != Some(entity) {
+        control.tracking = Some(entity);
+        println!("✓ Tracking {} from {}", satellite.name, control.station.name);
+        notify_writer.write(Notify::info(format!("Rotator tracking {}", satellite.name)));
+    }
+
+    let downlink_hz = control.downlink_hz.or_else(|| {
+        let list = transmitters.as_ref()?.0.get(&satellite.elements.norad_id)?;
+        list.iter()
+            .filter(|transmitter| transmitter.is_active())
+            .find_map(|transmitter| transmitter.downlink_low)
+            .map(|hz| hz as f64)
+    });
+    let frequency_hz = downlink_hz.and_then(|hz| {
+        let range_rate = passes::range_rate_at(satellite.propagator(), observer, now)?;
+        Some(passes::doppler_shifted(hz, range_rate))
+    });
+
+    // A busy daemon drops this update; the next one carries fresher angles anyway
+    let _ = control.sender.try_send(ControlCommand {
+        azimuth_deg: look.azimuth_deg,
+        elevation_deg: look.elevation_deg.clamp(0.0, 90.0),
+        frequency_hz,
+    });
+}
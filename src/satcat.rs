@@ -0,0 +1,276 @@
+//! Satellite catalog (SATCAT) metadata: owner, launch date, object type and radar cross
+//! section size of each object, keyed by NORAD ID
+//!
+//! Reads the CSV exports of CelesTrak (celestrak.org/pub/satcat.csv) and Space-Track;
+//! columns are found by their header, so either layout works. Downloads are cached like
+//! TLE data.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use chrono::NaiveDate;
+
+/// CelesTrak's copy of the full catalog
+pub const CELESTRAK_SATCAT_URL: &str = "https://celestrak.org/pub/satcat.csv";
+
+/// SATCAT entries keyed by NORAD catalog number
+pub type SatcatSet = HashMap<u64, SatcatEntry>;
+
+/// What an object is, according to the catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SatcatObjectType {
+    Payload,
+    RocketBody,
+    Debris,
+    Unknown,
+}
+
+impl SatcatObjectType {
+    /// CelesTrak codes ("PAY", "R/B", "DEB") or Space-Track words ("ROCKET BODY")
+    pub fn parse(text: &str) -> Self {
+        match text.trim().to_uppercase().as_str() {
+            "PAY" | "PAYLOAD" => SatcatObjectType::Payload,
+            "R/B" | "RB" | "ROCKET" | "ROCKET BODY" => SatcatObjectType::RocketBody,
+            "DEB" | "DEBRIS" => SatcatObjectType::Debris,
+            _ => SatcatObjectType::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SatcatObjectType::Payload => "Payload",
+            SatcatObjectType::RocketBody => "Rocket body",
+            SatcatObjectType::Debris => "Debris",
+            SatcatObjectType::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Radar cross section size class, as Space-Track publishes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RcsSize {
+    /// Under 0.1 m²
+    Small,
+    /// 0.1 to 1 m²
+    Medium,
+    /// Over 1 m²
+    Large,
+}
+
+impl RcsSize {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_uppercase().as_str() {
+            "SMALL" => Some(RcsSize::Small),
+            "MEDIUM" => Some(RcsSize::Medium),
+            "LARGE" => Some(RcsSize::Large),
+            _ => None,
+        }
+    }
+
+    /// Class of a radar cross section in m² (CelesTrak lists the value)
+    pub fn from_area(area_m2: f64) -> Self {
+        if area_m2 < 0.1 {
+            RcsSize::Small
+        } else if area_m2 <= 1.0 {
+            RcsSize::Medium
+        } else {
+            RcsSize::Large
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RcsSize::Small => "Small",
+            RcsSize::Medium => "Medium",
+            RcsSize::Large => "Large",
+        }
+    }
+}
+
+/// Catalog metadata of one object
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatcatEntry {
+    /// SATCAT owner code ("US", "PRC", "CIS", "ISS", ...)
+    pub owner: String,
+    pub launch_date: Option<NaiveDate>,
+    pub object_type: SatcatObjectType,
+    pub rcs_size: Option<RcsSize>,
+}
+
+/// Fields of one CSV line, with double-quoted fields unquoted
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Parse a SATCAT CSV export; rows without a catalog number are skipped
+pub fn parse_csv(text: &str) -> Result<SatcatSet, String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().ok_or("empty SATCAT file")?);
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|title| names.iter().any(|name| title.trim().eq_ignore_ascii_case(name)))
+    };
+    let norad_column = column(&["NORAD_CAT_ID"]).ok_or("no NORAD_CAT_ID column in the SATCAT header")?;
+    let owner_column = column(&["OWNER", "COUNTRY"]);
+    let launch_column = column(&["LAUNCH_DATE", "LAUNCH"]);
+    let type_column = column(&["OBJECT_TYPE"]);
+    let rcs_size_column = column(&["RCS_SIZE"]);
+    let rcs_column = column(&["RCS", "RCSVALUE"]);
+
+    let mut set = SatcatSet::new();
+    for line in lines {
+        let fields = split_csv_line(line);
+        let field = |column: Option<usize>| column.and_then(|column| fields.get(column)).map(|field| field.trim()).unwrap_or("");
+        let Ok(norad_id) = field(Some(norad_column)).parse::<u64>() else {
+            continue;
+        };
+        let rcs_size = RcsSize::parse(field(rcs_size_column))
+            .or_else(|| field(rcs_column).parse::<f64>().ok().map(RcsSize::from_area));
+        set.insert(norad_id, SatcatEntry {
+            owner: field(owner_column).to_string(),
+            launch_date: NaiveDate::parse_from_str(field(launch_column), "%Y-%m-%d").ok(),
+            object_type: SatcatObjectType::parse(field(type_column)),
+            rcs_size,
+        });
+    }
+    Ok(set)
+}
+
+/// Loads the SATCAT from CelesTrak or a local CSV file, caching downloads
+pub struct SatcatLoader {
+    source: String,
+    cache_file: String,
+    cache_max_age_hours: u64,
+}
+
+impl Default for SatcatLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SatcatLoader {
+    pub fn new() -> Self {
+        Self {
+            source: CELESTRAK_SATCAT_URL.to_string(),
+            cache_file: "cache/satcat.csv".to_string(),
+            cache_max_age_hours: 24 * 7,
+        }
+    }
+
+    /// Read from this URL or CSV file instead of CelesTrak
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Set how long a downloaded copy stays valid
+    pub fn with_cache_max_age_hours(mut self, hours: u64) -> Self {
+        self.cache_max_age_hours = hours;
+        self
+    }
+
+    fn is_url(&self) -> bool {
+        self.source.starts_with("http://") || self.source.starts_with("https://")
+    }
+
+    fn is_cache_valid(&self) -> bool {
+        let max_age = Duration::from_secs(self.cache_max_age_hours * 3600);
+        fs::metadata(&self.cache_file)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < max_age)
+    }
+
+    fn save_to_cache(&self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(&self.cache_file).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.cache_file, text)?;
+        Ok(())
+    }
+
+    /// Load the catalog, keyed by NORAD ID
+    ///
+    /// Downloads are reused from the cache while it is fresh; if a download fails,
+    /// an expired cache is still better than nothing.
+    pub fn load(&self) -> Result<SatcatSet, Box<dyn std::error::Error>> {
+        let text = if !self.is_url() {
+            fs::read_to_string(&self.source)?
+        } else if self.is_cache_valid() {
+            fs::read_to_string(&self.cache_file)?
+        } else {
+            println!("Downloading SATCAT data from {}...", self.source);
+            match reqwest::blocking::get(&self.source).and_then(|response| response.error_for_status()?.text()) {
+                Ok(text) => {
+                    if let Err(e) = self.save_to_cache(&text) {
+                        eprintln!("Warning: Failed to cache SATCAT data: {}", e);
+                    }
+                    text
+                }
+                Err(e) => {
+                    eprintln!("Warning: SATCAT download failed ({}), trying the expired cache", e);
+                    fs::read_to_string(&self.cache_file).map_err(|_| e)?
+                }
+            }
+        };
+
+        Ok(parse_csv(&text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn celestrak_rows_parse() {
+        let csv = "OBJECT_NAME,OBJECT_ID,NORAD_CAT_ID,OBJECT_TYPE,OPS_STATUS_CODE,OWNER,LAUNCH_DATE,LAUNCH_SITE,DECAY_DATE,PERIOD,INCLINATION,APOGEE,PERIGEE,RCS,DATA_STATUS_CODE,ORBIT_CENTER,ORBIT_TYPE\n\
+                   ISS (ZARYA),1998-067A,25544,PAY,+,ISS,1998-11-20,TYMSC,,92.9,51.64,422,417,399.05,,EA,ORB\n\
+                   \"SL-16 R/B, PART\",1999-057B,25861,R/B,,CIS,1999-10-14,TYMSC,,98.0,71.0,800,780,0.05,,EA,ORB\n\
+                   NOT A NUMBER,,,DEB,,US,,,,,,,,,,,\n";
+        let set = parse_csv(csv).unwrap();
+        assert_eq!(set.len(), 2);
+        let iss = &set[&25544];
+        assert_eq!(iss.owner, "ISS");
+        assert_eq!(iss.launch_date, NaiveDate::from_ymd_opt(1998, 11, 20));
+        assert_eq!(iss.object_type, SatcatObjectType::Payload);
+        assert_eq!(iss.rcs_size, Some(RcsSize::Large));
+        // The quoted name's comma doesn't shift the columns
+        assert_eq!(set[&25861].object_type, SatcatObjectType::RocketBody);
+        assert_eq!(set[&25861].rcs_size, Some(RcsSize::Small));
+    }
+
+    #[test]
+    fn space_track_rows_parse() {
+        let csv = "INTLDES,NORAD_CAT_ID,OBJECT_TYPE,SATNAME,COUNTRY,LAUNCH,SITE,DECAY,RCS_SIZE\n\
+                   1990-037B,20580,PAYLOAD,HST,US,1990-04-24,AFETR,,LARGE\n\
+                   1999-025DZZ,40000,DEBRIS,FENGYUN 1C DEB,PRC,1999-05-10,TSC,,\n";
+        let set = parse_csv(csv).unwrap();
+        assert_eq!(set[&20580].owner, "US");
+        assert_eq!(set[&20580].rcs_size, Some(RcsSize::Large));
+        assert_eq!(set[&40000].object_type, SatcatObjectType::Debris);
+        assert_eq!(set[&40000].rcs_size, None);
+        assert!(parse_csv("OBJECT_NAME,OWNER\nX,US\n").is_err());
+    }
+}
@@ -1,14 +1,130 @@
 use bevy::prelude::*;
-use chrono::{DateTime, Utc};
+use bevy::math::DVec3;
+use bevy::diagnostic::{Diagnostic, Diagnostics, RegisterDiagnostic};
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use chrono::{DateTime, Duration, Utc};
 use sgp4::Elements;
 use nalgebra::Vector3;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::camera::{camera_controller_system, CameraController};
+use crate::config::{MarkerSize, MarkerStyle, Settings, TleProvider};
+use crate::coords;
+use crate::culling::Culled;
+use crate::diagnostics;
+use crate::eclipse::SatelliteIllumination;
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::notifications::{Notify, Retry, RetryAction};
+use crate::propagation::Propagator;
+use crate::satcat::SatcatEntry;
+use crate::selection::{apply_selection_highlight, Selected, SELECTED_SCALE};
+use crate::time_simulation::TimeSimulation;
+use crate::tle_loader::{Celestrak, Composite, SpaceTrack, TleData, TleFile, TleLoader, TleOrigin, TleSet, TleSource};
+use crate::ui::SatelliteFilter;
+
+pub use crate::propagation::{OrbitalParameters, EARTH_MU, MAX_PROPAGATION_DAYS};
+
+/// Radius of the sphere used to render each satellite (50 km, in scene units)
+pub const SATELLITE_RADIUS: f32 = coords::km_to_scene(50.0);
+/// Radius of the smaller markers of debris and rocket bodies
+const DEBRIS_RADIUS: f32 = coords::km_to_scene(25.0);
+/// Radius of a satellite drawn to scale (km), roughly that of a large spacecraft
+const TRUE_SCALE_RADIUS_KM: f32 = 0.01;
+/// Size of the space station icon relative to the other payloads
+const STATION_ICON_SCALE: f32 = 1.6;
+/// Payloads drawn with the space station icon
+const STATION_NAMES: &[&str] = &["ISS (ZARYA)", "CSS (TIANHE)", "TIANGONG"];
+
+/// How often the TLE cache is checked for expiry while running (real seconds)
+const REFRESH_CHECK_SECONDS: f32 = 60.0;
+
+/// Satellites spawned per frame after a load, so a large catalog doesn't stall rendering
+const SPAWN_BATCH_SIZE: usize = 500;
+
+/// Longest simulated span between two propagation keyframes (seconds)
+/// Keeps the straight-line interpolation close to the curved orbit at high time acceleration
+const MAX_KEYFRAME_SPAN_SECONDS: f64 = 20.0;
+
+/// Fastest angular rate (revolutions per day) at which a satellite may skip keyframes;
+/// LEO satellites, above it, are propagated at every keyframe
+const FAST_MEAN_MOTION: f64 = 11.0;
+
+/// Keyframe intervals between two propagations of a culled satellite
+const CULLED_REFRESH_KEYFRAMES: i32 = 16;
+
+/// What a catalog object is, from the suffix Celestrak and Space-Track give its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Payload,
+    /// "... R/B"
+    RocketBody,
+    /// "... DEB"
+    Debris,
+}
+
+impl ObjectKind {
+    pub fn from_name(name: &str) -> Self {
+        // "SL-16 R/B", "DELTA 1 R/B(2)", "COSMOS 1408 DEB"
+        let name = name.to_uppercase();
+        let words: Vec<&str> = name.split_whitespace().skip(1).collect();
+        if words.iter().any(|word| word.starts_with("DEB")) {
+            ObjectKind::Debris
+        } else if words.iter().any(|word| word.starts_with("R/B")) {
+            ObjectKind::RocketBody
+        } else {
+            ObjectKind::Payload
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ObjectKind::Payload => "Payload",
+            ObjectKind::RocketBody => "Rocket body",
+            ObjectKind::Debris => "Debris",
+        }
+    }
+}
+
+/// Icon of a satellite in the sprite marker style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerIcon {
+    Payload,
+    Station,
+    RocketBody,
+    Debris,
+}
+
+impl MarkerIcon {
+    pub fn of(kind: ObjectKind, name: &str) -> Self {
+        match kind {
+            ObjectKind::Payload if STATION_NAMES.iter().any(|station| name.starts_with(station)) => MarkerIcon::Station,
+            ObjectKind::Payload => MarkerIcon::Payload,
+            ObjectKind::RocketBody => MarkerIcon::RocketBody,
+            ObjectKind::Debris => MarkerIcon::Debris,
+        }
+    }
+}
+
+/// Satellite drawn as a flat icon that is turned toward the camera every frame
+#[derive(Component)]
+pub struct Billboard;
 
 #[derive(Component)]
 pub struct Satellite {
     pub name: String,
     pub elements: Elements,
+    pub kind: ObjectKind,
     pub last_update: DateTime<Utc>,
+    /// Last propagated TEME position (km), valid at `last_update`
+    pub position: Option<Vector3<f64>>,
+    /// Last propagated TEME velocity (km/s), valid at `last_update`
+    pub velocity: Option<Vector3<f64>>,
+    #[allow(dead_code)]
     pub use_trajectory: bool,
+    /// Owner, launch date, type and radar size from the SATCAT, once loaded
+    pub satcat: Option<SatcatEntry>,
+    propagator: Propagator,
 }
 
 #[derive(Component)]
@@ -25,58 +141,242 @@ pub struct SatelliteLabelEntity(pub Entity);
 impl Satellite {
     pub fn new(name: String, elements: Elements) -> Self {
         Self {
+            propagator: Propagator::new(&elements),
+            kind: ObjectKind::from_name(&name),
             name,
+            last_update: elements.datetime.and_utc(),
             elements,
-            last_update: Utc::now(),
+            position: None,
+            velocity: None,
             use_trajectory: true,
+            satcat: None,
         }
     }
 
+    /// Orbital parameters derived from the mean elements
+    pub fn orbital_parameters(&self) -> OrbitalParameters {
+        OrbitalParameters::from_elements(&self.elements)
+    }
+
     pub fn update_position(&mut self, time: DateTime<Utc>) -> Option<Vector3<f64>> {
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            let epoch = self.elements.datetime;
-            let time_naive = time.naive_utc();
-            let duration = time_naive.signed_duration_since(epoch);
-            let minutes_since_epoch = duration.num_seconds() as f64 / 60.0;
-            
-            if minutes_since_epoch.abs() > 7.0 * 24.0 * 60.0 {
-                return None;
-            }
-            
-            let constants = sgp4::Constants::from_elements(&self.elements).ok()?;
-            match constants.propagate(minutes_since_epoch) {
-                Ok(state) => {
-                    Some(Vector3::new(state.position[0], state.position[1], state.position[2]))
-                }
-                Err(_) => None,
-            }
-        }));
-        
-        match result {
-            Ok(position) => position,
-            Err(_) => None,
+        let state = self.propagate_state(time);
+        self.position = state.map(|(position, _)| position);
+        self.velocity = state.map(|(_, velocity)| velocity);
+        self.last_update = time;
+        self.position
+    }
+
+    /// Speed (km/s) at `last_update`
+    pub fn speed(&self) -> Option<f64> {
+        self.velocity.map(|velocity| velocity.norm())
+    }
+
+    /// TEME position (km) at a time, reusing the last propagation when it was for that time
+    ///
+    /// Satellites on slow orbits keep keyframes further ahead, so `position` isn't at the same
+    /// time for every satellite; use this to compare satellites at one instant.
+    pub fn position_at(&self, time: DateTime<Utc>) -> Option<Vector3<f64>> {
+        if self.last_update == time && self.position.is_some() {
+            self.position
+        } else {
+            self.propagate(time)
         }
     }
+
+    /// Propagate the elements to the given time without updating the component
+    pub fn propagate(&self, time: DateTime<Utc>) -> Option<Vector3<f64>> {
+        self.propagate_state(time).map(|(position, _)| position)
+    }
+
+    /// Propagate to the given time, returning the TEME position (km) and velocity (km/s)
+    pub fn propagate_state(&self, time: DateTime<Utc>) -> Option<(Vector3<f64>, Vector3<f64>)> {
+        self.propagator.propagate_state(time)
+    }
+
+    /// SGP4 propagator of this satellite's elements
+    pub fn propagator(&self) -> &Propagator {
+        &self.propagator
+    }
+
+    /// Replace the elements (e.g. after a TLE refresh); the position follows at the next propagation
+    pub fn set_elements(&mut self, elements: Elements) {
+        self.propagator = Propagator::new(&elements);
+        self.elements = elements;
+    }
 }
 
-#[derive(Bundle)]
-pub struct SatelliteBundle {
-    pub satellite: Satellite,
-    pub mesh: Mesh3d,
-    pub material: MeshMaterial3d<StandardMaterial>,
-    pub transform: Transform,
-    pub visibility: Visibility,
+/// Scene positions of a satellite at the two surrounding propagation keyframes
+///
+/// Satellites are propagated at a fixed rate and rendered by interpolating
+/// between the last two results (see `PropagationSchedule`). Slow movers keep
+/// their keyframes over several intervals (see `keyframe_stride`).
+#[derive(Component, Default)]
+pub struct PositionKeyframes {
+    pub previous: DVec3,
+    pub next: DVec3,
+    /// Simulation times of `previous` and `next`, None until first propagated
+    pub times: Option<(DateTime<Utc>, DateTime<Utc>)>,
 }
 
-impl SatelliteBundle {
+impl PositionKeyframes {
+    /// Where `time` lies between the keyframes (0 = previous, 1 = next)
+    pub fn interpolation_factor(&self, time: DateTime<Utc>) -> f64 {
+        let Some((previous, next)) = self.times else { return 1.0 };
+        let span = (next - previous).num_microseconds().unwrap_or(0) as f64;
+        if span == 0.0 {
+            return 1.0;
+        }
+        let elapsed = (time - previous).num_microseconds().unwrap_or(0) as f64;
+        (elapsed / span).clamp(0.0, 1.0)
+    }
+
+    /// Whether both times lie between the keyframes, in either direction of time
+    fn covers(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        self.times.is_some_and(|(previous, next)| {
+            let (first, last) = (previous.min(next), previous.max(next));
+            [from, to].iter().all(|time| (first..=last).contains(time))
+        })
+    }
+}
+
+/// Keyframe intervals between two propagations of a satellite: 1 for LEO, more for slower orbits
+///
+/// Driven by the angular rate at perigee rather than the mean motion, so eccentric orbits
+/// (Molniya: slow on average, fast at perigee) stay at every keyframe. The interpolated
+/// chord then spans about the same arc for every satellite.
+pub fn keyframe_stride(elements: &Elements, max_stride: u32) -> u32 {
+    let e = elements.eccentricity.clamp(0.0, 0.99);
+    let perigee_rate = elements.mean_motion * (1.0 + e).powi(2) / (1.0 - e * e).powf(1.5);
+    if perigee_rate <= 0.0 {
+        return 1;
+    }
+    ((FAST_MEAN_MOTION / perigee_rate).floor() as u32).clamp(1, max_stride.max(1))
+}
+
+/// When satellites are propagated, decoupled from the frame rate
+#[derive(Resource)]
+pub struct PropagationSchedule {
+    /// Propagation keyframes per real second (0 = every frame)
+    pub update_rate_hz: f64,
+    /// Most keyframe intervals a slow satellite may skip (see `keyframe_stride`)
+    pub max_stride: u32,
+    previous_time: Option<DateTime<Utc>>,
+    next_time: Option<DateTime<Utc>>,
+}
+
+impl PropagationSchedule {
+    pub fn new(update_rate_hz: f64, max_stride: u32) -> Self {
+        Self {
+            update_rate_hz,
+            max_stride,
+            previous_time: None,
+            next_time: None,
+        }
+    }
+
+    /// Forget the current keyframes, e.g. after the clock jumped, so the next update starts afresh
+    pub fn reset(&mut self) {
+        self.previous_time = None;
+        self.next_time = None;
+    }
+
+    /// Time of the upcoming keyframe, the one most satellites were last propagated to
+    pub fn keyframe_time(&self) -> Option<DateTime<Utc>> {
+        self.next_time
+    }
+
+    /// Whether keyframes exist (false at startup and after `reset`)
+    pub fn is_started(&self) -> bool {
+        self.previous_time.is_some()
+    }
+
+    /// Where `now` lies between the current keyframes (0 = previous, 1 = next),
+    /// or None when `now` is outside them and new keyframes are needed
+    pub fn interpolation_factor(&self, now: DateTime<Utc>) -> Option<f32> {
+        let (previous, next) = (self.previous_time?, self.next_time?);
+        let span = (next - previous).num_microseconds()? as f64;
+        let elapsed = (now - previous).num_microseconds()? as f64;
+        if span == 0.0 {
+            return (elapsed == 0.0).then_some(1.0);
+        }
+        let alpha = elapsed / span;
+        (0.0..=1.0).contains(&alpha).then_some(alpha as f32)
+    }
+
+    /// Start a new keyframe interval at `now`, returning the time of the next keyframe
+    ///
+    /// `rate` is the signed simulation rate, so the next keyframe lies in the past when running backwards.
+    pub fn start_interval(&mut self, now: DateTime<Utc>, rate: f64) -> DateTime<Utc> {
+        let span_seconds = if self.update_rate_hz > 0.0 {
+            (rate / self.update_rate_hz).clamp(-MAX_KEYFRAME_SPAN_SECONDS, MAX_KEYFRAME_SPAN_SECONDS)
+        } else {
+            0.0
+        };
+        let next = now + Duration::microseconds((span_seconds * 1_000_000.0) as i64);
+        self.previous_time = Some(now);
+        self.next_time = Some(next);
+        next
+    }
+}
+
+/// Mesh and material shared by every satellite entity
+///
+/// Sharing a single mesh and material lets Bevy batch all satellites into
+/// instanced draw calls instead of issuing one draw per satellite.
+#[derive(Resource, Clone)]
+pub struct SatelliteAssets {
+    pub style: MarkerStyle,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    /// Smaller gray marker for debris and rocket bodies
+    pub debris_mesh: Handle<Mesh>,
+    pub debris_material: Handle<StandardMaterial>,
+    /// Flat icons of the sprite style, facing +Z
+    pub icons: Option<MarkerIcons>,
+}
+
+/// One flat mesh per `MarkerIcon`
+#[derive(Clone)]
+pub struct MarkerIcons {
+    pub payload: Handle<Mesh>,
+    pub station: Handle<Mesh>,
+    pub rocket_body: Handle<Mesh>,
+    pub debris: Handle<Mesh>,
+}
+
+impl MarkerIcons {
+    fn new(meshes: &mut Assets<Mesh>) -> Self {
+        Self {
+            payload: meshes.add(Circle::new(SATELLITE_RADIUS).mesh().resolution(12)),
+            station: meshes.add(RegularPolygon::new(SATELLITE_RADIUS * STATION_ICON_SCALE, 6)),
+            rocket_body: meshes.add(Triangle2d::new(
+                Vec2::new(0.0, DEBRIS_RADIUS * 1.4),
+                Vec2::new(-DEBRIS_RADIUS * 1.2, -DEBRIS_RADIUS * 0.7),
+                Vec2::new(DEBRIS_RADIUS * 1.2, -DEBRIS_RADIUS * 0.7),
+            )),
+            debris: meshes.add(Rhombus::new(DEBRIS_RADIUS * 1.6, DEBRIS_RADIUS * 1.6)),
+        }
+    }
+
+    fn get(&self, icon: MarkerIcon) -> Handle<Mesh> {
+        match icon {
+            MarkerIcon::Payload => self.payload.clone(),
+            MarkerIcon::Station => self.station.clone(),
+            MarkerIcon::RocketBody => self.rocket_body.clone(),
+            MarkerIcon::Debris => self.debris.clone(),
+        }
+    }
+}
+
+impl SatelliteAssets {
     pub fn new(
-        name: String,
-        elements: Elements,
         meshes: &mut ResMut<Assets<Mesh>>,
         materials: &mut ResMut<Assets<StandardMaterial>>,
+        style: MarkerStyle,
     ) -> Self {
-        let mesh_handle = meshes.add(Sphere::new(50.0));
-        
+        // Low-poly sphere: satellites are only a few pixels on screen
+        let mesh = meshes.add(Sphere::new(SATELLITE_RADIUS).mesh().uv(12, 6));
+
         let material = materials.add(StandardMaterial {
             base_color: Color::srgb(1.0, 0.5, 0.0),
             emissive: LinearRgba::from(Color::srgb(0.8, 0.4, 0.0)),
@@ -85,20 +385,675 @@ impl SatelliteBundle {
             ..default()
         });
 
-        let mut sat = Satellite::new(name.clone(), elements);
-        let initial_position = sat.update_position(chrono::Utc::now());
-        let initial_translation = if let Some(pos) = initial_position {
-            Vec3::new(pos.x as f32, pos.z as f32, -pos.y as f32)
+        let debris_mesh = meshes.add(Sphere::new(DEBRIS_RADIUS).mesh().uv(8, 4));
+        let debris_material = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.55, 0.55),
+            emissive: LinearRgba::from(Color::srgb(0.25, 0.25, 0.25)),
+            ..default()
+        });
+
+        let icons = match style {
+            MarkerStyle::Sphere => None,
+            MarkerStyle::Sprite => Some(MarkerIcons::new(meshes)),
+        };
+
+        Self { style, mesh, material, debris_mesh, debris_material, icons }
+    }
+
+    pub fn mesh_for(&self, satellite: &Satellite) -> Handle<Mesh> {
+        if let Some(icons) = &self.icons {
+            return icons.get(MarkerIcon::of(satellite.kind, &satellite.name));
+        }
+        match satellite.kind {
+            ObjectKind::Payload => self.mesh.clone(),
+            ObjectKind::RocketBody | ObjectKind::Debris => self.debris_mesh.clone(),
+        }
+    }
+
+    /// Default material (without group color) of an object
+    pub fn material_for(&self, kind: ObjectKind) -> Handle<StandardMaterial> {
+        match kind {
+            ObjectKind::Payload => self.material.clone(),
+            ObjectKind::RocketBody | ObjectKind::Debris => self.debris_material.clone(),
+        }
+    }
+}
+
+pub fn setup_satellite_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    settings: Res<Settings>,
+) {
+    commands.insert_resource(SatelliteAssets::new(&mut meshes, &mut materials, settings.satellites.marker));
+}
+
+/// Turn the sprite markers to face the orbit camera
+pub fn face_camera(
+    camera_query: Query<&GlobalTransform, With<CameraController>>,
+    mut billboard_query: Query<&mut Transform, With<Billboard>>,
+) {
+    let Some(camera) = camera_query.iter().next() else { return };
+    // Parallel to the screen rather than toward the camera position: icons side by side stay aligned
+    let rotation = camera.rotation();
+    billboard_query.par_iter_mut().for_each(|mut transform| {
+        transform.rotation = rotation;
+    });
+}
+
+/// Resize the markers for `[satellites] size`: to scale, or a constant size on screen
+/// (the exaggerated default keeps the meshes' own size)
+pub fn scale_markers(
+    settings: Res<Settings>,
+    camera_query: Query<(&Camera, &GlobalTransform, &Projection), With<CameraController>>,
+    mut satellite_query: Query<(&mut Transform, Has<Selected>), With<Satellite>>,
+) {
+    let size = settings.satellites.size;
+    if size == MarkerSize::Exaggerated {
+        return;
+    }
+    let Some((camera, camera_global, projection)) = camera_query.iter().next() else { return };
+    let eye = camera_global.translation();
+    // Marker scale per unit of distance from the camera, for the fixed pixel size: the size
+    // of a pixel one unit away, times the pixels wanted, over the marker diameter
+    let per_unit = match (projection, camera.logical_viewport_size()) {
+        (Projection::Perspective(perspective), Some(viewport)) => {
+            2.0 * (perspective.fov * 0.5).tan() / viewport.y * settings.satellites.pixel_size / (2.0 * SATELLITE_RADIUS)
+        }
+        _ => return,
+    };
+
+    satellite_query.par_iter_mut().for_each(|(mut transform, selected)| {
+        let base = if selected { SELECTED_SCALE } else { 1.0 };
+        let scale = match size {
+            MarkerSize::TrueScale => base * coords::km_to_scene(TRUE_SCALE_RADIUS_KM) / SATELLITE_RADIUS,
+            _ => base * per_unit * transform.translation.distance(eye),
+        };
+        transform.scale = Vec3::splat(scale);
+    });
+}
+
+#[derive(Bundle)]
+pub struct SatelliteBundle {
+    pub satellite: Satellite,
+    pub mesh: Mesh3d,
+    pub material: MeshMaterial3d<StandardMaterial>,
+    pub transform: Transform,
+    pub position: ScenePosition,
+    pub visibility: Visibility,
+    pub keyframes: PositionKeyframes,
+    pub illumination: SatelliteIllumination,
+    pub culled: Culled,
+}
+
+impl SatelliteBundle {
+    /// Satellite placed where it is at `time` (the simulation time)
+    pub fn new(
+        mut sat: Satellite,
+        assets: &SatelliteAssets,
+        time: DateTime<Utc>,
+    ) -> Self {
+        let initial_position = sat.update_position(time);
+        let initial_position = if let Some(pos) = initial_position {
+            coords::teme_to_scene_f64(pos, time)
         } else {
-            Vec3::ZERO
+            DVec3::ZERO
         };
 
         Self {
+            mesh: Mesh3d(assets.mesh_for(&sat)),
+            material: MeshMaterial3d(assets.material_for(sat.kind)),
             satellite: sat,
-            mesh: Mesh3d(mesh_handle),
-            material: MeshMaterial3d(material),
-            transform: Transform::from_translation(initial_translation),
+            // Placed relative to the floating origin by `place_scene_positions`
+            transform: Transform::default(),
+            position: ScenePosition(initial_position),
             visibility: Visibility::default(),
+            keyframes: PositionKeyframes {
+                previous: initial_position,
+                next: initial_position,
+                times: None,
+            },
+            illumination: SatelliteIllumination::default(),
+            culled: Culled::default(),
+        }
+    }
+}
+
+/// Order of the per-frame satellite updates: culling decides what `update_satellite_positions` skips
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SatelliteSystems {
+    Cull,
+    Propagate,
+}
+
+/// Loads satellites from TLE data in the background and keeps their positions propagated
+pub struct SatellitePlugin;
+
+impl Plugin for SatellitePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let satellite_settings = &app.world().resource::<Settings>().satellites;
+        let schedule = PropagationSchedule::new(satellite_settings.update_rate_hz, satellite_settings.max_update_stride);
+
+        app.insert_resource(schedule)
+            .insert_resource(TleRefresh {
+                timer: Timer::from_seconds(REFRESH_CHECK_SECONDS, TimerMode::Repeating),
+                task: None,
+            })
+            .add_message::<TleRefreshed>()
+            .init_resource::<SatelliteFilter>()
+            .register_diagnostic(Diagnostic::new(diagnostics::PROPAGATED_SATELLITES))
+            .register_diagnostic(Diagnostic::new(diagnostics::SGP4_CALLS))
+            .register_diagnostic(Diagnostic::new(diagnostics::PROPAGATION_TIME).with_suffix(" ms"))
+            .configure_sets(Update, (SatelliteSystems::Cull, SatelliteSystems::Propagate).chain())
+            .add_systems(Startup, (setup_satellite_assets, start_tle_loading))
+            .add_systems(Update, (
+                spawn_loaded_satellites,
+                retry_tle_loading,
+                start_tle_refresh,
+                apply_tle_refresh.before(update_satellite_positions),
+                update_satellite_positions.in_set(SatelliteSystems::Propagate),
+                face_camera.after(camera_controller_system),
+                scale_markers.after(camera_controller_system).after(apply_selection_highlight),
+            ));
+    }
+}
+
+/// Result of a background TLE load, with errors stringified so they can cross threads,
+/// and the warnings the loader raised along the way (cache problems, format fallback)
+pub struct TleLoadOutcome {
+    pub result: Result<(TleSet, TleOrigin), String>,
+    pub warnings: Vec<String>,
+}
+
+/// A first load (or reload) with its satellites already built on the worker thread
+pub struct SatelliteLoadOutcome {
+    pub load: TleLoadOutcome,
+    /// Satellites ready to spawn, at most the configured count
+    pub satellites: Vec<Satellite>,
+    /// Entries skipped because their elements don't parse
+    pub unparsed: usize,
+}
+
+/// Loaded satellites waiting to be spawned, a batch per frame
+pub struct SpawnQueue {
+    satellites: std::vec::IntoIter<Satellite>,
+    pub spawned: usize,
+    pub total: usize,
+}
+
+impl SpawnQueue {
+    /// Fraction of the satellites spawned so far
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.spawned as f32 / self.total as f32
         }
     }
 }
+
+/// Progress of the background TLE download/cache load
+#[derive(Resource)]
+pub enum TleLoadState {
+    /// Download, cache read and parsing running on the async compute pool
+    Loading(Task<SatelliteLoadOutcome>),
+    /// Satellites being spawned
+    Spawning(SpawnQueue),
+    /// Satellites spawned (count)
+    Loaded(#[allow(dead_code)] usize),
+    /// Loading failed (error message)
+    Failed(String),
+}
+
+/// The satellites come from offline data (expired cache or bundled snapshot) rather than a current download
+#[derive(Resource, Clone, Copy)]
+pub struct OfflineData(pub TleOrigin);
+
+/// Background re-download of TLE data once the cache expires during a session
+#[derive(Resource)]
+pub struct TleRefresh {
+    /// Time until the next cache expiry check
+    timer: Timer,
+    /// Download running on the async compute pool
+    task: Option<Task<TleLoadOutcome>>,
+}
+
+/// Sent after a background refresh merged new elements into the satellites
+#[derive(Message, Clone, Copy)]
+pub struct TleRefreshed {
+    /// Satellites whose elements changed (newer epoch)
+    pub changed: usize,
+}
+
+impl TleRefreshed {
+    /// Whether a refresh pending in `refreshed` brought newer elements, after which cached
+    /// passes, tracks and orbit statistics are recomputed (reads all the pending messages)
+    pub fn any_changed(refreshed: &mut MessageReader<TleRefreshed>) -> bool {
+        refreshed.read().map(|refresh| refresh.changed).sum::<usize>() > 0
+    }
+}
+
+/// Loader for the configured TLE sources
+pub fn tle_loader(settings: &Settings) -> TleLoader {
+    let mut sources: Vec<Box<dyn TleSource>> = Vec::new();
+    for provider in &settings.tle.sources {
+        match provider {
+            TleProvider::Celestrak => sources.push(Box::new(Celestrak::new(settings.tle.groups.clone()))),
+            TleProvider::Debris => sources.push(Box::new(
+                Celestrak::new(settings.tle.debris_groups.clone()).with_name("celestrak-debris"),
+            )),
+            #[cfg(feature = "n2yo")]
+            TleProvider::N2yo => sources.push(Box::new(crate::tle_loader::N2yo::new(
+                settings.n2yo.api_key.clone(),
+                settings.n2yo.satellites.clone(),
+            ))),
+            #[cfg(not(feature = "n2yo"))]
+            TleProvider::N2yo => eprintln!("Warning: This build has no N2YO support (build with --features n2yo)"),
+            TleProvider::SpaceTrack => sources.push(Box::new(SpaceTrack::new(
+                settings.space_track.username.clone(),
+                settings.space_track.password.clone(),
+            ))),
+            TleProvider::File => {
+                for path in &settings.tle.files {
+                    sources.push(Box::new(TleFile::new(path.clone())));
+                }
+            }
+        }
+    }
+
+    let loader = TleLoader::new().with_cache_max_age_hours(settings.tle.cache_max_age_hours);
+    match sources.len() {
+        0 => loader.with_groups(settings.tle.groups.clone()),
+        1 => loader.with_source(sources.remove(0)),
+        _ => loader.with_source(Box::new(Composite::new(sources))),
+    }
+}
+
+fn load(loader: &TleLoader) -> TleLoadOutcome {
+    let result = loader.load_with_fallback().map_err(|e| e.to_string());
+    TleLoadOutcome {
+        result,
+        warnings: loader.take_warnings(),
+    }
+}
+
+/// Run a load on the async compute pool; it may block on the network, which is fine off the main thread
+fn load_in_background(loader: TleLoader) -> Task<TleLoadOutcome> {
+    AsyncComputeTaskPool::get().spawn(async move { load(&loader) })
+}
+
+/// Load in the background and also parse the elements and set up the propagators there,
+/// which takes a while for tens of thousands of satellites
+fn load_satellites_in_background(loader: TleLoader, max_count: usize) -> Task<SatelliteLoadOutcome> {
+    AsyncComputeTaskPool::get().spawn(async move {
+        let load = load(&loader);
+        let (satellites, unparsed) = match &load.result {
+            Ok((data, _)) => build_satellites(data, max_count),
+            Err(_) => (Vec::new(), 0),
+        };
+        SatelliteLoadOutcome {
+            load,
+            satellites,
+            unparsed,
+        }
+    })
+}
+
+/// Build the first `max_count` satellites of the data, and count those whose elements don't parse
+fn build_satellites(data: &TleSet, max_count: usize) -> (Vec<Satellite>, usize) {
+    let mut satellites = Vec::with_capacity(data.len().min(max_count));
+    let mut unparsed = 0;
+    for tle_data in data.values().take(max_count) {
+        match tle_data.to_elements() {
+            Ok(elements) => satellites.push(Satellite::new(tle_data.name.clone(), elements)),
+            Err(_) => unparsed += 1,
+        }
+    }
+    (satellites, unparsed)
+}
+
+/// Start loading TLE data on a background thread so the window stays responsive
+fn start_tle_loading(mut commands: Commands, settings: Res<Settings>) {
+    // Load TLE data from Celestrak (open source satellite data)
+    let loader = tle_loader(&settings);
+    commands.insert_resource(TleLoadState::Loading(load_satellites_in_background(
+        loader,
+        settings.satellites.max_count,
+    )));
+}
+
+/// Try a failed load or refresh again when its notification's Retry button is clicked
+fn retry_tle_loading(
+    mut retries: MessageReader<Retry>,
+    mut load_state: ResMut<TleLoadState>,
+    mut refresh: ResMut<TleRefresh>,
+    offline: Option<Res<OfflineData>>,
+    settings: Res<Settings>,
+) {
+    for retry in retries.read() {
+        // Offline data is replaced by a full reload rather than refreshed in place
+        let reloadable = match *load_state {
+            TleLoadState::Loading(_) | TleLoadState::Spawning(_) => false,
+            TleLoadState::Loaded(_) => offline.is_some(),
+            TleLoadState::Failed(_) => true,
+        };
+        match retry.0 {
+            RetryAction::LoadTle if reloadable => {
+                println!("Retrying TLE download...");
+                *load_state = TleLoadState::Loading(load_satellites_in_background(
+                    tle_loader(&settings),
+                    settings.satellites.max_count,
+                ));
+            }
+            RetryAction::RefreshTle if matches!(*load_state, TleLoadState::Loaded(_)) && refresh.task.is_none() => {
+                println!("Retrying TLE refresh...");
+                refresh.task = Some(load_in_background(tle_loader(&settings)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Poll the background load, then spawn the satellites a batch per frame once the data has arrived
+///
+/// A reload (retrying after offline data) replaces the satellites already there.
+fn spawn_loaded_satellites(
+    mut commands: Commands,
+    mut load_state: ResMut<TleLoadState>,
+    satellite_assets: Res<SatelliteAssets>,
+    existing_query: Query<(Entity, Option<&SatelliteLabelEntity>), With<Satellite>>,
+    mut sim_time: ResMut<TimeSimulation>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let task = match load_state.as_mut() {
+        TleLoadState::Loading(task) => task,
+        TleLoadState::Spawning(queue) => {
+            spawn_satellites(&mut commands, &satellite_assets, queue, sim_time.current_time());
+            if queue.spawned == queue.total {
+                println!("✓ Spawned {} satellites", queue.spawned);
+                *load_state = TleLoadState::Loaded(queue.spawned);
+            }
+            return;
+        }
+        _ => return,
+    };
+
+    let outcome = match block_on(future::poll_once(task)) {
+        Some(outcome) => outcome,
+        None => return, // Still loading
+    };
+    for warning in outcome.load.warnings {
+        notify_writer.write(Notify::warning(warning));
+    }
+
+    match outcome.load.result {
+        Ok((_, source)) => {
+            for (entity, label) in existing_query.iter() {
+                commands.entity(entity).despawn();
+                if let Some(label) = label {
+                    commands.entity(label.0).despawn();
+                }
+            }
+
+            if outcome.unparsed > 0 {
+                eprintln!("Warning: Skipped {} satellites whose elements couldn't be parsed", outcome.unparsed);
+                notify_writer.write(Notify::warning(format!(
+                    "Skipped {} satellites whose elements couldn't be parsed",
+                    outcome.unparsed,
+                )));
+            }
+            *load_state = TleLoadState::Spawning(SpawnQueue {
+                total: outcome.satellites.len(),
+                spawned: 0,
+                satellites: outcome.satellites.into_iter(),
+            });
+
+            match source.offline_date() {
+                Some(date) => {
+                    commands.insert_resource(OfflineData(source));
+                    notify_writer.write(
+                        Notify::warning(format!("Offline: showing TLE data from {}", date.format("%Y-%m-%d")))
+                            .with_retry(RetryAction::LoadTle),
+                    );
+                    // Elements that old can't be propagated to today, so go back to when they were valid
+                    if (sim_time.current_time() - date).num_days().abs() > MAX_PROPAGATION_DAYS {
+                        println!("Offline data is from {}, moving the clock there", date.format("%Y-%m-%d"));
+                        sim_time.jump_to(date);
+                    }
+                }
+                None => commands.remove_resource::<OfflineData>(),
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to load TLE data: {}", e);
+            notify_writer.write(
+                Notify::error(format!("Failed to load TLE data: {}", e)).with_retry(RetryAction::LoadTle),
+            );
+            *load_state = TleLoadState::Failed(e);
+        }
+    }
+}
+
+/// Re-download TLE data in the background when the cache expires while the app is running
+fn start_tle_refresh(
+    time: Res<Time>,
+    mut refresh: ResMut<TleRefresh>,
+    load_state: Option<Res<TleLoadState>>,
+    offline: Option<Res<OfflineData>>,
+    settings: Res<Settings>,
+) {
+    // Offline data is replaced from its notification's Retry button instead
+    if !settings.tle.auto_refresh || offline.is_some() || !refresh.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    // Only refresh satellites that exist, and one download at a time
+    if !matches!(load_state.as_deref(), Some(TleLoadState::Loaded(_))) || refresh.task.is_some() {
+        return;
+    }
+
+    let loader = tle_loader(&settings);
+    if loader.is_cache_valid() {
+        return;
+    }
+    println!("TLE cache expired, refreshing in the background...");
+    refresh.task = Some(load_in_background(loader));
+}
+
+/// Merge refreshed elements into the existing satellites, matched by catalog number
+fn apply_tle_refresh(
+    mut refresh: ResMut<TleRefresh>,
+    mut query: Query<&mut Satellite>,
+    mut filter: ResMut<SatelliteFilter>,
+    mut refreshed_writer: MessageWriter<TleRefreshed>,
+    mut notify_writer: MessageWriter<Notify>,
+) {
+    let outcome = match refresh.task.as_mut().and_then(|task| block_on(future::poll_once(task))) {
+        Some(outcome) => outcome,
+        None => return,
+    };
+    refresh.task = None;
+    for warning in outcome.warnings {
+        notify_writer.write(Notify::warning(warning));
+    }
+
+    let data = match outcome.result {
+        Ok((data, TleOrigin::Current)) => data,
+        // Falling back to older data than what is loaded would only go backwards
+        Ok(_) => {
+            notify_writer.write(
+                Notify::warning("TLE refresh failed: no network").with_retry(RetryAction::RefreshTle),
+            );
+            return;
+        }
+        Err(e) => {
+            // The cache is still expired, so the next check tries again
+            eprintln!("Warning: TLE refresh failed: {}", e);
+            notify_writer.write(
+                Notify::warning(format!("TLE refresh failed: {}", e)).with_retry(RetryAction::RefreshTle),
+            );
+            return;
+        }
+    };
+
+    let mut changed = 0;
+    let mut missing = 0;
+    for mut satellite in query.iter_mut() {
+        let elements = match data.get(&satellite.elements.norad_id).map(TleData::to_elements) {
+            Some(Ok(elements)) => elements,
+            _ => {
+                missing += 1;
+                continue;
+            }
+        };
+        // Only newer elements: a mirror lagging behind must not roll a satellite back
+        if elements.datetime > satellite.elements.datetime {
+            satellite.set_elements(elements);
+            changed += 1;
+        }
+    }
+
+    println!(
+        "✓ Refreshed TLE data: {} of {} satellites updated ({} no longer listed)",
+        changed,
+        query.iter().len(),
+        missing,
+    );
+    notify_writer.write(Notify::info(format!("TLE data refreshed: {} satellites updated", changed)));
+    // Orbital parameters may have moved satellites in or out of the filter
+    if changed > 0 {
+        filter.set_changed();
+    }
+    refreshed_writer.write(TleRefreshed { changed });
+}
+
+/// Spawn the next batch of queued satellites and their labels
+fn spawn_satellites(
+    commands: &mut Commands,
+    satellite_assets: &SatelliteAssets,
+    queue: &mut SpawnQueue,
+    time: DateTime<Utc>,
+) {
+    for satellite in queue.satellites.by_ref().take(SPAWN_BATCH_SIZE) {
+        let name = satellite.name.clone();
+        let satellite_entity = commands.spawn(SatelliteBundle::new(satellite, satellite_assets, time)).id();
+        if satellite_assets.style == MarkerStyle::Sprite {
+            commands.entity(satellite_entity).insert(Billboard);
+        }
+
+        // Spawn text label - we'll position it manually each frame since Text2d is screen-space
+        let label_entity = commands.spawn((
+            Text2d::new(name.clone()),
+            Transform::default(),
+            SatelliteLabel { name },
+            SatelliteLabelParent(satellite_entity),
+            Visibility::Visible,
+        )).id();
+
+        // Store label entity reference on satellite for easy lookup
+        commands.entity(satellite_entity).insert(SatelliteLabelEntity(label_entity));
+        queue.spawned += 1;
+    }
+}
+
+/// Propagate satellites at the configured rate and interpolate their positions in between
+///
+/// Propagation runs in parallel on the compute task pool; the frames in between
+/// only lerp each satellite between its last two keyframes. Satellites on slow
+/// orbits keep their keyframes over several intervals and skip the propagation.
+#[allow(clippy::too_many_arguments)]
+pub fn update_satellite_positions(
+    mut query: Query<(&mut Transform, &mut ScenePosition, &mut Satellite, &mut PositionKeyframes, &Culled)>,
+    mut schedule: ResMut<PropagationSchedule>,
+    origin: Res<FloatingOrigin>,
+    mut filter: ResMut<SatelliteFilter>,
+    sim_time: Res<TimeSimulation>,
+    mut diagnostics: Diagnostics,
+    mut notify_writer: MessageWriter<Notify>,
+    mut unavailable_reported: Local<bool>,
+) {
+    let started = Instant::now();
+    let current_time = sim_time.current_time();
+
+    if schedule.interpolation_factor(current_time).is_none() {
+        // After a clock jump there is nothing to interpolate from: snap to the new positions
+        let continuous = schedule.is_started();
+        let next_time = schedule.start_interval(current_time, sim_time.rate());
+        let span = next_time - current_time;
+        let max_stride = schedule.max_stride;
+        let availability_changed = AtomicBool::new(false);
+        let propagated = AtomicUsize::new(0);
+        let sgp4_calls = AtomicUsize::new(0);
+        query.par_iter_mut().for_each(|(mut transform, mut position, mut satellite, mut keyframes, culled)| {
+            if continuous && keyframes.covers(current_time, next_time) {
+                // A slow mover still between its keyframes
+                if satellite.position.is_some() {
+                    propagated.fetch_add(1, Ordering::Relaxed);
+                }
+                return;
+            }
+
+            // Culled satellites are placed at the current time and left there for a while;
+            // the others start from where they are drawn now so motion stays continuous
+            let culled = culled.is_culled();
+            let snap = !continuous || keyframes.times.is_none();
+            keyframes.previous = position.0;
+            let stride = if snap { 1 } else { keyframe_stride(&satellite.elements, max_stride) };
+            let target_time = if culled { current_time } else { current_time + span * stride as i32 };
+            let had_position = satellite.position.is_some();
+            sgp4_calls.fetch_add(1, Ordering::Relaxed);
+            if let Some(teme) = satellite.update_position(target_time) {
+                // TEME -> ECEF (Earth rotation via GMST) -> scene, so satellites line up with the textured globe
+                keyframes.next = coords::teme_to_scene_f64(teme, target_time);
+            }
+            keyframes.times = Some((current_time, target_time));
+            if culled {
+                keyframes.previous = keyframes.next;
+                keyframes.times = Some((current_time, current_time + span * CULLED_REFRESH_KEYFRAMES));
+                position.0 = keyframes.next;
+                transform.translation = origin.to_render(position.0);
+            } else if snap {
+                keyframes.previous = keyframes.next;
+                keyframes.times = Some((target_time, target_time));
+            }
+            if had_position != satellite.position.is_some() {
+                availability_changed.store(true, Ordering::Relaxed);
+            }
+            if satellite.position.is_some() {
+                propagated.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        diagnostics.add_measurement(&diagnostics::PROPAGATED_SATELLITES, || {
+            propagated.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&diagnostics::SGP4_CALLS, || sgp4_calls.load(Ordering::Relaxed) as f64);
+        // Satellites that can't be propagated (too far from their epoch) are hidden by the filter
+        if availability_changed.load(Ordering::Relaxed) {
+            filter.set_changed();
+
+            let unavailable = query.iter().len() - propagated.load(Ordering::Relaxed);
+            if unavailable > 0 && !*unavailable_reported {
+                notify_writer.write(Notify::warning(format!(
+                    "{} satellites can't be propagated at this time and are hidden \
+                     (more than {} days from their TLE epoch, or SGP4 failed)",
+                    unavailable, MAX_PROPAGATION_DAYS,
+                )));
+            }
+            *unavailable_reported = unavailable > 0;
+        }
+    }
+
+    // Interpolated in f64, and only then brought to f32 relative to the floating origin
+    query.par_iter_mut().for_each(|(mut transform, mut position, _, keyframes, culled)| {
+        if culled.is_culled() {
+            return;
+        }
+        let alpha = keyframes.interpolation_factor(current_time);
+        position.0 = keyframes.previous.lerp(keyframes.next, alpha);
+        transform.translation = origin.to_render(position.0);
+    });
+
+    diagnostics.add_measurement(&diagnostics::PROPAGATION_TIME, || {
+        started.elapsed().as_secs_f64() * 1000.0
+    });
+}
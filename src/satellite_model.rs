@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use crate::camera::CameraController;
+use crate::chase_view::ChaseView;
+use crate::config::{ModelSettings, Settings};
+use crate::coords;
+use crate::culling::Culled;
+use crate::floating_origin::ScenePosition;
+use crate::satellite::{face_camera, ObjectKind, Satellite, SatelliteAssets, SATELLITE_RADIUS};
+use crate::selection::Selected;
+
+/// 3D models of the satellites close to the camera, in their nadir-pointing attitude
+pub struct SatelliteModelPlugin;
+
+impl Plugin for SatelliteModelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().models.clone();
+
+        app.insert_resource(SatelliteModels {
+            settings,
+            library: ModelLibrary::default(),
+        })
+        .add_systems(Startup, load_model_library)
+        .add_systems(Update, update_satellite_models.after(face_camera));
+    }
+}
+
+/// Model settings and the loaded models
+#[derive(Resource)]
+pub struct SatelliteModels {
+    pub settings: ModelSettings,
+    library: ModelLibrary,
+}
+
+/// glTF scenes by object, then by object kind
+#[derive(Default)]
+struct ModelLibrary {
+    by_norad_id: HashMap<u64, Handle<Scene>>,
+    payload: Option<Handle<Scene>>,
+    rocket_body: Option<Handle<Scene>>,
+    debris: Option<Handle<Scene>>,
+}
+
+impl ModelLibrary {
+    fn model_for(&self, satellite: &Satellite) -> Option<&Handle<Scene>> {
+        self.by_norad_id.get(&satellite.elements.norad_id).or(match satellite.kind {
+            ObjectKind::Payload => self.payload.as_ref(),
+            ObjectKind::RocketBody => self.rocket_body.as_ref(),
+            ObjectKind::Debris => self.debris.as_ref(),
+        })
+    }
+}
+
+/// Model attached to a satellite in place of its marker mesh
+#[derive(Component)]
+pub struct SatelliteModel;
+
+fn load_model_library(mut models: ResMut<SatelliteModels>, asset_server: Res<AssetServer>) {
+    if !models.settings.enabled {
+        return;
+    }
+    let load = |path: &str| {
+        (!path.is_empty()).then(|| asset_server.load(GltfAssetLabel::Scene(0).from_asset(path.to_string())))
+    };
+
+    let mut library = ModelLibrary {
+        by_norad_id: HashMap::new(),
+        payload: load(&models.settings.payload),
+        rocket_body: load(&models.settings.rocket_body),
+        debris: load(&models.settings.debris),
+    };
+    for (id, path) in &models.settings.norad_ids {
+        match id.trim().parse::<u64>() {
+            Ok(norad_id) => {
+                if let Some(scene) = load(path) {
+                    library.by_norad_id.insert(norad_id, scene);
+                }
+            }
+            Err(_) => eprintln!("Warning: Invalid NORAD ID \"{}\" in [models.norad_ids]", id),
+        }
+    }
+    models.library = library;
+}
+
+/// Attitude of a nadir-pointing satellite with its yaw following the velocity
+///
+/// The model's +Y points to the zenith, +X along the track and its panels span Z.
+pub fn nadir_attitude(satellite: &Satellite, position: DVec3) -> Option<Quat> {
+    let teme_position = satellite.position?;
+    let velocity = satellite.velocity?;
+    let zenith = position.normalize_or_zero();
+    // Direction of motion in the scene frame, as for the velocity arrow
+    let motion = coords::teme_to_scene_f64(teme_position + velocity, satellite.last_update)
+        - coords::teme_to_scene_f64(teme_position, satellite.last_update);
+    let along_track = (motion - zenith * motion.dot(zenith)).normalize_or_zero();
+    if zenith == DVec3::ZERO || along_track == DVec3::ZERO {
+        return None;
+    }
+    let cross_track = along_track.cross(zenith);
+    Some(Quat::from_mat3(&Mat3::from_cols(
+        along_track.as_vec3(),
+        zenith.as_vec3(),
+        cross_track.as_vec3(),
+    )))
+}
+
+/// Swap the markers of the nearest satellites with a loaded model for that model (level of
+/// detail), and keep the models in their attitude
+///
+/// The selected satellite gets its model at any distance while the chase view shows it.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_satellite_models(
+    mut commands: Commands,
+    models: Res<SatelliteModels>,
+    scenes: Res<Assets<Scene>>,
+    chase_view: Res<ChaseView>,
+    assets: Option<Res<SatelliteAssets>>,
+    camera_query: Query<&ScenePosition, With<CameraController>>,
+    satellite_query: Query<(Entity, &Satellite, &ScenePosition, &Transform, &Visibility, &Culled, Has<Selected>)>,
+    mut model_query: Query<(Entity, &ChildOf, &SceneRoot, &mut Transform), (With<SatelliteModel>, Without<Satellite>)>,
+) {
+    let Some(assets) = assets else { return };
+    let close_distance = coords::km_to_scene(models.settings.close_distance_km as f32) as f64;
+    let camera = camera_query.iter().next().map(|camera| camera.0);
+
+    let mut candidates: Vec<_> = satellite_query
+        .iter()
+        .filter_map(|(entity, satellite, position, transform, visibility, culled, selected)| {
+            if *visibility == Visibility::Hidden || culled.outside_view || culled.beyond_range {
+                return None;
+            }
+            let chased = selected && *chase_view != ChaseView::Off;
+            let distance = camera.map_or(f64::INFINITY, |camera| camera.distance(position.0));
+            if distance >= close_distance && !chased {
+                return None;
+            }
+            let scene = models.library.model_for(satellite).filter(|scene| scenes.contains(*scene))?;
+            let attitude = nadir_attitude(satellite, position.0)?;
+            // Undo the rotation of billboard markers
+            let rotation = transform.rotation.inverse() * attitude;
+            Some((!selected, distance, entity, scene.clone(), rotation))
+        })
+        .collect();
+    candidates.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap_or(std::cmp::Ordering::Equal));
+    candidates.truncate(models.settings.max_count);
+    let mut targets: HashMap<Entity, (Handle<Scene>, Quat)> = candidates
+        .into_iter()
+        .map(|(_, _, entity, scene, rotation)| (entity, (scene, rotation)))
+        .collect();
+
+    for (model, parent, scene_root, mut transform) in model_query.iter_mut() {
+        let satellite = parent.parent();
+        match targets.get(&satellite) {
+            Some((scene, rotation)) if *scene == scene_root.0 => {
+                transform.rotation = *rotation;
+                targets.remove(&satellite);
+            }
+            _ => {
+                commands.entity(model).despawn();
+                if let Ok((_, satellite_data, ..)) = satellite_query.get(satellite) {
+                    commands.entity(satellite).try_insert(Mesh3d(assets.mesh_for(satellite_data)));
+                }
+            }
+        }
+    }
+
+    for (satellite, (scene, rotation)) in targets {
+        commands.entity(satellite).remove::<Mesh3d>();
+        commands.spawn((
+            SceneRoot(scene),
+            // One model unit is the marker radius, in the parent's marker scale
+            Transform::from_rotation(rotation).with_scale(Vec3::splat(SATELLITE_RADIUS)),
+            SatelliteModel,
+            ChildOf(satellite),
+        ));
+    }
+}
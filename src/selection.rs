@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::camera::CameraController;
+use crate::coords::GlobeShape;
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::geometry::{globe_line_of_sight, ray_hits_globe};
+use crate::map_view::MapView;
+use crate::satellite::{Satellite, SatelliteLabelEntity, SATELLITE_RADIUS};
+
+/// Click-to-select satellites, with a highlight on the selected one
+pub struct SelectionPlugin;
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<SelectSatellite>()
+            .add_message::<GroundClicked>()
+            .init_resource::<MapView>()
+            .add_systems(Startup, setup_selection)
+            .add_systems(Update, (
+                pick_satellite,
+                apply_selection_requests,
+                apply_selection_highlight,
+            ).chain());
+    }
+}
+
+/// Marker for the currently selected satellite
+#[derive(Component)]
+pub struct Selected;
+
+/// Request to change the selection (`None` clears it)
+#[derive(Message, Clone, Copy)]
+pub struct SelectSatellite(pub Option<Entity>);
+
+/// A click on the globe that hit no satellite (scene position of the point clicked)
+#[derive(Message, Clone, Copy)]
+pub struct GroundClicked(pub Vec3);
+
+/// Original material of a highlighted satellite, restored when it is deselected
+#[derive(Component)]
+pub struct HighlightedMaterial(pub Handle<StandardMaterial>);
+
+/// Shared material used to highlight the selected satellite
+#[derive(Resource)]
+pub struct SelectionHighlight {
+    pub material: Handle<StandardMaterial>,
+}
+
+/// Angular pick tolerance (radians) so small distant spheres remain clickable
+const PICK_ANGULAR_TOLERANCE: f32 = 0.01;
+/// Maximum cursor travel (pixels) between press and release for a click
+const CLICK_MAX_DRAG_PIXELS: f32 = 4.0;
+/// Scale applied to the selected satellite's sphere
+pub const SELECTED_SCALE: f32 = 2.5;
+
+pub fn setup_selection(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.2, 1.0, 1.0),
+        emissive: LinearRgba::from(Color::srgb(0.2, 1.0, 1.0)),
+        unlit: true,
+        ..default()
+    });
+    commands.insert_resource(SelectionHighlight { material });
+}
+
+/// Find the satellite closest to the camera along a ray
+/// Satellites hidden behind the Earth are ignored
+pub fn pick_satellite_along_ray<'a>(
+    ray: Ray3d,
+    candidates: impl Iterator<Item = (Entity, Vec3)> + 'a,
+    globe: GlobeShape,
+) -> Option<Entity> {
+    let mut best: Option<(Entity, f32)> = None;
+
+    for (entity, position) in candidates {
+        let to_sat = position - ray.origin;
+        let t = to_sat.dot(*ray.direction);
+        if t <= 0.0 {
+            continue; // Behind the camera
+        }
+        if !globe_line_of_sight(ray.origin, position, globe) {
+            continue; // Occluded by Earth
+        }
+
+        let perpendicular_distance = (to_sat - *ray.direction * t).length();
+        let pick_radius = SATELLITE_RADIUS.max(t * PICK_ANGULAR_TOLERANCE);
+        if perpendicular_distance > pick_radius {
+            continue;
+        }
+
+        if best.is_none_or(|(_, best_t)| t < best_t) {
+            best = Some((entity, t));
+        }
+    }
+
+    best.map(|(entity, _)| entity)
+}
+
+/// Select a satellite by clicking on it (a left press and release without dragging)
+/// Clicking empty space clears the selection; clicking the globe keeps it and reports the point instead
+#[allow(clippy::too_many_arguments)]
+pub fn pick_satellite(
+    mut select_writer: MessageWriter<SelectSatellite>,
+    mut ground_writer: MessageWriter<GroundClicked>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraController>>,
+    satellite_query: Query<(Entity, &ScenePosition, &Visibility), With<Satellite>>,
+    ui_interactions: Query<&Interaction>,
+    origin: Res<FloatingOrigin>,
+    focus: Res<crate::text_input::InputFocus>,
+    map_view: Res<MapView>,
+    globe: Res<GlobeShape>,
+    mut press_position: Local<Option<Vec2>>,
+) {
+    // The map view does its own picking
+    if map_view.enabled {
+        return;
+    }
+
+    let window = match windows.iter().next() {
+        Some(w) => w,
+        None => return,
+    };
+    let cursor = window.cursor_position();
+
+    if mouse_button.just_pressed(MouseButton::Left) {
+        *press_position = cursor;
+    }
+    if !mouse_button.just_released(MouseButton::Left) {
+        return;
+    }
+
+    // Only treat it as a click if the cursor barely moved (otherwise it was a camera drag)
+    let (press, release) = match (press_position.take(), cursor) {
+        (Some(press), Some(release)) => (press, release),
+        _ => return,
+    };
+    if press.distance(release) > CLICK_MAX_DRAG_PIXELS {
+        return;
+    }
+
+    // Ignore clicks on UI widgets
+    if focus.is_focused() || ui_interactions.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+
+    let (camera, camera_transform) = match camera_query.iter().next() {
+        Some(c) => c,
+        None => return,
+    };
+    let ray = match camera.viewport_to_world(camera_transform, release) {
+        // Picked in scene coordinates, where the globe is centered
+        Ok(ray) => Ray3d::new(origin.to_scene(ray.origin).as_vec3(), ray.direction),
+        Err(_) => return,
+    };
+
+    let candidates = satellite_query
+        .iter()
+        .filter(|(_, _, visibility)| **visibility != Visibility::Hidden)
+        .map(|(entity, position, _)| (entity, position.scene()));
+    let picked = pick_satellite_along_ray(ray, candidates, *globe);
+    if picked.is_none() {
+        if let Some(distance) = ray_hits_globe(ray.origin, *ray.direction, *globe) {
+            ground_writer.write(GroundClicked(ray.get_point(distance)));
+            return;
+        }
+    }
+    select_writer.write(SelectSatellite(picked));
+}
+
+/// Apply selection requests: move the `Selected` marker to the requested satellite
+pub fn apply_selection_requests(
+    mut commands: Commands,
+    mut requests: MessageReader<SelectSatellite>,
+    selected_query: Query<Entity, With<Selected>>,
+    satellite_query: Query<(), With<Satellite>>,
+) {
+    // Only the latest request of the frame matters
+    let request = match requests.read().last() {
+        Some(request) => request.0,
+        None => return,
+    };
+    let target = request.filter(|entity| satellite_query.contains(*entity));
+
+    for entity in selected_query.iter() {
+        if Some(entity) != target {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    if let Some(entity) = target {
+        if !selected_query.contains(entity) {
+            commands.entity(entity).insert(Selected);
+        }
+    }
+}
+
+/// Swap in the highlight material and enlarge the selected satellite,
+/// and restore the original look when a satellite is deselected
+#[allow(clippy::type_complexity)]
+pub fn apply_selection_highlight(
+    mut commands: Commands,
+    highlight: Res<SelectionHighlight>,
+    mut newly_selected: Query<
+        (Entity, &mut MeshMaterial3d<StandardMaterial>, &mut Transform, Option<&SatelliteLabelEntity>),
+        (Added<Selected>, With<Selected>),
+    >,
+    mut deselected: RemovedComponents<Selected>,
+    mut restore_query: Query<
+        (&mut MeshMaterial3d<StandardMaterial>, &mut Transform, &HighlightedMaterial, Option<&SatelliteLabelEntity>),
+        Without<Selected>,
+    >,
+    mut label_query: Query<&mut TextColor>,
+) {
+    for entity in deselected.read() {
+        if let Ok((mut material, mut transform, original, label)) = restore_query.get_mut(entity) {
+            material.0 = original.0.clone();
+            transform.scale = Vec3::ONE;
+            if let Some(label) = label {
+                if let Ok(mut color) = label_query.get_mut(label.0) {
+                    *color = TextColor(Color::WHITE);
+                }
+            }
+            commands.entity(entity).remove::<HighlightedMaterial>();
+        }
+    }
+
+    for (entity, mut material, mut transform, label) in newly_selected.iter_mut() {
+        commands.entity(entity).insert(HighlightedMaterial(material.0.clone()));
+        material.0 = highlight.material.clone();
+        transform.scale = Vec3::splat(SELECTED_SCALE);
+        if let Some(label) = label {
+            if let Ok(mut color) = label_query.get_mut(label.0) {
+                *color = TextColor(Color::srgb(0.2, 1.0, 1.0));
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::config::Settings;
+use crate::coords::{self, Geodetic, GlobeShape, EARTH_RADIUS_KM};
+use crate::floating_origin::ScenePosition;
+use crate::footprint::cap_positions;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+
+/// Nadir-pointing sensor cone on the selected satellite, with the ground it images
+pub struct SensorPlugin;
+
+impl Plugin for SensorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let half_angle_deg = app.world().resource::<Settings>().sensor.half_angle_deg;
+
+        app.insert_resource(SensorDisplay {
+            enabled: false,
+            half_angle_deg: half_angle_deg.clamp(0.0, 89.0),
+        })
+        .add_systems(Startup, spawn_sensor_cone)
+        .add_systems(Update, (
+            update_sensor_cone.after(update_satellite_positions),
+            toggle_sensor_cone,
+        ));
+    }
+}
+
+/// Segments around the cone
+const CONE_SEGMENTS: usize = 72;
+/// Height of the imaged area above the surface, above the coverage footprint (km)
+const SENSOR_LIFT_KM: f64 = 20.0;
+
+/// Translucent cone from the selected satellite down to the ground it images
+#[derive(Component)]
+pub struct SensorCone;
+
+/// Whether the sensor cone is drawn (toggle with Q), and how wide it is
+#[derive(Resource)]
+pub struct SensorDisplay {
+    pub enabled: bool,
+    /// Angle between the boresight (nadir) and the edge of the field of view (degrees)
+    pub half_angle_deg: f64,
+}
+
+/// Earth central angle (radians) from the sub-satellite point to the edge of the ground seen by a
+/// nadir-pointing sensor of `half_angle_deg` at `altitude_km`
+///
+/// A cone wider than the Earth as seen from the satellite sees up to the horizon.
+pub fn sensor_ground_half_angle(altitude_km: f64, half_angle_deg: f64) -> f64 {
+    let earth_angular_radius = (EARTH_RADIUS_KM / (EARTH_RADIUS_KM + altitude_km.max(0.0))).asin();
+    let half_angle = half_angle_deg.to_radians();
+    if half_angle >= earth_angular_radius {
+        return std::f64::consts::FRAC_PI_2 - earth_angular_radius;
+    }
+    // Elevation of the satellite seen from the edge of the imaged area
+    let elevation = (half_angle.sin() / earth_angular_radius.sin()).acos();
+    std::f64::consts::FRAC_PI_2 - half_angle - elevation
+}
+
+/// Triangle list of the cone sides from `apex` to the circle of `half_angle` around the unit
+/// direction `center`, at `radius` from Earth's center
+fn cone_positions(apex: Vec3, center: Vec3, half_angle: f32, radius: f32) -> Vec<[f32; 3]> {
+    let (u, v) = center.any_orthonormal_pair();
+    let edge = |segment: usize| {
+        let azimuth = segment as f32 / CONE_SEGMENTS as f32 * std::f32::consts::TAU;
+        let direction = center * half_angle.cos() + (u * azimuth.cos() + v * azimuth.sin()) * half_angle.sin();
+        (direction * radius).to_array()
+    };
+    (0..CONE_SEGMENTS)
+        .flat_map(|segment| [apex.to_array(), edge(segment), edge(segment + 1)])
+        .collect()
+}
+
+pub fn spawn_sensor_cone(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, cone_positions(Vec3::ZERO, Vec3::Y, 0.0, 0.0));
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.3, 1.0, 0.5, 0.18),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        cull_mode: None,
+        double_sided: true,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        SensorCone,
+        Name::new("SensorCone"),
+    ));
+}
+
+/// Point the cone from the selected satellite to the ground below it, sized from its altitude
+#[allow(clippy::type_complexity)]
+pub fn update_sensor_cone(
+    display: Res<SensorDisplay>,
+    selected_query: Query<(&Satellite, &ScenePosition), (With<Selected>, Without<SensorCone>)>,
+    mut cone_query: Query<(&Mesh3d, &mut Visibility), With<SensorCone>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    globe: Res<GlobeShape>,
+) {
+    let cone = selected_query
+        .iter()
+        .next()
+        .filter(|(satellite, _)| display.enabled && satellite.position.is_some())
+        .map(|(_, position)| {
+            // Nadir is toward the center of the (spherical) globe, like the footprint's center
+            let geodetic = coords::ecef_to_geodetic(position.ecef());
+            let center = coords::geodetic_to_scene(Geodetic::new(geodetic.latitude_deg, geodetic.longitude_deg, 0.0), *globe);
+            let half_angle = sensor_ground_half_angle(geodetic.altitude_km, display.half_angle_deg);
+            (position.scene(), center.normalize(), half_angle as f32)
+        });
+
+    for (mesh_3d, mut visibility) in cone_query.iter_mut() {
+        let (apex, center, half_angle) = match cone {
+            Some(cone) => cone,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+        visibility.set_if_neq(Visibility::Visible);
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            let radius = coords::km_to_scene((EARTH_RADIUS_KM + SENSOR_LIFT_KM) as f32);
+            let mut positions = cone_positions(apex, center, half_angle, radius);
+            positions.extend(cap_positions(center, half_angle, radius));
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        }
+    }
+}
+
+/// Toggle the sensor cone with Q, reporting the width of the imaged area
+pub fn toggle_sensor_cone(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<SensorDisplay>,
+    selected_query: Query<&ScenePosition, (With<Selected>, With<Satellite>)>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleSensor, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+    if !display.enabled {
+        println!("Sensor cone: off");
+        return;
+    }
+
+    match selected_query.iter().next() {
+        Some(position) => {
+            let altitude_km = coords::ecef_to_geodetic(position.ecef()).altitude_km;
+            let swath_km = 2.0 * EARTH_RADIUS_KM * sensor_ground_half_angle(altitude_km, display.half_angle_deg);
+            println!(
+                "Sensor cone: {:.1}° half-angle, {:.0} km swath from {:.0} km",
+                display.half_angle_deg, swath_km, altitude_km
+            );
+        }
+        None => println!("Sensor cone: {:.1}° half-angle, select a satellite to show it", display.half_angle_deg),
+    }
+}
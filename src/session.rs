@@ -0,0 +1,217 @@
+//! The viewing session, saved to `session.toml` on exit and restored at the next launch
+//!
+//! A session is the camera pose, the filter, the "show only" group, the browser
+//! checkboxes and the selected satellite. The groups themselves live in `groups.toml`.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::browser::SatelliteBrowser;
+use crate::camera::CameraController;
+use crate::config::Settings;
+use crate::coords;
+use crate::groups::SatelliteGroups;
+use crate::satellite::{Satellite, TleLoadState};
+use crate::selection::{SelectSatellite, Selected};
+use crate::ui::SatelliteFilter;
+
+/// Default session file, read from and written to the working directory
+pub const SESSION_FILE: &str = "session.toml";
+
+/// Save the session on exit and restore it at startup
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        if !app.world().resource::<Settings>().session.enabled {
+            return;
+        }
+
+        let session = Session::load(SESSION_FILE);
+        app.insert_resource(PendingRestore {
+            camera: session.camera,
+            filter: Some(session.filter),
+            show_only: session.show_only,
+            browser: Some((session.hidden_constellations, session.hidden_satellites)),
+            selected: session.selected,
+        })
+        .add_systems(Update, restore_session)
+        .add_systems(Last, save_session_on_exit);
+    }
+}
+
+/// Orbit camera pose (km)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub orbit_center: [f32; 3],
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Everything restored at startup
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Filter expression typed in the search box
+    pub filter: String,
+    /// Group whose members alone are shown
+    pub show_only: Option<String>,
+    /// Constellations unchecked in the browser
+    pub hidden_constellations: BTreeSet<String>,
+    /// Satellites unchecked in the browser (NORAD IDs)
+    pub hidden_satellites: BTreeSet<u64>,
+    /// NORAD ID of the selected satellite
+    pub selected: Option<u64>,
+    pub camera: Option<CameraPose>,
+}
+
+impl Session {
+    /// Load a session from a TOML file, starting fresh if it is missing or invalid
+    pub fn load(path: &str) -> Self {
+        if !Path::new(path).exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str::<Self>(&contents).map_err(|e| e.to_string()))
+        {
+            Ok(session) => {
+                println!("✓ Restored session from {}", path);
+                session
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to load {}: {}. Starting a new session", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+}
+
+/// Parts of the loaded session not applied yet; removed once everything is restored
+#[derive(Resource)]
+struct PendingRestore {
+    camera: Option<CameraPose>,
+    filter: Option<String>,
+    show_only: Option<String>,
+    browser: Option<(BTreeSet<String>, BTreeSet<u64>)>,
+    /// Waits for the satellites to finish loading
+    selected: Option<u64>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn restore_session(
+    mut commands: Commands,
+    pending: Option<ResMut<PendingRestore>>,
+    mut camera_query: Query<&mut CameraController>,
+    mut filter: ResMut<SatelliteFilter>,
+    mut groups: ResMut<SatelliteGroups>,
+    mut browser: ResMut<SatelliteBrowser>,
+    satellite_query: Query<(Entity, &Satellite)>,
+    load_state: Option<Res<TleLoadState>>,
+    mut select_writer: MessageWriter<SelectSatellite>,
+) {
+    let mut pending = match pending {
+        Some(pending) => pending,
+        None => return,
+    };
+
+    if let Some(pose) = pending.camera.take() {
+        for mut controller in camera_query.iter_mut() {
+            let orbit_center = Vec3::from_array(pose.orbit_center.map(coords::km_to_scene)).as_dvec3();
+            *controller = CameraController {
+                orbit_center,
+                target_orbit_center: orbit_center,
+                ..CameraController::new(coords::km_to_scene(pose.distance), pose.yaw, pose.pitch)
+            };
+        }
+    }
+
+    if let Some(text) = pending.filter.take() {
+        filter.text = text;
+    }
+
+    // Groups deleted since the last session are ignored
+    if let Some(name) = pending.show_only.take() {
+        if groups.get(&name).is_some() {
+            groups.show_only = Some(name);
+        }
+    }
+
+    if let Some((hidden_constellations, hidden_satellites)) = pending.browser.take() {
+        if !hidden_constellations.is_empty() || !hidden_satellites.is_empty() {
+            browser.hidden_constellations = hidden_constellations;
+            browser.hidden_satellites = hidden_satellites;
+        }
+    }
+
+    if let Some(norad_id) = pending.selected {
+        match load_state.as_deref() {
+            Some(TleLoadState::Loaded(_)) | Some(TleLoadState::Failed(_)) => {
+                // The satellite may be gone from the data or beyond the configured count
+                let entity = satellite_query
+                    .iter()
+                    .find(|(_, satellite)| satellite.elements.norad_id == norad_id)
+                    .map(|(entity, _)| entity);
+                if entity.is_some() {
+                    select_writer.write(SelectSatellite(entity));
+                }
+                pending.selected = None;
+            }
+            _ => return, // Still loading
+        }
+    }
+
+    commands.remove_resource::<PendingRestore>();
+}
+
+/// Write the session when the app is about to close
+fn save_session_on_exit(
+    mut exits: MessageReader<AppExit>,
+    camera_query: Query<&CameraController>,
+    filter: Res<SatelliteFilter>,
+    groups: Res<SatelliteGroups>,
+    browser: Res<SatelliteBrowser>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    pending: Option<Res<PendingRestore>>,
+) {
+    if exits.read().last().is_none() {
+        return;
+    }
+
+    let camera = camera_query.iter().next().map(|controller| CameraPose {
+        orbit_center: controller.target_orbit_center.as_vec3().to_array().map(coords::scene_to_km),
+        distance: coords::scene_to_km(controller.target_distance),
+        yaw: controller.target_yaw,
+        pitch: controller.target_pitch,
+    });
+    // Closing before the satellites finished loading keeps the previous selection
+    let selected = selected_query
+        .iter()
+        .next()
+        .map(|satellite| satellite.elements.norad_id)
+        .or(pending.and_then(|pending| pending.selected));
+
+    let session = Session {
+        filter: filter.text.clone(),
+        show_only: groups.show_only.clone(),
+        hidden_constellations: browser.hidden_constellations.clone(),
+        hidden_satellites: browser.hidden_satellites.clone(),
+        selected,
+        camera,
+    };
+    match session.save(SESSION_FILE) {
+        Ok(()) => println!("✓ Saved session to {}", SESSION_FILE),
+        Err(e) => eprintln!("Error: Failed to save {}: {}", SESSION_FILE, e),
+    }
+}
@@ -0,0 +1,363 @@
+//! Orbital shells of the mega-constellations (Starlink, OneWeb, Kuiper)
+//!
+//! Satellites of a constellation are clustered by mean altitude and inclination from their
+//! element sets. Each shell gets its own color, and the panel lists its size and the share
+//! of Earth's surface its satellites cover right now. Satellites raising or lowering their
+//! orbit don't belong to a shell and keep their usual color.
+
+use bevy::prelude::*;
+use nalgebra::Vector3;
+use std::collections::HashMap;
+
+use crate::coords;
+use crate::filter::constellation_of;
+use crate::groups::GROUP_COLORS;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::text_input::InputFocus;
+
+/// Constellation shells: clustering, coloring and statistics panel
+pub struct ShellsPlugin;
+
+impl Plugin for ShellsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShellView>()
+            .insert_resource(CoverageTimer(Timer::from_seconds(COVERAGE_INTERVAL_SECONDS, TimerMode::Repeating)))
+            .add_systems(Startup, setup_shells_panel)
+            .add_systems(Update, (
+                toggle_shells,
+                assign_shells,
+                update_shell_coverage,
+                update_shells_panel,
+            ).chain());
+    }
+}
+
+/// Constellation (as `filter::constellation_of` names it) and display name of those split into shells
+const CONSTELLATIONS: [(&str, &str); 3] = [("STARLINK", "Starlink"), ("ONEWEB", "OneWeb"), ("KUIPER", "Kuiper")];
+/// Satellites within this mean altitude of a shell's average belong to it (km)
+const ALTITUDE_TOLERANCE_KM: f64 = 12.0;
+/// Satellites within this inclination of a shell's average belong to it (degrees)
+const INCLINATION_TOLERANCE_DEG: f64 = 0.5;
+/// Smaller clusters are satellites moving between shells, not a shell
+const MIN_SHELL_SIZE: usize = 20;
+/// Orbits more eccentric than this are in transfer, not on a shell
+const MAX_SHELL_ECCENTRICITY: f64 = 0.005;
+/// Elevation above which a satellite serves a ground user, for the coverage (degrees)
+const COVERAGE_MIN_ELEVATION_DEG: f64 = 25.0;
+/// Points sampled evenly over Earth's surface to measure coverage
+const COVERAGE_SAMPLES: usize = 2000;
+/// Real seconds between coverage updates
+const COVERAGE_INTERVAL_SECONDS: f32 = 2.0;
+/// Shells listed in the panel
+const MAX_LISTED_SHELLS: usize = 12;
+
+/// A cluster of satellites of one constellation at about the same altitude and inclination
+#[derive(Debug, Clone)]
+pub struct Shell {
+    pub constellation: &'static str,
+    pub altitude_km: f64,
+    pub inclination_deg: f64,
+    pub count: usize,
+    /// Share of Earth's surface seeing a member above `COVERAGE_MIN_ELEVATION_DEG`, once measured
+    pub coverage: Option<f64>,
+}
+
+impl Shell {
+    pub fn color(&self, index: usize) -> Color {
+        GROUP_COLORS[index % GROUP_COLORS.len()]
+    }
+}
+
+/// Shell mode (toggle with J): the shells found and which shell each satellite is on
+#[derive(Resource, Default)]
+pub struct ShellView {
+    pub enabled: bool,
+    pub shells: Vec<Shell>,
+    /// NORAD catalog number -> index in `shells`
+    pub members: HashMap<u64, usize>,
+    /// Constellation satellites in no shell (raising, lowering or deorbiting)
+    pub unassigned: usize,
+}
+
+impl ShellView {
+    /// Shell of a satellite while the mode is on
+    pub fn shell_of(&self, norad_id: u64) -> Option<usize> {
+        self.members.get(&norad_id).copied().filter(|_| self.enabled)
+    }
+}
+
+#[derive(Resource)]
+struct CoverageTimer(Timer);
+
+#[derive(Component)]
+pub struct ShellsPanel;
+
+#[derive(Component)]
+pub struct ShellsHeader;
+
+/// Row listing the shell at this index
+#[derive(Component)]
+pub struct ShellRow(pub usize);
+
+/// Constellation split into shells a satellite name belongs to
+fn shell_constellation(name: &str) -> Option<&'static str> {
+    let constellation = constellation_of(name);
+    CONSTELLATIONS
+        .iter()
+        .find(|(key, _)| constellation == *key)
+        .map(|(_, display_name)| *display_name)
+}
+
+/// Group orbits (catalog number, mean altitude, inclination) of one constellation into shells
+///
+/// Greedy clustering against the running mean of each cluster, visiting orbits by
+/// inclination then altitude. Returns the shells with their members, and the orbits left over.
+fn cluster_shells(constellation: &'static str, mut orbits: Vec<(u64, f64, f64)>) -> (Vec<(Shell, Vec<u64>)>, usize) {
+    orbits.sort_by(|a, b| a.2.total_cmp(&b.2).then(a.1.total_cmp(&b.1)));
+
+    let mut clusters: Vec<(Shell, Vec<u64>)> = Vec::new();
+    for (norad_id, altitude_km, inclination_deg) in orbits {
+        let cluster = clusters.iter_mut().find(|(shell, _)| {
+            (shell.altitude_km - altitude_km).abs() <= ALTITUDE_TOLERANCE_KM
+                && (shell.inclination_deg - inclination_deg).abs() <= INCLINATION_TOLERANCE_DEG
+        });
+        match cluster {
+            Some((shell, members)) => {
+                let n = shell.count as f64;
+                shell.altitude_km = (shell.altitude_km * n + altitude_km) / (n + 1.0);
+                shell.inclination_deg = (shell.inclination_deg * n + inclination_deg) / (n + 1.0);
+                shell.count += 1;
+                members.push(norad_id);
+            }
+            None => clusters.push((
+                Shell {
+                    constellation,
+                    altitude_km,
+                    inclination_deg,
+                    count: 1,
+                    coverage: None,
+                },
+                vec![norad_id],
+            )),
+        }
+    }
+
+    let (shells, strays): (Vec<_>, Vec<_>) = clusters.into_iter().partition(|(shell, _)| shell.count >= MIN_SHELL_SIZE);
+    (shells, strays.iter().map(|(shell, _)| shell.count).sum())
+}
+
+/// Points spread evenly over the unit sphere (Fibonacci lattice)
+fn sphere_samples(count: usize) -> Vec<Vector3<f64>> {
+    let golden_angle = std::f64::consts::PI * (3.0 - 5.0_f64.sqrt());
+    (0..count)
+        .map(|i| {
+            let z = 1.0 - 2.0 * (i as f64 + 0.5) / count as f64;
+            let radius = (1.0 - z * z).sqrt();
+            let (sin, cos) = (golden_angle * i as f64).sin_cos();
+            Vector3::new(radius * cos, radius * sin, z)
+        })
+        .collect()
+}
+
+/// Cosine of the Earth-central angle a satellite at this altitude serves above the minimum elevation
+fn footprint_cos_angle(altitude_km: f64) -> f64 {
+    let elevation = COVERAGE_MIN_ELEVATION_DEG.to_radians();
+    let ratio = coords::EARTH_RADIUS_KM / (coords::EARTH_RADIUS_KM + altitude_km);
+    ((ratio * elevation.cos()).acos() - elevation).cos()
+}
+
+/// Toggle the shell mode with J
+pub fn toggle_shells(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut view: ResMut<ShellView>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleShells, &keyboard_input) {
+        return;
+    }
+    view.enabled = !view.enabled;
+}
+
+/// Cluster the constellations into shells when the mode turns on and when the satellites change
+pub fn assign_shells(
+    mut view: ResMut<ShellView>,
+    added: Query<(), Added<Satellite>>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    satellite_query: Query<&Satellite>,
+    mut computed: Local<bool>,
+) {
+    let satellites_changed = !added.is_empty() || TleRefreshed::any_changed(&mut refreshed);
+    if satellites_changed {
+        *computed = false;
+    }
+    if !view.enabled || *computed {
+        return;
+    }
+
+    let mut orbits: HashMap<&'static str, Vec<(u64, f64, f64)>> = HashMap::new();
+    for satellite in satellite_query.iter() {
+        let Some(constellation) = shell_constellation(&satellite.name) else { continue };
+        let params = satellite.orbital_parameters();
+        if params.eccentricity > MAX_SHELL_ECCENTRICITY {
+            continue;
+        }
+        let altitude_km = (params.apogee_altitude_km + params.perigee_altitude_km) / 2.0;
+        orbits
+            .entry(constellation)
+            .or_default()
+            .push((satellite.elements.norad_id, altitude_km, params.inclination_deg));
+    }
+
+    let mut shells = Vec::new();
+    let mut members = HashMap::new();
+    let mut unassigned = 0;
+    for (_, constellation) in CONSTELLATIONS {
+        let Some(orbits) = orbits.remove(constellation) else { continue };
+        let (mut found, strays) = cluster_shells(constellation, orbits);
+        found.sort_by(|a, b| a.0.altitude_km.total_cmp(&b.0.altitude_km));
+        for (shell, norad_ids) in found {
+            for norad_id in norad_ids {
+                members.insert(norad_id, shells.len());
+            }
+            shells.push(shell);
+        }
+        unassigned += strays;
+    }
+    println!("✓ Found {} constellation shells ({} satellites between shells)", shells.len(), unassigned);
+
+    view.shells = shells;
+    view.members = members;
+    view.unassigned = unassigned;
+    *computed = true;
+}
+
+/// Measure each shell's coverage every few seconds from the current sub-satellite points
+fn update_shell_coverage(
+    time: Res<Time>,
+    mut timer: ResMut<CoverageTimer>,
+    mut view: ResMut<ShellView>,
+    satellite_query: Query<&Satellite>,
+    mut samples: Local<Vec<Vector3<f64>>>,
+) {
+    let due = timer.0.tick(time.delta()).just_finished();
+    if !view.enabled || view.shells.is_empty() || !(due || view.shells[0].coverage.is_none()) {
+        return;
+    }
+    if samples.is_empty() {
+        *samples = sphere_samples(COVERAGE_SAMPLES);
+    }
+
+    let mut subpoints: Vec<Vec<Vector3<f64>>> = vec![Vec::new(); view.shells.len()];
+    for satellite in satellite_query.iter() {
+        let (Some(shell), Some(position)) = (view.shell_of(satellite.elements.norad_id), satellite.position) else {
+            continue;
+        };
+        subpoints[shell].push(coords::teme_to_ecef(position, satellite.last_update).normalize());
+    }
+
+    let view = view.as_mut();
+    for (shell, subpoints) in view.shells.iter_mut().zip(subpoints) {
+        let min_cos = footprint_cos_angle(shell.altitude_km);
+        let covered = samples
+            .iter()
+            .filter(|point| subpoints.iter().any(|subpoint| point.dot(subpoint) >= min_cos))
+            .count();
+        shell.coverage = Some(covered as f64 / samples.len() as f64);
+    }
+}
+
+pub fn setup_shells_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Left of the groups panel
+                right: Val::Px(380.0),
+                top: Val::Percent(45.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            ShellsPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                ShellsHeader,
+            ));
+            for row in 0..MAX_LISTED_SHELLS {
+                parent.spawn((
+                    Node {
+                        display: Display::None,
+                        ..default()
+                    },
+                    Text::new(""),
+                    TextFont {
+                        font_size: 13.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    ShellRow(row),
+                ));
+            }
+        });
+}
+
+/// List the shells, in their colors, with their size and coverage
+fn update_shells_panel(
+    view: Res<ShellView>,
+    keymap: Res<Keymap>,
+    mut panel_query: Query<&mut Node, With<ShellsPanel>>,
+    mut header_query: Query<&mut Text, (With<ShellsHeader>, Without<ShellRow>)>,
+    mut row_query: Query<(&ShellRow, &mut Text, &mut TextColor, &mut Node), Without<ShellsPanel>>,
+) {
+    if !view.is_changed() {
+        return;
+    }
+    let display = if view.enabled { Display::Flex } else { Display::None };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !view.enabled {
+        return;
+    }
+
+    for mut text in header_query.iter_mut() {
+        let mut header = format!("Constellation shells: {}  [{}] hide", view.shells.len(), keymap.label(Action::ToggleShells));
+        if view.shells.is_empty() {
+            header.push_str("\nNo Starlink, OneWeb or Kuiper shells in the loaded data");
+        } else if view.unassigned > 0 {
+            header.push_str(&format!("\n{} satellites between shells", view.unassigned));
+        }
+        if view.shells.len() > MAX_LISTED_SHELLS {
+            header.push_str(&format!("\n({} more not listed)", view.shells.len() - MAX_LISTED_SHELLS));
+        }
+        text.0 = header;
+    }
+
+    for (row, mut text, mut color, mut node) in row_query.iter_mut() {
+        let shell = view.shells.get(row.0);
+        node.display = if shell.is_some() { Display::Flex } else { Display::None };
+        if let Some(shell) = shell {
+            let coverage = shell
+                .coverage
+                .map_or("-".to_string(), |coverage| format!("{:.0}%", coverage * 100.0));
+            text.0 = format!(
+                "{} {:.0} km, {:.1}°: {} sats, coverage {}",
+                shell.constellation, shell.altitude_km, shell.inclination_deg, shell.count, coverage,
+            );
+            color.0 = shell.color(row.0);
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Sky plot: a pass of the selected satellite drawn across a ground station's sky, as a
+//! polar azimuth/elevation chart (north up, east right, zenith at the center) with a tick
+//! every minute
+
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::coords::{self, LookAngles};
+use crate::ground_station::GroundStation;
+use crate::keymap::{Action, Keymap};
+use crate::passes::{self, Pass};
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
+
+/// Polar sky plot of the selected satellite's passes over a ground station
+pub struct SkyPlotPlugin;
+
+impl Plugin for SkyPlotPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SkyPlot {
+            enabled: false,
+            station_index: 0,
+            pass_index: 0,
+            key: None,
+            predicted_from: None,
+            passes: Vec::new(),
+            track: Vec::new(),
+            revision: 0,
+            timer: Timer::from_seconds(UPDATE_INTERVAL_SECONDS, TimerMode::Repeating),
+        })
+        .add_systems(Startup, setup_sky_plot)
+        .add_systems(Update, (
+            toggle_sky_plot,
+            handle_sky_plot_buttons,
+            update_sky_plot_passes,
+            draw_sky_plot,
+            update_sky_plot_marker,
+        ).chain());
+    }
+}
+
+/// Real seconds between checks of the pass prediction
+const UPDATE_INTERVAL_SECONDS: f32 = 0.5;
+/// How far ahead passes are predicted
+const PASS_WINDOW_HOURS: i64 = 24;
+/// Sampling step of the drawn trajectory (s)
+const TRACK_STEP_SECONDS: i64 = 5;
+/// Minutes between time ticks, and ticks between labelled ones
+const TICK_MINUTES: i64 = 1;
+const TICKS_PER_LABEL: usize = 2;
+/// Diameter of the horizon circle (px)
+const PLOT_SIZE: f32 = 260.0;
+/// Elevation circles drawn inside the horizon (degrees)
+const ELEVATION_RINGS_DEG: [f64; 2] = [30.0, 60.0];
+/// Azimuth step of the drawn terrain horizon (degrees)
+const MASK_STEP_DEG: usize = 3;
+const GRID_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.35);
+const TRACK_COLOR: Color = Color::srgb(0.3, 0.8, 1.0);
+const TICK_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const MASK_COLOR: Color = Color::srgb(0.7, 0.5, 0.3);
+const MARKER_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// Sky plot mode (toggle with F5), the station and pass shown and the pass's trajectory
+#[derive(Resource)]
+pub struct SkyPlot {
+    pub enabled: bool,
+    /// Among the ground stations, by name
+    station_index: usize,
+    /// Among the passes that haven't ended yet
+    pass_index: usize,
+    /// Satellite and station the passes were predicted for
+    key: Option<(Entity, Entity)>,
+    predicted_from: Option<DateTime<Utc>>,
+    passes: Vec<Pass>,
+    /// Look angles along the shown pass
+    track: Vec<(DateTime<Utc>, LookAngles)>,
+    /// Bumped whenever the plot needs redrawing
+    revision: u32,
+    timer: Timer,
+}
+
+impl SkyPlot {
+    /// Predict again at the next update
+    fn invalidate(&mut self) {
+        self.key = None;
+        let duration = self.timer.duration();
+        self.timer.set_elapsed(duration);
+    }
+}
+
+#[derive(Component)]
+pub struct SkyPlotPanel;
+
+#[derive(Component)]
+pub struct SkyPlotText;
+
+/// Trajectory, ticks and terrain horizon, rebuilt with the pass
+#[derive(Component)]
+pub struct SkyPlotLayer;
+
+/// Where the satellite is in the sky right now
+#[derive(Component)]
+pub struct SkyPlotMarker;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkyPlotButton {
+    PreviousPass,
+    NextPass,
+    NextStation,
+}
+
+impl SkyPlotButton {
+    fn label(self) -> &'static str {
+        match self {
+            SkyPlotButton::PreviousPass => "< Pass",
+            SkyPlotButton::NextPass => "Pass >",
+            SkyPlotButton::NextStation => "Station >",
+        }
+    }
+}
+
+/// Position in the plot (px from its top left corner): the distance from the center grows
+/// from the zenith to the horizon
+fn plot_position(azimuth_deg: f64, elevation_deg: f64) -> Vec2 {
+    let radius = PLOT_SIZE / 2.0;
+    let distance = radius * (90.0 - elevation_deg.clamp(0.0, 90.0)) as f32 / 90.0;
+    let azimuth = azimuth_deg.to_radians() as f32;
+    Vec2::new(radius + distance * azimuth.sin(), radius - distance * azimuth.cos())
+}
+
+/// A dot of `size` px centered on `center`
+fn dot(center: Vec2, size: f32, color: Color) -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(center.x - size / 2.0),
+            top: Val::Px(center.y - size / 2.0),
+            width: Val::Px(size),
+            height: Val::Px(size),
+            ..default()
+        },
+        BorderRadius::MAX,
+        BackgroundColor(color),
+    )
+}
+
+/// Small text whose top left corner is at `position`
+fn plot_label(text: String, position: Vec2, font_size: f32) -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x),
+            top: Val::Px(position.y),
+            ..default()
+        },
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+    )
+}
+
+pub fn setup_sky_plot(mut commands: Commands) {
+    let font = TextFont {
+        font_size: 13.0,
+        ..default()
+    };
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Left of the satellite info panel
+                right: Val::Px(380.0),
+                top: Val::Px(90.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+            SkyPlotPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new(""), font.clone(), SkyPlotText));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for button in [SkyPlotButton::PreviousPass, SkyPlotButton::NextPass, SkyPlotButton::NextStation] {
+                        parent.spawn((
+                            Node {
+                                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                                ..default()
+                            },
+                            Text::new(button.label()),
+                            font.clone(),
+                            BackgroundColor(BUTTON_COLOR),
+                            Interaction::default(),
+                            button,
+                        ));
+                    }
+                });
+
+            // The plot, with a margin for the cardinal points
+            parent
+                .spawn(Node {
+                    width: Val::Px(PLOT_SIZE),
+                    height: Val::Px(PLOT_SIZE),
+                    margin: UiRect::all(Val::Px(14.0)),
+                    ..default()
+                })
+                .with_children(|plot| {
+                    for elevation in [0.0].into_iter().chain(ELEVATION_RINGS_DEG) {
+                        let size = PLOT_SIZE * (90.0 - elevation as f32) / 90.0;
+                        let offset = (PLOT_SIZE - size) / 2.0;
+                        plot.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(offset),
+                                top: Val::Px(offset),
+                                width: Val::Px(size),
+                                height: Val::Px(size),
+                                border: UiRect::all(Val::Px(1.0)),
+                                ..default()
+                            },
+                            BorderRadius::MAX,
+                            BorderColor::all(GRID_COLOR),
+                        ));
+                        if elevation > 0.0 {
+                            let position = plot_position(0.0, elevation) + Vec2::new(3.0, -1.0);
+                            plot.spawn(plot_label(format!("{:.0}°", elevation), position, 9.0));
+                        }
+                    }
+                    // North-south and east-west axes
+                    for (width, height) in [(1.0, PLOT_SIZE), (PLOT_SIZE, 1.0)] {
+                        plot.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px((PLOT_SIZE - width) / 2.0),
+                                top: Val::Px((PLOT_SIZE - height) / 2.0),
+                                width: Val::Px(width),
+                                height: Val::Px(height),
+                                ..default()
+                            },
+                            BackgroundColor(GRID_COLOR),
+                        ));
+                    }
+                    let half = PLOT_SIZE / 2.0;
+                    for (label, position) in [
+                        ("N", Vec2::new(half - 4.0, -15.0)),
+                        ("E", Vec2::new(PLOT_SIZE + 3.0, half - 7.0)),
+                        ("S", Vec2::new(half - 4.0, PLOT_SIZE + 1.0)),
+                        ("W", Vec2::new(-13.0, half - 7.0)),
+                    ] {
+                        plot.spawn(plot_label(label.to_string(), position, 12.0));
+                    }
+
+                    plot.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        SkyPlotLayer,
+                    ));
+                    plot.spawn((dot(Vec2::ZERO, 10.0, MARKER_COLOR), Visibility::Hidden, SkyPlotMarker));
+                });
+        });
+}
+
+/// Toggle the sky plot with F5
+pub fn toggle_sky_plot(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut sky_plot: ResMut<SkyPlot>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleSkyPlot, &keyboard_input) {
+        return;
+    }
+    sky_plot.enabled = !sky_plot.enabled;
+    sky_plot.invalidate();
+}
+
+/// Step through the passes and the ground stations
+fn handle_sky_plot_buttons(
+    mut button_query: Query<(&Interaction, &SkyPlotButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut sky_plot: ResMut<SkyPlot>,
+) {
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::None => BUTTON_COLOR,
+            _ => BUTTON_COLOR.lighter(0.1),
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            SkyPlotButton::PreviousPass => sky_plot.pass_index = sky_plot.pass_index.saturating_sub(1),
+            SkyPlotButton::NextPass => sky_plot.pass_index += 1,
+            SkyPlotButton::NextStation => {
+                sky_plot.station_index += 1;
+                sky_plot.pass_index = 0;
+            }
+        }
+        sky_plot.invalidate();
+    }
+}
+
+/// Ground stations in a stable order, so the station button steps through all of them
+fn sorted_stations<'a>(station_query: &'a Query<(Entity, &GroundStation)>) -> Vec<(Entity, &'a GroundStation)> {
+    let mut stations: Vec<_> = station_query.iter().collect();
+    stations.sort_by(|a, b| a.1.name.cmp(&b.1.name).then(a.0.cmp(&b.0)));
+    stations
+}
+
+/// Predict the selected satellite's passes over the station, and the trajectory of the
+/// shown one
+fn update_sky_plot_passes(
+    time: Res<Time>,
+    sim_time: Res<TimeSimulation>,
+    mut sky_plot: ResMut<SkyPlot>,
+    selected_query: Query<(Entity, &Satellite), With<Selected>>,
+    station_query: Query<(Entity, &GroundStation)>,
+    mut refreshed: MessageReader<TleRefreshed>,
+) {
+    // New elements give new passes
+    if TleRefreshed::any_changed(&mut refreshed) {
+        sky_plot.key = None;
+    }
+    if !sky_plot.enabled || !sky_plot.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let now = sim_time.current_time();
+    let stations = sorted_stations(&station_query);
+    let (Some((satellite_entity, satellite)), false) = (selected_query.iter().next(), stations.is_empty()) else {
+        if sky_plot.key.is_some() || !sky_plot.passes.is_empty() {
+            sky_plot.key = None;
+            sky_plot.passes.clear();
+            sky_plot.track.clear();
+            sky_plot.revision += 1;
+        }
+        return;
+    };
+    let station_index = sky_plot.station_index % stations.len();
+    sky_plot.station_index = station_index;
+    let (station_entity, station) = stations[station_index];
+
+    let key = (satellite_entity, station_entity);
+    // Predicted passes go stale as the clock moves past them (or back before the prediction)
+    let stale = sky_plot.key != Some(key)
+        || sky_plot.predicted_from.is_none_or(|from| {
+            now < from
+                || now > from + Duration::hours(PASS_WINDOW_HOURS / 4)
+                || sky_plot.passes.first().is_some_and(|pass| pass.set_time < now)
+        });
+    if !stale {
+        return;
+    }
+    let observer = station.geodetic();
+    let predicted = passes::predict_passes(
+        satellite.propagator(),
+        observer,
+        &station.horizon(),
+        now,
+        now + Duration::hours(PASS_WINDOW_HOURS),
+        Duration::seconds(passes::PASS_SEARCH_STEP_SECONDS),
+    );
+    sky_plot.pass_index = sky_plot.pass_index.min(predicted.len().saturating_sub(1));
+    sky_plot.track = predicted
+        .get(sky_plot.pass_index)
+        .map(|pass| passes::pass_track(satellite.propagator(), observer, pass, Duration::seconds(TRACK_STEP_SECONDS)))
+        .unwrap_or_default();
+    sky_plot.passes = predicted;
+    sky_plot.key = Some(key);
+    sky_plot.predicted_from = Some(now);
+    sky_plot.revision += 1;
+}
+
+/// Redraw the trajectory, its time ticks, the terrain horizon and the pass summary
+#[allow(clippy::too_many_arguments)]
+fn draw_sky_plot(
+    mut commands: Commands,
+    sky_plot: Res<SkyPlot>,
+    keymap: Res<Keymap>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    station_query: Query<(Entity, &GroundStation)>,
+    mut panel_query: Query<&mut Node, With<SkyPlotPanel>>,
+    mut text_query: Query<&mut Text, With<SkyPlotText>>,
+    layer_query: Query<Entity, With<SkyPlotLayer>>,
+    mut drawn: Local<Option<u32>>,
+) {
+    let display = if sky_plot.enabled { Display::Flex } else { Display::None };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !sky_plot.enabled || *drawn == Some(sky_plot.revision) {
+        return;
+    }
+    *drawn = Some(sky_plot.revision);
+
+    let hide = format!("[{}] hide", keymap.label(Action::ToggleSkyPlot));
+    let station = sky_plot
+        .key
+        .and_then(|(_, station_entity)| station_query.get(station_entity).ok())
+        .map(|(_, station)| station);
+    let text = match (selected_query.iter().next(), station, sky_plot.passes.get(sky_plot.pass_index)) {
+        (None, _, _) => format!("Sky plot: select a satellite  {}", hide),
+        (Some(_), None, _) if station_query.is_empty() => format!("Sky plot: no ground station  {}", hide),
+        (Some(_), None, _) => format!("Sky plot  {}", hide),
+        (Some(satellite), Some(station), None) => format!(
+            "Sky plot: {} over {}  {}\nNo pass in the next {} h",
+            satellite.name, station.name, hide, PASS_WINDOW_HOURS
+        ),
+        (Some(satellite), Some(station), Some(pass)) => format!(
+            "Sky plot: {} over {}  {}\nPass {} of {}: {}\nRise {} az {:.0}°  max {:.1}° at {}  set {} az {:.0}°",
+            satellite.name,
+            station.name,
+            hide,
+            sky_plot.pass_index + 1,
+            sky_plot.passes.len(),
+            pass.rise_time.format("%Y-%m-%d"),
+            pass.rise_time.format("%H:%M:%S"),
+            pass.rise_azimuth_deg,
+            pass.max_elevation_deg,
+            pass.max_elevation_time.format("%H:%M:%S"),
+            pass.set_time.format("%H:%M:%S"),
+            pass.set_azimuth_deg,
+        ),
+    };
+    for mut panel_text in text_query.iter_mut() {
+        panel_text.0 = text.clone();
+    }
+
+    let mask = station.and_then(|station| station.mask.as_ref());
+    for layer in layer_query.iter() {
+        commands.entity(layer).despawn_related::<Children>();
+        commands.entity(layer).with_children(|layer| {
+            if let Some(mask) = mask {
+                for azimuth in (0..360).step_by(MASK_STEP_DEG) {
+                    let azimuth = azimuth as f64;
+                    layer.spawn(dot(plot_position(azimuth, mask.elevation_at(azimuth)), 2.0, MASK_COLOR));
+                }
+            }
+            for (_, look) in &sky_plot.track {
+                layer.spawn(dot(plot_position(look.azimuth_deg, look.elevation_deg), 3.0, TRACK_COLOR));
+            }
+
+            // Ticks on the whole minutes of the pass, the first and every few labelled
+            let Some(&(rise_time, _)) = sky_plot.track.first() else { return };
+            let tick_seconds = TICK_MINUTES * 60;
+            let first_tick = rise_time.timestamp().div_euclid(tick_seconds) * tick_seconds + tick_seconds;
+            let ticks = sky_plot.track.iter().filter(|(time, _)| {
+                let seconds = time.timestamp() - first_tick;
+                seconds >= 0 && seconds % tick_seconds < TRACK_STEP_SECONDS
+            });
+            for (index, (time, look)) in ticks.enumerate() {
+                let position = plot_position(look.azimuth_deg, look.elevation_deg);
+                layer.spawn(dot(position, 6.0, TICK_COLOR));
+                if index % TICKS_PER_LABEL == 0 {
+                    layer.spawn(plot_label(time.format("%H:%M").to_string(), position + Vec2::new(5.0, -5.0), 9.0));
+                }
+            }
+        });
+    }
+}
+
+/// Move the marker to where the satellite is in the station's sky, hidden below the horizon
+fn update_sky_plot_marker(
+    sky_plot: Res<SkyPlot>,
+    selected_query: Query<&Satellite, With<Selected>>,
+    station_query: Query<&GroundStation>,
+    mut marker_query: Query<(&mut Node, &mut Visibility), With<SkyPlotMarker>>,
+) {
+    let look = sky_plot
+        .key
+        .filter(|_| sky_plot.enabled)
+        .and_then(|(_, station_entity)| station_query.get(station_entity).ok())
+        .zip(selected_query.iter().next())
+        .and_then(|(station, satellite)| {
+            let ecef = coords::teme_to_ecef(satellite.position?, satellite.last_update);
+            Some(station.look_angles(ecef))
+        })
+        .filter(|look| look.elevation_deg >= 0.0);
+
+    for (mut node, mut visibility) in marker_query.iter_mut() {
+        let Some(look) = look else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let position = plot_position(look.azimuth_deg, look.elevation_deg);
+        node.left = Val::Px(position.x - 5.0);
+        node.top = Val::Px(position.y - 5.0);
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
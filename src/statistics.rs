@@ -0,0 +1,269 @@
+//! Statistics of a catalog: objects per orbit regime, altitude and inclination histograms,
+//! and objects per owner country from a bundled table
+
+use std::collections::HashMap;
+
+use crate::propagation::OrbitalParameters;
+
+/// Owners of well-known objects and name prefixes (see the file for the format)
+pub const BUNDLED_COUNTRIES: &str = include_str!("../assets/satellite_countries.csv");
+
+/// Upper edges of the altitude histogram bins (km); higher altitudes go in a last bin
+pub const ALTITUDE_BIN_EDGES_KM: [f64; 13] = [
+    300.0, 400.0, 500.0, 600.0, 800.0, 1000.0, 1500.0, 2000.0, 10000.0, 20000.0, 30000.0, 35500.0, 36100.0,
+];
+/// Width of the inclination histogram bins (degrees)
+pub const INCLINATION_BIN_DEG: f64 = 15.0;
+
+/// Orbit regime by altitude, period and eccentricity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrbitRegime {
+    /// Low Earth orbit, entirely below 2000 km
+    Leo,
+    /// Medium Earth orbit, between LEO and geosynchronous
+    Meo,
+    /// Geosynchronous, with a period within 40 minutes of a sidereal day
+    Geo,
+    /// Highly elliptical (Molniya, GTO, ...)
+    Heo,
+    /// Near circular beyond geosynchronous
+    High,
+}
+
+impl OrbitRegime {
+    pub const ALL: [OrbitRegime; 5] = [OrbitRegime::Leo, OrbitRegime::Meo, OrbitRegime::Geo, OrbitRegime::Heo, OrbitRegime::High];
+
+    pub fn of(params: &OrbitalParameters) -> Self {
+        if params.eccentricity >= 0.25 {
+            OrbitRegime::Heo
+        } else if params.apogee_altitude_km < 2000.0 {
+            OrbitRegime::Leo
+        } else if (params.period_minutes - 1436.1).abs() <= 40.0 {
+            OrbitRegime::Geo
+        } else if params.period_minutes < 1436.1 {
+            OrbitRegime::Meo
+        } else {
+            OrbitRegime::High
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrbitRegime::Leo => "LEO",
+            OrbitRegime::Meo => "MEO",
+            OrbitRegime::Geo => "GEO",
+            OrbitRegime::Heo => "HEO",
+            OrbitRegime::High => "Beyond GEO",
+        }
+    }
+}
+
+/// Counts of values in consecutive bins; the last bin is open-ended
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// Upper edge of each bin but the last
+    pub edges: Vec<f64>,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn new(edges: &[f64]) -> Self {
+        Self {
+            edges: edges.to_vec(),
+            counts: vec![0; edges.len() + 1],
+        }
+    }
+
+    /// Bins of the same width from 0 up to `end`
+    pub fn uniform(width: f64, end: f64) -> Self {
+        let edges: Vec<f64> = (1..).map(|i| i as f64 * width).take_while(|edge| *edge < end).collect();
+        Self::new(&edges)
+    }
+
+    pub fn add(&mut self, value: f64) {
+        let bin = self.edges.iter().take_while(|edge| value >= **edge).count();
+        self.counts[bin] += 1;
+    }
+
+    /// Range of a bin, as "400-500" or "36100+"
+    pub fn bin_label(&self, bin: usize) -> String {
+        let start = if bin == 0 { 0.0 } else { self.edges[bin - 1] };
+        match self.edges.get(bin) {
+            Some(end) => format!("{:.0}-{:.0}", start, end),
+            None => format!("{:.0}+", start),
+        }
+    }
+}
+
+/// Owner of each object, by catalog number then by name prefix
+#[derive(Debug, Clone, Default)]
+pub struct CountryTable {
+    by_norad_id: HashMap<u64, String>,
+    /// Longest prefixes first
+    by_prefix: Vec<(String, String)>,
+}
+
+impl CountryTable {
+    /// Parse "25544,ISS" and "STARLINK*,US" lines; blank lines and `#` comments are skipped
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut table = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, owner) = line
+                .split_once(',')
+                .ok_or_else(|| format!("line {}: expected \"catalog number or prefix*,owner\"", number + 1))?;
+            let owner = owner.trim().to_string();
+            match key.strip_suffix('*') {
+                Some(prefix) => table.by_prefix.push((prefix.trim_start().to_uppercase(), owner)),
+                None => {
+                    let norad_id = key.trim().parse().map_err(|_| format!("line {}: invalid catalog number \"{}\"", number + 1, key))?;
+                    table.by_norad_id.insert(norad_id, owner);
+                }
+            }
+        }
+        table.by_prefix.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Ok(table)
+    }
+
+    /// The table shipped with the crate
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_COUNTRIES).expect("bundled country table is valid")
+    }
+
+    /// Set the owner of one object, over the prefixes (from a SATCAT, for example)
+    pub fn insert(&mut self, norad_id: u64, owner: String) {
+        self.by_norad_id.insert(norad_id, owner);
+    }
+
+    pub fn owner(&self, norad_id: u64, name: &str) -> Option<&str> {
+        if let Some(owner) = self.by_norad_id.get(&norad_id) {
+            return Some(owner);
+        }
+        let name = name.trim_start().to_uppercase();
+        self.by_prefix
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix.as_str()))
+            .map(|(_, owner)| owner.as_str())
+    }
+}
+
+/// Summary of a catalog
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogStatistics {
+    pub total: usize,
+    /// Objects per regime, in the order of `OrbitRegime::ALL`
+    pub regimes: Vec<(OrbitRegime, usize)>,
+    /// Mean altitudes (km)
+    pub altitude: Histogram,
+    /// Inclinations (degrees)
+    pub inclination: Histogram,
+    /// Objects per owner, most first
+    pub countries: Vec<(String, usize)>,
+    /// Objects the table has no owner for
+    pub unknown_owner: usize,
+}
+
+impl CatalogStatistics {
+    /// Count objects given as (catalog number, name, orbit)
+    pub fn compute<'a>(objects: impl IntoIterator<Item = (u64, &'a str, OrbitalParameters)>, countries: &CountryTable) -> Self {
+        let mut regimes: HashMap<OrbitRegime, usize> = HashMap::new();
+        let mut owners: HashMap<&str, usize> = HashMap::new();
+        let mut altitude = Histogram::new(&ALTITUDE_BIN_EDGES_KM);
+        let mut inclination = Histogram::uniform(INCLINATION_BIN_DEG, 180.0);
+        let mut total = 0;
+        let mut unknown_owner = 0;
+
+        for (norad_id, name, params) in objects {
+            total += 1;
+            *regimes.entry(OrbitRegime::of(&params)).or_default() += 1;
+            altitude.add((params.apogee_altitude_km + params.perigee_altitude_km) / 2.0);
+            inclination.add(params.inclination_deg);
+            match countries.owner(norad_id, name) {
+                Some(owner) => *owners.entry(owner).or_default() += 1,
+                None => unknown_owner += 1,
+            }
+        }
+
+        let mut countries: Vec<(String, usize)> = owners.into_iter().map(|(owner, count)| (owner.to_string(), count)).collect();
+        countries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        Self {
+            total,
+            regimes: OrbitRegime::ALL.iter().map(|regime| (*regime, regimes.get(regime).copied().unwrap_or(0))).collect(),
+            altitude,
+            inclination,
+            countries,
+            unknown_owner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit(perigee_km: f64, apogee_km: f64, period_minutes: f64, inclination_deg: f64) -> OrbitalParameters {
+        let semi_major_axis = 6378.137 + (perigee_km + apogee_km) / 2.0;
+        OrbitalParameters {
+            inclination_deg,
+            eccentricity: (apogee_km - perigee_km) / (2.0 * semi_major_axis),
+            period_minutes,
+            apogee_altitude_km: apogee_km,
+            perigee_altitude_km: perigee_km,
+        }
+    }
+
+    #[test]
+    fn orbits_fall_in_their_regime() {
+        assert_eq!(OrbitRegime::of(&orbit(415.0, 420.0, 92.9, 51.6)), OrbitRegime::Leo);
+        assert_eq!(OrbitRegime::of(&orbit(20180.0, 20190.0, 718.0, 55.0)), OrbitRegime::Meo);
+        assert_eq!(OrbitRegime::of(&orbit(35780.0, 35790.0, 1436.1, 0.05)), OrbitRegime::Geo);
+        assert_eq!(OrbitRegime::of(&orbit(500.0, 39700.0, 718.0, 63.4)), OrbitRegime::Heo);
+        assert_eq!(OrbitRegime::of(&orbit(36200.0, 36300.0, 1450.0, 8.0)), OrbitRegime::Geo);
+        assert_eq!(OrbitRegime::of(&orbit(60000.0, 62000.0, 3000.0, 30.0)), OrbitRegime::High);
+    }
+
+    #[test]
+    fn histogram_bins_are_half_open() {
+        let mut histogram = Histogram::new(&[10.0, 20.0]);
+        for value in [-1.0, 0.0, 9.9, 10.0, 25.0, 1e6] {
+            histogram.add(value);
+        }
+        assert_eq!(histogram.counts, vec![3, 1, 2]);
+        assert_eq!(histogram.bin_label(1), "10-20");
+        assert_eq!(histogram.bin_label(2), "20+");
+        assert_eq!(Histogram::uniform(15.0, 180.0).counts.len(), 12);
+    }
+
+    #[test]
+    fn owners_come_from_numbers_then_longest_prefix() {
+        let table = CountryTable::parse("# comment\n25544,ISS\nCREW*,XX\nCREW DRAGON*,US\n\nSTARLINK*,US\n").unwrap();
+        assert_eq!(table.owner(25544, "ISS (ZARYA)"), Some("ISS"));
+        assert_eq!(table.owner(1, "starlink-1007"), Some("US"));
+        assert_eq!(table.owner(2, "CREW DRAGON 9"), Some("US"));
+        assert_eq!(table.owner(3, "CREWED"), Some("XX"));
+        assert_eq!(table.owner(4, "ONEWEB-0012"), None);
+        assert!(CountryTable::parse("abc,US").is_err());
+        assert!(CountryTable::bundled().owner(44713, "STARLINK-1007").is_some());
+    }
+
+    #[test]
+    fn statistics_count_every_object() {
+        let table = CountryTable::parse("STARLINK*,US\n").unwrap();
+        let objects = vec![
+            (1, "STARLINK-1", orbit(540.0, 550.0, 95.6, 53.0)),
+            (2, "STARLINK-2", orbit(540.0, 550.0, 95.6, 53.0)),
+            (3, "GPS BIIR-2", orbit(20180.0, 20190.0, 718.0, 55.0)),
+        ];
+        let statistics = CatalogStatistics::compute(objects, &table);
+        assert_eq!(statistics.total, 3);
+        assert_eq!(statistics.regimes[0], (OrbitRegime::Leo, 2));
+        assert_eq!(statistics.regimes[1], (OrbitRegime::Meo, 1));
+        assert_eq!(statistics.altitude.counts.iter().sum::<usize>(), 3);
+        assert_eq!(statistics.inclination.counts[3], 3);
+        assert_eq!(statistics.countries, vec![("US".to_string(), 2)]);
+        assert_eq!(statistics.unknown_owner, 1);
+    }
+}
@@ -0,0 +1,242 @@
+//! Statistics of the loaded catalog as bar charts: objects per orbit regime, mean altitude,
+//! inclination and owner country
+
+use bevy::prelude::*;
+
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{Satellite, TleRefreshed};
+use crate::statistics::{CatalogStatistics, CountryTable, Histogram};
+use crate::text_input::InputFocus;
+
+/// Catalog statistics dashboard
+pub struct StatisticsPanelPlugin;
+
+impl Plugin for StatisticsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StatisticsView {
+            enabled: false,
+            countries: CountryTable::bundled(),
+            statistics: None,
+        })
+        .add_systems(Startup, setup_statistics_panel)
+        .add_systems(Update, (
+            toggle_statistics,
+            compute_statistics,
+            update_statistics_panel,
+        ).chain());
+    }
+}
+
+/// Owners listed in the country chart
+const MAX_LISTED_COUNTRIES: usize = 12;
+/// Length of the longest bar of a chart (px)
+const BAR_MAX_WIDTH: f32 = 160.0;
+/// Width of the bar labels (px)
+const BAR_LABEL_WIDTH: f32 = 80.0;
+const REGIME_COLOR: Color = Color::srgb(0.3, 0.7, 1.0);
+const ALTITUDE_COLOR: Color = Color::srgb(0.4, 0.9, 0.5);
+const INCLINATION_COLOR: Color = Color::srgb(1.0, 0.7, 0.3);
+const COUNTRY_COLOR: Color = Color::srgb(0.8, 0.5, 1.0);
+
+/// Statistics mode (toggle with F8), the owner table and the last statistics
+#[derive(Resource)]
+pub struct StatisticsView {
+    pub enabled: bool,
+    pub countries: CountryTable,
+    pub statistics: Option<CatalogStatistics>,
+}
+
+#[derive(Component)]
+pub struct StatisticsPanel;
+
+#[derive(Component)]
+pub struct StatisticsHeader;
+
+/// Container of the charts, rebuilt with the statistics
+#[derive(Component)]
+pub struct StatisticsCharts;
+
+pub fn setup_statistics_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                // Under the time controls, left of the catalog timeline
+                left: Val::Percent(20.0),
+                top: Val::Px(90.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+            StatisticsPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                StatisticsHeader,
+            ));
+            parent.spawn((
+                Node {
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                },
+                StatisticsCharts,
+            ));
+        });
+}
+
+/// Toggle the statistics with F8
+pub fn toggle_statistics(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut view: ResMut<StatisticsView>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleStatistics, &keyboard_input) {
+        return;
+    }
+    view.enabled = !view.enabled;
+}
+
+/// Count the catalog when the panel opens and when the satellites change
+pub fn compute_statistics(
+    mut view: ResMut<StatisticsView>,
+    added: Query<(), Added<Satellite>>,
+    mut refreshed: MessageReader<TleRefreshed>,
+    satellite_query: Query<&Satellite>,
+    mut computed: Local<bool>,
+) {
+    let satellites_changed = !added.is_empty() || TleRefreshed::any_changed(&mut refreshed);
+    // The statistics are also cleared when owners arrive from the SATCAT
+    if satellites_changed || view.statistics.is_none() {
+        *computed = false;
+    }
+    if !view.enabled || *computed {
+        return;
+    }
+    *computed = true;
+
+    let objects = satellite_query
+        .iter()
+        .map(|satellite| (satellite.elements.norad_id, satellite.name.as_str(), satellite.orbital_parameters()));
+    let statistics = CatalogStatistics::compute(objects, &view.countries);
+    view.statistics = Some(statistics);
+}
+
+/// Rows of a histogram, from its bin labels
+fn histogram_rows(histogram: &Histogram) -> Vec<(String, usize)> {
+    histogram
+        .counts
+        .iter()
+        .enumerate()
+        .map(|(bin, count)| (histogram.bin_label(bin), *count))
+        .collect()
+}
+
+/// A titled bar chart, one row per (label, count)
+fn spawn_bar_chart(parent: &mut ChildSpawnerCommands, title: &str, rows: &[(String, usize)], color: Color) {
+    let font = TextFont {
+        font_size: 12.0,
+        ..default()
+    };
+    let max = rows.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+    parent.spawn((Text::new(title), TextFont {
+        font_size: 13.0,
+        ..default()
+    }));
+    for (label, count) in rows {
+        parent
+            .spawn(Node {
+                column_gap: Val::Px(4.0),
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn((
+                    Node {
+                        width: Val::Px(BAR_LABEL_WIDTH),
+                        ..default()
+                    },
+                    Text::new(label.clone()),
+                    font.clone(),
+                ));
+                // Empty bins keep a sliver so the row still reads as a bar
+                let width = (*count as f32 / max as f32 * BAR_MAX_WIDTH).max(1.0);
+                row.spawn((
+                    Node {
+                        width: Val::Px(width),
+                        height: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(if *count > 0 { color } else { color.with_alpha(0.3) }),
+                ));
+                row.spawn((Text::new(count.to_string()), font.clone()));
+            });
+    }
+}
+
+fn update_statistics_panel(
+    mut commands: Commands,
+    view: Res<StatisticsView>,
+    keymap: Res<Keymap>,
+    mut panel_query: Query<&mut Node, With<StatisticsPanel>>,
+    mut header_query: Query<&mut Text, With<StatisticsHeader>>,
+    charts_query: Query<Entity, With<StatisticsCharts>>,
+) {
+    if !view.is_changed() {
+        return;
+    }
+    let display = if view.enabled { Display::Flex } else { Display::None };
+    for mut node in panel_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    let (true, Some(statistics)) = (view.enabled, view.statistics.as_ref()) else {
+        return;
+    };
+
+    for mut text in header_query.iter_mut() {
+        text.0 = format!("Catalog statistics: {} objects  [{}] hide", statistics.total, keymap.label(Action::ToggleStatistics));
+    }
+
+    let regimes: Vec<(String, usize)> = statistics
+        .regimes
+        .iter()
+        .map(|(regime, count)| (regime.label().to_string(), *count))
+        .collect();
+    let mut countries: Vec<(String, usize)> = statistics.countries.iter().take(MAX_LISTED_COUNTRIES).cloned().collect();
+    let others: usize = statistics.countries.iter().skip(MAX_LISTED_COUNTRIES).map(|(_, count)| count).sum();
+    if others > 0 {
+        countries.push(("Others".to_string(), others));
+    }
+    if statistics.unknown_owner > 0 {
+        countries.push(("Unknown".to_string(), statistics.unknown_owner));
+    }
+
+    for charts in charts_query.iter() {
+        commands.entity(charts).despawn_related::<Children>();
+        commands.entity(charts).with_children(|parent| {
+            let column = Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            };
+            parent.spawn(column.clone()).with_children(|column| {
+                spawn_bar_chart(column, "Orbit regime", &regimes, REGIME_COLOR);
+                spawn_bar_chart(column, "Owner", &countries, COUNTRY_COLOR);
+            });
+            parent.spawn(column).with_children(|column| {
+                spawn_bar_chart(column, "Mean altitude (km)", &histogram_rows(&statistics.altitude), ALTITUDE_COLOR);
+                spawn_bar_chart(column, "Inclination (°)", &histogram_rows(&statistics.inclination), INCLINATION_COLOR);
+            });
+        });
+    }
+}
@@ -0,0 +1,588 @@
+//! Live position stream for external dashboards, as JSON over WebSocket or UDP
+//!
+//! The network side runs on its own threads; the app hands it a snapshot of the
+//! satellites at the configured rate and drops snapshots while it is still busy.
+//! WebSocket clients get pongs to their pings and an answer to their close; anything
+//! else they send is ignored.
+//!
+//! WebSocket message (one per update):
+//!
+//! ```json
+//! {"time": "2024-05-01T12:00:00Z", "frame": "geodetic", "satellites": [
+//!   {"norad_id": 25544, "name": "ISS (ZARYA)", "epoch": "2024-05-01T12:00:00Z",
+//!    "latitude_deg": 51.2, "longitude_deg": -12.7, "altitude_km": 418.3}]}
+//! ```
+//!
+//! UDP datagrams carry one satellite each, with the same fields plus `time` and `frame`.
+//! In the TEME frame, `position_km` and `velocity_km_s` replace the geodetic fields.
+
+use bevy::prelude::*;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::{Settings, StreamFrame, StreamProtocol, StreamSettings};
+use crate::coords;
+use crate::satellite::Satellite;
+use crate::time_simulation::TimeSimulation;
+
+/// Stream the propagated positions over the network
+pub struct StreamPlugin;
+
+impl Plugin for StreamPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let settings = app.world().resource::<Settings>().stream.clone();
+        if !settings.enabled {
+            return;
+        }
+
+        match PositionStream::start(&settings) {
+            Ok(stream) => {
+                app.insert_resource(stream).add_systems(Update, stream_positions);
+            }
+            Err(e) => eprintln!("Error: Failed to start the position stream on {}: {}", settings.address, e),
+        }
+    }
+}
+
+/// A client that doesn't take a message within this time is dropped
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// GUID appended to the client key in the WebSocket handshake (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Clients only have control frames to send; a larger frame drops the connection
+const MAX_CLIENT_FRAME_BYTES: u64 = 1 << 16;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// State of one satellite at the snapshot time
+#[derive(Serialize, Clone)]
+struct SatelliteState {
+    norad_id: u64,
+    name: String,
+    /// Time the position was propagated to
+    epoch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latitude_deg: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    longitude_deg: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    altitude_km: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position_km: Option<[f64; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    velocity_km_s: Option<[f64; 3]>,
+}
+
+#[derive(Serialize)]
+struct Snapshot {
+    time: String,
+    frame: StreamFrame,
+    satellites: Vec<SatelliteState>,
+}
+
+/// One satellite per datagram
+#[derive(Serialize)]
+struct Datagram<'a> {
+    time: &'a str,
+    frame: StreamFrame,
+    #[serde(flatten)]
+    satellite: &'a SatelliteState,
+}
+
+/// Hands snapshots to the network thread at the configured rate
+#[derive(Resource)]
+pub struct PositionStream {
+    sender: SyncSender<Snapshot>,
+    frame: StreamFrame,
+    timer: Timer,
+}
+
+impl PositionStream {
+    fn start(settings: &StreamSettings) -> Result<Self, String> {
+        // One snapshot in flight; newer ones are dropped until the network thread catches up
+        let (sender, receiver) = mpsc::sync_channel::<Snapshot>(1);
+        match settings.protocol {
+            StreamProtocol::WebSocket => start_websocket_server(&settings.address, receiver)?,
+            StreamProtocol::Udp => start_udp_sender(&settings.address, receiver)?,
+        }
+        let period = 1.0 / settings.rate_hz.max(0.01);
+        Ok(Self {
+            sender,
+            frame: settings.frame,
+            timer: Timer::from_seconds(period as f32, TimerMode::Repeating),
+        })
+    }
+}
+
+/// A WebSocket client past the handshake; the broadcast and the replies to its pings take
+/// turns writing to it
+struct Client {
+    stream: Mutex<TcpStream>,
+    /// Cleared once the client closed the connection or stopped taking messages
+    open: AtomicBool,
+}
+
+impl Client {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream: Mutex::new(stream),
+            open: AtomicBool::new(true),
+        }
+    }
+
+    /// Write a whole frame, closing the connection if it fails
+    fn send(&self, frame: &[u8]) -> bool {
+        if !self.open.load(Ordering::Relaxed) {
+            return false;
+        }
+        let mut stream = self.stream.lock().unwrap();
+        if stream.write_all(frame).is_ok() {
+            return true;
+        }
+        self.open.store(false, Ordering::Relaxed);
+        let _ = stream.shutdown(Shutdown::Both);
+        false
+    }
+
+    fn close(&self) {
+        self.open.store(false, Ordering::Relaxed);
+        let _ = self.stream.lock().unwrap().shutdown(Shutdown::Both);
+    }
+}
+
+fn start_websocket_server(address: &str, receiver: Receiver<Snapshot>) -> Result<(), String> {
+    let listener = TcpListener::bind(address).map_err(|e| e.to_string())?;
+    println!("✓ Streaming positions on ws://{}/", address);
+    let clients: Arc<Mutex<Vec<Arc<Client>>>> = Arc::default();
+
+    let accepted = clients.clone();
+    thread::spawn(move || {
+        for connection in listener.incoming().flatten() {
+            // Each handshake on its own thread, so a slow client doesn't hold up the others
+            let accepted = accepted.clone();
+            thread::spawn(move || {
+                let peer = connection.peer_addr().map(|peer| peer.to_string()).unwrap_or_default();
+                if let Err(e) = websocket_handshake(&connection) {
+                    eprintln!("Warning: Rejected stream client {}: {}", peer, e);
+                    return;
+                }
+                // The handshake's read timeout would drop a client that is just listening
+                let reader = match connection.try_clone() {
+                    Ok(reader) if reader.set_read_timeout(None).is_ok() => reader,
+                    _ => return,
+                };
+                println!("Stream client connected: {}", peer);
+                let _ = connection.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT));
+                let client = Arc::new(Client::new(connection));
+                accepted.lock().unwrap().push(client.clone());
+                // This thread goes on reading the client's frames until it leaves
+                read_client_frames(&client, reader);
+                println!("Stream client disconnected: {}", peer);
+            });
+        }
+    });
+
+    thread::spawn(move || {
+        for snapshot in receiver {
+            let message = match serde_json::to_string(&snapshot) {
+                Ok(message) => websocket_text_frame(&message),
+                Err(e) => {
+                    eprintln!("Error: Failed to encode positions: {}", e);
+                    continue;
+                }
+            };
+            broadcast(&clients, &message);
+        }
+    });
+    Ok(())
+}
+
+/// Send a frame to every client, then drop the ones that closed the connection or stopped
+/// reading; the writes happen outside the list's lock, so a stalled client holds up
+/// neither the handshakes nor the replies to the other clients
+fn broadcast(clients: &Mutex<Vec<Arc<Client>>>, frame: &[u8]) {
+    let current = clients.lock().unwrap().clone();
+    let gone: Vec<Arc<Client>> = current.into_iter().filter(|client| !client.send(frame)).collect();
+    if !gone.is_empty() {
+        clients.lock().unwrap().retain(|client| !gone.iter().any(|gone| Arc::ptr_eq(client, gone)));
+    }
+}
+
+/// Answer the client's pings and its closing handshake, until the connection ends
+fn read_client_frames(client: &Client, mut reader: impl Read) {
+    while let Ok((opcode, payload)) = read_websocket_frame(&mut reader) {
+        match opcode {
+            OPCODE_PING => {
+                client.send(&websocket_frame(OPCODE_PONG, &payload));
+            }
+            OPCODE_CLOSE => {
+                // Echo the status code back, then hang up
+                client.send(&websocket_frame(OPCODE_CLOSE, &payload[..payload.len().min(2)]));
+                break;
+            }
+            // Messages and pongs from the client have nothing to answer
+            _ => {}
+        }
+    }
+    client.close();
+}
+
+/// One frame sent by a client: its opcode and unmasked payload
+fn read_websocket_frame(reader: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    let opcode = header[0] & 0x0f;
+    let length = match header[1] & 0x7f {
+        126 => {
+            let mut length = [0u8; 2];
+            reader.read_exact(&mut length)?;
+            u16::from_be_bytes(length) as u64
+        }
+        127 => {
+            let mut length = [0u8; 8];
+            reader.read_exact(&mut length)?;
+            u64::from_be_bytes(length)
+        }
+        length => length as u64,
+    };
+    if length > MAX_CLIENT_FRAME_BYTES {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Client frame too large"));
+    }
+
+    // Frames from clients are masked (RFC 6455 section 5.3)
+    let mut mask = [0u8; 4];
+    if header[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; length as usize];
+    reader.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((opcode, payload))
+}
+
+fn start_udp_sender(address: &str, receiver: Receiver<Snapshot>) -> Result<(), String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket.connect(address).map_err(|e| e.to_string())?;
+    println!("✓ Streaming positions to udp://{}", address);
+
+    thread::spawn(move || {
+        let mut warned = false;
+        for snapshot in receiver {
+            for satellite in &snapshot.satellites {
+                let datagram = Datagram {
+                    time: &snapshot.time,
+                    frame: snapshot.frame,
+                    satellite,
+                };
+                let Ok(payload) = serde_json::to_vec(&datagram) else { continue };
+                // Nobody listening shows up as "connection refused"; say it once
+                if let Err(e) = socket.send(&payload) {
+                    if !warned {
+                        eprintln!("Warning: Position stream datagram not delivered: {}", e);
+                        warned = true;
+                    }
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Answer a WebSocket upgrade request
+fn websocket_handshake(mut stream: &TcpStream) -> Result<(), String> {
+    stream.set_read_timeout(Some(CLIENT_WRITE_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream);
+    let mut key = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            return Err("Connection closed during the handshake".to_string());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let key = match key {
+        Some(key) => key,
+        None => {
+            let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
+            return Err("Not a WebSocket upgrade request".to_string());
+        }
+    };
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        websocket_accept(&key)
+    );
+    stream.write_all(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// `Sec-WebSocket-Accept` answer to a client's `Sec-WebSocket-Key`
+fn websocket_accept(key: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(sha1(format!("{}{}", key, WEBSOCKET_GUID).as_bytes()))
+}
+
+/// Unmasked, unfragmented text frame (server to client)
+fn websocket_text_frame(text: &str) -> Vec<u8> {
+    websocket_frame(OPCODE_TEXT, text.as_bytes())
+}
+
+/// Unmasked, unfragmented frame (server to client)
+fn websocket_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode); // FIN + opcode
+    match payload.len() {
+        length if length < 126 => frame.push(length as u8),
+        length if length <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// SHA-1 digest, needed only for the WebSocket handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (bytes, word) in digest.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Send the satellites' latest positions at the configured rate
+pub fn stream_positions(
+    time: Res<Time>,
+    sim_time: Res<TimeSimulation>,
+    mut stream: ResMut<PositionStream>,
+    satellite_query: Query<&Satellite>,
+) {
+    if !stream.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let frame = stream.frame;
+    let satellites = satellite_query
+        .iter()
+        .filter_map(|satellite| {
+            let position = satellite.position?;
+            let mut state = SatelliteState {
+                norad_id: satellite.elements.norad_id,
+                name: satellite.name.clone(),
+                epoch: format_time(satellite.last_update),
+                latitude_deg: None,
+                longitude_deg: None,
+                altitude_km: None,
+                position_km: None,
+                velocity_km_s: None,
+            };
+            match frame {
+                StreamFrame::Geodetic => {
+                    let geodetic = coords::teme_to_geodetic(position, satellite.last_update);
+                    state.latitude_deg = Some(geodetic.latitude_deg);
+                    state.longitude_deg = Some(geodetic.longitude_deg);
+                    state.altitude_km = Some(geodetic.altitude_km);
+                }
+                StreamFrame::Teme => {
+                    state.position_km = Some([position.x, position.y, position.z]);
+                    state.velocity_km_s = satellite.velocity.map(|velocity| [velocity.x, velocity.y, velocity.z]);
+                }
+            }
+            Some(state)
+        })
+        .collect();
+
+    let snapshot = Snapshot {
+        time: format_time(sim_time.current_time()),
+        frame,
+        satellites,
+    };
+    if let Err(TrySendError::Disconnected(_)) = stream.sender.try_send(snapshot) {
+        eprintln!("Error: The position stream stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A frame as a client sends it, masked
+    fn masked_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mask = [0x37, 0xfa, 0x21, 0x3d];
+        let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+        frame
+    }
+
+    #[test]
+    fn sha1_matches_known_digests() {
+        let hex = |digest: [u8; 20]| digest.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+        assert_eq!(hex(sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(hex(sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        // Two blocks of padding
+        assert_eq!(
+            hex(sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+    }
+
+    #[test]
+    fn handshake_answers_the_rfc_6455_example() {
+        assert_eq!(websocket_accept("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n")
+            .unwrap();
+        let (server, _) = listener.accept().unwrap();
+        websocket_handshake(&server).unwrap();
+        drop(server);
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+        assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+    }
+
+    #[test]
+    fn frame_headers_switch_length_encoding_at_the_boundaries() {
+        let header = |length: usize| {
+            let frame = websocket_text_frame(&"x".repeat(length));
+            assert_eq!(frame[0], 0x81);
+            let header_length = frame.len() - length;
+            frame[1..header_length].to_vec()
+        };
+        assert_eq!(header(0), vec![0]);
+        assert_eq!(header(125), vec![125]);
+        assert_eq!(header(126), vec![126, 0x00, 0x7e]);
+        assert_eq!(header(65535), vec![126, 0xff, 0xff]);
+        assert_eq!(header(65536), vec![127, 0, 0, 0, 0, 0, 0x01, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn client_frames_are_unmasked() {
+        // The masked "Hello" of RFC 6455 section 5.7
+        let frame = [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58];
+        assert_eq!(read_websocket_frame(&mut &frame[..]).unwrap(), (OPCODE_TEXT, b"Hello".to_vec()));
+        assert_eq!(read_websocket_frame(&mut &masked_frame(OPCODE_PING, b"")[..]).unwrap(), (OPCODE_PING, Vec::new()));
+
+        let mut too_large = vec![0x82, 0xff];
+        too_large.extend_from_slice(&(MAX_CLIENT_FRAME_BYTES + 1).to_be_bytes());
+        assert!(read_websocket_frame(&mut &too_large[..]).is_err());
+    }
+
+    #[test]
+    fn pings_get_pongs_and_a_close_is_echoed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut remote = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        remote.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let reader = server.try_clone().unwrap();
+        let client = Arc::new(Client::new(server));
+        let reading = {
+            let client = client.clone();
+            thread::spawn(move || read_client_frames(&client, reader))
+        };
+
+        remote.write_all(&masked_frame(OPCODE_PING, b"hi")).unwrap();
+        let mut pong = [0u8; 4];
+        remote.read_exact(&mut pong).unwrap();
+        assert_eq!(pong, [0x8a, 2, b'h', b'i']);
+
+        // Status 1000 (normal closure) and a reason, of which only the status comes back
+        remote.write_all(&masked_frame(OPCODE_CLOSE, &[0x03, 0xe8, b'b', b'y', b'e'])).unwrap();
+        let mut reply = Vec::new();
+        remote.read_to_end(&mut reply).unwrap();
+        assert_eq!(reply, [0x88, 2, 0x03, 0xe8]);
+        reading.join().unwrap();
+        assert!(!client.send(&websocket_text_frame("late")));
+    }
+
+    #[test]
+    fn broadcast_drops_closed_clients() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut remotes = Vec::new();
+        let mut clients = Vec::new();
+        for _ in 0..2 {
+            remotes.push(TcpStream::connect(address).unwrap());
+            clients.push(Arc::new(Client::new(listener.accept().unwrap().0)));
+        }
+        clients[0].close();
+        let clients = Mutex::new(clients);
+
+        let frame = websocket_text_frame("{}");
+        broadcast(&clients, &frame);
+        assert_eq!(clients.lock().unwrap().len(), 1);
+        let mut received = vec![0u8; frame.len()];
+        remotes[1].read_exact(&mut received).unwrap();
+        assert_eq!(received, frame);
+    }
+}
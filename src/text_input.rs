@@ -0,0 +1,374 @@
+//! Single-line text fields: click to focus, type with the keyboard layout's characters
+//! (and IME), move the caret and select with the arrow keys, copy/cut/paste, Esc to leave.
+//!
+//! A field is a `Text` node with a `TextInput`; its value is drawn in child spans
+//! with a blinking caret, so the field's own text stays empty. The field's
+//! `TextColor` is the color of the value.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::window::Ime;
+
+/// Text fields and keyboard focus
+pub struct TextInputPlugin;
+
+impl Plugin for TextInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputFocus>()
+            .add_message::<TextSubmitted>()
+            .add_systems(Update, (
+                spawn_text_input_spans,
+                focus_on_click,
+                edit_text_inputs,
+                update_text_input_display,
+                enable_ime,
+            ).chain());
+    }
+}
+
+/// Caret blink period (seconds for one on + off cycle)
+const CARET_BLINK_SECONDS: f32 = 1.0;
+/// Color of selected text
+const SELECTION_COLOR: Color = Color::srgb(0.3, 0.7, 1.0);
+
+/// The text field receiving the keyboard, if any
+#[derive(Resource, Default)]
+pub struct InputFocus {
+    pub field: Option<Entity>,
+}
+
+impl InputFocus {
+    /// Whether a text field is capturing the keyboard (other shortcuts should be ignored)
+    pub fn is_focused(&self) -> bool {
+        self.field.is_some()
+    }
+}
+
+/// Sent when Enter is pressed in a focused field
+#[derive(Message, Clone, Copy)]
+pub struct TextSubmitted(pub Entity);
+
+/// Editable value of a text field
+#[derive(Component, Default)]
+#[require(Interaction, Text)]
+pub struct TextInput {
+    pub value: String,
+    /// Caret position, in characters
+    cursor: usize,
+    /// Other end of the selection, in characters
+    anchor: Option<usize>,
+}
+
+/// Child span showing part of a field: before the selection, caret, selection, caret, after
+#[derive(Component)]
+struct TextInputSpan(usize);
+
+impl TextInput {
+    /// Replace the value, putting the caret at the end
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+        self.anchor = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_value(String::new());
+    }
+
+    /// Byte offset of a character position
+    fn byte_index(&self, position: usize) -> usize {
+        self.value.char_indices().nth(position).map_or(self.value.len(), |(index, _)| index)
+    }
+
+    /// Selected character range, if it isn't empty
+    fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor.filter(|&anchor| anchor != self.cursor)?;
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    fn selected_text(&self) -> Option<&str> {
+        let (start, end) = self.selection()?;
+        Some(&self.value[self.byte_index(start)..self.byte_index(end)])
+    }
+
+    /// Remove the selected text; false if nothing was selected
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = match self.selection() {
+            Some(range) => range,
+            None => return false,
+        };
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.value.replace_range(range, "");
+        self.cursor = start;
+        self.anchor = None;
+        true
+    }
+
+    /// Type text at the caret, replacing the selection
+    fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        let index = self.byte_index(self.cursor);
+        self.value.insert_str(index, &text);
+        self.cursor += text.chars().count();
+        self.anchor = None;
+    }
+
+    /// Move the caret, extending the selection when `select` is set
+    fn move_cursor(&mut self, position: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = position.min(self.value.chars().count());
+    }
+
+    fn backspace(&mut self) {
+        if !self.delete_selection() && self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.value.remove(index);
+        }
+    }
+
+    fn delete(&mut self) {
+        if !self.delete_selection() && self.cursor < self.value.chars().count() {
+            let index = self.byte_index(self.cursor);
+            self.value.remove(index);
+        }
+    }
+}
+
+/// Read the system clipboard through the platform's command-line tools
+fn read_clipboard() -> Option<String> {
+    let tools: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else if cfg!(windows) {
+        &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])]
+    } else {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    };
+    tools.iter().find_map(|(program, args)| {
+        let output = Command::new(program).args(*args).stderr(Stdio::null()).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    })
+}
+
+/// Write the system clipboard through the platform's command-line tools; false if none worked
+fn write_clipboard(text: &str) -> bool {
+    let tools: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+    tools.iter().any(|(program, args)| {
+        let child = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(_) => return false,
+        };
+        let written = child.stdin.take().is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        child.wait().is_ok_and(|status| status.success()) && written
+    })
+}
+
+/// Give new fields the spans their value is drawn in
+fn spawn_text_input_spans(mut commands: Commands, added: Query<(Entity, &TextFont), Added<TextInput>>) {
+    for (entity, font) in added.iter() {
+        commands.entity(entity).with_children(|parent| {
+            for index in 0..5 {
+                parent.spawn((TextSpan::new(""), font.clone(), TextColor::WHITE, TextInputSpan(index)));
+            }
+        });
+    }
+}
+
+/// Focus a field when it is clicked, and drop the focus on a click anywhere else
+pub fn focus_on_click(
+    mut focus: ResMut<InputFocus>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut inputs: Query<(Entity, &Interaction, &mut TextInput)>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let clicked = inputs.iter_mut().find(|(_, interaction, _)| **interaction == Interaction::Pressed);
+    match clicked {
+        Some((entity, _, mut input)) => {
+            if focus.field != Some(entity) {
+                let end = input.value.chars().count();
+                input.move_cursor(end, false);
+                focus.field = Some(entity);
+            }
+        }
+        None => focus.field = None,
+    }
+}
+
+/// Apply typed text, editing keys and IME input to the focused field
+pub fn edit_text_inputs(
+    mut focus: ResMut<InputFocus>,
+    mut keyboard_events: MessageReader<KeyboardInput>,
+    mut ime_events: MessageReader<Ime>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut inputs: Query<&mut TextInput>,
+    mut submitted: MessageWriter<TextSubmitted>,
+    mut fallback_clipboard: Local<String>,
+) {
+    let (entity, mut input) = match focus.field.and_then(|entity| Some((entity, inputs.get_mut(entity).ok()?))) {
+        Some(field) => field,
+        None => {
+            // The field went away (or nothing is focused): nothing to type into
+            if focus.field.is_some() {
+                focus.field = None;
+            }
+            keyboard_events.clear();
+            ime_events.clear();
+            return;
+        }
+    };
+
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+    let shift = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    // AltGr arrives as Ctrl+Alt on Windows and still types characters
+    let alt = keyboard_input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let end = input.value.chars().count();
+        match (&event.logical_key, event.key_code) {
+            (Key::Escape, _) => {
+                focus.field = None;
+                return;
+            }
+            (Key::Enter, _) => {
+                submitted.write(TextSubmitted(entity));
+            }
+            (Key::Backspace, _) => input.backspace(),
+            (Key::Delete, _) => input.delete(),
+            (Key::ArrowLeft, _) => {
+                let position = match input.selection() {
+                    Some((start, _)) if !shift => start,
+                    _ => input.cursor.saturating_sub(1),
+                };
+                input.move_cursor(position, shift);
+            }
+            (Key::ArrowRight, _) => {
+                let position = match input.selection() {
+                    Some((_, end)) if !shift => end,
+                    _ => input.cursor + 1,
+                };
+                input.move_cursor(position, shift);
+            }
+            (Key::Home, _) => input.move_cursor(0, shift),
+            (Key::End, _) => input.move_cursor(end, shift),
+            // Shortcuts by physical key, like the rest of the app's bindings
+            (_, KeyCode::KeyA) if ctrl => {
+                input.anchor = Some(0);
+                input.cursor = end;
+            }
+            (_, KeyCode::KeyC | KeyCode::KeyX) if ctrl => {
+                if let Some(text) = input.selected_text().map(str::to_string) {
+                    if !write_clipboard(&text) {
+                        *fallback_clipboard = text;
+                    }
+                    if event.key_code == KeyCode::KeyX {
+                        input.delete_selection();
+                    }
+                }
+            }
+            (_, KeyCode::KeyV) if ctrl => {
+                let text = read_clipboard().unwrap_or_else(|| fallback_clipboard.clone());
+                // Single-line field: pasted line breaks become spaces
+                input.insert(&text.trim_end().replace(['\r', '\n'], " "));
+            }
+            _ if ctrl && !alt => {}
+            _ => {
+                if let Some(text) = &event.text {
+                    input.insert(text);
+                }
+            }
+        }
+    }
+
+    for event in ime_events.read() {
+        if let Ime::Commit { value, .. } = event {
+            input.insert(value);
+        }
+    }
+}
+
+/// Draw each field's value with its selection and a blinking caret in the focused field
+fn update_text_input_display(
+    focus: Res<InputFocus>,
+    time: Res<Time>,
+    inputs: Query<(Entity, &TextInput, &TextColor, &Children)>,
+    mut spans: Query<(&TextInputSpan, &mut TextSpan, &mut TextColor), Without<TextInput>>,
+) {
+    let caret_on = time.elapsed_secs() % CARET_BLINK_SECONDS < CARET_BLINK_SECONDS / 2.0;
+
+    for (entity, input, color, children) in inputs.iter() {
+        let focused = focus.field == Some(entity);
+        let (start, end) = input.selection().unwrap_or((input.cursor, input.cursor));
+        let (start_byte, end_byte) = (input.byte_index(start), input.byte_index(end));
+        let caret = |at: usize| if focused && input.cursor == at { "|" } else { "" };
+
+        // The caret sits on whichever end of the selection is being moved
+        let parts = [
+            &input.value[..start_byte],
+            caret(start),
+            &input.value[start_byte..end_byte],
+            if start == end { "" } else { caret(end) },
+            &input.value[end_byte..],
+        ];
+        for child in children.iter() {
+            let (span, mut text, mut span_color) = match spans.get_mut(child) {
+                Ok(span) => span,
+                Err(_) => continue,
+            };
+            let part = parts[span.0];
+            if text.0 != part {
+                text.0 = part.to_string();
+            }
+            let target = match span.0 {
+                1 | 3 if !caret_on => Color::NONE,
+                2 => SELECTION_COLOR,
+                _ => color.0,
+            };
+            if span_color.0 != target {
+                span_color.0 = target;
+            }
+        }
+    }
+}
+
+/// Let the platform input method compose text while a field is focused
+pub fn enable_ime(focus: Res<InputFocus>, mut windows: Query<&mut Window>) {
+    if !focus.is_changed() {
+        return;
+    }
+    for mut window in windows.iter_mut() {
+        window.ime_enabled = focus.is_focused();
+    }
+}
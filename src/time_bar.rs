@@ -0,0 +1,279 @@
+//! Time bar along the bottom of the window: the simulation date and time, playback buttons
+//! (reverse, pause, 1x, 60x, 3600x, back to now), a speed slider, a "Go to" field and a
+//! scrubber over a day either side of the simulation time
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::Settings;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{PropagationSchedule, MAX_PROPAGATION_DAYS};
+use crate::text_input::InputFocus;
+use crate::time_simulation::{self, TimeSimulation, MAX_SPEED, MIN_SPEED};
+use crate::ui::{anchored_panel, INVALID_TEXT_COLOR};
+
+/// Stellarium-like time bar driving the simulation clock
+pub struct TimeBarPlugin;
+
+impl Plugin for TimeBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let enabled = app.world().resource::<Settings>().time.time_bar;
+
+        app.insert_resource(TimeBar {
+            enabled,
+            time_jump: String::new(),
+            scrub_anchor: None,
+        })
+        .add_systems(Update, toggle_time_bar)
+        .add_systems(EguiPrimaryContextPass, time_bar_panel);
+    }
+}
+
+/// The scrubber spans this many hours either side of the simulation time
+const SCRUB_RANGE_HOURS: f64 = 24.0;
+const BAR_HEIGHT: f32 = 36.0;
+/// Gap between the bar and the window edges
+const BAR_MARGIN: f32 = 10.0;
+const BAR_PADDING: i8 = 8;
+/// Room the bar takes at the bottom of the window, kept clear by the panels docked above it
+pub const TIME_BAR_STRIP: f32 = BAR_MARGIN + BAR_HEIGHT;
+const SCRUBBER_HEIGHT: f32 = 14.0;
+const THUMB_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 128, 0);
+const WARNING_TEXT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 204, 51);
+
+/// Whether the time bar is shown (toggle with F1) and the text of its "Go to" field
+#[derive(Resource)]
+pub struct TimeBar {
+    pub enabled: bool,
+    pub time_jump: String,
+    /// Simulation time when the scrubber was grabbed, at its middle while it is dragged
+    scrub_anchor: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBarButton {
+    Reverse,
+    Pause,
+    RealTime,
+    Minutes,
+    Hours,
+    Now,
+}
+
+impl TimeBarButton {
+    const ALL: [TimeBarButton; 6] = [
+        TimeBarButton::Reverse,
+        TimeBarButton::Pause,
+        TimeBarButton::RealTime,
+        TimeBarButton::Minutes,
+        TimeBarButton::Hours,
+        TimeBarButton::Now,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            TimeBarButton::Reverse => "Reverse",
+            TimeBarButton::Pause => "Pause",
+            TimeBarButton::RealTime => "1x",
+            TimeBarButton::Minutes => "60x",
+            TimeBarButton::Hours => "3600x",
+            TimeBarButton::Now => "Now",
+        }
+    }
+
+    /// Playback speed set by the speed buttons
+    fn speed(self) -> Option<f64> {
+        match self {
+            TimeBarButton::RealTime => Some(1.0),
+            TimeBarButton::Minutes => Some(60.0),
+            TimeBarButton::Hours => Some(3600.0),
+            _ => None,
+        }
+    }
+
+    /// Keyboard shortcut shown when hovering the button
+    fn hint(self, keymap: &Keymap) -> String {
+        match self {
+            TimeBarButton::Reverse => format!("Reverse [{}]", keymap.label(Action::ToggleReverse)),
+            TimeBarButton::Pause => format!("Pause [{}]", keymap.label(Action::TogglePause)),
+            TimeBarButton::Now => "Back to the real time at 1x".to_string(),
+            _ => format!(
+                "Faster [{}]  slower [{}]",
+                keymap.label(Action::Faster),
+                keymap.label(Action::Slower),
+            ),
+        }
+    }
+
+    /// Whether the button shows the clock's current state
+    fn is_active(self, sim_time: &TimeSimulation) -> bool {
+        match self {
+            TimeBarButton::Reverse => sim_time.reversed,
+            TimeBarButton::Pause => sim_time.paused,
+            TimeBarButton::Now => false,
+            _ => !sim_time.paused && self.speed() == Some(sim_time.speed),
+        }
+    }
+
+    /// The speed buttons also resume a paused clock
+    fn press(self, sim_time: &mut TimeSimulation) {
+        match self {
+            TimeBarButton::Reverse => sim_time.toggle_reverse(),
+            TimeBarButton::Pause => sim_time.toggle_pause(),
+            TimeBarButton::Now => {
+                let now = sim_time.real_time();
+                sim_time.jump_to(now);
+                sim_time.set_speed(1.0);
+                sim_time.paused = false;
+                sim_time.reversed = false;
+            }
+            speed_button => {
+                if let Some(speed) = speed_button.speed() {
+                    sim_time.set_speed(speed);
+                    sim_time.paused = false;
+                }
+            }
+        }
+    }
+}
+
+/// Show or hide the time bar with F1
+pub fn toggle_time_bar(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut time_bar: ResMut<TimeBar>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleTimeBar, &keyboard_input) {
+        return;
+    }
+    time_bar.enabled = !time_bar.enabled;
+}
+
+/// The bar itself, spanning the bottom of the window
+pub fn time_bar_panel(
+    mut contexts: EguiContexts,
+    mut time_bar: ResMut<TimeBar>,
+    mut sim_time: ResMut<TimeSimulation>,
+    mut schedule: ResMut<PropagationSchedule>,
+    keymap: Res<Keymap>,
+) -> Result {
+    if !time_bar.enabled {
+        return Ok(());
+    }
+    let ctx = contexts.ctx_mut()?;
+    let width = ctx.content_rect().width() - 2.0 * BAR_MARGIN - 2.0 * BAR_PADDING as f32;
+
+    let state = if sim_time.paused {
+        "Paused".to_string()
+    } else if sim_time.reversed {
+        format!("Reverse {}x", sim_time.speed)
+    } else {
+        format!("{}x", sim_time.speed)
+    };
+    let readout = format!("{}  {}", sim_time.current_time().format("%Y-%m-%d %H:%M:%S UTC"), state);
+    // TLEs are only propagated a limited time from their epoch, so far jumps empty the sky
+    let days_from_now = (sim_time.current_time() - sim_time.real_time()).num_days();
+    let too_far = days_from_now.abs() > MAX_PROPAGATION_DAYS;
+
+    let frame = egui::Frame::window(&ctx.style()).inner_margin(egui::Margin::symmetric(BAR_PADDING, 0));
+    anchored_panel("time_bar", egui::Align2::LEFT_BOTTOM, [BAR_MARGIN, -BAR_MARGIN])
+        .frame(frame)
+        .show(ctx, |ui| {
+            let layout = egui::Layout::left_to_right(egui::Align::Center);
+            ui.allocate_ui_with_layout(egui::vec2(width, BAR_HEIGHT), layout, |ui| {
+                ui.set_min_size(egui::vec2(width, BAR_HEIGHT));
+
+                let readout = egui::RichText::new(readout).size(16.0);
+                let readout = if too_far { readout.color(WARNING_TEXT_COLOR) } else { readout };
+                let label = ui.add_sized([270.0, BAR_HEIGHT], egui::Label::new(readout));
+                if too_far {
+                    label.on_hover_text(format!(
+                        "{} days from today: satellites beyond {} days of their TLE epoch are hidden",
+                        days_from_now, MAX_PROPAGATION_DAYS,
+                    ));
+                }
+
+                for button in TimeBarButton::ALL {
+                    let response = ui
+                        .selectable_label(button.is_active(&sim_time), button.label())
+                        .on_hover_text(button.hint(&keymap));
+                    if response.clicked() {
+                        button.press(&mut sim_time);
+                    }
+                }
+
+                // Logarithmic from MIN_SPEED to MAX_SPEED, snapped to whole multipliers so the readout stays readable
+                let mut speed = sim_time.speed;
+                ui.spacing_mut().slider_width = 100.0;
+                let slider = egui::Slider::new(&mut speed, MIN_SPEED..=MAX_SPEED)
+                    .logarithmic(true)
+                    .show_value(false);
+                if ui.add(slider).on_hover_text("Speed").changed() {
+                    sim_time.set_speed(speed.round());
+                }
+
+                // Type a date/time, Enter to jump
+                let parse = |text: &str| time_simulation::parse_time_input(text, sim_time.current_time(), sim_time.real_time());
+                // In red while the text isn't a valid time (an empty field is neutral)
+                let invalid = !time_bar.time_jump.is_empty() && parse(&time_bar.time_jump).is_err();
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut time_bar.time_jump)
+                        .hint_text("Go to")
+                        .text_color_opt(invalid.then_some(INVALID_TEXT_COLOR))
+                        .desired_width(150.0),
+                );
+                if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                    match parse(&time_bar.time_jump) {
+                        Ok(time) => {
+                            println!("Jumping to {}", time.format("%Y-%m-%d %H:%M:%S UTC"));
+                            sim_time.jump_to(time);
+                            // Re-propagate right away instead of sliding from the old positions
+                            schedule.reset();
+                            time_bar.time_jump.clear();
+                        }
+                        Err(error) => println!("{}", error),
+                    }
+                }
+
+                ui.label(format!("-{:.0}h", SCRUB_RANGE_HOURS));
+                let plus = format!("+{:.0}h", SCRUB_RANGE_HOURS);
+                let scrubber_width = (ui.available_width() - 40.0).max(120.0);
+                scrubber(ui, scrubber_width, &mut time_bar.scrub_anchor, &mut sim_time);
+                ui.label(plus);
+            });
+        });
+    Ok(())
+}
+
+/// Track centered on the simulation time, clicked or dragged to move it by up to a day either
+/// way from where it was grabbed
+fn scrubber(ui: &mut egui::Ui, width: f32, anchor: &mut Option<DateTime<Utc>>, sim_time: &mut TimeSimulation) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, SCRUBBER_HEIGHT), egui::Sense::click_and_drag());
+    if response.is_pointer_button_down_on() {
+        let grabbed_at = *anchor.get_or_insert(sim_time.current_time());
+        if let Some(pointer) = response.interact_pointer_pos() {
+            let offset = ((pointer.x - rect.center().x) / rect.width()).clamp(-0.5, 0.5) as f64;
+            let hours = offset * 2.0 * SCRUB_RANGE_HOURS;
+            sim_time.jump_to(grabbed_at + Duration::seconds((hours * 3600.0) as i64));
+        }
+    } else if anchor.is_some() {
+        *anchor = None;
+    }
+
+    // Offset from where the scrubber was grabbed as a fraction of it, pinned (and dimmed) at
+    // its ends; at rest the scrubber is centered on the simulation time
+    let grabbed_at = anchor.unwrap_or(sim_time.current_time());
+    let hours = (sim_time.current_time() - grabbed_at).num_seconds() as f64 / 3600.0;
+    let fraction = (hours / SCRUB_RANGE_HOURS).clamp(-1.0, 1.0) * 0.5 + 0.5;
+    let color = if hours.abs() > SCRUB_RANGE_HOURS { THUMB_COLOR.gamma_multiply(0.4) } else { THUMB_COLOR };
+
+    let painter = ui.painter();
+    painter.rect_filled(rect, 0.0, ui.visuals().widgets.inactive.bg_fill);
+    painter.vline(rect.center().x, rect.y_range(), egui::Stroke::new(2.0, egui::Color32::from_white_alpha(128)));
+    let thumb_x = rect.left() + fraction as f32 * rect.width();
+    let thumb = egui::Rect::from_center_size(egui::pos2(thumb_x, rect.center().y), egui::vec2(6.0, rect.height()));
+    painter.rect_filled(thumb, 0.0, color);
+}
@@ -0,0 +1,338 @@
+use bevy::prelude::*;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+
+use crate::config::Settings;
+use crate::keymap::{Action, Keymap};
+use crate::text_input::InputFocus;
+
+/// Slowest playback speed (simulated seconds per real second)
+pub const MIN_SPEED: f64 = 1.0;
+/// Fastest playback speed (simulated seconds per real second)
+pub const MAX_SPEED: f64 = 10000.0;
+
+/// Preset speeds used by the faster/slower keyboard shortcuts
+const SPEED_STEPS: [f64; 12] = [
+    1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0, 10000.0,
+];
+
+/// Simulation clock, starting at the configured instant (now by default) and playback speed
+///
+/// A `TimeSimulation` inserted before the plugin is added is kept as is, so a test can
+/// pin the clock with `TimeSimulation::fixed` and get the same positions every run.
+/// Everything that needs the real time reads it from the clock too (`real_time`).
+pub struct TimePlugin;
+
+impl Plugin for TimePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<TimeSimulation>() {
+            app.init_resource::<Settings>();
+            let settings = &app.world().resource::<Settings>().time;
+            let mut time_simulation = TimeSimulation::new(start_time(settings.start.as_deref(), Utc::now()));
+            time_simulation.paused = settings.paused;
+            time_simulation.set_speed(settings.acceleration);
+            app.insert_resource(time_simulation);
+        }
+
+        app.add_systems(Update, (advance_simulation_time, time_control_keyboard));
+    }
+}
+
+/// Instant the clock starts at: the `[time] start` setting, or now
+fn start_time(start: Option<&str>, now: DateTime<Utc>) -> DateTime<Utc> {
+    let Some(text) = start else { return now };
+    match parse_time_input(text, now, now) {
+        Ok(time) => {
+            println!("✓ Simulation starts at {}", time.format("%Y-%m-%d %H:%M:%S UTC"));
+            time
+        }
+        Err(e) => {
+            eprintln!("Warning: Ignoring [time] start: {}", e);
+            now
+        }
+    }
+}
+
+/// Simulation clock driving satellite propagation, sun position and terminator
+///
+/// The clock starts at the real current time (or `[time] start`) and is then advanced every frame by
+/// the real frame delta multiplied by `speed`, so it can be paused, sped up or run
+/// backwards without losing the current simulated instant.
+#[derive(Resource)]
+pub struct TimeSimulation {
+    pub current_time: DateTime<Utc>,
+    pub speed: f64,
+    pub paused: bool,
+    pub reversed: bool,
+    /// The real time at a fixed instant, for a clock pinned by `fixed`
+    pinned_real_time: Option<DateTime<Utc>>,
+}
+
+impl Default for TimeSimulation {
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl TimeSimulation {
+    pub fn new(start_time: DateTime<Utc>) -> Self {
+        Self {
+            current_time: start_time,
+            speed: MIN_SPEED,
+            paused: false,
+            reversed: false,
+            pinned_real_time: None,
+        }
+    }
+
+    /// A paused clock at `time` whose real time is `time` too, for reproducible snapshots
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn fixed(time: DateTime<Utc>) -> Self {
+        Self {
+            paused: true,
+            pinned_real_time: Some(time),
+            ..Self::new(time)
+        }
+    }
+
+    /// The real (wall clock) time, or the instant a fixed clock is pinned to
+    pub fn real_time(&self) -> DateTime<Utc> {
+        self.pinned_real_time.unwrap_or_else(Utc::now)
+    }
+
+    /// Current simulated UTC time
+    pub fn current_time(&self) -> DateTime<Utc> {
+        self.current_time
+    }
+
+    /// Effective signed time rate (0 when paused, negative when running backwards)
+    pub fn rate(&self) -> f64 {
+        if self.paused {
+            0.0
+        } else if self.reversed {
+            -self.speed
+        } else {
+            self.speed
+        }
+    }
+
+    /// Advance the simulation clock by a real-time delta (in seconds)
+    pub fn advance(&mut self, real_seconds: f64) {
+        let simulated_seconds = real_seconds * self.rate();
+        if simulated_seconds == 0.0 {
+            return;
+        }
+        let nanos = (simulated_seconds * 1_000_000_000.0) as i64;
+        self.current_time += Duration::nanoseconds(nanos);
+    }
+
+    /// Set playback speed, clamped to the supported range
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    /// Step up to the next preset speed
+    pub fn faster(&mut self) {
+        if let Some(next) = SPEED_STEPS.iter().find(|&&s| s > self.speed + 1e-6) {
+            self.speed = *next;
+        }
+    }
+
+    /// Step down to the previous preset speed
+    pub fn slower(&mut self) {
+        if let Some(prev) = SPEED_STEPS.iter().rev().find(|&&s| s < self.speed - 1e-6) {
+            self.speed = *prev;
+        }
+    }
+
+    /// Jump the clock to an arbitrary instant, keeping speed and direction
+    pub fn jump_to(&mut self, time: DateTime<Utc>) {
+        self.current_time = time;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+}
+
+/// Parse a time typed by the user, relative to `now` (the current simulated time)
+///
+/// Accepted forms:
+/// - `now`: `real_now`, the real current time
+/// - `2024-05-01T12:30:00Z` (RFC 3339 / ISO 8601, any offset)
+/// - `2024-05-01 12:30[:00]` or `2024-05-01T12:30[:00]`, taken as UTC
+/// - `2024-05-01`: midnight UTC
+/// - `+6h`, `-1d`, `+30m`, `-90s`: offset from `now`
+pub fn parse_time_input(text: &str, now: DateTime<Utc>, real_now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Empty time".to_string());
+    }
+    if text.eq_ignore_ascii_case("now") {
+        return Ok(real_now);
+    }
+
+    if let Some(sign) = text.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let body = &text[1..];
+        let unit = body
+            .chars()
+            .last()
+            .ok_or_else(|| format!("Invalid offset \"{}\"", text))?;
+        let amount: i64 = body[..body.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| format!("Invalid offset \"{}\"", text))?;
+        let offset = match unit.to_ascii_lowercase() {
+            'd' => Duration::days(amount),
+            'h' => Duration::hours(amount),
+            'm' => Duration::minutes(amount),
+            's' => Duration::seconds(amount),
+            _ => return Err(format!("Unknown unit in \"{}\" (use d, h, m or s)", text)),
+        };
+        return Ok(if sign == '+' { now + offset } else { now - offset });
+    }
+
+    // The field is typed lowercase unless shift is held, so accept "t" and "z" too
+    let text = text.to_uppercase();
+    if let Ok(time) = DateTime::parse_from_rfc3339(&text) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let naive = text.trim_end_matches('Z').replace('T', " ");
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(&naive, format) {
+            return Ok(time.and_utc());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&naive, "%Y-%m-%d") {
+        return Ok(date.and_time(Default::default()).and_utc());
+    }
+    Err(format!("Invalid time \"{}\" (expected e.g. 2024-05-01T12:00:00Z or +6h)", text))
+}
+
+/// Advance the simulation clock using the real frame delta
+pub fn advance_simulation_time(time: Res<Time>, mut sim_time: ResMut<TimeSimulation>) {
+    sim_time.advance(time.delta_secs_f64());
+}
+
+/// Keyboard shortcuts for time control (defaults, see `keymap`)
+/// - Space: pause / play
+/// - `+` / `-`: faster / slower
+/// - R: toggle reverse playback
+pub fn time_control_keyboard(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut sim_time: ResMut<TimeSimulation>,
+) {
+    // Don't steal keys while the user is typing in the filter field
+    if focus.is_focused() {
+        return;
+    }
+
+    if keymap.just_pressed(Action::TogglePause, &keyboard_input) {
+        sim_time.toggle_pause();
+    }
+    if keymap.just_pressed(Action::Faster, &keyboard_input) {
+        sim_time.faster();
+    }
+    if keymap.just_pressed(Action::Slower, &keyboard_input) {
+        sim_time.slower();
+    }
+    if keymap.just_pressed(Action::ToggleReverse, &keyboard_input) {
+        sim_time.toggle_reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::config::MarkerStyle;
+    use crate::coords;
+    use crate::floating_origin::ScenePosition;
+    use crate::satellite::{Satellite, SatelliteAssets, SatelliteBundle};
+    use crate::sun;
+    use crate::tle_loader::TleData;
+
+    /// Vallado's eccentric near-Earth case (satellite 00005) and its TEME position (km)
+    /// 360 minutes after the epoch, from the SGP4 verification set
+    const LINE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+    const LINE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+    const POSITION_AT_360_MIN: [f64; 3] = [-7154.03120202, -3783.17682504, -3536.19412294];
+
+    fn satellite() -> Satellite {
+        let tle = TleData {
+            line1: LINE1.to_string(),
+            line2: LINE2.to_string(),
+            name: "VANGUARD 1".to_string(),
+            omm: None,
+        };
+        Satellite::new(tle.name.clone(), tle.to_elements().unwrap())
+    }
+
+    /// An app whose clock is pinned to `time`, with the sun following it
+    fn fixed_app(time: DateTime<Utc>) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeSimulation::fixed(time))
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .add_systems(Update, (advance_simulation_time, sun::update_sun_position).chain());
+        app.world_mut().spawn((DirectionalLight::default(), Transform::default(), Name::new("Sun")));
+        app
+    }
+
+    #[test]
+    fn fixed_clock_pins_simulated_and_real_time() {
+        let time = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut app = fixed_app(time);
+        for _ in 0..3 {
+            app.update();
+        }
+        let clock = app.world().resource::<TimeSimulation>();
+        assert_eq!(clock.current_time(), time);
+        assert_eq!(clock.real_time(), time);
+        assert_eq!(parse_time_input("now", time, clock.real_time()), Ok(time));
+        assert_eq!(parse_time_input("-6h", time, clock.real_time()), Ok(time - Duration::hours(6)));
+    }
+
+    #[test]
+    fn satellites_and_sun_are_placed_at_the_fixed_instant() {
+        let time = satellite().elements.datetime.and_utc() + Duration::minutes(360);
+        let mut app = fixed_app(time);
+        app.world_mut()
+            .run_system_once(
+                |mut commands: Commands,
+                 mut meshes: ResMut<Assets<Mesh>>,
+                 mut materials: ResMut<Assets<StandardMaterial>>,
+                 sim_time: Res<TimeSimulation>| {
+                    let assets = SatelliteAssets::new(&mut meshes, &mut materials, MarkerStyle::Sphere);
+                    commands.spawn(SatelliteBundle::new(satellite(), &assets, sim_time.current_time()));
+                },
+            )
+            .unwrap();
+        app.update();
+
+        let world = app.world_mut();
+        let (satellite, position) = world.query::<(&Satellite, &ScenePosition)>().single(world).unwrap();
+        let teme = satellite.position.unwrap();
+        assert_eq!(satellite.last_update, time);
+        // WGS72 reference against the WGS84 propagator: a few tens of meters apart
+        assert!((teme - nalgebra::Vector3::from(POSITION_AT_360_MIN)).norm() < 0.1, "TEME position {:?}", teme);
+        assert!(position.0.distance(coords::teme_to_scene_f64(teme, time)) < 1e-6);
+
+        // 2000-06-28 00:50 UTC, a week after the June solstice: local noon over the western
+        // Pacific, a little late as the equation of time is -3 minutes
+        let sun = world
+            .query::<(&Transform, &Name)>()
+            .iter(world)
+            .find(|(_, name)| name.as_str() == "Sun")
+            .map(|(transform, _)| transform.translation.normalize())
+            .unwrap();
+        let subsolar = coords::scene_to_geodetic(sun * coords::km_to_scene(coords::EARTH_RADIUS_KM as f32), coords::GlobeShape::Sphere);
+        assert!((subsolar.latitude_deg - 23.3).abs() < 0.2, "subsolar latitude {}", subsolar.latitude_deg);
+        assert!((subsolar.longitude_deg - 168.2).abs() < 0.5, "subsolar longitude {}", subsolar.longitude_deg);
+    }
+}
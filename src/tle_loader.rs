@@ -1,51 +1,663 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::catalog::{self, DroppedObject};
+
+/// Satellites keyed by NORAD catalog number
+///
+/// Names aren't unique (payloads and their debris, renamed objects), catalog numbers are.
+pub type TleSet = HashMap<u64, TleData>;
+
+/// Gzipped three-line TLE text shipped with the app, used when there is no network and no cache
+pub const SNAPSHOT_FILE: &str = "assets/tle_snapshot.txt.gz";
+
+/// Whether loaded TLE data is current or an offline fallback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TleOrigin {
+    /// A fresh cache or a new download
+    Current,
+    /// The cache, past its lifetime, because the download failed (with its download date)
+    ExpiredCache(DateTime<Utc>),
+    /// The bundled snapshot (with the epoch of its newest elements)
+    Snapshot(DateTime<Utc>),
+}
+
+impl TleOrigin {
+    /// Date of the data when it was loaded offline, None if it is current
+    pub fn offline_date(&self) -> Option<DateTime<Utc>> {
+        match self {
+            TleOrigin::Current => None,
+            TleOrigin::ExpiredCache(date) | TleOrigin::Snapshot(date) => Some(*date),
+        }
+    }
+}
+
+/// One satellite's data as a source returns it
+#[derive(Debug, Clone)]
+pub struct TleRecord {
+    pub norad_id: u64,
+    pub data: TleData,
+}
+
+/// A provider of current orbital data, asked for it when the cache expires
+///
+/// Sources run on a background thread, and may block on the network.
+pub trait TleSource: Send + Sync {
+    /// Short lowercase name, used in messages and to keep each source's cache apart
+    fn name(&self) -> &str;
+
+    /// What exactly is fetched (e.g. the groups), kept in the cache so a configuration
+    /// change isn't answered with the old data
+    fn description(&self) -> String {
+        self.name().to_string()
+    }
+
+    /// Get the records; problems worked around along the way go to `warnings`
+    fn fetch(&self, warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>>;
+}
+
+fn into_records(satellites: TleSet) -> Vec<TleRecord> {
+    satellites
+        .into_iter()
+        .map(|(norad_id, data)| TleRecord { norad_id, data })
+        .collect()
+}
+
+/// One entry per satellite: where records repeat, the newest elements win
+///
+/// Epochs are only parsed for repeated satellites; records that don't parse lose
+/// against ones that do, and the first of equal epochs is kept.
+pub fn merge_newest(records: impl IntoIterator<Item = TleRecord>) -> TleSet {
+    let mut merged: TleSet = HashMap::new();
+    for record in records {
+        match merged.get(&record.norad_id) {
+            Some(existing) if existing.epoch() >= record.data.epoch() => {}
+            _ => {
+                merged.insert(record.norad_id, record.data);
+            }
+        }
+    }
+    merged
+}
+
+/// Celestrak GP data by group (see celestrak.org/NORAD/elements)
+pub struct Celestrak {
+    name: String,
+    groups: Vec<String>,
+}
+
+impl Celestrak {
+    pub fn new(groups: Vec<String>) -> Self {
+        Self {
+            name: "celestrak".to_string(),
+            groups,
+        }
+    }
+
+    /// Name another set of groups so it keeps its own cache file (e.g. "celestrak-debris")
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Download one group as legacy three-line TLE text
+    fn download_tle_group(group: &str) -> Result<TleSet, Box<dyn std::error::Error>> {
+        let url = format!("https://celestrak.org/NORAD/elements/gp.php?GROUP={}&FORMAT=tle", group);
+        let response = reqwest::blocking::get(&url)?;
+        let text = response.text()?;
+        Ok(TleLoader::parse_tle_text(&text))
+    }
+}
+
+impl TleSource for Celestrak {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> String {
+        format!("{} ({})", self.name, self.groups.join(", "))
+    }
+
+    /// OMM JSON is preferred, with legacy TLE text as a fallback
+    fn fetch(&self, warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+        let mut records = Vec::new();
+        for group in &self.groups {
+            println!("Downloading OMM data from Celestrak (group: {})...", group);
+            let url = format!("https://celestrak.org/NORAD/elements/gp.php?GROUP={}&FORMAT=json", group);
+            match load_omm_json(&url) {
+                Ok((data, skipped)) => {
+                    if skipped > 0 {
+                        warnings.push(format!("Skipped {} OMM records that couldn't be parsed", skipped));
+                    }
+                    records.extend(into_records(data));
+                }
+                Err(e) => {
+                    warnings.push(format!("OMM download failed ({}). Falling back to TLE format", e));
+                    records.extend(into_records(Self::download_tle_group(group)?));
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// A local file: three-line TLE text or an OMM JSON array, optionally gzipped (".gz")
+pub struct TleFile {
+    path: String,
+}
+
+impl TleFile {
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+impl TleSource for TleFile {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn description(&self) -> String {
+        format!("file ({})", self.path)
+    }
+
+    fn fetch(&self, warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+        let text = read_text_file(&self.path)?;
+        let satellites = if text.trim_start().starts_with('[') {
+            let (satellites, skipped) = parse_omm_text(&text)?;
+            if skipped > 0 {
+                warnings.push(format!("Skipped {} OMM records of {} that couldn't be parsed", skipped, self.path));
+            }
+            satellites
+        } else {
+            TleLoader::parse_tle_text(&text)
+        };
+        if satellites.is_empty() {
+            return Err(format!("No valid TLEs in {}", self.path).into());
+        }
+        println!("✓ Read {} satellites from {}", satellites.len(), self.path);
+        Ok(into_records(satellites))
+    }
+}
+
+/// Login endpoint of Space-Track, which also runs the query passed along with the credentials
+const SPACE_TRACK_LOGIN_URL: &str = "https://www.space-track.org/ajaxauth/login";
+/// Latest elements of every object still in orbit (updated within 30 days), as three-line TLEs
+const SPACE_TRACK_QUERY_URL: &str = "https://www.space-track.org/basicspacedata/query/class/gp/decay_date/null-val/epoch/%3Enow-30/orderby/norad_cat_id/format/3le";
+
+/// The full catalog from Space-Track (an account from space-track.org is needed)
+///
+/// Space-Track asks for bulk queries like this one to be made at most once an hour,
+/// which the cache takes care of.
+pub struct SpaceTrack {
+    username: String,
+    password: String,
+}
+
+impl SpaceTrack {
+    pub fn new(username: String, password: String) -> Self {
+        Self { username, password }
+    }
+}
+
+impl TleSource for SpaceTrack {
+    fn name(&self) -> &str {
+        "spacetrack"
+    }
+
+    fn fetch(&self, _warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+        if self.username.is_empty() {
+            return Err("No Space-Track credentials set".into());
+        }
+        println!("Downloading the catalog from Space-Track...");
+        let client = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(300))
+            .build()?;
+        let text = client
+            .post(SPACE_TRACK_LOGIN_URL)
+            .form(&[
+                ("identity", self.username.as_str()),
+                ("password", self.password.as_str()),
+                ("query", SPACE_TRACK_QUERY_URL),
+            ])
+            .send()?
+            .error_for_status()?
+            .text()?;
+        // A failed login still answers 200, with {"Login":"Failed"}
+        if text.trim_start().starts_with('{') {
+            return Err(format!("Space-Track login failed: {}", text.trim()).into());
+        }
+
+        // Name lines of the 3LE format start with "0 "
+        let text: String = text
+            .lines()
+            .map(|line| line.strip_prefix("0 ").unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(into_records(TleLoader::parse_tle_text(&text)))
+    }
+}
+
+/// TLEs of a fixed list of satellites from the N2YO API (an API key from n2yo.com is needed)
+///
+/// The API has no group queries and counts requests per hour, so it suits a short watch list.
+#[cfg(feature = "n2yo")]
+pub struct N2yo {
+    api_key: String,
+    norad_ids: Vec<u64>,
+}
+
+#[cfg(feature = "n2yo")]
+impl N2yo {
+    pub fn new(api_key: String, norad_ids: Vec<u64>) -> Self {
+        Self { api_key, norad_ids }
+    }
+}
+
+#[cfg(feature = "n2yo")]
+impl TleSource for N2yo {
+    fn name(&self) -> &str {
+        "n2yo"
+    }
+
+    fn description(&self) -> String {
+        let ids: Vec<String> = self.norad_ids.iter().map(u64::to_string).collect();
+        format!("n2yo ({})", ids.join(", "))
+    }
+
+    fn fetch(&self, warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+        #[derive(Deserialize)]
+        struct Info {
+            satname: String,
+        }
+        #[derive(Deserialize)]
+        struct Reply {
+            info: Info,
+            tle: String,
+        }
+
+        if self.api_key.is_empty() {
+            return Err("No N2YO API key set".into());
+        }
+        println!("Downloading TLEs of {} satellites from N2YO...", self.norad_ids.len());
+        let mut records = Vec::new();
+        for norad_id in &self.norad_ids {
+            // The key follows the path with '&', as in the N2YO documentation
+            let url = format!("https://api.n2yo.com/rest/v1/satellite/tle/{}&apiKey={}", norad_id, self.api_key);
+            let reply: Reply = reqwest::blocking::get(&url)?.error_for_status()?.json()?;
+            let mut lines = reply.tle.lines().map(str::trim);
+            match (lines.next(), lines.next()) {
+                (Some(line1), Some(line2)) if !line1.is_empty() => records.push(TleRecord {
+                    norad_id: *norad_id,
+                    data: TleData {
+                        name: reply.info.satname,
+                        line1: line1.to_string(),
+                        line2: line2.to_string(),
+                        omm: None,
+                    },
+                }),
+                _ => warnings.push(format!("N2YO has no TLE for NORAD {}", norad_id)),
+            }
+        }
+
+        if records.is_empty() {
+            return Err("N2YO returned no TLEs".into());
+        }
+        Ok(records)
+    }
+}
+
+/// Records given up front, for tests and for data built by other code
+pub struct InMemory {
+    records: Vec<TleRecord>,
+}
+
+impl InMemory {
+    pub fn new(records: Vec<TleRecord>) -> Self {
+        Self { records }
+    }
+}
+
+impl TleSource for InMemory {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn fetch(&self, _warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+        Ok(self.records.clone())
+    }
+}
+
+/// Several sources at once, merged with the newest elements of each satellite winning
+///
+/// A failing source is reported as a warning as long as another one answers.
+pub struct Composite {
+    name: String,
+    sources: Vec<Box<dyn TleSource>>,
+}
+
+impl Composite {
+    pub fn new(sources: Vec<Box<dyn TleSource>>) -> Self {
+        let name = sources.iter().map(|source| source.name()).collect::<Vec<_>>().join("+");
+        Self { name, sources }
+    }
+}
+
+impl TleSource for Composite {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> String {
+        self.sources.iter().map(|source| source.description()).collect::<Vec<_>>().join(" + ")
+    }
+
+    fn fetch(&self, warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+        let mut records = Vec::new();
+        let mut errors = Vec::new();
+        for source in &self.sources {
+            match source.fetch(warnings) {
+                Ok(fetched) => records.extend(fetched),
+                Err(e) => errors.push(format!("{}: {}", source.name(), e)),
+            }
+        }
+        if errors.len() == self.sources.len() {
+            return Err(format!("Every TLE source failed ({})", errors.join("; ")).into());
+        }
+        warnings.extend(errors.into_iter().map(|error| format!("TLE source failed, using the others ({})", error)));
+        Ok(into_records(merge_newest(records)))
+    }
+}
+
+/// Read a text file, gunzipping it if its name ends in ".gz"
+fn read_text_file(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if !path.ends_with(".gz") {
+        return Ok(fs::read_to_string(path)?);
+    }
+    let mut text = String::new();
+    flate2::read::GzDecoder::new(fs::File::open(path)?).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Load OMM records from a Celestrak JSON file or URL (a JSON array of OMM objects),
+/// with the number of records skipped because they don't parse into `sgp4::Elements`
+fn load_omm_json(url_or_path: &str) -> Result<(TleSet, usize), Box<dyn std::error::Error>> {
+    let text = if url_or_path.starts_with("http://") || url_or_path.starts_with("https://") {
+        reqwest::blocking::get(url_or_path)?.error_for_status()?.text()?
+    } else {
+        fs::read_to_string(url_or_path)?
+    };
+    let (satellites, skipped) = parse_omm_text(&text)?;
+    if satellites.is_empty() {
+        return Err(format!("No valid OMM records in {}", url_or_path).into());
+    }
+    Ok((satellites, skipped))
+}
+
+/// Parse a JSON array of OMM objects, counting the records that don't parse
+fn parse_omm_text(text: &str) -> Result<(TleSet, usize), Box<dyn std::error::Error>> {
+    let records: Vec<serde_json::Value> = serde_json::from_str(text)?;
+    let mut satellites = HashMap::new();
+    let mut skipped = 0;
+    for record in records {
+        let elements: sgp4::Elements = match serde_json::from_value(record.clone()) {
+            Ok(elements) => elements,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+        let name = elements
+            .object_name
+            .clone()
+            .unwrap_or_else(|| format!("NORAD {}", elements.norad_id));
+        satellites.insert(
+            elements.norad_id,
+            TleData {
+                name,
+                line1: String::new(),
+                line2: String::new(),
+                omm: Some(record),
+            },
+        );
+    }
+    Ok((satellites, skipped))
+}
+
+/// Orbital data of one satellite, either as TLE lines or as an OMM record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TleData {
+    #[serde(default)]
     pub line1: String,
+    #[serde(default)]
     pub line2: String,
     pub name: String,
+    /// OMM record (Celestrak JSON format), used instead of the TLE lines when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub omm: Option<serde_json::Value>,
 }
 
 impl TleData {
     pub fn to_elements(&self) -> Result<sgp4::Elements, String> {
+        if let Some(omm) = &self.omm {
+            return serde_json::from_value(omm.clone())
+                .map_err(|e| format!("OMM parsing error: {}", e));
+        }
+
         sgp4::Elements::from_tle(
             None,
             self.line1.as_bytes(),
             self.line2.as_bytes(),
         ).map_err(|e| format!("TLE parsing error: {:?}", e))
     }
+
+    /// Epoch of the elements, None if they don't parse
+    pub fn epoch(&self) -> Option<chrono::NaiveDateTime> {
+        self.to_elements().ok().map(|elements| elements.datetime)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+/// NORAD catalog number from columns 3-7 of TLE line 1
+///
+/// Accepts the Alpha-5 scheme used past 99999, where a leading letter stands for the
+/// ten-thousands (A = 10 … Z = 33, skipping I and O): "A0001" is 100001.
+pub fn norad_id_from_line1(line1: &str) -> Option<u64> {
+    let field = line1.get(2..7)?.trim();
+    let mut chars = field.chars();
+    let first = chars.next()?;
+    let rest = chars.as_str();
+    if first.is_ascii_alphabetic() {
+        let letter = first.to_ascii_uppercase();
+        if letter == 'I' || letter == 'O' {
+            return None;
+        }
+        // Letters after I and O shift down past the skipped ones
+        let skipped = (letter > 'I') as u64 + (letter > 'O') as u64;
+        let prefix = 10 + (letter as u64 - 'A' as u64) - skipped;
+        if rest.len() != 4 {
+            return None;
+        }
+        return Some(prefix * 10_000 + rest.parse::<u64>().ok()?);
+    }
+    field.parse().ok()
+}
+
+/// First bytes of a cache file
+const CACHE_MAGIC: &[u8; 8] = b"AISTTLE\0";
+/// Layout of the cache file, bumped when it changes; other versions are downloaded again
+const CACHE_VERSION: u32 = 1;
+
+/// Cached data with its metadata
+#[derive(Debug)]
 struct TleCache {
-    data: HashMap<String, TleData>,
-    downloaded_at: i64, // Unix timestamp
+    metadata: CacheMetadata,
+    data: TleSet,
+}
+
+/// Header of the cache file, readable without unpacking the data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMetadata {
+    /// Unix timestamp of the download
+    pub downloaded_at: i64,
+    /// Source the data came from, with its groups or files (`TleSource::description`)
+    pub source: String,
+    pub satellites: usize,
+    /// CRC-32 of the compressed data that follows the header
+    crc32: u32,
+}
+
+impl CacheMetadata {
+    pub fn downloaded_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::<Utc>::from_timestamp(self.downloaded_at, 0)
+    }
+}
+
+/// Cache written before the versioned format: one pretty-printed JSON object
+#[derive(Deserialize)]
+struct LegacyTleCache {
+    data: TleSet,
+    downloaded_at: i64,
+}
+
+/// Write a cache file:
+///
+/// ```text
+/// magic "AISTTLE\0" | format version (u32 LE) | metadata length (u32 LE) | metadata JSON | gzipped JSON data
+/// ```
+///
+/// The file is written next to its destination, then renamed over it, so a crash never leaves half a cache.
+fn write_cache_file(path: &Path, downloaded_at: i64, source: String, data: &TleSet) -> Result<(), Box<dyn std::error::Error>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    serde_json::to_writer(&mut encoder, data)?;
+    let compressed = encoder.finish()?;
+    let mut crc = flate2::Crc::new();
+    crc.update(&compressed);
+
+    let metadata = serde_json::to_vec(&CacheMetadata {
+        downloaded_at,
+        source,
+        satellites: data.len(),
+        crc32: crc.sum(),
+    })?;
+    let mut contents = Vec::with_capacity(16 + metadata.len() + compressed.len());
+    contents.extend_from_slice(CACHE_MAGIC);
+    contents.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    contents.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+    contents.extend_from_slice(&metadata);
+    contents.extend_from_slice(&compressed);
+
+    let partial = path.with_extension("part");
+    fs::write(&partial, contents)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Read the header of a cache file, returning the metadata and where the data starts
+fn read_cache_header(contents: &[u8]) -> Result<(CacheMetadata, usize), Box<dyn std::error::Error>> {
+    if contents.get(..8) != Some(CACHE_MAGIC.as_slice()) {
+        return Err("Not a TLE cache file".into());
+    }
+    let word = |offset: usize| -> Result<u32, Box<dyn std::error::Error>> {
+        let bytes = contents.get(offset..offset + 4).ok_or("Truncated cache file")?;
+        Ok(u32::from_le_bytes(bytes.try_into()?))
+    };
+    let version = word(8)?;
+    if version != CACHE_VERSION {
+        return Err(format!("Cache format version {} is not supported (expected {})", version, CACHE_VERSION).into());
+    }
+    let end = 16 + word(12)? as usize;
+    let metadata = serde_json::from_slice(contents.get(16..end).ok_or("Truncated cache file")?)?;
+    Ok((metadata, end))
+}
+
+/// Read and check a whole cache file
+fn read_cache_file(path: &Path) -> Result<TleCache, Box<dyn std::error::Error>> {
+    let contents = fs::read(path)?;
+    let (metadata, start) = read_cache_header(&contents)?;
+    let compressed = &contents[start..];
+    let mut crc = flate2::Crc::new();
+    crc.update(compressed);
+    if crc.sum() != metadata.crc32 {
+        return Err("Cache file is corrupted (checksum mismatch)".into());
+    }
+
+    let data: TleSet = serde_json::from_reader(flate2::read::GzDecoder::new(compressed))?;
+    if data.len() != metadata.satellites {
+        return Err(format!("Cache file holds {} satellites, its header says {}", data.len(), metadata.satellites).into());
+    }
+    Ok(TleCache { metadata, data })
 }
 
 pub struct TleLoader {
     cache_dir: String,
     cache_file: String,
     cache_max_age_hours: u64,
+    source: Box<dyn TleSource>,
+    /// Problems worked around while loading (cache, format fallback, skipped records)
+    warnings: RefCell<Vec<String>>,
+}
+
+impl Default for TleLoader {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TleLoader {
     pub fn new() -> Self {
         let cache_dir = "cache".to_string();
-        let cache_file = format!("{}/tle_cache.json", cache_dir);
+        let cache_file = format!("{}/tle_cache.bin", cache_dir);
         
         Self {
             cache_dir,
             cache_file,
             cache_max_age_hours: 24, // Cache for 24 hours
+            source: Box::new(Celestrak::new(vec!["active".to_string()])),
+            warnings: RefCell::new(Vec::new()),
         }
     }
 
+    /// Set how long cached TLE data stays valid
+    pub fn with_cache_max_age_hours(mut self, hours: u64) -> Self {
+        self.cache_max_age_hours = hours;
+        self
+    }
+
+    /// Set which Celestrak groups to download (e.g. "active", "stations", "starlink")
+    pub fn with_groups(self, groups: Vec<String>) -> Self {
+        if groups.is_empty() {
+            return self;
+        }
+        self.with_source(Box::new(Celestrak::new(groups)))
+    }
+
+    /// Download from another source than Celestrak; each source keeps its own cache file
+    pub fn with_source(mut self, source: Box<dyn TleSource>) -> Self {
+        self.cache_file = match source.name() {
+            "celestrak" => format!("{}/tle_cache.bin", self.cache_dir),
+            name => format!("{}/tle_cache_{}.bin", self.cache_dir, name),
+        };
+        self.source = source;
+        self
+    }
+
+    /// Print a warning and keep it for `take_warnings`
+    fn warn(&self, message: String) {
+        eprintln!("Warning: {}", message);
+        self.warnings.borrow_mut().push(message);
+    }
+
+    /// Warnings raised by the loads since the last call
+    pub fn take_warnings(&self) -> Vec<String> {
+        self.warnings.take()
+    }
+
     /// Get cache directory path
     fn cache_path(&self) -> &Path {
         Path::new(&self.cache_dir)
@@ -56,83 +668,177 @@ impl TleLoader {
         Path::new(&self.cache_file)
     }
 
-    /// Check if cache exists and is still valid
-    fn is_cache_valid(&self) -> bool {
-        let cache_path = self.cache_file_path();
-        
-        if !cache_path.exists() {
-            return false;
-        }
+    /// Cache written by earlier versions, next to the current one
+    fn legacy_cache_file_path(&self) -> std::path::PathBuf {
+        self.cache_file_path().with_extension("json")
+    }
 
-        // Check file modification time
-        if let Ok(metadata) = fs::metadata(cache_path) {
-            if let Ok(modified) = metadata.modified() {
-                if let Ok(duration) = modified.duration_since(UNIX_EPOCH) {
-                    let cache_age_hours = duration.as_secs() / 3600;
-                    let current_time = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs() / 3600;
-                    
-                    let age = current_time.saturating_sub(cache_age_hours);
-                    return age < self.cache_max_age_hours;
-                }
+    /// Convert a cache left by an earlier version, keeping its download date
+    ///
+    /// The data is assumed to come from the current source, since each source has its own file.
+    fn migrate_legacy_cache(&self) {
+        let legacy_path = self.legacy_cache_file_path();
+        if self.cache_file_path().exists() || !legacy_path.exists() {
+            return;
+        }
+        let migrated = fs::read_to_string(&legacy_path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str::<LegacyTleCache>(&contents).map_err(|e| e.to_string()))
+            .and_then(|legacy| {
+                write_cache_file(self.cache_file_path(), legacy.downloaded_at, self.source.description(), &legacy.data)
+                    .map_err(|e| e.to_string())
+            });
+        match migrated {
+            Ok(()) => {
+                let _ = fs::remove_file(&legacy_path);
+                println!("✓ Migrated the TLE cache to {}", self.cache_file);
             }
+            Err(e) => self.warn(format!("Failed to migrate {}: {}", legacy_path.display(), e)),
         }
-        
-        false
+    }
+
+    /// Metadata of the cache, if there is a readable one
+    pub fn cache_metadata(&self) -> Option<CacheMetadata> {
+        self.migrate_legacy_cache();
+        let mut header = Vec::new();
+        // The header is small; the data after it isn't needed
+        fs::File::open(self.cache_file_path()).ok()?.take(64 * 1024).read_to_end(&mut header).ok()?;
+        read_cache_header(&header).ok().map(|(metadata, _)| metadata)
+    }
+
+    /// When the cached data was downloaded, if there is a cache
+    pub fn cache_modified(&self) -> Option<SystemTime> {
+        let downloaded_at = u64::try_from(self.cache_metadata()?.downloaded_at).ok()?;
+        Some(UNIX_EPOCH + std::time::Duration::from_secs(downloaded_at))
+    }
+
+    /// Check if the cache exists, holds what the source would download, and is still valid
+    pub fn is_cache_valid(&self) -> bool {
+        let metadata = match self.cache_metadata() {
+            Some(metadata) => metadata,
+            None => return false,
+        };
+        if metadata.source != self.source.description() {
+            println!("Cache holds {}, not {}", metadata.source, self.source.description());
+            return false;
+        }
+        let age_hours = (Utc::now().timestamp() - metadata.downloaded_at).max(0) as u64 / 3600;
+        age_hours < self.cache_max_age_hours
     }
 
     /// Load TLE data from cache
-    fn load_from_cache(&self) -> Result<HashMap<String, TleData>, Box<dyn std::error::Error>> {
+    fn load_from_cache(&self) -> Result<TleSet, Box<dyn std::error::Error>> {
+        Ok(self.read_cache()?.data)
+    }
+
+    /// Read the cache file, whatever its age or source
+    fn read_cache(&self) -> Result<TleCache, Box<dyn std::error::Error>> {
+        self.migrate_legacy_cache();
         let cache_path = self.cache_file_path();
-        
         if !cache_path.exists() {
             return Err("Cache file does not exist".into());
         }
 
-        let contents = fs::read_to_string(cache_path)?;
-        let cache: TleCache = serde_json::from_str(&contents)?;
-        
-        println!("✓ Loaded {} satellites from cache (downloaded at {})", 
+        let cache = read_cache_file(cache_path)?;
+        println!("✓ Loaded {} satellites from cache ({}, downloaded at {})",
             cache.data.len(),
-            DateTime::<Utc>::from_timestamp(cache.downloaded_at, 0)
+            cache.metadata.source,
+            cache.metadata.downloaded_at()
                 .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
                 .unwrap_or_else(|| "unknown".to_string()));
-        
-        Ok(cache.data)
+
+        Ok(cache)
+    }
+
+    /// Read the bundled snapshot, returning it with the epoch of its newest elements
+    pub fn load_snapshot(path: &str) -> Result<(TleSet, DateTime<Utc>), Box<dyn std::error::Error>> {
+        let satellites = Self::parse_tle_text(&read_text_file(path)?);
+        let date = satellites
+            .values()
+            .filter_map(|tle| tle.to_elements().ok())
+            .map(|elements| elements.datetime.and_utc())
+            .max()
+            .ok_or_else(|| format!("No valid TLEs in {}", path))?;
+        Ok((satellites, date))
     }
 
     /// Save TLE data to cache
-    fn save_to_cache(&self, data: &HashMap<String, TleData>) -> Result<(), Box<dyn std::error::Error>> {
+    fn save_to_cache(&self, data: &TleSet) -> Result<(), Box<dyn std::error::Error>> {
         // Create cache directory if it doesn't exist
         if let Some(parent) = self.cache_path().parent() {
             fs::create_dir_all(parent)?;
         }
         fs::create_dir_all(self.cache_path())?;
 
-        let cache = TleCache {
-            data: data.clone(),
-            downloaded_at: Utc::now().timestamp(),
-        };
+        write_cache_file(self.cache_file_path(), Utc::now().timestamp(), self.source.description(), data)?;
 
-        let json = serde_json::to_string_pretty(&cache)?;
-        fs::write(self.cache_file_path(), json)?;
-        
         println!("✓ Cached {} satellites to {}", data.len(), self.cache_file);
         
         Ok(())
     }
 
-    /// Download TLE data from Celestrak
-    fn download_tle_data(&self) -> Result<HashMap<String, TleData>, Box<dyn std::error::Error>> {
-        println!("Downloading TLE data from Celestrak...");
-        
-        // Download active satellites TLE data
-        let url = "https://celestrak.org/NORAD/elements/gp.php?GROUP=active&FORMAT=tle";
-        let response = reqwest::blocking::get(url)?;
-        let text = response.text()?;
+    /// Log of the objects that dropped out of this source's downloads (see `catalog`)
+    fn drop_log_path(&self) -> std::path::PathBuf {
+        self.cache_file_path().with_extension("dropped.json")
+    }
+
+    /// Objects that dropped out of this source's downloads recently, in the order noticed
+    pub fn dropped_objects(&self) -> Vec<DroppedObject> {
+        catalog::read_drop_log(&self.drop_log_path())
+    }
+
+    /// Log the objects of the cached download missing from a new one
+    fn log_dropped(&self, current: &TleSet) {
+        // Only a cache of the same source lists the same objects
+        let previous = match read_cache_file(self.cache_file_path()) {
+            Ok(cache) if cache.metadata.source == self.source.description() => cache.data,
+            _ => return,
+        };
+        match catalog::update_drop_log(&self.drop_log_path(), &previous, current, Utc::now().timestamp()) {
+            Ok(0) => {}
+            Ok(dropped) => println!("{} objects dropped out of the catalog since the last download", dropped),
+            Err(e) => self.warn(format!("Failed to update the dropped objects log: {}", e)),
+        }
+    }
+
+    /// Load OMM records from a Celestrak JSON file or URL (a JSON array of OMM objects)
+    ///
+    /// Records that don't parse into `sgp4::Elements` are skipped.
+    pub fn load_omm_json(&self, url_or_path: &str) -> Result<TleSet, Box<dyn std::error::Error>> {
+        let (satellites, skipped) = load_omm_json(url_or_path)?;
+        if skipped > 0 {
+            self.warn(format!("Skipped {} OMM records that couldn't be parsed", skipped));
+        }
+        Ok(satellites)
+    }
+
+    /// Download data from the source and cache it
+    fn download_tle_data(&self) -> Result<TleSet, Box<dyn std::error::Error>> {
+        let mut warnings = Vec::new();
+        let result = self.source.fetch(&mut warnings);
+        // A source that failed would make all its satellites look dropped
+        let complete = warnings.is_empty();
+        for warning in warnings {
+            self.warn(warning);
+        }
+        // Celestrak groups overlap, and composite sources may repeat satellites
+        let satellites = merge_newest(result?);
 
+        println!("✓ Downloaded {} satellites from {}", satellites.len(), self.source.name());
+        if complete {
+            self.log_dropped(&satellites);
+        }
+
+        // Save to cache
+        if let Err(e) = self.save_to_cache(&satellites) {
+            self.warn(format!("Failed to save the TLE cache: {}", e));
+        }
+
+        Ok(satellites)
+    }
+
+    /// Parse three-line TLE text (name, line 1, line 2)
+    fn parse_tle_text(text: &str) -> TleSet {
         let mut satellites = HashMap::new();
         let lines: Vec<&str> = text.lines().collect();
         
@@ -150,13 +856,15 @@ impl TleLoader {
                 let line2 = lines[i + 2].trim().to_string();
                 
                 // Validate TLE format (line1 should start with "1 ", line2 with "2 ")
-                if line1.starts_with("1 ") && line2.starts_with("2 ") {
+                let valid = line1.starts_with("1 ") && line2.starts_with("2 ");
+                if let Some(norad_id) = norad_id_from_line1(&line1).filter(|_| valid) {
                     satellites.insert(
-                        name.clone(),
+                        norad_id,
                         TleData {
                             name,
                             line1,
                             line2,
+                            omm: None,
                         },
                     );
                 }
@@ -165,28 +873,29 @@ impl TleLoader {
             i += 3;
         }
 
-        println!("✓ Downloaded {} satellites from Celestrak", satellites.len());
-        
-        // Save to cache
-        if let Err(e) = self.save_to_cache(&satellites) {
-            eprintln!("Warning: Failed to save cache: {}", e);
-        }
-
-        Ok(satellites)
+        satellites
     }
 
     /// Load active satellites (with caching)
-    pub fn load_active_satellites(&self) -> Result<HashMap<String, TleData>, Box<dyn std::error::Error>> {
+    pub fn load_active_satellites(&self) -> Result<TleSet, Box<dyn std::error::Error>> {
+        self.load_with_fallback().map(|(data, _)| data)
+    }
+
+    /// Load active satellites (with caching), falling back to offline data
+    ///
+    /// When the download fails, an expired cache is used if there is one, then the
+    /// bundled snapshot, so there is always something to show.
+    pub fn load_with_fallback(&self) -> Result<(TleSet, TleOrigin), Box<dyn std::error::Error>> {
         // Check if cache is valid
         if self.is_cache_valid() {
             match self.load_from_cache() {
                 Ok(data) => {
                     println!("Using cached TLE data (cache is less than {} hours old)", 
                         self.cache_max_age_hours);
-                    return Ok(data);
+                    return Ok((data, TleOrigin::Current));
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to load cache: {}. Downloading fresh data...", e);
+                    self.warn(format!("Failed to load the TLE cache: {}. Downloading fresh data", e));
                 }
             }
         } else {
@@ -199,17 +908,169 @@ impl TleLoader {
         }
 
         // Download fresh data
-        self.download_tle_data()
+        let error = match self.download_tle_data() {
+            Ok(data) => return Ok((data, TleOrigin::Current)),
+            Err(e) => e,
+        };
+
+        if let Ok(cache) = self.read_cache() {
+            let date = cache.metadata.downloaded_at().unwrap_or_default();
+            self.warn(format!("TLE download failed ({}). Using the expired cache", error));
+            return Ok((cache.data, TleOrigin::ExpiredCache(date)));
+        }
+        match Self::load_snapshot(SNAPSHOT_FILE) {
+            Ok((data, date)) => {
+                self.warn(format!("TLE download failed ({}). Using the bundled snapshot", error));
+                println!("✓ Loaded {} satellites from {}", data.len(), SNAPSHOT_FILE);
+                Ok((data, TleOrigin::Snapshot(date)))
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to load {}: {}", SNAPSHOT_FILE, e);
+                Err(error)
+            }
+        }
     }
 
     /// Clear the cache (useful for testing or forcing refresh)
+    #[allow(dead_code)]
     pub fn clear_cache(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let cache_path = self.cache_file_path();
-        if cache_path.exists() {
-            fs::remove_file(cache_path)?;
-            println!("Cache cleared");
+        for cache_path in [self.cache_file_path().to_path_buf(), self.legacy_cache_file_path()] {
+            if cache_path.exists() {
+                fs::remove_file(cache_path)?;
+                println!("Cache cleared");
+            }
         }
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ISS elements (from the sgp4 documentation) with another name and epoch
+    fn record(norad_id: u64, name: &str, epoch: &str) -> TleRecord {
+        let omm = serde_json::json!({
+            "OBJECT_NAME": name,
+            "OBJECT_ID": "1998-067A",
+            "EPOCH": epoch,
+            "MEAN_MOTION": 15.49560532,
+            "ECCENTRICITY": 0.0001771,
+            "INCLINATION": 51.6435,
+            "RA_OF_ASC_NODE": 225.4004,
+            "ARG_OF_PERICENTER": 44.9625,
+            "MEAN_ANOMALY": 5.1087,
+            "EPHEMERIS_TYPE": 0,
+            "CLASSIFICATION_TYPE": "U",
+            "NORAD_CAT_ID": norad_id,
+            "ELEMENT_SET_NO": 999,
+            "REV_AT_EPOCH": 23587,
+            "BSTAR": 0.0049645,
+            "MEAN_MOTION_DOT": 0.00289036,
+            "MEAN_MOTION_DDOT": 0
+        });
+        TleRecord {
+            norad_id,
+            data: TleData {
+                name: name.to_string(),
+                line1: String::new(),
+                line2: String::new(),
+                omm: Some(omm),
+            },
+        }
+    }
+
+    struct Failing;
+
+    impl TleSource for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn fetch(&self, _warnings: &mut Vec<String>) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+            Err("offline".into())
+        }
+    }
+
+    #[test]
+    fn merge_keeps_newest_epoch() {
+        let older = record(25544, "old", "2020-07-12T01:19:07.402656");
+        let newer = record(25544, "new", "2020-07-13T01:19:07.402656");
+        for records in [vec![older.clone(), newer.clone()], vec![newer, older]] {
+            let merged = merge_newest(records);
+            assert_eq!(merged.len(), 1);
+            assert_eq!(merged[&25544].name, "new");
+        }
+    }
+
+    #[test]
+    fn merge_prefers_records_that_parse() {
+        let mut broken = record(25544, "broken", "2020-07-14T00:00:00");
+        broken.data.omm = Some(serde_json::json!({ "OBJECT_NAME": "broken" }));
+        let valid = record(25544, "valid", "2020-07-12T01:19:07.402656");
+        assert_eq!(merge_newest(vec![broken, valid])[&25544].name, "valid");
+    }
+
+    #[test]
+    fn composite_merges_sources() {
+        let composite = Composite::new(vec![
+            Box::new(InMemory::new(vec![
+                record(25544, "ISS old", "2020-07-12T00:00:00"),
+                record(20580, "HST", "2020-07-12T00:00:00"),
+            ])),
+            Box::new(InMemory::new(vec![record(25544, "ISS new", "2020-07-12T06:00:00")])),
+        ]);
+        assert_eq!(composite.name(), "memory+memory");
+
+        let mut warnings = Vec::new();
+        let merged = merge_newest(composite.fetch(&mut warnings).unwrap());
+        assert!(warnings.is_empty());
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[&25544].name, "ISS new");
+        assert_eq!(merged[&20580].name, "HST");
+    }
+
+    #[test]
+    fn composite_tolerates_a_failing_source() {
+        let composite = Composite::new(vec![
+            Box::new(Failing),
+            Box::new(InMemory::new(vec![record(25544, "ISS", "2020-07-12T00:00:00")])),
+        ]);
+        let mut warnings = Vec::new();
+        assert_eq!(composite.fetch(&mut warnings).unwrap().len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("failing: offline"));
+
+        let all_failing = Composite::new(vec![Box::new(Failing), Box::new(Failing)]);
+        assert!(all_failing.fetch(&mut Vec::new()).is_err());
+    }
+    #[test]
+    fn cache_file_round_trip_and_checks() {
+        let path = std::env::temp_dir().join(format!("tle_cache_test_{}.bin", std::process::id()));
+        let data = merge_newest(vec![
+            record(25544, "ISS", "2020-07-12T00:00:00"),
+            record(20580, "HST", "2020-07-12T00:00:00"),
+        ]);
+        write_cache_file(&path, 1_700_000_000, "memory".to_string(), &data).unwrap();
+
+        let cache = read_cache_file(&path).unwrap();
+        assert_eq!(cache.metadata.downloaded_at, 1_700_000_000);
+        assert_eq!(cache.metadata.source, "memory");
+        assert_eq!(cache.metadata.satellites, 2);
+        assert_eq!(cache.data[&25544].name, "ISS");
+
+        // A flipped bit in the data fails the checksum
+        let mut contents = fs::read(&path).unwrap();
+        let last = contents.len() - 1;
+        contents[last] ^= 1;
+        fs::write(&path, &contents).unwrap();
+        assert!(read_cache_file(&path).unwrap_err().to_string().contains("checksum"));
+
+        // Another format version is refused
+        contents[8] = 99;
+        fs::write(&path, &contents).unwrap();
+        assert!(read_cache_file(&path).unwrap_err().to_string().contains("version 99"));
+
+        let _ = fs::remove_file(&path);
+    }
+}
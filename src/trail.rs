@@ -0,0 +1,186 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+
+use crate::camera::CameraController;
+use crate::config::Settings;
+use crate::floating_origin::{FloatingOrigin, ScenePosition};
+use crate::ground_track::GroundTrackDisplay;
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::Selected;
+use crate::time_simulation::TimeSimulation;
+
+/// Fading trail behind the selected satellite, showing where it has been drawn
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        let minutes = app.world().resource::<Settings>().trail.minutes.max(0.0);
+
+        app.insert_resource(TrailLength(Duration::milliseconds((minutes * 60_000.0) as i64)))
+            .init_resource::<GroundTrackDisplay>()
+            .add_systems(Startup, spawn_trail)
+            .add_systems(Update, (
+                start_trail_history,
+                record_trail_history.after(update_satellite_positions),
+                update_trail.after(record_trail_history),
+            ));
+    }
+}
+
+/// Simulated time between two trail samples (seconds); a LEO satellite covers ~40 km
+const SAMPLE_SECONDS: i64 = 5;
+/// Trail width as a fraction of the camera distance, so it looks the same at any zoom
+const TRAIL_WIDTH_FRACTION: f32 = 0.003;
+/// Color of the newest end of the trail, fading to transparent at the oldest
+const TRAIL_COLOR: Color = Color::srgb(0.2, 1.0, 1.0);
+
+/// How far back the trail reaches, in simulated time (`[trail] minutes`)
+#[derive(Resource, Clone, Copy)]
+pub struct TrailLength(pub Duration);
+
+/// Ring buffer of where a satellite was drawn, oldest first
+#[derive(Component, Default)]
+pub struct PositionHistory {
+    pub samples: VecDeque<(DateTime<Utc>, DVec3)>,
+}
+
+impl PositionHistory {
+    /// Add a sample if the last one is old enough, forgetting those older than `length`
+    ///
+    /// Running the clock backward or jumping past the whole trail starts it over, since
+    /// the samples no longer describe the path that led here.
+    fn record(&mut self, time: DateTime<Utc>, position: DVec3, length: Duration) {
+        if let Some(&(last, _)) = self.samples.back() {
+            if time < last || time - last > length {
+                self.samples.clear();
+            } else if time - last < Duration::seconds(SAMPLE_SECONDS) {
+                return;
+            }
+        }
+        self.samples.push_back((time, position));
+        while self.samples.front().is_some_and(|&(oldest, _)| time - oldest > length) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+/// Mesh showing the selected satellite's trail
+#[derive(Component)]
+pub struct Trail;
+
+pub fn spawn_trail(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        // Seen edge-on from either side as the camera moves around
+        cull_mode: None,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(ribbon_mesh(&[Vec3::ZERO; 2], Vec3::Z, 0.0))),
+        MeshMaterial3d(material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        Trail,
+        Name::new("Trail"),
+    ));
+}
+
+/// Start a history on newly selected satellites and drop it from deselected ones
+pub fn start_trail_history(
+    mut commands: Commands,
+    selected_query: Query<Entity, Added<Selected>>,
+    mut deselected: RemovedComponents<Selected>,
+    history_query: Query<(), With<PositionHistory>>,
+) {
+    for entity in deselected.read() {
+        if history_query.contains(entity) {
+            commands.entity(entity).remove::<PositionHistory>();
+        }
+    }
+    for entity in selected_query.iter() {
+        commands.entity(entity).insert(PositionHistory::default());
+    }
+}
+
+/// Sample where each satellite with a history is drawn, at the simulated time
+pub fn record_trail_history(
+    sim_time: Res<TimeSimulation>,
+    length: Res<TrailLength>,
+    mut history_query: Query<(&ScenePosition, &mut PositionHistory), With<Satellite>>,
+) {
+    let now = sim_time.current_time();
+    for (position, mut history) in history_query.iter_mut() {
+        history.record(now, position.0, length.0);
+    }
+}
+
+/// Triangle strip along `points` (oldest first), `width` wide and turned toward the camera,
+/// fading in from transparent at the oldest point
+fn ribbon_mesh(points: &[Vec3], camera: Vec3, width: f32) -> Mesh {
+    let mut positions = Vec::with_capacity(points.len() * 2);
+    let mut colors = Vec::with_capacity(points.len() * 2);
+    let last = points.len().saturating_sub(1).max(1) as f32;
+    for (i, &point) in points.iter().enumerate() {
+        let along = points[(i + 1).min(points.len() - 1)] - points[i.saturating_sub(1)];
+        let side = along.cross(camera - point).normalize_or_zero() * width * 0.5;
+        let color = TRAIL_COLOR.with_alpha(i as f32 / last).to_linear().to_f32_array();
+        positions.extend([(point - side).to_array(), (point + side).to_array()]);
+        colors.extend([color, color]);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleStrip, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh
+}
+
+/// Rebuild the trail ribbon from the selected satellite's history up to where it is now
+#[allow(clippy::type_complexity)]
+pub fn update_trail(
+    display: Res<GroundTrackDisplay>,
+    selected_query: Query<(&ScenePosition, &PositionHistory), (With<Selected>, Without<Trail>)>,
+    camera_query: Query<(&GlobalTransform, &CameraController)>,
+    mut trail_query: Query<(&Mesh3d, &mut ScenePosition, &mut Visibility), With<Trail>>,
+    origin: Res<FloatingOrigin>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let selected = selected_query.iter().next().filter(|_| display.enabled);
+    let camera = camera_query.iter().next();
+
+    for (mesh_3d, mut position, mut visibility) in trail_query.iter_mut() {
+        let ((satellite, history), (camera_global, controller)) = match (selected, camera) {
+            (Some(selected), Some(camera)) if !selected.1.samples.is_empty() => (selected, camera),
+            _ => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+
+        // Built around the satellite, where the trail's entity sits, so the newest end stays precise
+        position.set_if_neq(*satellite);
+        let points: Vec<Vec3> = history
+            .samples
+            .iter()
+            .map(|&(_, sample)| (sample - satellite.0).as_vec3())
+            .chain(std::iter::once(Vec3::ZERO))
+            .collect();
+        let camera = (origin.to_scene(camera_global.translation()) - satellite.0).as_vec3();
+        let width = controller.distance * TRAIL_WIDTH_FRACTION;
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            *mesh = ribbon_mesh(&points, camera, width);
+        }
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
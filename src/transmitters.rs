@@ -0,0 +1,191 @@
+//! Radio transmitters from the SatNOGS DB (db.satnogs.org), keyed by NORAD ID
+//!
+//! The data comes from the SatNOGS API or a JSON snapshot on disk in the same
+//! format (an array of transmitter objects); downloads are cached like TLE data.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// SatNOGS DB endpoint listing every known transmitter
+pub const SATNOGS_TRANSMITTERS_URL: &str = "https://db.satnogs.org/api/transmitters/?format=json";
+
+/// Transmitters keyed by the NORAD catalog number of the satellite carrying them
+pub type TransmitterSet = HashMap<u64, Vec<Transmitter>>;
+
+/// One transmitter, transceiver or transponder as listed by SatNOGS
+///
+/// Frequencies are in Hz; only the fields the viewer uses are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transmitter {
+    #[serde(default)]
+    pub description: String,
+    /// "active", "inactive" or "invalid"
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub alive: bool,
+    pub uplink_low: Option<u64>,
+    pub uplink_high: Option<u64>,
+    pub downlink_low: Option<u64>,
+    pub downlink_high: Option<u64>,
+    /// Downlink modulation (e.g. "FM", "BPSK", "CW")
+    pub mode: Option<String>,
+    pub uplink_mode: Option<String>,
+    pub baud: Option<f64>,
+    pub norad_cat_id: Option<u64>,
+}
+
+impl Transmitter {
+    /// Whether the transmitter is believed to be working
+    pub fn is_active(&self) -> bool {
+        self.alive && self.status == "active"
+    }
+
+    /// "145.800 MHz" for a single frequency, "435.000-438.000 MHz" for a transponder passband
+    fn format_band(low: Option<u64>, high: Option<u64>) -> Option<String> {
+        let mhz = |hz: u64| hz as f64 / 1e6;
+        match (low, high) {
+            (Some(low), Some(high)) if high != low => Some(format!("{:.3}-{:.3} MHz", mhz(low), mhz(high))),
+            (Some(frequency), _) | (None, Some(frequency)) => Some(format!("{:.3} MHz", mhz(frequency))),
+            (None, None) => None,
+        }
+    }
+
+    /// Downlink frequency or passband
+    pub fn downlink(&self) -> Option<String> {
+        Self::format_band(self.downlink_low, self.downlink_high)
+    }
+
+    /// Uplink frequency or passband
+    pub fn uplink(&self) -> Option<String> {
+        Self::format_band(self.uplink_low, self.uplink_high)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransmitterCache {
+    data: Vec<Transmitter>,
+    downloaded_at: i64, // Unix timestamp
+}
+
+/// Loads transmitters from the SatNOGS API or a local snapshot, caching downloads
+pub struct TransmitterLoader {
+    source: String,
+    cache_file: String,
+    cache_max_age_hours: u64,
+}
+
+impl Default for TransmitterLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransmitterLoader {
+    pub fn new() -> Self {
+        Self {
+            source: SATNOGS_TRANSMITTERS_URL.to_string(),
+            cache_file: "cache/transmitters_cache.json".to_string(),
+            cache_max_age_hours: 24 * 7,
+        }
+    }
+
+    /// Read from this URL or JSON file instead of the SatNOGS API
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Set how long a downloaded copy stays valid
+    pub fn with_cache_max_age_hours(mut self, hours: u64) -> Self {
+        self.cache_max_age_hours = hours;
+        self
+    }
+
+    fn is_url(&self) -> bool {
+        self.source.starts_with("http://") || self.source.starts_with("https://")
+    }
+
+    fn is_cache_valid(&self) -> bool {
+        let max_age = Duration::from_secs(self.cache_max_age_hours * 3600);
+        fs::metadata(&self.cache_file)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < max_age)
+    }
+
+    fn load_from_cache(&self) -> Result<Vec<Transmitter>, Box<dyn std::error::Error>> {
+        let contents = fs::read_to_string(&self.cache_file)?;
+        let cache: TransmitterCache = serde_json::from_str(&contents)?;
+        Ok(cache.data)
+    }
+
+    fn save_to_cache(&self, data: &[Transmitter]) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = Path::new(&self.cache_file).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let cache = TransmitterCache {
+            data: data.to_vec(),
+            downloaded_at: Utc::now().timestamp(),
+        };
+        fs::write(&self.cache_file, serde_json::to_string(&cache)?)?;
+        Ok(())
+    }
+
+    /// Fetch the transmitter list from the source, without touching the cache
+    fn fetch(&self) -> Result<Vec<Transmitter>, Box<dyn std::error::Error>> {
+        let text = if self.is_url() {
+            reqwest::blocking::get(&self.source)?.error_for_status()?.text()?
+        } else {
+            fs::read_to_string(&self.source)?
+        };
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Load transmitters, grouped by NORAD ID
+    ///
+    /// Downloads are reused from the cache while it is fresh; if a download fails,
+    /// an expired cache is still better than nothing.
+    pub fn load(&self) -> Result<TransmitterSet, Box<dyn std::error::Error>> {
+        let transmitters = if !self.is_url() {
+            self.fetch()?
+        } else if self.is_cache_valid() {
+            self.load_from_cache()?
+        } else {
+            println!("Downloading transmitter data from {}...", self.source);
+            match self.fetch() {
+                Ok(data) => {
+                    if let Err(e) = self.save_to_cache(&data) {
+                        eprintln!("Warning: Failed to cache transmitter data: {}", e);
+                    }
+                    data
+                }
+                Err(e) => {
+                    eprintln!("Warning: Transmitter download failed ({}), trying the expired cache", e);
+                    self.load_from_cache().map_err(|_| e)?
+                }
+            }
+        };
+
+        Ok(group_by_norad_id(transmitters))
+    }
+}
+
+/// Group transmitters by satellite, active ones first
+pub fn group_by_norad_id(transmitters: Vec<Transmitter>) -> TransmitterSet {
+    let mut set: TransmitterSet = HashMap::new();
+    for transmitter in transmitters {
+        if let Some(norad_id) = transmitter.norad_cat_id {
+            set.entry(norad_id).or_default().push(transmitter);
+        }
+    }
+    for list in set.values_mut() {
+        list.sort_by_key(|transmitter| !transmitter.is_active());
+    }
+    set
+}
@@ -1,209 +1,504 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy_egui::{egui, EguiContexts, EguiGlobalSettings, EguiPlugin, EguiPrimaryContextPass, PrimaryEguiContext};
+
+use crate::borders::BorderDisplay;
+use crate::browser::SatelliteBrowser;
+use crate::camera::FocusCamera;
+use crate::config::Settings;
+use crate::coords;
+use crate::culling::Culled;
+use crate::eclipse::SatelliteIllumination;
+use crate::export_panel::{self, ExportKind, ExportStatus};
+use crate::filter::FilterExpr;
+use crate::floating_origin::ScenePosition;
+use crate::footprint::FootprintDisplay;
+use crate::geo_belt::GeoBeltDisplay;
+use crate::graticule::GraticuleDisplay;
+use crate::ground_track::GroundTrackDisplay;
+use crate::groups::SatelliteGroups;
+use crate::keymap::{Action, Keymap};
+use crate::labels::LabelDisplay;
+use crate::orbit::OrbitDisplay;
+use crate::radio::{self, Transmitters};
+use crate::satellite::{ObjectKind, OfflineData, Satellite, TleLoadState};
+use crate::selection::{SelectSatellite, Selected};
+use crate::sensor::SensorDisplay;
+use crate::text_input::InputFocus;
+use crate::time_simulation::TimeSimulation;
+use crate::velocity::VelocityArrowDisplay;
+
+/// Pixels scrolled per mouse wheel notch in lists
+const SCROLL_LINE_PIXELS: f32 = 20.0;
+/// Gap between the egui panels and the window edges
+pub const PANEL_MARGIN: f32 = 10.0;
+/// Text that doesn't parse (filter expression, "Go to" time)
+pub const INVALID_TEXT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 102, 102);
+const SAVED_TEXT_COLOR: egui::Color32 = egui::Color32::from_rgb(153, 255, 153);
+
+/// Control panels: filter/search box, selected satellite info, display settings
+/// (egui windows) and loading status
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        // The context goes on the UI camera; clicks, wheel and keys egui takes don't reach the scene
+        app.insert_resource(EguiGlobalSettings {
+            auto_create_primary_context: false,
+            enable_absorb_bevy_input_system: true,
+            ..default()
+        })
+        .add_plugins(EguiPlugin::default())
+        .init_resource::<SatelliteFilter>()
+        .init_resource::<SatelliteGroups>()
+        .init_resource::<SatelliteBrowser>()
+        .init_resource::<ExportStatus>()
+        .init_resource::<Settings>()
+        .init_resource::<Keymap>()
+        .init_resource::<UiPanels>()
+        .add_systems(Startup, setup_ui)
+        .add_systems(Update, (
+            focus_search_shortcut,
+            scroll_lists,
+            filter_satellites,
+            update_loading_indicator,
+            update_loading_progress,
+        ))
+        .add_systems(EguiPrimaryContextPass, (
+            filter_panel,
+            satellite_info_panel,
+            settings_window,
+        ));
+    }
+}
 
 #[derive(Resource, Default)]
 pub struct SatelliteFilter {
     pub text: String,
+    /// Hide debris and rocket bodies ("payloads only" rather than the full catalog)
+    pub payloads_only: bool,
+}
+
+/// State of the egui panels kept between frames
+#[derive(Resource, Default)]
+pub struct UiPanels {
+    /// Whether the display settings window is open
+    pub settings_open: bool,
+    /// Move the keyboard to the filter field on the next frame
+    focus_filter: bool,
 }
 
 #[derive(Component)]
-pub struct FilterTextDisplay;
+pub struct LoadingIndicator;
 
+/// Bar under the loading indicator filling up as the loaded satellites are spawned
 #[derive(Component)]
-pub struct FilterInputField;
+pub struct LoadingProgressBar;
 
-#[derive(Resource, Default)]
-pub struct InputFocus {
-    pub is_focused: bool,
-}
+#[derive(Component)]
+pub struct LoadingProgressFill;
+
+/// Node with `Overflow::scroll_y` that scrolls with the mouse wheel while hovered
+/// (needs a `RelativeCursorPosition`)
+#[derive(Component)]
+pub struct ScrollArea;
 
 pub fn setup_ui(mut commands: Commands) {
-    // Spawn UI camera with order 1 (renders on top of 3D scene)
+    // Spawn UI camera with order 3 (renders on top of the 3D scene, the map view and the chase inset);
+    // the egui panels are drawn by it too, above the bevy_ui nodes
     commands.spawn((
-        Camera2d::default(),
+        Camera2d,
         Camera {
-            order: 1, // Higher order renders on top
+            order: 3, // Higher order renders on top
             ..default()
         },
+        IsDefaultUiCamera,
+        PrimaryEguiContext,
     ));
-    
-    // Create UI root
-    commands
-        .spawn(Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            justify_content: JustifyContent::FlexStart,
-            align_items: AlignItems::FlexStart,
-            padding: UiRect::all(Val::Px(10.0)),
+
+    // Loading indicator (top-center), visible while TLE data is being fetched
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Percent(40.0),
+            padding: UiRect::all(Val::Px(8.0)),
             ..default()
-        })
+        },
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+        Text::new("Loading satellite data..."),
+        TextColor(Color::srgb(1.0, 0.8, 0.2)),
+        LoadingIndicator,
+    ));
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(46.0),
+                left: Val::Percent(40.0),
+                width: Val::Px(260.0),
+                height: Val::Px(6.0),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+            LoadingProgressBar,
+        ))
         .with_children(|parent| {
-            // Filter text input container
-            parent
-                .spawn((
-                    Node {
-                        width: Val::Px(400.0),
-                        height: Val::Px(50.0),
-                        padding: UiRect::all(Val::Px(5.0)),
-                        ..default()
-                    },
-                    BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
-                ))
-                .with_children(|parent| {
-                    // Label
-                    parent.spawn(Text::new("Filter: "));
-                    
-                    // Text input display
-                    parent
-                        .spawn((
-                            Node {
-                                width: Val::Percent(100.0),
-                                height: Val::Percent(100.0),
-                                padding: UiRect::all(Val::Px(5.0)),
-                                ..default()
-                            },
-                            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
-                            FilterTextDisplay,
-                            FilterInputField,
-                            Text::new(""),
-                        ));
-                });
+            parent.spawn((
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(1.0, 0.8, 0.2)),
+                LoadingProgressFill,
+            ));
         });
 }
 
-// System to check if mouse is over input field
-pub fn check_input_focus(
-    mut focus: ResMut<InputFocus>,
-    windows: Query<&Window>,
+/// Frameless window pinned to a corner of the screen
+pub fn anchored_panel(id: &'static str, align: egui::Align2, offset: [f32; 2]) -> egui::Window<'static> {
+    egui::Window::new(id)
+        .title_bar(false)
+        .resizable(false)
+        .anchor(align, offset)
+}
+
+/// Scroll the hovered list with the mouse wheel
+pub fn scroll_lists(
+    mut mouse_wheel_events: MessageReader<MouseWheel>,
+    mut list_query: Query<(&RelativeCursorPosition, &InheritedVisibility, &mut ScrollPosition), With<ScrollArea>>,
 ) {
-    let window = match windows.iter().next() {
-        Some(w) => w,
-        None => return,
-    };
-    
-    let cursor_pos = window.cursor_position();
-    
-    // Check if cursor is over input field using screen coordinates
-    // Input field is at top-left: x: 10-410, y: 10-60 (from top)
-    focus.is_focused = if let Some(cursor) = cursor_pos {
-        let input_x_min = 10.0;
-        let input_x_max = 410.0;
-        let input_y_min = 10.0;
-        let input_y_max = 60.0;
-        
-        cursor.x >= input_x_min && cursor.x <= input_x_max &&
-        cursor.y >= input_y_min && cursor.y <= input_y_max
+    let lines: f32 = mouse_wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_PIXELS,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if lines == 0.0 {
+        return;
+    }
+
+    for (cursor, visibility, mut scroll) in list_query.iter_mut() {
+        if visibility.get() && cursor.cursor_over() {
+            // Clamped to the content by the layout
+            scroll.y = (scroll.y - lines).max(0.0);
+        }
+    }
+}
+
+fn catalog_view_label(payloads_only: bool) -> &'static str {
+    if payloads_only {
+        "Payloads only"
     } else {
-        false
-    };
+        "Full catalog"
+    }
 }
 
-pub fn update_filter_text(
-    mut filter: ResMut<SatelliteFilter>,
-    mut query: Query<&mut Text, With<FilterTextDisplay>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+/// Find the satellite a search string refers to: the one with that catalog number,
+/// the only partial name match, or an exact (case-insensitive) name match among several
+fn find_search_match<'a>(
+    search: &str,
+    satellites: impl Iterator<Item = (Entity, &'a str, u64)>,
+) -> Option<Entity> {
+    let search = search.trim().to_lowercase();
+    if search.is_empty() {
+        return None;
+    }
+    let norad_id = search.parse::<u64>().ok();
+
+    let matches: Vec<(Entity, &str, u64)> = satellites
+        .filter(|(_, name, id)| Some(*id) == norad_id || name.to_lowercase().contains(&search))
+        .collect();
+
+    if let Some((entity, _, _)) = matches.iter().find(|(_, _, id)| Some(*id) == norad_id) {
+        return Some(*entity);
+    }
+    match matches.as_slice() {
+        [(entity, _, _)] => Some(*entity),
+        _ => matches
+            .iter()
+            .find(|(_, name, _)| name.to_lowercase() == search)
+            .map(|(entity, _, _)| *entity),
+    }
+}
+
+/// Move the keyboard to the filter field (/ by default)
+pub fn focus_search_shortcut(
+    mut panels: ResMut<UiPanels>,
     focus: Res<InputFocus>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    keymap: Res<Keymap>,
 ) {
-    // Only process input if mouse is over input field
-    if !focus.is_focused {
+    if focus.is_focused() || !keymap.just_pressed(Action::FocusSearch, &keyboard_input) {
         return;
     }
-    
-    // Handle backspace
-    if keyboard_input.just_pressed(KeyCode::Backspace) {
-        filter.text.pop();
+    panels.focus_filter = true;
+}
+
+/// Filter box (top-left): applied as it is typed, in red while the expression is invalid;
+/// Enter selects the matching satellite and flies to it
+pub fn filter_panel(
+    mut contexts: EguiContexts,
+    mut filter: ResMut<SatelliteFilter>,
+    mut panels: ResMut<UiPanels>,
+    satellite_query: Query<(Entity, &Satellite, &ScenePosition)>,
+    mut select_writer: MessageWriter<SelectSatellite>,
+    mut focus_writer: MessageWriter<FocusCamera>,
+) -> Result {
+    let ctx = contexts.ctx_mut()?;
+    let mut submitted = false;
+
+    anchored_panel("filter", egui::Align2::LEFT_TOP, [PANEL_MARGIN, PANEL_MARGIN]).show(ctx, |ui| {
+        ui.set_width(400.0);
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            let mut text = filter.text.clone();
+            let invalid = FilterExpr::parse(&text).is_err();
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut text)
+                    .text_color_opt(invalid.then_some(INVALID_TEXT_COLOR))
+                    .desired_width(200.0),
+            );
+            // Only touch the resource on edits, it refilters every satellite
+            if response.changed() {
+                filter.text = text;
+            }
+            if std::mem::take(&mut panels.focus_filter) {
+                response.request_focus();
+            }
+            // Enter also hands the keyboard back to the shortcuts
+            submitted = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+
+            if ui.button(catalog_view_label(filter.payloads_only)).clicked() {
+                filter.payloads_only = !filter.payloads_only;
+            }
+            ui.toggle_value(&mut panels.settings_open, "Settings");
+        });
+    });
+
+    if submitted {
+        search_and_focus(&filter.text, &satellite_query, &mut select_writer, &mut focus_writer);
     }
-    
-    // Handle alphanumeric keys
-    let shift = keyboard_input.pressed(KeyCode::ShiftLeft) || keyboard_input.pressed(KeyCode::ShiftRight);
-    
-    for key in [
-        KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE,
-        KeyCode::KeyF, KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ,
-        KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO,
-        KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR, KeyCode::KeyS, KeyCode::KeyT,
-        KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY,
-        KeyCode::KeyZ,
-        KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
-        KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
-        KeyCode::Space, KeyCode::Minus,
-    ] {
-        if keyboard_input.just_pressed(key) {
-            if let Some(ch) = key_to_char(key, shift) {
-                filter.text.push(ch);
+    Ok(())
+}
+
+/// Select and fly to the satellite a search string refers to
+fn search_and_focus(
+    search: &str,
+    satellite_query: &Query<(Entity, &Satellite, &ScenePosition)>,
+    select_writer: &mut MessageWriter<SelectSatellite>,
+    focus_writer: &mut MessageWriter<FocusCamera>,
+) {
+    let found = find_search_match(
+        search,
+        satellite_query
+            .iter()
+            .map(|(entity, satellite, _)| (entity, satellite.name.as_str(), satellite.elements.norad_id)),
+    );
+
+    match found.and_then(|entity| satellite_query.get(entity).ok()) {
+        Some((entity, satellite, position)) => {
+            println!("Focusing on {}", satellite.name);
+            select_writer.write(SelectSatellite(Some(entity)));
+            focus_writer.write(FocusCamera {
+                target: position.scene(),
+            });
+        }
+        None => println!("No unique satellite matches \"{}\"", search),
+    }
+}
+
+/// Orbital details of the selected satellite (top-right) with its export buttons
+pub fn satellite_info_panel(
+    mut contexts: EguiContexts,
+    selected_query: Query<(&Satellite, &SatelliteIllumination), With<Selected>>,
+    transmitters: Option<Res<Transmitters>>,
+    sim_time: Res<TimeSimulation>,
+    settings: Res<Settings>,
+    mut status: ResMut<ExportStatus>,
+) -> Result {
+    let (satellite, illumination) = match selected_query.iter().next() {
+        Some(selected) => selected,
+        None => return Ok(()),
+    };
+    let info = satellite_info(satellite, illumination, transmitters.as_deref());
+    let ctx = contexts.ctx_mut()?;
+
+    anchored_panel("info", egui::Align2::RIGHT_TOP, [-PANEL_MARGIN, PANEL_MARGIN]).show(ctx, |ui| {
+        ui.set_width(360.0);
+        ui.label(info);
+
+        // Ground track (GeoJSON, KML) and ephemeris (OEM, CSV)
+        ui.horizontal(|ui| {
+            ui.label("Export:");
+            for kind in ExportKind::ALL {
+                if ui.button(kind.label()).clicked() {
+                    export_panel::export_satellite(satellite, kind, sim_time.current_time(), &settings.export, &mut status);
+                }
+            }
+        });
+        match &status.0 {
+            Some(Ok(path)) => {
+                ui.colored_label(SAVED_TEXT_COLOR, format!("Saved {}", path));
+            }
+            Some(Err(e)) => {
+                ui.colored_label(INVALID_TEXT_COLOR, format!("Export failed: {}", e));
+            }
+            None => {}
+        }
+    });
+    Ok(())
+}
+
+/// Text of the info panel: elements, current position, catalog data and radio transmitters
+fn satellite_info(satellite: &Satellite, illumination: &SatelliteIllumination, transmitters: Option<&Transmitters>) -> String {
+    let elements = &satellite.elements;
+    let params = satellite.orbital_parameters();
+
+    let mut info = format!(
+        "{}\n\
+         NORAD ID: {}\n\
+         Type: {}\n\
+         Int'l designator: {}\n\
+         Epoch: {} UTC ({:+.1} days)\n\
+         Inclination: {:.2}°\n\
+         Eccentricity: {:.6}\n\
+         Apogee: {:.0} km\n\
+         Perigee: {:.0} km\n\
+         Period: {:.1} min",
+        satellite.name,
+        elements.norad_id,
+        satellite.kind.label(),
+        elements.international_designator.as_deref().unwrap_or("n/a"),
+        elements.datetime.format("%Y-%m-%d %H:%M:%S"),
+        (satellite.last_update.naive_utc() - elements.datetime).num_seconds() as f64 / 86400.0,
+        params.inclination_deg,
+        params.eccentricity,
+        params.apogee_altitude_km,
+        params.perigee_altitude_km,
+        params.period_minutes,
+    );
+
+    match satellite.position {
+        Some(position) => {
+            let geodetic = coords::teme_to_geodetic(position, satellite.last_update);
+            info.push_str(&format!(
+                "\nLatitude: {:.2}°\nLongitude: {:.2}°\nAltitude: {:.0} km",
+                geodetic.latitude_deg, geodetic.longitude_deg, geodetic.altitude_km,
+            ));
+            if let Some(speed) = satellite.speed() {
+                info.push_str(&format!("\nSpeed: {:.2} km/s", speed));
             }
+            info.push_str(&format!("\nIllumination: {}", illumination.0.name()));
         }
+        None => info.push_str("\nPosition: unavailable (propagation failed)"),
     }
-    
-    // Update displayed text
-    for mut text in query.iter_mut() {
-        *text = Text::new(&filter.text);
-    }
-}
-
-fn key_to_char(key: KeyCode, shift: bool) -> Option<char> {
-    match key {
-        KeyCode::KeyA => Some(if shift { 'A' } else { 'a' }),
-        KeyCode::KeyB => Some(if shift { 'B' } else { 'b' }),
-        KeyCode::KeyC => Some(if shift { 'C' } else { 'c' }),
-        KeyCode::KeyD => Some(if shift { 'D' } else { 'd' }),
-        KeyCode::KeyE => Some(if shift { 'E' } else { 'e' }),
-        KeyCode::KeyF => Some(if shift { 'F' } else { 'f' }),
-        KeyCode::KeyG => Some(if shift { 'G' } else { 'g' }),
-        KeyCode::KeyH => Some(if shift { 'H' } else { 'h' }),
-        KeyCode::KeyI => Some(if shift { 'I' } else { 'i' }),
-        KeyCode::KeyJ => Some(if shift { 'J' } else { 'j' }),
-        KeyCode::KeyK => Some(if shift { 'K' } else { 'k' }),
-        KeyCode::KeyL => Some(if shift { 'L' } else { 'l' }),
-        KeyCode::KeyM => Some(if shift { 'M' } else { 'm' }),
-        KeyCode::KeyN => Some(if shift { 'N' } else { 'n' }),
-        KeyCode::KeyO => Some(if shift { 'O' } else { 'o' }),
-        KeyCode::KeyP => Some(if shift { 'P' } else { 'p' }),
-        KeyCode::KeyQ => Some(if shift { 'Q' } else { 'q' }),
-        KeyCode::KeyR => Some(if shift { 'R' } else { 'r' }),
-        KeyCode::KeyS => Some(if shift { 'S' } else { 's' }),
-        KeyCode::KeyT => Some(if shift { 'T' } else { 't' }),
-        KeyCode::KeyU => Some(if shift { 'U' } else { 'u' }),
-        KeyCode::KeyV => Some(if shift { 'V' } else { 'v' }),
-        KeyCode::KeyW => Some(if shift { 'W' } else { 'w' }),
-        KeyCode::KeyX => Some(if shift { 'X' } else { 'x' }),
-        KeyCode::KeyY => Some(if shift { 'Y' } else { 'y' }),
-        KeyCode::KeyZ => Some(if shift { 'Z' } else { 'z' }),
-        KeyCode::Digit0 => Some('0'),
-        KeyCode::Digit1 => Some('1'),
-        KeyCode::Digit2 => Some('2'),
-        KeyCode::Digit3 => Some('3'),
-        KeyCode::Digit4 => Some('4'),
-        KeyCode::Digit5 => Some('5'),
-        KeyCode::Digit6 => Some('6'),
-        KeyCode::Digit7 => Some('7'),
-        KeyCode::Digit8 => Some('8'),
-        KeyCode::Digit9 => Some('9'),
-        KeyCode::Space => Some(' '),
-        KeyCode::Minus => Some(if shift { '_' } else { '-' }),
-        _ => None,
+
+    if let Some(satcat) = &satellite.satcat {
+        info.push_str(&format!(
+            "\n\nOwner: {}\nLaunched: {}\nSATCAT type: {}\nRadar size: {}",
+            if satcat.owner.is_empty() { "n/a" } else { satcat.owner.as_str() },
+            satcat.launch_date.map_or("n/a".to_string(), |date| date.format("%Y-%m-%d").to_string()),
+            satcat.object_type.label(),
+            satcat.rcs_size.map_or("n/a", |size| size.label()),
+        ));
+    }
+
+    if let Some(summary) = transmitters.and_then(|transmitters| radio::transmitter_summary(transmitters, elements.norad_id)) {
+        info.push_str("\n\n");
+        info.push_str(&summary);
+    }
+    info
+}
+
+/// Display settings window (opened from the filter box): the scene overlays, next to their shortcuts
+#[allow(clippy::too_many_arguments)]
+pub fn settings_window(
+    mut contexts: EguiContexts,
+    mut panels: ResMut<UiPanels>,
+    keymap: Res<Keymap>,
+    labels: ResMut<LabelDisplay>,
+    ground_track: ResMut<GroundTrackDisplay>,
+    orbit: ResMut<OrbitDisplay>,
+    velocity: ResMut<VelocityArrowDisplay>,
+    footprint: ResMut<FootprintDisplay>,
+    sensor: ResMut<SensorDisplay>,
+    graticule: ResMut<GraticuleDisplay>,
+    borders: ResMut<BorderDisplay>,
+    geo_belt: ResMut<GeoBeltDisplay>,
+) -> Result {
+    if !panels.settings_open {
+        return Ok(());
     }
+    let ctx = contexts.ctx_mut()?;
+
+    // Only flagged as changed when a box is ticked, like the keyboard toggles
+    let toggles = [
+        ("Satellite labels", Action::ToggleLabels, labels.map_unchanged(|display| &mut display.enabled)),
+        ("Ground track and trail", Action::ToggleTrails, ground_track.map_unchanged(|display| &mut display.enabled)),
+        ("Orbit", Action::ToggleOrbit, orbit.map_unchanged(|display| &mut display.enabled)),
+        ("Velocity arrow", Action::ToggleVelocity, velocity.map_unchanged(|display| &mut display.enabled)),
+        ("Footprint", Action::ToggleFootprint, footprint.map_unchanged(|display| &mut display.enabled)),
+        ("Sensor cone", Action::ToggleSensor, sensor.map_unchanged(|display| &mut display.enabled)),
+        ("Lat/lon grid", Action::ToggleGraticule, graticule.map_unchanged(|display| &mut display.enabled)),
+        ("Coastlines and borders", Action::ToggleBorders, borders.map_unchanged(|display| &mut display.enabled)),
+        ("GEO belt", Action::ToggleGeoBelt, geo_belt.map_unchanged(|display| &mut display.enabled)),
+    ];
+
+    egui::Window::new("Settings")
+        .open(&mut panels.settings_open)
+        .collapsible(false)
+        .resizable(false)
+        .default_pos([420.0 + 2.0 * PANEL_MARGIN, PANEL_MARGIN])
+        .show(ctx, |ui| {
+            for (label, action, mut enabled) in toggles {
+                let mut checked = *enabled;
+                if ui.checkbox(&mut checked, format!("{}  [{}]", label, keymap.label(action))).changed() {
+                    *enabled = checked;
+                }
+            }
+        });
+    Ok(())
 }
 
 pub fn filter_satellites(
     filter: Res<SatelliteFilter>,
-    mut satellite_query: Query<(&mut Visibility, &crate::satellite::Satellite, Option<&crate::satellite::SatelliteLabelEntity>)>,
+    groups: Res<SatelliteGroups>,
+    browser: Res<SatelliteBrowser>,
+    mut satellite_query: Query<(
+        &mut Visibility,
+        &crate::satellite::Satellite,
+        Option<&crate::satellite::SatelliteLabelEntity>,
+        Option<&Culled>,
+    )>,
     mut label_query: Query<&mut Visibility, (With<crate::satellite::SatelliteLabel>, Without<crate::satellite::Satellite>)>,
 ) {
-    // Only update if filter changed
-    if !filter.is_changed() {
+    // Only update if the filter, the "show only" group or the browser checkboxes changed
+    if !filter.is_changed() && !groups.is_changed() && !browser.is_changed() {
         return;
     }
     
-    let filter_lower = filter.text.to_lowercase();
+    // Keep the previous result while the expression is incomplete (e.g. "alt>" mid-typing)
+    let expr = match FilterExpr::parse(&filter.text) {
+        Ok(expr) => expr,
+        Err(_) => return,
+    };
     
-    for (mut visibility, satellite, label_entity) in satellite_query.iter_mut() {
-        let should_show = if filter.text.is_empty() {
-            // Show all if filter is empty
-            true
-        } else {
-            // Partial match (case-insensitive)
-            satellite.name.to_lowercase().contains(&filter_lower)
-        };
+    for (mut visibility, satellite, label_entity, culled) in satellite_query.iter_mut() {
+        // Show all if filter is empty, otherwise every term must match;
+        // satellites outside the "show only" group, unchecked in the browser or that
+        // couldn't be propagated at the current time are hidden either way, as are those
+        // beyond the culling distance, and debris and rocket bodies in the "payloads only" view
+        let should_show = (expr.is_empty() || expr.matches(satellite))
+            && groups.is_shown(satellite.elements.norad_id)
+            && browser.is_shown(&satellite.name, satellite.elements.norad_id)
+            && satellite.position.is_some()
+            && (!filter.payloads_only || satellite.kind == ObjectKind::Payload)
+            && !culled.is_some_and(|culled| culled.beyond_range);
         
         // Update satellite visibility
         *visibility = if should_show {
@@ -220,3 +515,61 @@ pub fn filter_satellites(
         }
     }
 }
+
+// System to show TLE loading progress and errors
+/// Show the spawn progress bar while satellites are being added
+pub fn update_loading_progress(
+    load_state: Option<Res<TleLoadState>>,
+    mut bar_query: Query<&mut Node, (With<LoadingProgressBar>, Without<LoadingProgressFill>)>,
+    mut fill_query: Query<&mut Node, (With<LoadingProgressFill>, Without<LoadingProgressBar>)>,
+) {
+    let progress = match load_state.as_deref() {
+        Some(TleLoadState::Spawning(queue)) => Some(queue.progress()),
+        _ => None,
+    };
+    for mut node in bar_query.iter_mut() {
+        node.display = if progress.is_some() { Display::Flex } else { Display::None };
+    }
+    for mut node in fill_query.iter_mut() {
+        node.width = Val::Percent(progress.unwrap_or(0.0) * 100.0);
+    }
+}
+
+pub fn update_loading_indicator(
+    load_state: Option<Res<TleLoadState>>,
+    offline: Option<Res<OfflineData>>,
+    mut indicator_query: Query<(&mut Text, &mut Visibility), With<LoadingIndicator>>,
+    time: Res<Time>,
+) {
+    let load_state = match load_state {
+        Some(state) => state,
+        None => return,
+    };
+
+    for (mut text, mut visibility) in indicator_query.iter_mut() {
+        match load_state.as_ref() {
+            TleLoadState::Loading(_) => {
+                // Simple animated ellipsis so the user can see the app is alive
+                let dots = ".".repeat((time.elapsed_secs() * 2.0) as usize % 4);
+                *text = Text::new(format!("Loading satellite data{}", dots));
+                *visibility = Visibility::Visible;
+            }
+            TleLoadState::Spawning(queue) => {
+                *text = Text::new(format!("Adding satellites: {} / {}", queue.spawned, queue.total));
+                *visibility = Visibility::Visible;
+            }
+            // Stays up as a banner while the satellites come from offline data
+            TleLoadState::Loaded(_) => match offline.as_ref().and_then(|offline| offline.0.offline_date()) {
+                Some(date) => {
+                    *text = Text::new(format!("Offline data from {}", date.format("%Y-%m-%d")));
+                    *visibility = Visibility::Visible;
+                }
+                None => *visibility = Visibility::Hidden,
+            },
+            TleLoadState::Failed(error) => {
+                *text = Text::new(format!("Failed to load satellite data: {}", error));
+                *visibility = Visibility::Visible;
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::coords;
+use crate::floating_origin::ScenePosition;
+use crate::keymap::{Action, Keymap};
+use crate::satellite::{update_satellite_positions, Satellite};
+use crate::selection::Selected;
+use crate::text_input::InputFocus;
+
+/// Velocity arrow on the selected satellite
+pub struct VelocityPlugin;
+
+impl Plugin for VelocityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VelocityArrowDisplay>()
+            .add_systems(Startup, spawn_velocity_arrow)
+            .add_systems(Update, (
+                update_velocity_arrow.after(update_satellite_positions),
+                toggle_velocity_arrow,
+            ));
+    }
+}
+
+/// Arrow length: distance covered at the current velocity in this many seconds
+const ARROW_SECONDS: f32 = 200.0;
+/// Length of the arrow head strokes, as a fraction of the arrow length
+const ARROW_HEAD_FRACTION: f32 = 0.15;
+
+/// Velocity arrow drawn from the selected satellite
+#[derive(Component)]
+pub struct VelocityArrow;
+
+/// Whether the velocity arrow of the selected satellite is drawn (toggle with V)
+#[derive(Resource)]
+pub struct VelocityArrowDisplay {
+    pub enabled: bool,
+}
+
+impl Default for VelocityArrowDisplay {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Shaft and two head strokes as a line list
+fn arrow_positions(start: Vec3, end: Vec3) -> Vec<[f32; 3]> {
+    let shaft = end - start;
+    let direction = shaft.normalize_or_zero();
+    // Spread the head sideways, in the plane containing the local vertical
+    let side = direction.cross(start.normalize_or_zero()).normalize_or_zero();
+    let head = shaft.length() * ARROW_HEAD_FRACTION;
+    let back = end - direction * head;
+
+    vec![
+        start.to_array(),
+        end.to_array(),
+        end.to_array(),
+        (back + side * head * 0.5).to_array(),
+        end.to_array(),
+        (back - side * head * 0.5).to_array(),
+    ]
+}
+
+pub fn spawn_velocity_arrow(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, Default::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, arrow_positions(Vec3::ZERO, Vec3::ZERO));
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.3, 1.0, 0.3),
+        unlit: true,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        Transform::default(),
+        ScenePosition::default(),
+        Visibility::Hidden,
+        VelocityArrow,
+        Name::new("VelocityArrow"),
+    ));
+}
+
+/// Point the arrow along the selected satellite's velocity, from where it is drawn
+#[allow(clippy::type_complexity)]
+pub fn update_velocity_arrow(
+    display: Res<VelocityArrowDisplay>,
+    selected_query: Query<(&Satellite, &ScenePosition), (With<Selected>, Without<VelocityArrow>)>,
+    mut arrow_query: Query<(&Mesh3d, &mut ScenePosition, &mut Visibility), With<VelocityArrow>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let arrow = selected_query
+        .iter()
+        .next()
+        .filter(|_| display.enabled)
+        .and_then(|(satellite, satellite_position)| {
+            let position = satellite.position?;
+            let velocity = satellite.velocity?;
+            // Rotate the (inertial) velocity into the scene frame at the propagation time
+            let direction = coords::teme_to_scene(position + velocity, satellite.last_update)
+                - coords::teme_to_scene(position, satellite.last_update);
+            Some((satellite_position.0, direction * ARROW_SECONDS))
+        });
+
+    // The arrow's entity sits on the satellite and the mesh is drawn from there
+    for (mesh_3d, mut position, mut visibility) in arrow_query.iter_mut() {
+        let (start, arrow) = match arrow {
+            Some(arrow) => arrow,
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+                continue;
+            }
+        };
+        visibility.set_if_neq(Visibility::Visible);
+        position.set_if_neq(ScenePosition(start));
+        if let Some(mesh) = meshes.get_mut(&mesh_3d.0) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, arrow_positions(Vec3::ZERO, arrow));
+        }
+    }
+}
+
+/// Toggle the velocity arrow with V
+pub fn toggle_velocity_arrow(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    keymap: Res<Keymap>,
+    mut display: ResMut<VelocityArrowDisplay>,
+) {
+    if focus.is_focused() || !keymap.just_pressed(Action::ToggleVelocity, &keyboard_input) {
+        return;
+    }
+    display.enabled = !display.enabled;
+}
@@ -0,0 +1,238 @@
+//! Propagation and coordinate frame checks against reference values
+//!
+//! The SGP4 vectors come from the verification set published with Vallado et al.,
+//! "Revisiting Spacetrack Report #3" (AIAA 2006-6753), file `tcppver.out`.
+
+use ai_space_tracker::coords;
+use ai_space_tracker::propagation::Propagator;
+use ai_space_tracker::tle_loader::TleData;
+use chrono::{DateTime, Duration, Utc};
+use nalgebra::Vector3;
+
+/// The reference vectors use the WGS72 constants, `Propagator` the WGS84 ones; the
+/// two differ by a few tens of meters over these spans
+const POSITION_TOLERANCE_KM: f64 = 0.1;
+const VELOCITY_TOLERANCE_KM_S: f64 = 1e-4;
+
+/// A verification case: TLE lines and (minutes since epoch, position km, velocity km/s)
+struct Reference {
+    line1: &'static str,
+    line2: &'static str,
+    states: &'static [(f64, [f64; 3], [f64; 3])],
+}
+
+const REFERENCES: &[Reference] = &[
+    // Near-Earth, eccentric (the TEME example satellite)
+    Reference {
+        line1: "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+        line2: "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667",
+        states: &[
+            (0.0, [7022.46529266, -1400.08296755, 0.03995155], [1.893841015, 6.405893759, 4.534807250]),
+            (360.0, [-7154.03120202, -3783.17682504, -3536.19412294], [4.741887409, -4.151817765, -2.093935425]),
+            (720.0, [-7134.59340119, 6531.68641334, 3260.27186483], [-4.113793027, -2.911922039, -2.557327851]),
+        ],
+    },
+    // Near-Earth, sun-synchronous
+    Reference {
+        line1: "1 28057U 03049A   06177.78615833  .00000060  00000-0  35940-4 0  1836",
+        line2: "2 28057  98.4283 247.6961 0000884  88.1964 271.9322 14.35478080140550",
+        states: &[
+            (0.0, [-2715.28237486, -6619.26436889, -0.01341443], [-1.008587273, 0.422782003, 7.385272942]),
+            (120.0, [-1816.87920942, -1835.78762132, 6661.07926465], [2.325140071, 6.655669329, 2.463394512]),
+            (240.0, [1483.17364291, 5395.21248786, 4448.65907172], [2.560540387, 4.039025766, -5.736648561]),
+        ],
+    },
+    // Deep space, Molniya
+    Reference {
+        line1: "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+        line2: "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656",
+        states: &[
+            (0.0, [2349.89483350, -14785.93811562, 0.02119378], [2.721488096, -3.256811655, 4.498416672]),
+            (120.0, [15223.91713658, -17852.95881713, 25280.39558224], [1.079041732, 0.875187372, 2.485682813]),
+            (240.0, [19752.78050009, -8600.07130962, 37522.72921090], [0.238105279, 1.546110924, 0.986410447]),
+        ],
+    },
+    // Deep space, geostationary
+    Reference {
+        line1: "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190",
+        line2: "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891",
+        states: &[
+            (0.0, [42080.71852213, -2646.86387436, 0.81851294], [0.193105177, 3.068688251, 0.000438449]),
+            (120.0, [37740.00085593, 18802.76872802, 3.45512584], [-1.371035206, 2.752105932, 0.000336883]),
+            (240.0, [23232.82515008, 35187.33981802, 4.98927428], [-2.565776620, 1.694193132, 0.000163365]),
+        ],
+    },
+];
+
+/// ISS-like orbit (51.64°, 15.5 rev/day, near circular) with its epoch at
+/// 2000-01-01 12:00 UTC, where GMST is 280.46°. Perigee and mean anomaly put it at
+/// its northernmost point at the epoch, and the node is chosen so that point is at
+/// 10°E, over Germany.
+const ISS_LINE1: &str = "1 25544U 98067A   00001.50000000  .00000000  00000-0  00000-0 0  9996";
+const ISS_LINE2: &str = "2 25544  51.6400 200.4606 0001000   0.0000  90.0000 15.50000000    18";
+
+fn propagator(line1: &str, line2: &str) -> (Propagator, DateTime<Utc>) {
+    let tle = TleData {
+        line1: line1.to_string(),
+        line2: line2.to_string(),
+        name: String::new(),
+        omm: None,
+    };
+    let elements = tle.to_elements().expect("reference TLE parses");
+    (Propagator::new(&elements), elements.datetime.and_utc())
+}
+
+fn minutes_after(epoch: DateTime<Utc>, minutes: f64) -> DateTime<Utc> {
+    epoch + Duration::milliseconds((minutes * 60_000.0).round() as i64)
+}
+
+fn assert_vector_close(actual: Vector3<f64>, expected: [f64; 3], tolerance: f64, what: &str) {
+    let error = (actual - Vector3::from(expected)).norm();
+    assert!(
+        error <= tolerance,
+        "{}: expected {:?} ± {}, got {:?} (off by {})",
+        what,
+        expected,
+        tolerance,
+        actual,
+        error
+    );
+}
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64, what: &str) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{}: expected {} ± {}, got {}",
+        what,
+        expected,
+        tolerance,
+        actual
+    );
+}
+
+/// Difference between two longitudes (degrees), wrapped to [-180, 180)
+fn longitude_difference(a: f64, b: f64) -> f64 {
+    (a - b + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[test]
+fn propagation_matches_reference_vectors() {
+    for reference in REFERENCES {
+        let (propagator, epoch) = propagator(reference.line1, reference.line2);
+        for &(minutes, position, velocity) in reference.states {
+            let what = format!("{} at {} min", &reference.line1[2..7], minutes);
+            let (actual_position, actual_velocity) = propagator
+                .propagate_state(minutes_after(epoch, minutes))
+                .unwrap_or_else(|| panic!("{}: propagation failed", what));
+            assert_vector_close(actual_position, position, POSITION_TOLERANCE_KM, &format!("{} position", what));
+            assert_vector_close(actual_velocity, velocity, VELOCITY_TOLERANCE_KM_S, &format!("{} velocity", what));
+        }
+    }
+}
+
+#[test]
+fn propagation_stops_past_the_limit() {
+    let (propagator, epoch) = propagator(REFERENCES[0].line1, REFERENCES[0].line2);
+    let days = ai_space_tracker::propagation::MAX_PROPAGATION_DAYS;
+    assert!(propagator.propagate(epoch + Duration::days(days) - Duration::minutes(1)).is_some());
+    assert!(propagator.propagate(epoch + Duration::days(days) + Duration::minutes(1)).is_none());
+}
+
+#[test]
+fn teme_to_ecef_preserves_geometry() {
+    let (propagator, epoch) = propagator(REFERENCES[1].line1, REFERENCES[1].line2);
+    let time = minutes_after(epoch, 120.0);
+    let (position, velocity) = propagator.propagate_state(time).unwrap();
+    let ahead = position + velocity * 60.0;
+
+    let ecef = coords::teme_to_ecef(position, time);
+    let ecef_ahead = coords::teme_to_ecef(ahead, time);
+    assert_close(ecef.norm(), position.norm(), 1e-9, "radius");
+    assert_close((ecef_ahead - ecef).norm(), (ahead - position).norm(), 1e-9, "chord");
+    // A rotation about the pole leaves the height above the equator alone
+    assert_close(ecef.z, position.z, 1e-9, "z");
+    assert_vector_close(coords::ecef_to_teme(ecef, time), position.into(), 1e-9, "round trip");
+}
+
+#[test]
+fn iss_passes_over_the_expected_continents() {
+    let (propagator, epoch) = propagator(ISS_LINE1, ISS_LINE2);
+    let quarter_orbit = 1440.0 / 15.5 / 4.0;
+    let sub_point = |minutes: f64| {
+        let time = minutes_after(epoch, minutes);
+        coords::teme_to_geodetic(propagator.propagate(time).unwrap(), time)
+    };
+
+    // Northernmost point over central Europe
+    let north = sub_point(0.0);
+    assert_close(north.latitude_deg, 51.8, 0.5, "latitude over Europe");
+    assert_close(longitude_difference(north.longitude_deg, 10.0), 0.0, 1.0, "longitude over Europe");
+    assert_close(north.altitude_km, 425.0, 15.0, "altitude");
+
+    // A quarter orbit earlier it crossed the equator northbound over South America, the
+    // Earth having turned 5.8° east since
+    let node = sub_point(-quarter_orbit);
+    assert_close(node.latitude_deg, 0.0, 0.5, "latitude over South America");
+    assert_close(longitude_difference(node.longitude_deg, -74.2), 0.0, 1.0, "longitude over South America");
+
+    // And a quarter orbit later it crosses southbound over the eastern Indian Ocean
+    let descending = sub_point(quarter_orbit);
+    assert_close(descending.latitude_deg, 0.0, 0.5, "latitude over the Indian Ocean");
+    assert_close(longitude_difference(descending.longitude_deg, 94.2), 0.0, 1.0, "longitude over the Indian Ocean");
+    assert!(sub_point(quarter_orbit - 1.0).latitude_deg > sub_point(quarter_orbit + 1.0).latitude_deg);
+}
+
+#[cfg(feature = "visualizer")]
+mod scene {
+    use super::*;
+    use ai_space_tracker::coords::{Geodetic, GlobeShape};
+    use bevy::math::Vec3;
+
+    #[test]
+    fn scene_axes_follow_the_globe() {
+        // Greenwich on the equator, 90°E on the equator and the north pole
+        let greenwich = coords::geodetic_to_scene(Geodetic::new(0.0, 0.0, 0.0), GlobeShape::Sphere).normalize();
+        let east = coords::geodetic_to_scene(Geodetic::new(0.0, 90.0, 0.0), GlobeShape::Sphere).normalize();
+        let pole = coords::geodetic_to_scene(Geodetic::new(90.0, 0.0, 0.0), GlobeShape::Sphere).normalize();
+        assert!(greenwich.distance(-Vec3::X) < 1e-6, "greenwich at {:?}", greenwich);
+        assert!(east.distance(Vec3::Z) < 1e-6, "90°E at {:?}", east);
+        assert!(pole.distance(Vec3::Y) < 1e-6, "pole at {:?}", pole);
+    }
+
+    #[test]
+    fn teme_to_scene_preserves_geometry() {
+        let (propagator, epoch) = propagator(REFERENCES[0].line1, REFERENCES[0].line2);
+        let times = [0.0, 360.0, 720.0].map(|minutes| minutes_after(epoch, minutes));
+        let time = times[0];
+        let positions = times.map(|t| propagator.propagate(t).unwrap());
+        // Back to km, whatever the world scale
+        let scene = positions.map(|position| coords::teme_to_scene(position, time) / coords::WORLD_SCALE);
+
+        // Distances from the center and between points are kept, and orientation isn't
+        // mirrored (the scene frame is a rotation of ECEF)
+        for (teme, scene) in positions.iter().zip(&scene) {
+            assert_close(scene.length() as f64, teme.norm(), 1e-2, "radius");
+        }
+        assert_close(
+            scene[0].distance(scene[1]) as f64,
+            (positions[0] - positions[1]).norm(),
+            1e-2,
+            "distance",
+        );
+        let teme_volume = positions[0].cross(&positions[1]).dot(&positions[2]);
+        let scene_volume = scene[0].as_dvec3().cross(scene[1].as_dvec3()).dot(scene[2].as_dvec3());
+        assert_close(scene_volume / teme_volume, 1.0, 1e-5, "handedness");
+    }
+
+    #[test]
+    fn iss_is_drawn_over_europe() {
+        let (propagator, epoch) = propagator(ISS_LINE1, ISS_LINE2);
+        let scene = coords::teme_to_scene(propagator.propagate(epoch).unwrap(), epoch);
+        let drawn = coords::scene_to_geodetic(scene, GlobeShape::Sphere);
+        let sub_point = coords::teme_to_geodetic(propagator.propagate(epoch).unwrap(), epoch);
+
+        assert_close(longitude_difference(drawn.longitude_deg, sub_point.longitude_deg), 0.0, 1e-3, "longitude");
+        // The scene latitude is geocentric, a little under the geodetic one at 51°
+        assert_close(drawn.latitude_deg, sub_point.latitude_deg - 0.19, 0.05, "latitude");
+    }
+}